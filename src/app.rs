@@ -1,12 +1,71 @@
-use crate::{cfg::Cfg, steam_api::Api, constants};
+use crate::{cfg::Cfg, constants, mock_api::MockApi, snapshot::GameSnapshot, state::{LastGameStore, UnlockHistoryStore, VanityIdStore}, steam_api::{self, Achievement, Api, ApiClient, ApiError, Game, GameAchievements, GlobalAchievement}};
+use futures::stream::Stream;
+use std::io;
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+// Creates a Steam API client from the application configuration.
+//
+// <purpose-start>
+// This function is used by `AppContext::new`. A Steam ID configured as a profile URL or vanity name
+// is only resolved once a command requests player data, see `Api::with_identity_resolution`.
+// <purpose-end>
+//
+// <inputs-start>
+// - `cfg`: The application configuration, containing the API key, Steam ID, base URL, language, network settings, endpoint paths,
+//   dry-run mode and whether to resolve vanity names again.
+// <inputs-end>
+//
+// <outputs-start>
+// - `Api`: A new Steam API client.
+// <outputs-end>
+//
+// <side-effects-start>
+// - None.
+// <side-effects-end>
+pub fn api_from_cfg(cfg: &Cfg) -> Api {
+    let api = Api::new(
+        cfg.api_key().to_string(),
+        cfg.steam_id().to_string(),
+        cfg.base_url().to_string(),
+    )
+    .with_language(cfg.language().to_string())
+    .with_timeout(Duration::from_secs(cfg.timeout_secs()))
+    .with_pool_idle_timeout(Duration::from_secs(cfg.pool_idle_timeout_secs()))
+    .with_retries(cfg.retries())
+    .with_endpoint_paths(cfg.endpoint_paths().clone())
+    .with_dry_run(cfg.dry_run())
+    .with_identity_resolution(VanityIdStore::default_path().map(VanityIdStore::new), cfg.refresh_id());
+
+    match cfg.proxy() {
+        Some(proxy) => api.with_proxy(proxy.to_string()),
+        None => api,
+    }
+}
 
 // The main application structure.
 //
 // <purpose-start>
-// This struct holds the state of the application, including the Steam API client.
+// This struct holds the state of the application, including the source of Steam data,
+// the stores remembering the last used game and the unlocks of each game, whether non-essential messages are suppressed,
+// whether the stable porcelain output is requested, whether large numbers are grouped by thousands, whether the errors of each game are reported,
+// whether the user can be prompted, the number of concurrent requests of bulk fetches and the process exit code requested by a plugin.
+// The games list is memoized once fetched, see `get_games_list_cached`.
 // <purpose-end>
 pub struct AppContext {
-    pub api: Api,
+    pub api: Box<dyn ApiClient + Send + Sync>,
+    pub last_game: Option<LastGameStore>,
+    pub unlock_history: Option<UnlockHistoryStore>,
+    quiet: bool,
+    porcelain: bool,
+    group_thousands: bool,
+    verbose: bool,
+    interactive: bool,
+    concurrency: usize,
+    exit_code: AtomicI32,
+    games_list: Arc<Mutex<Option<Vec<Game>>>>,
 }
 
 impl AppContext {
@@ -14,10 +73,12 @@ impl AppContext {
     //
     // <purpose-start>
     // This function initializes the `AppContext` struct, creating a new `Api` instance with the provided configuration.
+    // In offline mode the bundled sample data is used instead, and nothing is persisted.
     // <purpose-end>
     //
     // <inputs-start>
-    // - `cfg`: The application configuration, containing the API key and Steam ID.
+    // - `cfg`: The application configuration, containing the API key, Steam ID, language, network settings
+    //   and whether to run offline.
     // <inputs-end>
     //
     // <outputs-start>
@@ -28,12 +89,537 @@ impl AppContext {
     // - None.
     // <side-effects-end>
     pub fn new(cfg: Cfg) -> AppContext {
-        let api = Api::new(
-            cfg.api_key().to_string(),
-            cfg.steam_id().to_string(),
-            constants::STEAM_API_BASE_URL.to_string(),
-        );
+        if cfg.offline() {
+            return AppContext::with_api(MockApi::new());
+        }
+
+        AppContext {
+            api: Box::new(api_from_cfg(&cfg)),
+            last_game: LastGameStore::default_path().map(LastGameStore::new),
+            unlock_history: UnlockHistoryStore::default_path().map(UnlockHistoryStore::new),
+            quiet: false,
+            porcelain: false,
+            group_thousands: true,
+            verbose: false,
+            interactive: false,
+            concurrency: constants::DEFAULT_CONCURRENCY,
+            exit_code: AtomicI32::new(0),
+            games_list: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    // Creates a new `AppContext` around an already configured source of Steam data.
+    //
+    // <purpose-start>
+    // This function is used where the API client is built by the caller, e.g. in tests pointing the
+    // client at a mock server, or in offline mode. Persistent state is disabled so that nothing is written to disk.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `api`: The source of Steam data to use.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `AppContext`: A new `AppContext` instance without persistent state.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None.
+    // <side-effects-end>
+    pub fn with_api(api: impl ApiClient + Send + Sync + 'static) -> AppContext {
+        AppContext {
+            api: Box::new(api),
+            last_game: None,
+            unlock_history: None,
+            quiet: false,
+            porcelain: false,
+            group_thousands: true,
+            verbose: false,
+            interactive: false,
+            concurrency: constants::DEFAULT_CONCURRENCY,
+            exit_code: AtomicI32::new(0),
+            games_list: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    // Sets whether non-essential messages are suppressed.
+    //
+    // <purpose-start>
+    // This function applies the `--quiet` flag. Plugins keep reporting errors, but skip notices,
+    // warnings and progress lines, see `quiet`.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `quiet`: Whether to suppress non-essential messages.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `AppContext`: The updated `AppContext` instance.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None.
+    // <side-effects-end>
+    pub fn with_quiet(mut self, quiet: bool) -> AppContext {
+        self.quiet = quiet;
+        self
+    }
+
+    // Returns whether non-essential messages are suppressed.
+    //
+    // <purpose-start>
+    // This function is checked by plugins before writing anything to standard error that is not an error.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - None.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `bool`: `true` if `--quiet` was given.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None.
+    // <side-effects-end>
+    pub fn quiet(&self) -> bool {
+        self.quiet
+    }
+
+    // Sets whether commands print the stable porcelain output.
+    //
+    // <purpose-start>
+    // This function applies the `--porcelain` flag. Listing and progress commands then print one
+    // tab-separated record per line, starting with the record type, in a format that is kept
+    // stable across releases, see the README.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `porcelain`: Whether to print the porcelain output.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `AppContext`: The updated `AppContext` instance.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None.
+    // <side-effects-end>
+    pub fn with_porcelain(mut self, porcelain: bool) -> AppContext {
+        self.porcelain = porcelain;
+        self
+    }
+
+    // Returns whether commands print the stable porcelain output.
+    pub fn porcelain(&self) -> bool {
+        self.porcelain
+    }
+
+    // Sets whether counts and playtimes in human-readable output are grouped by thousands.
+    //
+    // <purpose-start>
+    // This function applies the `--no-thousands` flag, for users who prefer plain numbers, e.g.
+    // `123456` instead of `123,456`. Grouping is on by default.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `group_thousands`: Whether to separate the thousands, see `ui::format_count`.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `AppContext`: The updated `AppContext` instance.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None.
+    // <side-effects-end>
+    pub fn with_group_thousands(mut self, group_thousands: bool) -> AppContext {
+        self.group_thousands = group_thousands;
+        self
+    }
+
+    // Returns whether counts and playtimes in human-readable output are grouped by thousands.
+    pub fn group_thousands(&self) -> bool {
+        self.group_thousands
+    }
+
+    // Sets whether the error of each game skipped by a bulk command is reported.
+    //
+    // <purpose-start>
+    // This function applies the global `--verbose` flag. Commands fetching many games otherwise
+    // only report the ids of the skipped games at the end, see `plugins::SkippedGames`.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `verbose`: Whether to report the error of each game.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `AppContext`: The updated `AppContext` instance.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None.
+    // <side-effects-end>
+    pub fn with_verbose(mut self, verbose: bool) -> AppContext {
+        self.verbose = verbose;
+        self
+    }
+
+    // Returns whether the error of each skipped game is reported.
+    pub fn verbose(&self) -> bool {
+        self.verbose
+    }
+
+    // Sets whether the user can be prompted interactively.
+    //
+    // <purpose-start>
+    // This function is called by `main` when the standard output is a terminal. Commands missing
+    // a game then let the user pick one instead of failing, see `plugins::game_arg_or_last`.
+    // Contexts created otherwise, e.g. in tests, never prompt.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `interactive`: Whether the user can be prompted.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `AppContext`: The updated `AppContext` instance.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None.
+    // <side-effects-end>
+    pub fn with_interactive(mut self, interactive: bool) -> AppContext {
+        self.interactive = interactive;
+        self
+    }
+
+    // Returns whether the user can be prompted interactively.
+    pub fn interactive(&self) -> bool {
+        self.interactive
+    }
+
+    // Sets the maximum number of requests in flight when fetching data for many games.
+    //
+    // <purpose-start>
+    // This function applies the `--concurrency` flag, see `game_achievements`.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `concurrency`: The maximum number of requests in flight, at least 1.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `AppContext`: The updated `AppContext` instance.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None.
+    // <side-effects-end>
+    pub fn with_concurrency(mut self, concurrency: usize) -> AppContext {
+        self.concurrency = concurrency;
+        self
+    }
+
+    // Fetches the achievements of many games with a bounded number of requests in flight.
+    //
+    // <purpose-start>
+    // Every command fetching achievements for more than one game goes through this function,
+    // so that `--concurrency` limits all of them.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `appids`: The ids of the games to fetch the achievements of.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - A stream of the game names and achievements, or errors, in the order of `appids`.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - **Network requests**: Fetches the achievements of each game once the stream is polled.
+    // <side-effects-end>
+    pub fn game_achievements(
+        &self,
+        appids: Vec<u32>,
+    ) -> impl Stream<Item = Result<GameAchievements, ApiError>> + Send + '_ {
+        steam_api::fetch_game_achievements(self.api.as_ref(), appids, self.concurrency)
+    }
+
+    // Fetches the achievements of many games along with their global unlock percentages.
+    //
+    // <purpose-start>
+    // The counterpart of `game_achievements` for commands that also need the rarity of each
+    // achievement, limited by `--concurrency` as well.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `appids`: The ids of the games to fetch the achievements of.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - A stream of the player and global achievements of each game, or errors, in the order of `appids`.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - **Network requests**: Fetches the achievements of each game once the stream is polled.
+    // <side-effects-end>
+    pub fn game_achievements_with_global(
+        &self,
+        appids: Vec<u32>,
+    ) -> impl Stream<Item = (Result<GameAchievements, ApiError>, Result<Vec<GlobalAchievement>, ApiError>)> + Send + '_ {
+        steam_api::fetch_game_achievements_with_global(self.api.as_ref(), appids, self.concurrency)
+    }
+
+    // Retrieves the list of games owned by the user, fetching it at most once.
+    //
+    // <purpose-start>
+    // Resolving a game name, recovering the name of a game with private stats and listing the
+    // games may all need the games list within one command. The first successful fetch is kept
+    // for the lifetime of the context, failures are not, so that a later call tries again.
+    // The lock is held during the fetch, so that concurrent callers share a single request.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - None.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `Ok(Vec<Game>)`: The owned games.
+    // - `Err(ApiError)`: An error if the games list had to be fetched and the request failed.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - **Network request**: Fetches the games list from the Steam API on the first call.
+    // <side-effects-end>
+    pub async fn get_games_list_cached(&self) -> Result<Vec<Game>, ApiError> {
+        let mut games_list = self.games_list.lock().await;
+        if let Some(games) = games_list.as_ref() {
+            return Ok(games.clone());
+        }
+
+        let games = self.api.get_games_list().await?;
+        *games_list = Some(games.clone());
+        Ok(games)
+    }
+
+    // Looks up a game's name in the user's games list.
+    //
+    // <purpose-start>
+    // The player achievements endpoint returns an empty game name for private stats. The owned
+    // games list still knows the name, so it is used to recover it.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `appid`: The ID of the game.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `Some(String)`: The name of the game.
+    // - `None`: If the games list cannot be fetched or does not contain the game.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - **Network request**: Fetches the games list from the Steam API.
+    // <side-effects-end>
+    pub async fn game_name(&self, appid: u32) -> Option<String> {
+        let games = self.get_games_list_cached().await.ok()?;
+        games.into_iter().find(|g| g.appid == appid).map(|g| g.name)
+    }
+
+    // Returns the id of the last game used with a game-specific command.
+    //
+    // <purpose-start>
+    // This function allows commands to fall back to the previously used game when no id is given.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - None.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `Some(u32)`: The last used game id.
+    // - `None`: If persistence is disabled or no game id has been stored yet.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - **Reads a file**: Reads the last game file, if persistence is enabled.
+    // <side-effects-end>
+    pub fn last_game_id(&self) -> Option<u32> {
+        self.last_game.as_ref().and_then(|store| store.load())
+    }
 
-        AppContext { api }
+    // Records the id of the game used with a game-specific command.
+    //
+    // <purpose-start>
+    // This function remembers the game so that it can be omitted on the next invocation.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `appid`: The id of the game to remember.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `Ok(())` if the id was stored or persistence is disabled.
+    // - `Err(io::Error)` if the id could not be written.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - **Writes a file**: Overwrites the last game file, if persistence is enabled.
+    // <side-effects-end>
+    pub fn remember_game(&self, appid: u32) -> io::Result<()> {
+        match &self.last_game {
+            Some(store) => store.save(appid),
+            None => Ok(()),
+        }
     }
-}
\ No newline at end of file
+
+    // Returns the unlock state of a game recorded by the previous run.
+    //
+    // <purpose-start>
+    // This function allows commands to list the achievements unlocked since the game was last seen.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `appid`: The ID of the game.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `Some(GameSnapshot)`: The previously recorded state of the game.
+    // - `None`: If persistence is disabled or the game has not been recorded yet.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - **Reads a file**: Reads the file of the game, if persistence is enabled.
+    // <side-effects-end>
+    pub fn previous_unlocks(&self, appid: u32) -> Option<GameSnapshot> {
+        self.unlock_history.as_ref().and_then(|store| store.load(appid))
+    }
+
+    // Records the unlock state of a game.
+    //
+    // <purpose-start>
+    // This function replaces the previously recorded state, so that the next run compares against it.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `appid`: The ID of the game.
+    // - `name`: The name of the game.
+    // - `achievements`: The user's achievements for the game.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `Ok(())` if the state was stored or persistence is disabled.
+    // - `Err(io::Error)` if the state could not be written.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - **Writes a file**: Overwrites the file of the game, if persistence is enabled.
+    // <side-effects-end>
+    pub fn record_unlocks(&self, appid: u32, name: &str, achievements: &[Achievement]) -> io::Result<()> {
+        match &self.unlock_history {
+            Some(store) => store.save(appid, name, achievements),
+            None => Ok(()),
+        }
+    }
+
+    // Requests a process exit code.
+    //
+    // <purpose-start>
+    // Plugins report problems through their writers and do not return a status. Commands meant for
+    // scripts use this to make the process exit with a non-zero code without terminating it
+    // themselves, which keeps them testable.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `code`: The exit code to use once the command has finished.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - None.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None.
+    // <side-effects-end>
+    pub fn set_exit_code(&self, code: i32) {
+        self.exit_code.store(code, Ordering::Relaxed);
+    }
+
+    // Returns the process exit code requested by the executed command.
+    //
+    // <purpose-start>
+    // This function is read by `main` once the command has finished.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - None.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `i32`: The requested exit code, `0` if none was requested.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None.
+    // <side-effects-end>
+    pub fn exit_code(&self) -> i32 {
+        self.exit_code.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_api::FakeApi;
+
+    #[tokio::test]
+    async fn test_get_games_list_cached_fetches_once() {
+        let api = FakeApi::new().with_game(440, "Team Fortress 2");
+        let calls = api.games_list_calls();
+        let app_context = AppContext::with_api(api);
+
+        let first = app_context.get_games_list_cached().await.unwrap();
+        let second = app_context.get_games_list_cached().await.unwrap();
+
+        assert_eq!(first, second);
+        assert_eq!(second[0].name, "Team Fortress 2");
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(app_context.game_name(440).await.as_deref(), Some("Team Fortress 2"));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_get_games_list_cached_retries_failures() {
+        let api = FakeApi::failing();
+        let calls = api.games_list_calls();
+        let app_context = AppContext::with_api(api);
+
+        assert!(app_context.get_games_list_cached().await.is_err());
+        assert!(app_context.get_games_list_cached().await.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_new_uses_default_base_url() {
+        let app_context = AppContext::new(Cfg::new());
+        assert_eq!(app_context.api.base_url(), Some(constants::STEAM_API_BASE_URL));
+    }
+
+    #[test]
+    fn test_new_uses_base_url_override() {
+        let mut cfg = Cfg::new();
+        cfg.set_base_url("http://localhost:8080".to_string());
+        let app_context = AppContext::new(cfg);
+        assert_eq!(app_context.api.base_url(), Some("http://localhost:8080"));
+    }
+
+    #[test]
+    fn test_new_offline_uses_sample_data() {
+        let mut cfg = Cfg::new();
+        cfg.set_offline(true);
+        let app_context = AppContext::new(cfg);
+        assert_eq!(app_context.api.base_url(), None);
+    }
+}
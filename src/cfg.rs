@@ -1,13 +1,62 @@
+use crate::{constants, steam_api::EndpointPaths};
+use std::collections::HashMap;
 use std::env;
+use std::fs;
+use std::path::Path;
+
+// The language code used when neither `TROGUE_LANG` nor `--lang` is provided.
+const DEFAULT_LANGUAGE: &str = "en";
+
+// The request timeout in seconds used when neither `TROGUE_TIMEOUT` nor `--timeout` is provided.
+const DEFAULT_TIMEOUT_SECS: u64 = 30;
+
+// The time in seconds idle connections are kept open when neither `TROGUE_POOL_IDLE_TIMEOUT` nor
+// `--pool-idle-timeout` is provided, the default of reqwest.
+const DEFAULT_POOL_IDLE_TIMEOUT_SECS: u64 = 90;
+
+// Parses a Steam API base URL, given with `--base-url` or `TROGUE_BASE_URL`.
+//
+// <purpose-start>
+// Only http and https URLs with a host are accepted. A trailing slash is dropped, as the endpoint
+// paths are appended after a slash.
+// <purpose-end>
+//
+// <inputs-start>
+// - `value`: The raw URL.
+// <inputs-end>
+//
+// <outputs-start>
+// - `Ok(String)`: The base URL, without a trailing slash.
+// - `Err(String)`: A user-facing message if the value is not such a URL.
+// <outputs-end>
+pub fn parse_base_url(value: &str) -> Result<String, String> {
+    let value = value.trim();
+    match reqwest::Url::parse(value) {
+        Ok(url) if matches!(url.scheme(), "http" | "https") && url.has_host() => Ok(value.trim_end_matches('/').to_string()),
+        _ => Err(format!("'{}' is not an http or https URL", value)),
+    }
+}
 
 // Represents the application configuration.
 //
 // <purpose-start>
-// This struct holds the configuration for the application, including the Steam API key and Steam ID.
+// This struct holds the configuration for the application, including the Steam API key, Steam ID
+// and the language used for localized achievement data.
 // <purpose-end>
 pub struct Cfg {
     api_key: String,
     steam_id: String,
+    language: String,
+    timeout_secs: u64,
+    pool_idle_timeout_secs: u64,
+    retries: u32,
+    base_url: String,
+    proxy: Option<String>,
+    offline: bool,
+    dry_run: bool,
+    refresh_id: bool,
+    endpoint_paths: EndpointPaths,
+    dotenv: HashMap<String, String>,
 }
 
 impl Cfg {
@@ -32,6 +81,17 @@ impl Cfg {
         Self {
             api_key: "".to_string(),
             steam_id: "".to_string(),
+            language: DEFAULT_LANGUAGE.to_string(),
+            timeout_secs: DEFAULT_TIMEOUT_SECS,
+            pool_idle_timeout_secs: DEFAULT_POOL_IDLE_TIMEOUT_SECS,
+            retries: 0,
+            base_url: constants::STEAM_API_BASE_URL.to_string(),
+            proxy: None,
+            offline: false,
+            dry_run: false,
+            refresh_id: false,
+            endpoint_paths: EndpointPaths::default(),
+            dotenv: HashMap::new(),
         }
     }
 
@@ -77,10 +137,463 @@ impl Cfg {
         &self.steam_id
     }
 
+    // Overrides the Steam ID.
+    //
+    // <purpose-start>
+    // This function allows the `--steam-id` flag to take precedence over the `TROGUE_STEAM_ID` environment
+    // variable. When called before `load`, the variable is not required.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `steam_id`: The Steam ID, profile URL or vanity name to use.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - None.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None.
+    // <side-effects-end>
+    pub fn set_steam_id(&mut self, steam_id: String) {
+        self.steam_id = steam_id;
+    }
+
+    // Returns the language code used for localized achievement data.
+    //
+    // <purpose-start>
+    // This function returns the Steam language code passed as the `l` parameter to the achievement endpoints.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - None.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `&str`: A reference to the language code.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None.
+    // <side-effects-end>
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+
+    // Overrides the language code.
+    //
+    // <purpose-start>
+    // This function allows a command-line flag to take precedence over the `TROGUE_LANG` environment variable.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `language`: The Steam language code to use.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - None.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None.
+    // <side-effects-end>
+    pub fn set_language(&mut self, language: String) {
+        self.language = language;
+    }
+
+    // Returns the request timeout in seconds.
+    //
+    // <purpose-start>
+    // This function returns the maximum duration of a single Steam API request.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - None.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `u64`: The request timeout in seconds.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None.
+    // <side-effects-end>
+    pub fn timeout_secs(&self) -> u64 {
+        self.timeout_secs
+    }
+
+    // Overrides the request timeout.
+    //
+    // <purpose-start>
+    // This function allows a command-line flag to take precedence over the `TROGUE_TIMEOUT` environment variable.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `timeout_secs`: The request timeout in seconds.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - None.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None.
+    // <side-effects-end>
+    pub fn set_timeout_secs(&mut self, timeout_secs: u64) {
+        self.timeout_secs = timeout_secs;
+    }
+
+    // Returns how long idle connections are kept open, in seconds.
+    //
+    // <purpose-start>
+    // This function returns the time a pooled connection may stay unused before it is closed.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - None.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `u64`: The idle timeout in seconds.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None.
+    // <side-effects-end>
+    pub fn pool_idle_timeout_secs(&self) -> u64 {
+        self.pool_idle_timeout_secs
+    }
+
+    // Overrides how long idle connections are kept open.
+    //
+    // <purpose-start>
+    // This function allows a command-line flag to take precedence over the `TROGUE_POOL_IDLE_TIMEOUT` environment variable.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `pool_idle_timeout_secs`: The idle timeout in seconds.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - None.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None.
+    // <side-effects-end>
+    pub fn set_pool_idle_timeout_secs(&mut self, pool_idle_timeout_secs: u64) {
+        self.pool_idle_timeout_secs = pool_idle_timeout_secs;
+    }
+
+    // Returns the number of retries of failed requests.
+    //
+    // <purpose-start>
+    // This function returns how many times a transient Steam API failure is retried.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - None.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `u32`: The number of retries.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None.
+    // <side-effects-end>
+    pub fn retries(&self) -> u32 {
+        self.retries
+    }
+
+    // Overrides the number of retries.
+    //
+    // <purpose-start>
+    // This function allows a command-line flag to take precedence over the `TROGUE_RETRIES` environment variable.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `retries`: The number of retries of failed requests.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - None.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None.
+    // <side-effects-end>
+    pub fn set_retries(&mut self, retries: u32) {
+        self.retries = retries;
+    }
+
+    // Returns the base URL of the Steam API.
+    //
+    // <purpose-start>
+    // This function returns the host requests are sent to, the Steam API unless overridden to go
+    // through a proxy.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - None.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `&str`: The base URL, without a trailing slash.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None.
+    // <side-effects-end>
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    // Overrides the base URL of the Steam API.
+    //
+    // <purpose-start>
+    // This function allows a command-line flag to take precedence over the `TROGUE_BASE_URL` environment variable.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `base_url`: The base URL, validated with `parse_base_url`.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - None.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None.
+    // <side-effects-end>
+    pub fn set_base_url(&mut self, base_url: String) {
+        self.base_url = base_url;
+    }
+
+    // Returns the explicit proxy of Steam API requests.
+    //
+    // <purpose-start>
+    // This function returns the proxy set with `--proxy`, the proxy environment variables are handled by the HTTP client.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - None.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `Option<&str>`: The proxy URL, `None` when no proxy was set explicitly.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None.
+    // <side-effects-end>
+    pub fn proxy(&self) -> Option<&str> {
+        self.proxy.as_deref()
+    }
+
+    // Sets the proxy Steam API requests are sent through.
+    //
+    // <purpose-start>
+    // This function allows a command-line flag to take precedence over the `HTTP_PROXY`/`HTTPS_PROXY` environment variables.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `proxy`: The proxy URL, validated with `parse_base_url`.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - None.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None.
+    // <side-effects-end>
+    pub fn set_proxy(&mut self, proxy: String) {
+        self.proxy = Some(proxy);
+    }
+
+    // Returns whether trogue runs against the bundled sample data.
+    //
+    // <purpose-start>
+    // This function tells whether the offline mode is enabled, in which no requests are made
+    // and no credentials are needed.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - None.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `bool`: `true` if the offline mode is enabled.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None.
+    // <side-effects-end>
+    pub fn offline(&self) -> bool {
+        self.offline
+    }
+
+    // Enables the offline mode.
+    //
+    // <purpose-start>
+    // This function allows the `--offline` flag to enable the offline mode before the configuration
+    // is loaded, so that the missing credentials are not reported.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `offline`: Whether to run against the bundled sample data.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - None.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None.
+    // <side-effects-end>
+    pub fn set_offline(&mut self, offline: bool) {
+        self.offline = offline;
+    }
+
+    // Returns whether requests are planned rather than sent.
+    //
+    // <purpose-start>
+    // This function tells whether the `--dry-run` flag was given, see `Api::with_dry_run`.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - None.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `bool`: `true` if no request may be sent.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None.
+    // <side-effects-end>
+    pub fn dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    // Enables the dry-run mode.
+    //
+    // <purpose-start>
+    // This function allows the `--dry-run` flag to stop the Steam API client from sending requests.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `dry_run`: Whether to plan requests instead of sending them.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - None.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None.
+    // <side-effects-end>
+    pub fn set_dry_run(&mut self, dry_run: bool) {
+        self.dry_run = dry_run;
+    }
+
+    // Returns whether a vanity name Steam ID is resolved again.
+    //
+    // <purpose-start>
+    // This function tells whether the `--refresh-id` flag was given, see `Api::with_identity_resolution`.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - None.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `bool`: `true` if the SteamID64 stored by an earlier run may not be reused.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None.
+    // <side-effects-end>
+    pub fn refresh_id(&self) -> bool {
+        self.refresh_id
+    }
+
+    // Forces a vanity name Steam ID to be resolved again.
+    //
+    // <purpose-start>
+    // This function allows the `--refresh-id` flag to bypass the stored SteamID64 of a vanity name,
+    // e.g. after the name moved to another profile.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `refresh_id`: Whether to resolve the vanity name again.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - None.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None.
+    // <side-effects-end>
+    pub fn set_refresh_id(&mut self, refresh_id: bool) {
+        self.refresh_id = refresh_id;
+    }
+
+    // Returns the paths of the Steam API endpoints.
+    //
+    // <purpose-start>
+    // This function returns the endpoint versions to request, the defaults unless overridden
+    // with `TROGUE_*_PATH` variables, see `EndpointPaths::from_lookup`.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - None.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `&EndpointPaths`: A reference to the endpoint paths.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None.
+    // <side-effects-end>
+    pub fn endpoint_paths(&self) -> &EndpointPaths {
+        &self.endpoint_paths
+    }
+
+    // Sets the variables read from a `.env` file.
+    //
+    // <purpose-start>
+    // This function provides fallback values for `load`, which are only used for variables
+    // that are not set in the environment.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `vars`: The variables read from a `.env` file, see `read_dotenv`.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - None.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None.
+    // <side-effects-end>
+    pub fn set_dotenv(&mut self, vars: HashMap<String, String>) {
+        self.dotenv = vars;
+    }
+
     // Loads the configuration from environment variables.
     //
     // <purpose-start>
-    // This function loads the Steam API key and Steam ID from environment variables.
+    // This function loads the Steam API key and Steam ID from environment variables,
+    // falling back to the values from the `.env` file set with `set_dotenv`.
+    // A Steam ID set with `set_steam_id` takes precedence over `TROGUE_STEAM_ID`.
+    // The optional language code, request timeout, idle connection timeout and number of retries are read as well,
+    // falling back to their defaults when they are not set.
+    // The credentials are not required in offline mode, enabled by `TROGUE_OFFLINE` or `set_offline`.
     // <purpose-end>
     //
     // <inputs-start>
@@ -89,26 +602,136 @@ impl Cfg {
     //
     // <outputs-start>
     // - `Ok(())` if the configuration was loaded successfully.
-    // - `Err(&str)` if an environment variable is missing.
+    // - `Err(&str)` if an environment variable is missing or not a valid number.
     // <outputs-end>
     //
     // <side-effects-start>
-    // - **Reads environment variables**: Reads the `TROGUE_STEAM_API_KEY` and `TROGUE_STEAM_ID` environment variables.
+    // - **Reads environment variables**: Reads the `TROGUE_STEAM_API_KEY`, `TROGUE_STEAM_ID`, `TROGUE_LANG`,
+    //   `TROGUE_TIMEOUT`, `TROGUE_POOL_IDLE_TIMEOUT`, `TROGUE_RETRIES`, `TROGUE_BASE_URL`, `TROGUE_OFFLINE`
+    //   and `TROGUE_*_PATH` environment variables.
     // <side-effects-end>
     pub fn load(&mut self) -> Result<(), &str> {
-        match Cfg::read_env("TROGUE_STEAM_API_KEY") {
-            Ok(api_key) => self.api_key = api_key,
-            Err(_) => return Err("Missing TROGUE_STEAM_API_KEY environment variable."),
+        if let Some(offline) = self.lookup("TROGUE_OFFLINE") {
+            self.offline |= !matches!(offline.trim(), "" | "0" | "false");
+        }
+
+        match self.lookup("TROGUE_STEAM_API_KEY") {
+            Some(api_key) => self.api_key = api_key,
+            None if self.offline => {}
+            None => return Err("Missing TROGUE_STEAM_API_KEY environment variable."),
+        }
+
+        if self.steam_id.is_empty() {
+            match self.lookup("TROGUE_STEAM_ID") {
+                Some(steam_id) => self.steam_id = steam_id,
+                None if self.offline => {}
+                None => return Err("Missing TROGUE_STEAM_ID environment variable."),
+            }
+        }
+
+        if let Some(language) = self.lookup("TROGUE_LANG") {
+            self.language = language;
+        }
+
+        if let Some(timeout) = self.lookup("TROGUE_TIMEOUT") {
+            match timeout.trim().parse() {
+                Ok(timeout) if timeout > 0 => self.timeout_secs = timeout,
+                _ => return Err("Invalid TROGUE_TIMEOUT environment variable, expected a positive number of seconds."),
+            }
+        }
+
+        if let Some(pool_idle_timeout) = self.lookup("TROGUE_POOL_IDLE_TIMEOUT") {
+            match pool_idle_timeout.trim().parse() {
+                Ok(pool_idle_timeout) => self.pool_idle_timeout_secs = pool_idle_timeout,
+                Err(_) => return Err("Invalid TROGUE_POOL_IDLE_TIMEOUT environment variable, expected a number of seconds."),
+            }
         }
 
-        match Cfg::read_env("TROGUE_STEAM_ID") {
-            Ok(steam_id) => self.steam_id = steam_id,
-            Err(_) => return Err("Missing TROGUE_STEAM_ID environment variable."),
+        if let Some(retries) = self.lookup("TROGUE_RETRIES") {
+            match retries.trim().parse() {
+                Ok(retries) => self.retries = retries,
+                Err(_) => return Err("Invalid TROGUE_RETRIES environment variable, expected a number."),
+            }
         }
 
+        if let Some(base_url) = self.lookup("TROGUE_BASE_URL") {
+            match parse_base_url(&base_url) {
+                Ok(base_url) => self.base_url = base_url,
+                Err(_) => return Err("Invalid TROGUE_BASE_URL environment variable, expected an http or https URL."),
+            }
+        }
+
+        self.endpoint_paths = EndpointPaths::from_lookup(|key| self.lookup(key));
+
         Ok(())
     }
 
+    // Looks up a configuration variable.
+    //
+    // <purpose-start>
+    // This function reads a variable from the environment, falling back to the `.env` file values.
+    // Real environment variables always take precedence.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `key`: The name of the variable to read.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `Some(String)` if the variable is set in the environment or in the `.env` file.
+    // - `None` otherwise.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - **Reads environment variables**: Reads the specified environment variable.
+    // <side-effects-end>
+    fn lookup(&self, key: &str) -> Option<String> {
+        Cfg::read_env(key).ok().or_else(|| self.dotenv.get(key).cloned())
+    }
+
+    // Reads the variables from a `.env` file.
+    //
+    // <purpose-start>
+    // This function reads `KEY=VALUE` lines from the given file. Blank lines and lines starting
+    // with `#` are skipped, an optional `export ` prefix is allowed and values may be wrapped
+    // in single or double quotes. A missing or unreadable file yields no variables.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `path`: The path of the `.env` file.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `HashMap<String, String>`: The variables defined in the file.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - **Reads a file**: Reads the `.env` file.
+    // <side-effects-end>
+    pub fn read_dotenv(path: &Path) -> HashMap<String, String> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(_) => return HashMap::new(),
+        };
+
+        contents
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| {
+                let line = line.strip_prefix("export ").unwrap_or(line);
+                let (key, value) = line.split_once('=')?;
+                let value = value.trim();
+                let value = ['"', '\'']
+                    .iter()
+                    .find_map(|&q| value.strip_prefix(q).and_then(|v| v.strip_suffix(q)))
+                    .unwrap_or(value);
+
+                Some((key.trim().to_string(), value.to_string()))
+            })
+            .collect()
+    }
+
     // Reads an environment variable.
     //
     // <purpose-start>
@@ -154,3 +777,232 @@ impl Default for Cfg {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_temp_dotenv(name: &str, contents: &str) -> std::path::PathBuf {
+        let dir = env::temp_dir().join(format!("trogue-cfg-test-{}-{}", std::process::id(), name));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(".env");
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_read_dotenv() {
+        let path = write_temp_dotenv(
+            "read",
+            "# trogue credentials\n\nTROGUE_STEAM_API_KEY=abc123\nexport TROGUE_STEAM_ID=\"76561197960287930\"\nTROGUE_LANG = 'german'\nnot a variable\n",
+        );
+
+        let vars = Cfg::read_dotenv(&path);
+
+        assert_eq!(vars.len(), 3);
+        assert_eq!(vars["TROGUE_STEAM_API_KEY"], "abc123");
+        assert_eq!(vars["TROGUE_STEAM_ID"], "76561197960287930");
+        assert_eq!(vars["TROGUE_LANG"], "german");
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_read_dotenv_missing_file() {
+        let vars = Cfg::read_dotenv(Path::new("/nonexistent/trogue/.env"));
+        assert!(vars.is_empty());
+    }
+
+    #[test]
+    fn test_load_uses_dotenv_values() {
+        let path = write_temp_dotenv("load", "TROGUE_STEAM_API_KEY=abc123\nTROGUE_STEAM_ID=76561197960287930\n");
+        let mut cfg = Cfg::new();
+        cfg.set_dotenv(Cfg::read_dotenv(&path));
+
+        cfg.load().unwrap();
+
+        // Variables set in the real environment take precedence over the file
+        let expected_key = env::var("TROGUE_STEAM_API_KEY").unwrap_or("abc123".to_string());
+        let expected_id = env::var("TROGUE_STEAM_ID").unwrap_or("76561197960287930".to_string());
+        assert_eq!(cfg.api_key(), expected_key);
+        assert_eq!(cfg.steam_id(), expected_id);
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_environment_takes_precedence_over_dotenv() {
+        let mut cfg = Cfg::new();
+        cfg.set_dotenv(HashMap::from([
+            ("PATH".to_string(), "from-dotenv".to_string()),
+            ("TROGUE_TEST_DOTENV_ONLY".to_string(), "from-dotenv".to_string()),
+        ]));
+
+        assert_eq!(cfg.lookup("PATH"), env::var("PATH").ok());
+        assert_eq!(cfg.lookup("TROGUE_TEST_DOTENV_ONLY"), Some("from-dotenv".to_string()));
+    }
+
+    #[test]
+    fn test_network_settings_precedence() {
+        let mut cfg = Cfg::new();
+        cfg.set_dotenv(HashMap::from([
+            ("TROGUE_STEAM_API_KEY".to_string(), "abc123".to_string()),
+            ("TROGUE_STEAM_ID".to_string(), "76561197960287930".to_string()),
+            ("TROGUE_TIMEOUT".to_string(), "5".to_string()),
+            ("TROGUE_RETRIES".to_string(), "2".to_string()),
+            ("TROGUE_POOL_IDLE_TIMEOUT".to_string(), "15".to_string()),
+        ]));
+
+        // Defaults apply until the configuration is loaded
+        assert_eq!(cfg.timeout_secs(), DEFAULT_TIMEOUT_SECS);
+        assert_eq!(cfg.retries(), 0);
+        assert_eq!(cfg.pool_idle_timeout_secs(), DEFAULT_POOL_IDLE_TIMEOUT_SECS);
+
+        // Variables take precedence over the defaults
+        cfg.load().unwrap();
+        let expected_timeout = env::var("TROGUE_TIMEOUT").map_or(5, |v| v.parse().unwrap());
+        let expected_retries = env::var("TROGUE_RETRIES").map_or(2, |v| v.parse().unwrap());
+        let expected_pool_idle_timeout = env::var("TROGUE_POOL_IDLE_TIMEOUT").map_or(15, |v| v.parse().unwrap());
+        assert_eq!(cfg.timeout_secs(), expected_timeout);
+        assert_eq!(cfg.retries(), expected_retries);
+        assert_eq!(cfg.pool_idle_timeout_secs(), expected_pool_idle_timeout);
+
+        // Flags take precedence over the variables
+        cfg.set_timeout_secs(60);
+        cfg.set_retries(4);
+        cfg.set_pool_idle_timeout_secs(0);
+        assert_eq!(cfg.timeout_secs(), 60);
+        assert_eq!(cfg.retries(), 4);
+        assert_eq!(cfg.pool_idle_timeout_secs(), 0);
+    }
+
+    #[test]
+    fn test_endpoint_path_overrides() {
+        if env::var("TROGUE_PLAYER_ACHIEVEMENTS_PATH").is_ok() || env::var("TROGUE_OWNED_GAMES_PATH").is_ok() {
+            return;
+        }
+
+        let mut cfg = Cfg::new();
+        cfg.set_dotenv(HashMap::from([
+            ("TROGUE_STEAM_API_KEY".to_string(), "abc123".to_string()),
+            ("TROGUE_STEAM_ID".to_string(), "76561197960287930".to_string()),
+            ("TROGUE_PLAYER_ACHIEVEMENTS_PATH".to_string(), "/ISteamUserStats/GetPlayerAchievements/v0002/".to_string()),
+            ("TROGUE_OWNED_GAMES_PATH".to_string(), " ".to_string()),
+        ]));
+        assert_eq!(cfg.endpoint_paths(), &EndpointPaths::default());
+
+        cfg.load().unwrap();
+        assert_eq!(cfg.endpoint_paths().player_achievements, "ISteamUserStats/GetPlayerAchievements/v0002");
+        assert_eq!(cfg.endpoint_paths().owned_games, crate::constants::OWNED_GAMES_PATH);
+    }
+
+    #[test]
+    fn test_parse_base_url() {
+        assert_eq!(parse_base_url("http://localhost:8080").unwrap(), "http://localhost:8080");
+        assert_eq!(parse_base_url(" https://proxy.example.com/steam/ ").unwrap(), "https://proxy.example.com/steam");
+        assert!(parse_base_url("proxy.example.com").is_err());
+        assert!(parse_base_url("ftp://proxy.example.com").is_err());
+        assert!(parse_base_url("http://").is_err());
+    }
+
+    #[test]
+    fn test_base_url_override() {
+        if env::var("TROGUE_BASE_URL").is_ok() {
+            return;
+        }
+
+        let mut cfg = Cfg::new();
+        cfg.set_dotenv(HashMap::from([
+            ("TROGUE_STEAM_API_KEY".to_string(), "abc123".to_string()),
+            ("TROGUE_STEAM_ID".to_string(), "76561197960287930".to_string()),
+            ("TROGUE_BASE_URL".to_string(), "http://localhost:8080/".to_string()),
+        ]));
+        assert_eq!(cfg.base_url(), constants::STEAM_API_BASE_URL);
+
+        cfg.load().unwrap();
+        assert_eq!(cfg.base_url(), "http://localhost:8080");
+
+        cfg.set_dotenv(HashMap::from([
+            ("TROGUE_STEAM_API_KEY".to_string(), "abc123".to_string()),
+            ("TROGUE_STEAM_ID".to_string(), "76561197960287930".to_string()),
+            ("TROGUE_BASE_URL".to_string(), "localhost".to_string()),
+        ]));
+        assert!(cfg.load().unwrap_err().contains("TROGUE_BASE_URL"));
+    }
+
+    #[test]
+    fn test_proxy_override() {
+        let mut cfg = Cfg::new();
+        assert_eq!(cfg.proxy(), None);
+
+        cfg.set_proxy("http://proxy.example.com:3128".to_string());
+        assert_eq!(cfg.proxy(), Some("http://proxy.example.com:3128"));
+    }
+
+    #[test]
+    fn test_invalid_network_settings() {
+        if env::var("TROGUE_TIMEOUT").is_ok() || env::var("TROGUE_RETRIES").is_ok() {
+            return;
+        }
+
+        let credentials = [
+            ("TROGUE_STEAM_API_KEY".to_string(), "abc123".to_string()),
+            ("TROGUE_STEAM_ID".to_string(), "76561197960287930".to_string()),
+        ];
+
+        let mut cfg = Cfg::new();
+        let mut vars = HashMap::from(credentials.clone());
+        vars.insert("TROGUE_TIMEOUT".to_string(), "soon".to_string());
+        cfg.set_dotenv(vars);
+        assert!(cfg.load().unwrap_err().contains("TROGUE_TIMEOUT"));
+
+        let mut cfg = Cfg::new();
+        let mut vars = HashMap::from(credentials);
+        vars.insert("TROGUE_RETRIES".to_string(), "-1".to_string());
+        cfg.set_dotenv(vars);
+        assert!(cfg.load().unwrap_err().contains("TROGUE_RETRIES"));
+    }
+
+    #[test]
+    fn test_offline_mode_does_not_require_credentials() {
+        let mut cfg = Cfg::new();
+        cfg.set_offline(true);
+
+        assert!(cfg.load().is_ok());
+        assert!(cfg.offline());
+    }
+
+    #[test]
+    fn test_offline_mode_from_variable() {
+        if env::var("TROGUE_OFFLINE").is_ok() {
+            return;
+        }
+
+        let mut cfg = Cfg::new();
+        cfg.set_dotenv(HashMap::from([("TROGUE_OFFLINE".to_string(), "1".to_string())]));
+        assert!(cfg.load().is_ok());
+        assert!(cfg.offline());
+
+        let mut cfg = Cfg::new();
+        cfg.set_dotenv(HashMap::from([
+            ("TROGUE_OFFLINE".to_string(), "false".to_string()),
+            ("TROGUE_STEAM_API_KEY".to_string(), "abc123".to_string()),
+            ("TROGUE_STEAM_ID".to_string(), "76561197960287930".to_string()),
+        ]));
+        assert!(cfg.load().is_ok());
+        assert!(!cfg.offline());
+    }
+
+    #[test]
+    fn test_steam_id_override_takes_precedence() {
+        let mut cfg = Cfg::new();
+        cfg.set_dotenv(HashMap::from([
+            ("TROGUE_STEAM_API_KEY".to_string(), "abc123".to_string()),
+            ("TROGUE_STEAM_ID".to_string(), "76561197960287930".to_string()),
+        ]));
+        cfg.set_steam_id("https://steamcommunity.com/id/gabelogannewell".to_string());
+
+        assert!(cfg.load().is_ok());
+        assert_eq!(cfg.steam_id(), "https://steamcommunity.com/id/gabelogannewell");
+    }
+}
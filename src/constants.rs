@@ -17,3 +17,68 @@
 // - None
 // <side-effects-end>
 pub const STEAM_API_BASE_URL: &str = "http://api.steampowered.com";
+
+// The default maximum number of concurrent requests when fetching data for many games.
+//
+// <purpose-start>
+// Steam rate-limits bursts of requests, so commands covering a whole library keep at most this
+// many requests in flight unless `--concurrency` says otherwise.
+// <purpose-end>
+//
+// <inputs-start>
+// - None
+// <inputs-end>
+//
+// <outputs-start>
+// - The default number of requests in flight.
+// <outputs-end>
+//
+// <side-effects-start>
+// - None
+// <side-effects-end>
+pub const DEFAULT_CONCURRENCY: usize = 8;
+
+// The path of the file providing configuration variables missing from the environment.
+//
+// <purpose-start>
+// The file is looked up in the current directory, see `Cfg::read_dotenv`.
+// <purpose-end>
+//
+// <inputs-start>
+// - None
+// <inputs-end>
+//
+// <outputs-start>
+// - A string slice representing the relative path of the `.env` file.
+// <outputs-end>
+//
+// <side-effects-start>
+// - None
+// <side-effects-end>
+pub const DOTENV_PATH: &str = ".env";
+
+// The paths of the Steam API endpoints, including their versions, relative to the base URL.
+//
+// <purpose-start>
+// Steam occasionally deprecates an endpoint version in favor of a newer one. Keeping the paths
+// here means a version bump is a one-line change. Each path can also be overridden at runtime,
+// see `steam_api::EndpointPaths`.
+// <purpose-end>
+//
+// <inputs-start>
+// - None
+// <inputs-end>
+//
+// <outputs-start>
+// - String slices representing the endpoint paths, without leading or trailing slashes.
+// <outputs-end>
+//
+// <side-effects-start>
+// - None
+// <side-effects-end>
+pub const OWNED_GAMES_PATH: &str = "IPlayerService/GetOwnedGames/v0001";
+pub const RECENTLY_PLAYED_GAMES_PATH: &str = "IPlayerService/GetRecentlyPlayedGames/v0001";
+pub const PLAYER_ACHIEVEMENTS_PATH: &str = "ISteamUserStats/GetPlayerAchievements/v0001";
+pub const GLOBAL_ACHIEVEMENTS_PATH: &str = "ISteamUserStats/GetGlobalAchievementPercentagesForApp/v0002";
+pub const RESOLVE_VANITY_URL_PATH: &str = "ISteamUser/ResolveVanityURL/v0001";
+pub const GAME_SCHEMA_PATH: &str = "ISteamUserStats/GetSchemaForGame/v2";
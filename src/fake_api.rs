@@ -0,0 +1,200 @@
+use crate::steam_api::{Achievement, ApiClient, ApiError, Game, GameAchievements, GlobalAchievement, SchemaAchievement};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+// An in-memory source of Steam data for unit tests.
+//
+// <purpose-start>
+// Lets plugin tests describe the data they need directly, without running a mock HTTP server
+// and spelling out request URLs and JSON bodies. Games without configured achievements behave
+// like games without stats, no games are recently played unless added, and `failing` makes every request fail. Achievement requests
+// can be slowed down and count how many of them ran at the same time, and games list requests are counted.
+// <purpose-end>
+#[derive(Default)]
+pub struct FakeApi {
+    games: Vec<Game>,
+    recent_games: Vec<Game>,
+    achievements: HashMap<u32, GameAchievements>,
+    global_achievements: HashMap<u32, Vec<GlobalAchievement>>,
+    schemas: HashMap<u32, Vec<SchemaAchievement>>,
+    failing: bool,
+    delay: Duration,
+    in_flight: AtomicUsize,
+    max_in_flight: AtomicUsize,
+    games_list_calls: Arc<AtomicUsize>,
+}
+
+impl FakeApi {
+    // Creates a fake without any data.
+    pub fn new() -> FakeApi {
+        FakeApi::default()
+    }
+
+    // Creates a fake whose requests all fail with a server error.
+    pub fn failing() -> FakeApi {
+        FakeApi {
+            failing: true,
+            ..FakeApi::default()
+        }
+    }
+
+    // Adds a game to the owned games list.
+    pub fn with_game(mut self, appid: u32, name: &str) -> FakeApi {
+        self.games.push(game(appid, name));
+        self
+    }
+
+    // Adds a game last played at the given Unix timestamp to the owned games list.
+    pub fn with_played_game(mut self, appid: u32, name: &str, rtime_last_played: u64) -> FakeApi {
+        self.games.push(Game {
+            rtime_last_played,
+            ..game(appid, name)
+        });
+        self
+    }
+
+    // Adds a game played for the given number of minutes to the owned games list.
+    pub fn with_game_playtime(mut self, appid: u32, name: &str, playtime_forever: u32) -> FakeApi {
+        self.games.push(Game {
+            playtime_forever,
+            ..game(appid, name)
+        });
+        self
+    }
+
+    // Adds a game to the recently played games.
+    pub fn with_recent_game(mut self, appid: u32, name: &str) -> FakeApi {
+        self.recent_games.push(game(appid, name));
+        self
+    }
+
+    // Adds a game played for the given number of minutes to the recently played games.
+    pub fn with_recent_game_playtime(mut self, appid: u32, name: &str, playtime_forever: u32) -> FakeApi {
+        self.recent_games.push(Game {
+            playtime_forever,
+            ..game(appid, name)
+        });
+        self
+    }
+
+    // Sets the game name and achievements returned for a game.
+    pub fn with_achievements(mut self, appid: u32, game_name: &str, achievements: Vec<Achievement>) -> FakeApi {
+        self.achievements.insert(
+            appid,
+            GameAchievements {
+                game_name: game_name.to_string(),
+                achievements,
+            },
+        );
+        self
+    }
+
+    // Sets the global achievement percentages returned for a game.
+    pub fn with_global_achievements(mut self, appid: u32, global_achievements: Vec<GlobalAchievement>) -> FakeApi {
+        self.global_achievements.insert(appid, global_achievements);
+        self
+    }
+
+    // Sets the achievement definitions returned for a game.
+    pub fn with_schema(mut self, appid: u32, schema: Vec<SchemaAchievement>) -> FakeApi {
+        self.schemas.insert(appid, schema);
+        self
+    }
+
+    // Makes achievement requests take the given time.
+    pub fn with_delay(mut self, delay: Duration) -> FakeApi {
+        self.delay = delay;
+        self
+    }
+
+    // Returns the highest number of achievement requests that were in flight at the same time.
+    pub fn max_in_flight(&self) -> usize {
+        self.max_in_flight.load(Ordering::SeqCst)
+    }
+
+    // Returns the number of games list requests, which stays readable once the fake is moved into a context.
+    pub fn games_list_calls(&self) -> Arc<AtomicUsize> {
+        Arc::clone(&self.games_list_calls)
+    }
+
+    fn check_failing(&self) -> Result<(), ApiError> {
+        if self.failing {
+            return Err(ApiError::Status(reqwest::StatusCode::INTERNAL_SERVER_ERROR));
+        }
+
+        Ok(())
+    }
+}
+
+fn game(appid: u32, name: &str) -> Game {
+    Game {
+        appid,
+        name: name.to_string(),
+        playtime_forever: 0,
+        img_icon_url: "".to_string(),
+        playtime_windows_forever: 0,
+        playtime_mac_forever: 0,
+        playtime_linux_forever: 0,
+        rtime_last_played: 0,
+        playtime_disconnected: 0,
+    }
+}
+
+#[async_trait]
+impl ApiClient for FakeApi {
+    fn has_api_key(&self) -> bool {
+        true
+    }
+
+    fn steam_id(&self) -> &str {
+        "test_id"
+    }
+
+    async fn get_games_list(&self) -> Result<Vec<Game>, ApiError> {
+        self.games_list_calls.fetch_add(1, Ordering::SeqCst);
+        self.check_failing()?;
+        Ok(self.games.clone())
+    }
+
+    async fn get_recently_played_games(&self, count: u32) -> Result<Vec<Game>, ApiError> {
+        self.check_failing()?;
+        Ok(self.recent_games.iter().take(count as usize).cloned().collect())
+    }
+
+    async fn get_games_count(&self) -> Result<u32, ApiError> {
+        self.check_failing()?;
+        Ok(self.games.len() as u32)
+    }
+
+    async fn get_game_achievements(&self, appid: u32) -> Result<GameAchievements, ApiError> {
+        let in_flight = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+        self.max_in_flight.fetch_max(in_flight, Ordering::SeqCst);
+        tokio::time::sleep(self.delay).await;
+        self.in_flight.fetch_sub(1, Ordering::SeqCst);
+
+        self.check_failing()?;
+        self.achievements
+            .get(&appid)
+            .cloned()
+            .ok_or(ApiError::Status(reqwest::StatusCode::BAD_REQUEST))
+    }
+
+    async fn get_global_achievements(&self, appid: u32) -> Result<Vec<GlobalAchievement>, ApiError> {
+        self.check_failing()?;
+        self.global_achievements
+            .get(&appid)
+            .cloned()
+            .ok_or(ApiError::Status(reqwest::StatusCode::BAD_REQUEST))
+    }
+
+    async fn get_game_schema(&self, appid: u32) -> Result<Vec<SchemaAchievement>, ApiError> {
+        self.check_failing()?;
+        self.schemas
+            .get(&appid)
+            .cloned()
+            .ok_or(ApiError::Status(reqwest::StatusCode::BAD_REQUEST))
+    }
+}
@@ -4,21 +4,31 @@ pub mod constants;
 pub mod steam_api;
 pub mod ui;
 pub mod plugins;
+pub mod state;
+pub mod snapshot;
+pub mod mock_api;
+pub mod tui;
+#[cfg(test)]
+pub mod fake_api;
 
 use cfg::Cfg;
-use clap::Command;
-use std::io::{stdout, stderr};
+use clap::builder::styling::{AnsiColor, Effects, Styles};
+use clap::{Arg, Command};
+use std::io::{self, stdout, stderr, IsTerminal, Write};
+use std::path::Path;
 use std::process;
 
 // Loads the application configuration.
 //
 // <purpose-start>
 // This function is responsible for loading the application configuration from environment variables.
+// A `.env` file in the current directory provides values for the variables that are not set.
 // If the configuration cannot be loaded, it prints an error message and exits the process.
 // <purpose-end>
 //
 // <inputs-start>
-// - None.
+// - `offline`: Whether the `--offline` flag was given, in which case no credentials are required.
+// - `steam_id`: The value of the `--steam-id` flag, if given, overriding `TROGUE_STEAM_ID`.
 // <inputs-end>
 //
 // <outputs-start>
@@ -26,10 +36,17 @@ use std::process;
 // <outputs-end>
 //
 // <side-effects-start>
+// - **Reads a file**: Reads the `.env` file in the current directory, if present.
 // - **Exits the process**: If the configuration cannot be loaded, the process is terminated with a non-zero exit code.
 // <side-effects-end>
-fn load_cfg() -> Cfg {
+fn load_cfg(offline: bool, steam_id: Option<&String>) -> Cfg {
     let mut cfg = Cfg::new();
+    cfg.set_dotenv(Cfg::read_dotenv(Path::new(constants::DOTENV_PATH)));
+    cfg.set_offline(offline);
+
+    if let Some(steam_id) = steam_id {
+        cfg.set_steam_id(steam_id.clone());
+    }
 
     if let Err(e) = cfg.load() {
         eprintln!("Error: {}", e);
@@ -39,6 +56,263 @@ fn load_cfg() -> Cfg {
     cfg
 }
 
+// Runs a command under `--dry-run`.
+//
+// <purpose-start>
+// The Steam API client only records the requests, see `Api::with_dry_run`, so the output of the
+// command is discarded and the requests are printed instead, with the API key redacted.
+// <purpose-end>
+//
+// <inputs-start>
+// - `plugin`: The plugin of the command.
+// - `app_context`: The shared application context, whose client records the requests.
+// - `matches`: The clap argument matches for the command.
+// - `err_writer`: A mutable reference to a writer for standard error.
+// <inputs-end>
+//
+// <side-effects-start>
+// - Writes one `GET <url>` line per planned request to `err_writer`.
+// <side-effects-end>
+async fn execute_dry_run(
+    plugin: &dyn plugins::Plugin,
+    app_context: &app::AppContext,
+    matches: &clap::ArgMatches,
+    err_writer: &mut (dyn Write + Send),
+) {
+    plugin.execute(app_context, matches, &mut io::sink(), &mut io::sink()).await;
+    write_planned_requests(app_context.api.as_ref(), err_writer);
+}
+
+// Writes the requests planned by a Steam data source under `--dry-run`, one per line.
+fn write_planned_requests(api: &dyn steam_api::ApiClient, err_writer: &mut (dyn Write + Send)) {
+    for url in api.planned_requests() {
+        writeln!(err_writer, "GET {}", url).unwrap();
+    }
+}
+
+// Reports a command-line parsing error and exits.
+//
+// <purpose-start>
+// This function prints clap's error, followed by a hint pointing users to the way out when one
+// applies, such as finding the id of a game, see `plugins::parse_error_hint`.
+// <purpose-end>
+//
+// <inputs-start>
+// - `error`: The error returned by clap, which also covers `--help` and `--version`.
+// <inputs-end>
+//
+// <outputs-start>
+// - None, the function never returns.
+// <outputs-end>
+//
+// <side-effects-start>
+// - **Prints to the console**: Prints the error to standard error, or the help to standard output.
+// - **Exits the process**: Terminates the process with clap's exit code.
+// <side-effects-end>
+fn exit_with_hint(error: clap::Error) -> ! {
+    let _ = error.print();
+
+    if let Some(hint) = plugins::parse_error_hint(&error) {
+        eprintln!("\n{}", hint);
+    }
+
+    process::exit(error.exit_code());
+}
+
+// Initializes the logger.
+//
+// <purpose-start>
+// Logging is off by default. `RUST_LOG` sets the levels as usual, while `--verbose` given once or twice enables the
+// debug and trace levels of this application only, leaving the logs of the dependencies out.
+// <purpose-end>
+//
+// <inputs-start>
+// - `verbosity`: The number of times `--verbose` was given.
+// <inputs-end>
+//
+// <side-effects-start>
+// - Installs the global logger, which writes to standard error.
+// <side-effects-end>
+fn init_logging(verbosity: u8) {
+    let mut builder = env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("off"));
+
+    match verbosity {
+        0 => {}
+        1 => {
+            builder.filter_module(env!("CARGO_CRATE_NAME"), log::LevelFilter::Debug);
+        }
+        _ => {
+            builder.filter_module(env!("CARGO_CRATE_NAME"), log::LevelFilter::Trace);
+        }
+    }
+
+    builder.init();
+}
+
+// Builds the command-line parser of the application.
+//
+// <purpose-start>
+// The global options are defined here, the commands are contributed by the plugins.
+// <purpose-end>
+//
+// <inputs-start>
+// - `plugins`: The registered plugins.
+// <inputs-end>
+//
+// <outputs-start>
+// - `Command`: The root `clap` command.
+// <outputs-end>
+//
+// <side-effects-start>
+// - None.
+// <side-effects-end>
+fn build_command(plugins: &[Box<dyn plugins::Plugin>]) -> Command {
+    let mut command = Command::new("trogue")
+        .version(env!("CARGO_PKG_VERSION"))
+        .author("Hieropold <hieropold@gmail.com>")
+        .about("A CLI tool for displaying Steam achievements")
+        .styles(
+            Styles::styled()
+                .header(AnsiColor::Yellow.on_default() | Effects::BOLD)
+                .usage(AnsiColor::Yellow.on_default() | Effects::BOLD)
+                .literal(AnsiColor::Green.on_default() | Effects::BOLD)
+                .error(AnsiColor::Red.on_default() | Effects::BOLD)
+                .invalid(AnsiColor::Red.on_default())
+                .valid(AnsiColor::Green.on_default()),
+        )
+        .arg(
+            Arg::new("steam_id")
+                .long("steam-id")
+                .value_name("id")
+                .global(true)
+                .help("SteamID64, profile URL (https://steamcommunity.com/id/<name> or /profiles/<id>) or vanity name of the user (overrides TROGUE_STEAM_ID)"),
+        )
+        .arg(
+            Arg::new("refresh_id")
+                .long("refresh-id")
+                .action(clap::ArgAction::SetTrue)
+                .global(true)
+                .help("Resolves a vanity name Steam ID again instead of reusing the SteamID64 stored by an earlier run (kept for 30 days)"),
+        )
+        .arg(
+            Arg::new("lang")
+                .long("lang")
+                .value_name("lang")
+                .global(true)
+                .help("Language code for achievement names and descriptions (overrides TROGUE_LANG, default: en)"),
+        )
+        .arg(
+            Arg::new("timeout")
+                .long("timeout")
+                .value_name("secs")
+                .value_parser(clap::value_parser!(u64).range(1..))
+                .global(true)
+                .help("Timeout of each Steam API request in seconds (overrides TROGUE_TIMEOUT, default: 30)"),
+        )
+        .arg(
+            Arg::new("pool_idle_timeout")
+                .long("pool-idle-timeout")
+                .value_name("secs")
+                .value_parser(clap::value_parser!(u64))
+                .global(true)
+                .help("Time in seconds an idle connection is kept open for later Steam API requests, 0 closes it (overrides TROGUE_POOL_IDLE_TIMEOUT, default: 90)"),
+        )
+        .arg(
+            Arg::new("base_url")
+                .long("base-url")
+                .value_name("url")
+                .value_parser(cfg::parse_base_url)
+                .global(true)
+                .help("Base URL requests are sent to instead of the Steam API, e.g. a proxy (overrides TROGUE_BASE_URL, default: http://api.steampowered.com)"),
+        )
+        .arg(
+            Arg::new("proxy")
+                .long("proxy")
+                .value_name("url")
+                .value_parser(cfg::parse_base_url)
+                .global(true)
+                .help("Proxy all Steam API requests are sent through (overrides HTTP_PROXY/HTTPS_PROXY)"),
+        )
+        .arg(
+            Arg::new("retries")
+                .long("retries")
+                .value_name("n")
+                .value_parser(clap::value_parser!(u32))
+                .global(true)
+                .help("Number of retries of failed Steam API requests (overrides TROGUE_RETRIES, default: 0)"),
+        )
+        .arg(
+            Arg::new("concurrency")
+                .long("concurrency")
+                .value_name("n")
+                .value_parser(clap::value_parser!(u32).range(1..))
+                .global(true)
+                .help("Maximum number of concurrent Steam API requests when fetching data for many games (default: 8)"),
+        )
+        .arg(
+            Arg::new("offline")
+                .long("offline")
+                .action(clap::ArgAction::SetTrue)
+                .global(true)
+                .help("Uses bundled sample data instead of the Steam API, no credentials needed (also enabled by TROGUE_OFFLINE)"),
+        )
+        .arg(
+            Arg::new("quiet")
+                .short('q')
+                .long("quiet")
+                .action(clap::ArgAction::SetTrue)
+                .global(true)
+                .help("Suppresses notices, warnings and progress messages, errors are still reported"),
+        )
+        .arg(
+            Arg::new("no_thousands")
+                .long("no-thousands")
+                .action(clap::ArgAction::SetTrue)
+                .global(true)
+                .help("Prints large counts and playtimes without thousands separators, e.g. 123456 instead of 123,456"),
+        )
+        .arg(
+            Arg::new("porcelain")
+                .long("porcelain")
+                .action(clap::ArgAction::SetTrue)
+                .global(true)
+                .help("Prints list, achievements and progress as stable tab-separated records for scripts, one per line (implies --quiet, see the README for the format)"),
+        )
+        .arg(
+            Arg::new("dry_run")
+                .long("dry-run")
+                .action(clap::ArgAction::SetTrue)
+                .global(true)
+                .help("Prints the Steam API requests the command would send to stderr, with the API key redacted, instead of sending them and printing the output"),
+        )
+        .arg(
+            Arg::new("verbose")
+                .long("verbose")
+                .action(clap::ArgAction::Count)
+                .help("Logs the Steam API requests to standard error and reports the error of each game skipped by dashboard, export or stats, given twice for more detail (given before the command, RUST_LOG also works)"),
+        );
+
+    for plugin in plugins {
+        command = command.subcommand(plugin.command());
+    }
+
+    command
+}
+
+// Writes the help shown when no command is given and no default command is set.
+//
+// <inputs-start>
+// - `command`: The root `clap` command.
+// - `writer`: The writer to print the help to, standard error outside of tests.
+// <inputs-end>
+//
+// <side-effects-start>
+// - Writes the full help to `writer`.
+// <side-effects-end>
+fn write_missing_command_help(command: &mut Command, writer: &mut dyn Write) {
+    write!(writer, "{}", command.render_help()).unwrap();
+}
+
 // The main entry point of the application.
 //
 // <purpose-start>
@@ -56,34 +330,195 @@ fn load_cfg() -> Cfg {
 //
 // <side-effects-start>
 // - **Prints to the console**: The output of the commands is printed to the standard output.
-// - **Exits the process**: The process is terminated when the command has finished executing.
+// - **Exits the process**: The process is terminated when the command has finished executing,
+//   with the exit code requested by the command, if any.
 // <side-effects-end>
 #[tokio::main]
 async fn main() {
-    let cfg = load_cfg();
-    let app_context = app::AppContext::new(cfg);
     let plugins = plugins::get_plugins();
 
-    let mut command = Command::new("trogue")
-        .version(env!("CARGO_PKG_VERSION"))
-        .author("Hieropold <hieropold@gmail.com>")
-        .about("A CLI tool for displaying Steam achievements");
+    let mut command = build_command(&plugins);
+    let mut matches = command.clone().try_get_matches().unwrap_or_else(|e| exit_with_hint(e));
+    if matches.subcommand().is_none() {
+        match plugins::resolve_default_command(&plugins, Cfg::read_env(plugins::DEFAULT_COMMAND_ENV).ok().as_deref()) {
+            // The root command has no positional arguments, so the command can follow the global options
+            Ok(Some(name)) => {
+                let args = std::env::args_os().chain(std::iter::once(name.into()));
+                matches = command.try_get_matches_from(args).unwrap_or_else(|e| exit_with_hint(e));
+            }
+            // A usage error, reported before loading the configuration so that first-time users
+            // without credentials get the help rather than a missing variable error
+            Ok(None) => {
+                write_missing_command_help(&mut command, &mut stderr());
+                process::exit(2);
+            }
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                process::exit(2);
+            }
+        }
+    }
+    init_logging(matches.get_count("verbose"));
 
-    for plugin in &plugins {
-        command = command.subcommand(plugin.command());
+    let mut cfg = load_cfg(matches.get_flag("offline"), matches.get_one::<String>("steam_id"));
+
+    if let Some(language) = matches.get_one::<String>("lang") {
+        cfg.set_language(language.clone());
     }
 
-    let matches = command.get_matches();
+    if let Some(timeout) = matches.get_one::<u64>("timeout") {
+        cfg.set_timeout_secs(*timeout);
+    }
+
+    if let Some(pool_idle_timeout) = matches.get_one::<u64>("pool_idle_timeout") {
+        cfg.set_pool_idle_timeout_secs(*pool_idle_timeout);
+    }
+
+    if let Some(retries) = matches.get_one::<u32>("retries") {
+        cfg.set_retries(*retries);
+    }
+
+    if let Some(base_url) = matches.get_one::<String>("base_url") {
+        cfg.set_base_url(base_url.clone());
+    }
+
+    if let Some(proxy) = matches.get_one::<String>("proxy") {
+        cfg.set_proxy(proxy.clone());
+    }
+
+    let dry_run = matches.get_flag("dry_run");
+    cfg.set_dry_run(dry_run);
+    cfg.set_refresh_id(matches.get_flag("refresh_id"));
+
+    let porcelain = matches.get_flag("porcelain");
+    // Porcelain output is for scripts, so it never prompts and keeps stderr to errors, as does a dry run
+    let mut app_context = app::AppContext::new(cfg)
+        .with_quiet(matches.get_flag("quiet") || porcelain)
+        .with_porcelain(porcelain)
+        .with_group_thousands(!matches.get_flag("no_thousands"))
+        .with_verbose(matches.get_count("verbose") > 0)
+        .with_interactive(stdout().is_terminal() && !porcelain && !dry_run);
+
+    if let Some(concurrency) = matches.get_one::<u32>("concurrency") {
+        app_context = app_context.with_concurrency(*concurrency as usize);
+    }
 
     for plugin in &plugins {
         if let Some(sub_matches) = matches.subcommand_matches(plugin.command().get_name()) {
+            if dry_run {
+                execute_dry_run(plugin.as_ref(), &app_context, sub_matches, &mut stderr()).await;
+                return;
+            }
+
             plugin.execute(
                 &app_context,
                 sub_matches,
                 &mut stdout(),
                 &mut stderr(),
             ).await;
+
+            let exit_code = app_context.exit_code();
+            if exit_code != 0 {
+                process::exit(exit_code);
+            }
             return;
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_command_help() {
+        let plugins = plugins::get_plugins();
+        let mut command = build_command(&plugins);
+        let matches = command.clone().try_get_matches_from(["trogue", "--offline"]).unwrap();
+        assert!(matches.subcommand().is_none());
+
+        let mut writer = Vec::new();
+        write_missing_command_help(&mut command, &mut writer);
+
+        let help = String::from_utf8(writer).unwrap();
+        assert!(help.contains("Usage: trogue [OPTIONS] [COMMAND]"));
+        assert!(help.contains("Commands:"));
+        assert!(help.contains("dashboard"));
+        assert!(!help.contains("__complete-games"));
+    }
+
+    #[test]
+    fn test_short_verbose_belongs_to_the_commands() {
+        let plugins = plugins::get_plugins();
+        let matches = build_command(&plugins).try_get_matches_from(["trogue", "achievements", "440", "-v"]).unwrap();
+        assert_eq!(matches.get_count("verbose"), 0);
+        assert!(matches.subcommand_matches("achievements").unwrap().get_flag("verbose"));
+
+        let matches = build_command(&plugins).try_get_matches_from(["trogue", "--verbose", "--verbose", "list"]).unwrap();
+        assert_eq!(matches.get_count("verbose"), 2);
+        assert!(build_command(&plugins).try_get_matches_from(["trogue", "-v", "list"]).is_err());
+    }
+
+    // Runs a command line under `--dry-run` against a client recording its requests.
+    async fn dry_run_output(args: &[&str]) -> String {
+        let plugins = plugins::get_plugins();
+        let matches = build_command(&plugins).try_get_matches_from(args).unwrap();
+        let (name, sub_matches) = matches.subcommand().unwrap();
+        let plugin = plugins.iter().find(|plugin| plugin.command().get_name() == name).unwrap();
+        let api = steam_api::Api::new("test_key".to_string(), "76561197960287930".to_string(), "http://127.0.0.1:1".to_string())
+            .with_dry_run(true);
+        let app_context = app::AppContext::with_api(api);
+        let mut err_writer = Vec::new();
+
+        execute_dry_run(plugin.as_ref(), &app_context, sub_matches, &mut err_writer).await;
+
+        String::from_utf8(err_writer).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_list() {
+        assert_eq!(
+            dry_run_output(&["trogue", "--dry-run", "list"]).await,
+            "GET http://127.0.0.1:1/IPlayerService/GetOwnedGames/v0001/?key=***&steamid=76561197960287930&format=json&include_appinfo=1\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_progress() {
+        assert_eq!(
+            dry_run_output(&["trogue", "--dry-run", "progress", "440", "570"]).await,
+            concat!(
+                "GET http://127.0.0.1:1/ISteamUserStats/GetPlayerAchievements/v0001/?appid=440&key=***&steamid=76561197960287930&l=en\n",
+                "GET http://127.0.0.1:1/ISteamUserStats/GetPlayerAchievements/v0001/?appid=570&key=***&steamid=76561197960287930&l=en\n",
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_achievements_global_only() {
+        assert_eq!(
+            dry_run_output(&["trogue", "--dry-run", "achievements", "440", "--global-only"]).await,
+            "GET http://127.0.0.1:1/ISteamUserStats/GetGlobalAchievementPercentagesForApp/v0002/?gameid=440&format=json&l=en\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_dashboard() {
+        assert_eq!(
+            dry_run_output(&["trogue", "--dry-run", "dashboard", "--limit", "5"]).await,
+            concat!(
+                "GET http://127.0.0.1:1/IPlayerService/GetRecentlyPlayedGames/v0001/?key=***&steamid=76561197960287930&count=5&format=json\n",
+                "GET http://127.0.0.1:1/IPlayerService/GetOwnedGames/v0001/?key=***&steamid=76561197960287930&format=json&include_appinfo=1\n",
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_never_prints_the_api_key() {
+        for command in ["list", "today", "export", "verify"] {
+            let output = dry_run_output(&["trogue", "--dry-run", command]).await;
+            assert!(output.starts_with("GET http://127.0.0.1:1/"), "{}: {}", command, output);
+            assert!(!output.contains("test_key"));
+        }
+    }
+}
@@ -0,0 +1,208 @@
+use crate::steam_api::{Achievement, ApiClient, ApiError, Game, GameAchievements, GlobalAchievement, SchemaAchievement};
+use async_trait::async_trait;
+
+// The Steam ID reported by the sample data.
+const MOCK_STEAM_ID: &str = "76561197960287930";
+
+// The hidden sample achievements and their descriptions, which only the schema reveals while locked.
+const HIDDEN_ACHIEVEMENTS: [(&str, &str); 1] = [("PACIFIST", "Finish the campaign without fighting")];
+
+// A Steam data source serving bundled sample data.
+//
+// <purpose-start>
+// Used by the offline mode, so that trogue can be demonstrated without a network connection
+// or an API key. The data is fixed: a few games, some with achievements in various states of
+// completion, and their global unlock percentages.
+// <purpose-end>
+pub struct MockApi;
+
+impl MockApi {
+    // Creates a new `MockApi` instance.
+    //
+    // <inputs-start>
+    // - None.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `MockApi`: A new data source serving the sample data.
+    // <outputs-end>
+    pub fn new() -> MockApi {
+        MockApi
+    }
+}
+
+impl Default for MockApi {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Builds a sample game.
+fn game(appid: u32, name: &str, playtime_forever: u32, rtime_last_played: u64) -> Game {
+    Game {
+        appid,
+        name: name.to_string(),
+        playtime_forever,
+        img_icon_url: "".to_string(),
+        playtime_windows_forever: 0,
+        playtime_mac_forever: 0,
+        playtime_linux_forever: playtime_forever,
+        rtime_last_played,
+        playtime_disconnected: 0,
+    }
+}
+
+// Builds a sample achievement, locked if `unlocktime` is 0.
+fn achievement(apiname: &str, name: &str, description: &str, unlocktime: u64) -> Achievement {
+    Achievement {
+        apiname: apiname.to_string(),
+        achieved: if unlocktime > 0 { 1 } else { 0 },
+        unlocktime,
+        name: name.to_string(),
+        description: description.to_string(),
+    }
+}
+
+// Builds the sample achievements of a game.
+fn game_achievements(game_name: &str, achievements: Vec<Achievement>) -> GameAchievements {
+    GameAchievements {
+        game_name: game_name.to_string(),
+        achievements,
+    }
+}
+
+// Builds a sample global achievement percentage.
+fn global(name: &str, percent: f32) -> GlobalAchievement {
+    GlobalAchievement {
+        name: name.to_string(),
+        percent,
+    }
+}
+
+#[async_trait]
+impl ApiClient for MockApi {
+    fn has_api_key(&self) -> bool {
+        true
+    }
+
+    fn steam_id(&self) -> &str {
+        MOCK_STEAM_ID
+    }
+
+    async fn get_games_list(&self) -> Result<Vec<Game>, ApiError> {
+        Ok(vec![
+            game(100, "Starfall Tactics", 5400, 1717200000),
+            game(200, "Hollow Depths", 1260, 1714521600),
+            game(300, "Pixel Harvest", 90, 1709251200),
+        ])
+    }
+
+    async fn get_recently_played_games(&self, count: u32) -> Result<Vec<Game>, ApiError> {
+        let mut games = self.get_games_list().await?;
+        games.truncate(count as usize);
+        Ok(games)
+    }
+
+    async fn get_games_count(&self) -> Result<u32, ApiError> {
+        Ok(self.get_games_list().await?.len() as u32)
+    }
+
+    async fn get_game_achievements(&self, appid: u32) -> Result<GameAchievements, ApiError> {
+        match appid {
+            100 => Ok(game_achievements(
+                "Starfall Tactics",
+                vec![
+                    achievement("FIRST_JUMP", "First Jump", "Complete your first hyperspace jump", 1704067200),
+                    achievement("FLEET_ADMIRAL", "Fleet Admiral", "Command a fleet of ten ships", 1709251200),
+                    achievement("NO_CASUALTIES", "Flawless", "Win a battle without losing a ship", 1717200000),
+                    achievement("CONQUEROR", "Conqueror", "Control every star system", 0),
+                    achievement("PACIFIST", "Pacifist", "", 0),
+                ],
+            )),
+            200 => Ok(game_achievements(
+                "Hollow Depths",
+                vec![
+                    achievement("DESCENT", "Descent", "Reach the first cavern", 1711929600),
+                    achievement("LANTERN", "Lightbringer", "Find the ancient lantern", 1712534400),
+                    achievement("DEEP_END", "The Deep End", "Reach the bottom of the abyss", 1713139200),
+                    achievement("SPEEDRUN", "In a Hurry", "Finish the game in under two hours", 1714521600),
+                ],
+            )),
+            300 => Ok(game_achievements("Pixel Harvest", Vec::new())),
+            _ => Err(ApiError::Status(reqwest::StatusCode::BAD_REQUEST)),
+        }
+    }
+
+    async fn get_global_achievements(&self, appid: u32) -> Result<Vec<GlobalAchievement>, ApiError> {
+        match appid {
+            100 => Ok(vec![
+                global("FIRST_JUMP", 92.4),
+                global("FLEET_ADMIRAL", 41.7),
+                global("NO_CASUALTIES", 8.3),
+                global("CONQUEROR", 3.1),
+                global("PACIFIST", 0.9),
+            ]),
+            200 => Ok(vec![
+                global("DESCENT", 88.0),
+                global("LANTERN", 63.5),
+                global("DEEP_END", 22.2),
+                global("SPEEDRUN", 4.6),
+            ]),
+            300 => Ok(Vec::new()),
+            _ => Err(ApiError::Status(reqwest::StatusCode::BAD_REQUEST)),
+        }
+    }
+
+    async fn get_game_schema(&self, appid: u32) -> Result<Vec<SchemaAchievement>, ApiError> {
+        let achievements = self.get_game_achievements(appid).await?.achievements;
+        Ok(achievements
+            .into_iter()
+            .map(|achievement| {
+                let hidden = HIDDEN_ACHIEVEMENTS.iter().find(|(apiname, _)| *apiname == achievement.apiname);
+                SchemaAchievement {
+                    hidden: hidden.is_some() as u8,
+                    description: hidden.map_or(achievement.description, |(_, description)| description.to_string()),
+                    name: achievement.apiname,
+                    display_name: achievement.name,
+                }
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_sample_achievements_exist_for_every_game() {
+        let api = MockApi::new();
+
+        for game in api.get_games_list().await.unwrap() {
+            let game_achievements = api.get_game_achievements(game.appid).await.unwrap();
+            let global_achievements = api.get_global_achievements(game.appid).await.unwrap();
+
+            assert_eq!(game_achievements.game_name, game.name);
+            assert_eq!(game_achievements.total(), global_achievements.len());
+            assert_eq!(api.get_game_schema(game.appid).await.unwrap().len(), game_achievements.total());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_schema_reveals_hidden_descriptions() {
+        let api = MockApi::new();
+
+        let schema = api.get_game_schema(100).await.unwrap();
+        let pacifist = schema.iter().find(|achievement| achievement.name == "PACIFIST").unwrap();
+
+        assert!(pacifist.is_hidden());
+        assert_eq!(pacifist.description, "Finish the campaign without fighting");
+        assert!(schema.iter().filter(|achievement| achievement.is_hidden()).count() == 1);
+    }
+
+    #[tokio::test]
+    async fn test_unknown_game() {
+        let api = MockApi::new();
+        assert!(api.get_game_achievements(1).await.is_err());
+    }
+}
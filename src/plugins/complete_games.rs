@@ -0,0 +1,164 @@
+//! Plugin listing the owned games for shell completion scripts.
+//!
+//! <purpose-start>
+//! This plugin provides the hidden `__complete-games` command, which the bash and zsh completion
+//! scripts call to complete game ids. It is not meant to be run by users.
+//! <purpose-end>
+//!
+//! <inputs-start>
+//! - `app_context`: The shared application context, providing access to the Steam API client.
+//! <inputs-end>
+//!
+//! <outputs-start>
+//! - One game per line, its id and name separated by a tab.
+//! <outputs-end>
+//!
+//! <side-effects-start>
+//! - Makes a network request to the Steam API to fetch the list of games.
+//! <side-effects-end>
+
+use crate::{app::AppContext, plugins::Plugin, ui};
+use async_trait::async_trait;
+use clap::Command;
+use std::io::Write;
+
+pub struct CompleteGamesPlugin;
+
+// The name of the command, also called by the completion scripts.
+pub const COMMAND_NAME: &str = "__complete-games";
+
+#[async_trait]
+impl Plugin for CompleteGamesPlugin {
+    // Defines the clap command for the `__complete-games` plugin.
+    //
+    // <purpose-start>
+    // The command is hidden from the help, as it only serves the completion scripts.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `&self`: A reference to the plugin instance.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `clap::Command`: The clap command definition for the `__complete-games` plugin.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None.
+    // <side-effects-end>
+    fn command(&self) -> Command {
+        Command::new(COMMAND_NAME)
+            .about("Lists the ids and names of the owned games for shell completion scripts")
+            .hide(true)
+    }
+
+    // Executes the `__complete-games` plugin's logic.
+    //
+    // <purpose-start>
+    // This method is called by the completion scripts when a game id is completed. Errors are
+    // only reported to `err_writer`, which the scripts discard, so that a failing request simply
+    // offers no completions.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `&self`: A reference to the plugin instance.
+    // - `app_context`: The shared application context.
+    // - `_matches`: The clap argument matches for the `__complete-games` subcommand (unused).
+    // - `writer`: A mutable reference to a writer for standard output.
+    // - `err_writer`: A mutable reference to a writer for standard error.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - None.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - Makes a network request to the Steam API to fetch the list of games.
+    // - Writes one line per game to the provided writer.
+    // <side-effects-end>
+    async fn execute(
+        &self,
+        app_context: &AppContext,
+        _matches: &clap::ArgMatches,
+        writer: &mut (dyn Write + Send),
+        err_writer: &mut (dyn Write + Send),
+    ) {
+        let games = match app_context.get_games_list_cached().await {
+            Ok(games) => games,
+            Err(e) => {
+                writeln!(err_writer, "Error while trying to get games list: {}", ui::scrub_api_key(&e.to_string())).unwrap();
+                return;
+            }
+        };
+
+        for game in games {
+            writeln!(writer, "{}\t{}", game.appid, ui::tsv_field(&game.name)).unwrap();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::steam_api::{Api, Game};
+
+    fn create_mock_game(appid: u32, name: &str) -> Game {
+        Game {
+            appid,
+            name: name.to_string(),
+            playtime_forever: 0,
+            img_icon_url: "".to_string(),
+            playtime_windows_forever: 0,
+            playtime_mac_forever: 0,
+            playtime_linux_forever: 0,
+            rtime_last_played: 0,
+            playtime_disconnected: 0,
+        }
+    }
+
+    #[test]
+    fn test_command() {
+        let cmd = CompleteGamesPlugin.command();
+        assert_eq!(cmd.get_name(), "__complete-games");
+        assert!(cmd.is_hide_set());
+    }
+
+    #[tokio::test]
+    async fn test_execute() {
+        let mut server = mockito::Server::new_async().await;
+        let games = vec![create_mock_game(440, "Team Fortress 2"), create_mock_game(620, "Portal 2")];
+        server.mock("GET", "/IPlayerService/GetOwnedGames/v0001/?key=test_key&steamid=test_id&format=json&include_appinfo=1")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(serde_json::json!({ "response": { "game_count": 2, "games": games } }).to_string())
+            .create_async().await;
+        let api = Api::new("test_key".to_string(), "test_id".to_string(), server.url());
+        let app_context = AppContext::with_api(api);
+        let matches = CompleteGamesPlugin.command().get_matches_from([COMMAND_NAME]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        CompleteGamesPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        assert_eq!(String::from_utf8(writer).unwrap(), "440\tTeam Fortress 2\n620\tPortal 2\n");
+        assert!(err_writer.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_execute_games_list_error() {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("GET", "/IPlayerService/GetOwnedGames/v0001/?key=test_key&steamid=test_id&format=json&include_appinfo=1")
+            .with_status(500)
+            .create_async().await;
+        let api = Api::new("test_key".to_string(), "test_id".to_string(), server.url());
+        let app_context = AppContext::with_api(api);
+        let matches = CompleteGamesPlugin.command().get_matches_from([COMMAND_NAME]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        CompleteGamesPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        assert!(writer.is_empty());
+        assert!(String::from_utf8(err_writer).unwrap().starts_with("Error while trying to get games list"));
+    }
+}
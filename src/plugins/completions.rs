@@ -2,7 +2,8 @@
 //!
 //! <purpose-start>
 //! This plugin provides the `completions` command, which generates shell completion scripts
-//! for bash and zsh. These scripts enable tab completion for trogue commands and subcommands.
+//! for bash, zsh, fish, PowerShell, Elvish and Nushell. These scripts enable tab completion for trogue commands and subcommands.
+//! The bash and zsh scripts also complete the ids of the owned games, through the hidden `__complete-games` command.
 //! <purpose-end>
 //!
 //! <inputs-start>
@@ -18,10 +19,11 @@
 //! - Writes the completion script to the provided writer (stdout).
 //! <side-effects-end>
 
-use crate::{app::AppContext, plugins::Plugin};
+use crate::{app::AppContext, plugins::{complete_games, Plugin}};
 use async_trait::async_trait;
 use clap::{Arg, Command, ValueEnum};
 use clap_complete::{generate, Shell};
+use clap_complete_nushell::Nushell;
 use std::io::Write;
 
 pub struct CompletionsPlugin;
@@ -42,9 +44,136 @@ enum ShellType {
     Fish,
     // PowerShell
     PowerShell,
+    // Elvish shell
+    Elvish,
+    // Nushell
+    Nushell,
 }
 
-// No need for CommandFactory - we'll build the command structure manually
+impl ShellType {
+    // Returns the matching shell of `clap_complete`.
+    //
+    // <purpose-start>
+    // Nushell is not one of the shells built into `clap_complete`, its completions are generated
+    // by the `clap_complete_nushell` crate instead.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `self`: The shell type.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `Some(Shell)`: The `clap_complete` shell.
+    // - `None`: For Nushell.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None.
+    // <side-effects-end>
+    fn shell(self) -> Option<Shell> {
+        match self {
+            ShellType::Bash => Some(Shell::Bash),
+            ShellType::Zsh => Some(Shell::Zsh),
+            ShellType::Fish => Some(Shell::Fish),
+            ShellType::PowerShell => Some(Shell::PowerShell),
+            ShellType::Elvish => Some(Shell::Elvish),
+            ShellType::Nushell => None,
+        }
+    }
+}
+
+// The commands whose first positional argument is a game id.
+const GAME_ID_COMMANDS: [&str; 4] = ["achievements", "progress", "top", "open"];
+
+// Wraps the bash completion function, so that game ids are completed after the commands taking one.
+const BASH_GAME_IDS: &str = r#"
+_trogue_with_game_ids() {
+    local cur="${COMP_WORDS[COMP_CWORD]}" prev="${COMP_WORDS[COMP_CWORD-1]}"
+    case "${prev}" in
+        @COMMANDS@)
+            if [[ ${cur} != -* ]]; then
+                COMPREPLY=( $(compgen -W "$(trogue @COMPLETE_GAMES@ 2>/dev/null | cut -f1)" -- "${cur}") )
+                return 0
+            fi
+            ;;
+    esac
+    _trogue "$@"
+}
+
+if [[ "${BASH_VERSINFO[0]}" -eq 4 && "${BASH_VERSINFO[1]}" -ge 4 || "${BASH_VERSINFO[0]}" -gt 4 ]]; then
+    complete -F _trogue_with_game_ids -o nosort -o bashdefault -o default trogue
+else
+    complete -F _trogue_with_game_ids -o bashdefault -o default trogue
+fi
+"#;
+
+// Completes game ids in zsh, showing the game names as descriptions.
+const ZSH_GAME_IDS: &str = r#"(( $+functions[_trogue_game_ids] )) ||
+_trogue_game_ids() {
+    local -a games
+    games=(${(f)"$(trogue @COMPLETE_GAMES@ 2>/dev/null)"})
+    games=(${games/$'\t'/:})
+    _describe -t games 'game ids' games
+}
+
+"#;
+
+// The start of the block dispatching to `_trogue` at the end of the zsh script.
+const ZSH_DISPATCH: &str = "if [ \"$funcstack[1]\" = \"_trogue\" ]; then";
+
+// Adds the completion of game ids to a generated bash or zsh script.
+//
+// <purpose-start>
+// `clap_complete` only knows the static structure of the commands. For bash, a wrapper around the
+// generated completion function is appended and registered in its place. For zsh, the game id
+// arguments are completed by a function defined before the script dispatches, so that it also
+// exists when the script is autoloaded. Other shells are left unchanged.
+// <purpose-end>
+//
+// <inputs-start>
+// - `shell_type`: The shell the script was generated for.
+// - `script`: The script generated by `clap_complete`.
+// <inputs-end>
+//
+// <outputs-start>
+// - `String`: The script completing game ids.
+// <outputs-end>
+//
+// <side-effects-start>
+// - None.
+// <side-effects-end>
+fn with_game_id_completion(shell_type: ShellType, script: String) -> String {
+    match shell_type {
+        ShellType::Bash => {
+            let wrapper = BASH_GAME_IDS
+                .replace("@COMMANDS@", &GAME_ID_COMMANDS.join("|"))
+                .replace("@COMPLETE_GAMES@", complete_games::COMMAND_NAME);
+            script + &wrapper
+        }
+        ShellType::Zsh => {
+            let script: String = script
+                .lines()
+                .map(|line| {
+                    // Positional specs start with one or two colons, preceded by * if repeatable
+                    let spec = line.strip_prefix('\'').unwrap_or(line);
+                    let spec = spec.strip_prefix('*').unwrap_or(spec);
+                    let is_game_id = spec.starts_with(":game") || spec.starts_with("::game");
+                    match line.strip_suffix(":_default' \\") {
+                        Some(spec) if is_game_id => format!("{}:_trogue_game_ids' \\\n", spec),
+                        _ => format!("{}\n", line),
+                    }
+                })
+                .collect();
+            let function = ZSH_GAME_IDS.replace("@COMPLETE_GAMES@", complete_games::COMMAND_NAME);
+
+            match script.find(ZSH_DISPATCH) {
+                Some(pos) => format!("{}{}{}", &script[..pos], function, &script[pos..]),
+                None => script + &function,
+            }
+        }
+        _ => script,
+    }
+}
 
 #[async_trait]
 impl Plugin for CompletionsPlugin {
@@ -69,6 +198,11 @@ impl Plugin for CompletionsPlugin {
     fn command(&self) -> Command {
         Command::new("completions")
             .about("Generate shell completion scripts")
+            .after_help(concat!(
+                "Examples:\n",
+                "  trogue completions bash >> ~/.bashrc\n",
+                "  trogue completions fish > ~/.config/fish/completions/trogue.fish",
+            ))
             .long_about(
                 "Generate shell completion scripts for trogue.\n\n\
                 To install completions:\n\n\
@@ -78,14 +212,19 @@ impl Plugin for CompletionsPlugin {
                 Zsh:\n  \
                 trogue completions zsh > ~/.zsh/completions/_trogue\n  \
                 Add 'fpath=(~/.zsh/completions $fpath)' to ~/.zshrc\n  \
-                source ~/.zshrc"
+                source ~/.zshrc\n\n\
+                Elvish:\n  \
+                trogue completions elvish >> ~/.config/elvish/rc.elv\n\n\
+                Nushell:\n  \
+                trogue completions nushell | save -f ~/.config/nushell/trogue.nu\n  \
+                Add 'source ~/.config/nushell/trogue.nu' to config.nu"
             )
             .arg(
                 Arg::new("shell")
                     .value_name("SHELL")
                     .required(true)
                     .value_parser(clap::value_parser!(ShellType))
-                    .help("The shell to generate completions for (bash, zsh, fish, powershell)"),
+                    .help("The shell to generate completions for (bash, zsh, fish, powershell, elvish, nushell)"),
             )
     }
 
@@ -110,7 +249,7 @@ impl Plugin for CompletionsPlugin {
     // <outputs-end>
     //
     // <side-effects-start>
-    // - Writes the completion script to the provided writer.
+    // - Writes the completion script to the provided writer, completing game ids in bash and zsh.
     // - The script must be redirected to a file and sourced by the shell to enable completions.
     // <side-effects-end>
     async fn execute(
@@ -120,7 +259,7 @@ impl Plugin for CompletionsPlugin {
         writer: &mut (dyn Write + Send),
         _err_writer: &mut (dyn Write + Send),
     ) {
-        let shell_type = matches.get_one::<ShellType>("shell").unwrap();
+        let shell_type = *matches.get_one::<ShellType>("shell").unwrap();
 
         // Build the complete command structure with all subcommands
         let mut cmd = Command::new("trogue")
@@ -128,20 +267,23 @@ impl Plugin for CompletionsPlugin {
             .author("Hieropold <hieropold@gmail.com>")
             .about("A CLI tool for displaying Steam achievements");
 
-        // Add all plugin commands
+        // Add all plugin commands, except the hidden ones only meant for the scripts themselves
         for plugin in crate::plugins::get_plugins() {
-            cmd = cmd.subcommand(plugin.command());
+            let command = plugin.command();
+            if !command.is_hide_set() {
+                cmd = cmd.subcommand(command);
+            }
         }
 
         // Generate the completion script using clap_complete
-        let shell = match shell_type {
-            ShellType::Bash => Shell::Bash,
-            ShellType::Zsh => Shell::Zsh,
-            ShellType::Fish => Shell::Fish,
-            ShellType::PowerShell => Shell::PowerShell,
-        };
-
-        generate(shell, &mut cmd, "trogue", writer);
+        let mut script = Vec::new();
+        match shell_type.shell() {
+            Some(shell) => generate(shell, &mut cmd, "trogue", &mut script),
+            None => generate(Nushell, &mut cmd, "trogue", &mut script),
+        }
+
+        let script = with_game_id_completion(shell_type, String::from_utf8(script).unwrap());
+        writer.write_all(script.as_bytes()).unwrap();
     }
 }
 
@@ -172,7 +314,7 @@ mod tests {
             "test_id".to_string(),
             "http://localhost".to_string(),
         );
-        let app_context = AppContext { api };
+        let app_context = AppContext::with_api(api);
         let matches = get_matches_for_args(&["completions", "bash"]);
         let mut writer = Vec::new();
         let mut err_writer = Vec::new();
@@ -193,7 +335,7 @@ mod tests {
             "test_id".to_string(),
             "http://localhost".to_string(),
         );
-        let app_context = AppContext { api };
+        let app_context = AppContext::with_api(api);
         let matches = get_matches_for_args(&["completions", "zsh"]);
         let mut writer = Vec::new();
         let mut err_writer = Vec::new();
@@ -207,38 +349,92 @@ mod tests {
         assert!(output.contains("#compdef") || output.contains("_trogue"));
     }
 
+    #[tokio::test]
+    async fn test_execute_elvish() {
+        let api = Api::new(
+            "test_key".to_string(),
+            "test_id".to_string(),
+            "http://localhost".to_string(),
+        );
+        let app_context = AppContext::with_api(api);
+        let matches = get_matches_for_args(&["completions", "elvish"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        CompletionsPlugin
+            .execute(&app_context, &matches, &mut writer, &mut err_writer)
+            .await;
+
+        let output = String::from_utf8(writer).unwrap();
+        // Verify that the output contains elvish completion script markers
+        assert!(output.contains("set edit:completion:arg-completer[trogue]"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_nushell() {
+        let api = Api::new(
+            "test_key".to_string(),
+            "test_id".to_string(),
+            "http://localhost".to_string(),
+        );
+        let app_context = AppContext::with_api(api);
+        let matches = get_matches_for_args(&["completions", "nushell"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        CompletionsPlugin
+            .execute(&app_context, &matches, &mut writer, &mut err_writer)
+            .await;
+
+        let output = String::from_utf8(writer).unwrap();
+        // Verify that the output contains nushell completion script markers
+        assert!(output.contains("export extern trogue"));
+    }
+
+    async fn generate_script(shell: &str) -> String {
+        let app_context = AppContext::with_api(crate::fake_api::FakeApi::new());
+        let matches = get_matches_for_args(&["completions", shell]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        CompletionsPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        String::from_utf8(writer).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_bash_completes_game_ids() {
+        let output = generate_script("bash").await;
+
+        assert!(output.contains("        achievements|progress|top|open)\n"));
+        assert!(output.contains("$(trogue __complete-games 2>/dev/null | cut -f1)"));
+        assert!(output.trim_end().ends_with("complete -F _trogue_with_game_ids -o bashdefault -o default trogue\nfi"));
+        assert!(!output.contains("__complete-games)"));
+    }
+
+    #[tokio::test]
+    async fn test_zsh_completes_game_ids() {
+        let output = generate_script("zsh").await;
+
+        assert_eq!(output.matches(":_trogue_game_ids' \\\n").count(), GAME_ID_COMMANDS.len());
+        assert!(output.contains("':game_id -- The ID of the game to open the page for:_trogue_game_ids' \\\n"));
+        assert!(output.find("_trogue_game_ids() {").unwrap() < output.find(ZSH_DISPATCH).unwrap());
+        assert!(output.contains("trogue __complete-games 2>/dev/null"));
+    }
+
+    #[tokio::test]
+    async fn test_fish_is_unchanged() {
+        let output = generate_script("fish").await;
+        assert!(!output.contains("__complete-games"));
+    }
+
     #[test]
     fn test_shell_type_conversion() {
-        let bash: Shell = match ShellType::Bash {
-            ShellType::Bash => Shell::Bash,
-            ShellType::Zsh => Shell::Zsh,
-            ShellType::Fish => Shell::Fish,
-            ShellType::PowerShell => Shell::PowerShell,
-        };
-        assert!(matches!(bash, Shell::Bash));
-
-        let zsh: Shell = match ShellType::Zsh {
-            ShellType::Bash => Shell::Bash,
-            ShellType::Zsh => Shell::Zsh,
-            ShellType::Fish => Shell::Fish,
-            ShellType::PowerShell => Shell::PowerShell,
-        };
-        assert!(matches!(zsh, Shell::Zsh));
-
-        let fish: Shell = match ShellType::Fish {
-            ShellType::Bash => Shell::Bash,
-            ShellType::Zsh => Shell::Zsh,
-            ShellType::Fish => Shell::Fish,
-            ShellType::PowerShell => Shell::PowerShell,
-        };
-        assert!(matches!(fish, Shell::Fish));
-
-        let powershell: Shell = match ShellType::PowerShell {
-            ShellType::Bash => Shell::Bash,
-            ShellType::Zsh => Shell::Zsh,
-            ShellType::Fish => Shell::Fish,
-            ShellType::PowerShell => Shell::PowerShell,
-        };
-        assert!(matches!(powershell, Shell::PowerShell));
+        assert_eq!(ShellType::Bash.shell(), Some(Shell::Bash));
+        assert_eq!(ShellType::Zsh.shell(), Some(Shell::Zsh));
+        assert_eq!(ShellType::Fish.shell(), Some(Shell::Fish));
+        assert_eq!(ShellType::PowerShell.shell(), Some(Shell::PowerShell));
+        assert_eq!(ShellType::Elvish.shell(), Some(Shell::Elvish));
+        assert_eq!(ShellType::Nushell.shell(), None);
     }
-}
\ No newline at end of file
+}
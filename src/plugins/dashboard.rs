@@ -7,7 +7,7 @@
 //!
 //! <inputs-start>
 //! - `app_context`: The shared application context, providing access to the Steam API client.
-//! - `_matches`: The command-line arguments parsed by `clap` (unused in this plugin).
+//! - `matches`: The command-line arguments parsed by `clap`.
 //! <inputs-end>
 //!
 //! <outputs-start>
@@ -18,13 +18,281 @@
 //! - Makes multiple network requests to the Steam API to fetch game lists and achievement data.
 //! <side-effects-end>
 
-use crate::{app::AppContext, plugins::Plugin};
+use crate::{app::AppContext, plugins::{self, Plugin}, steam_api::{Game, GameAchievements, ProgressSummary}, ui};
 use async_trait::async_trait;
-use clap::Command;
+use chrono::Utc;
+use clap::{Arg, Command, ValueEnum};
+use futures::StreamExt;
+use serde::Serialize;
+use std::collections::HashSet;
 use std::io::Write;
+use std::pin::pin;
 
 pub struct DashboardPlugin;
 
+// Represents the supported dashboard output formats.
+//
+// <purpose-start>
+// The text format is meant for humans, while the JSON format allows the dashboard data to be
+// consumed by other tools, such as status bars.
+// <purpose-end>
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+enum DashboardFormat {
+    // Human-readable title box and progress bars
+    Text,
+    // A JSON array with one object per game
+    Json,
+}
+
+// Represents the values of the `--sort` option.
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+enum DashboardSort {
+    // Most recently played first
+    Recent,
+    // Most completed first, games without achievements last
+    Completion,
+    // Alphabetically, ignoring case
+    Name,
+    // Most played first
+    Playtime,
+}
+
+// Holds the dashboard settings parsed from the command line.
+//
+// <purpose-start>
+// Keeps the rendering independent from clap, so that it can be exercised directly in tests.
+// <purpose-end>
+struct DashboardOptions {
+    format: DashboardFormat,
+    bar_style: ui::BarStyle,
+    fail_fast: bool,
+    width: Option<usize>,
+    box_width: Option<usize>,
+    bar_ratio: f32,
+    limit: usize,
+    days: Option<u64>,
+    filter: Option<plugins::NameFilter>,
+    above: Option<f32>,
+    below: Option<f32>,
+    tree: bool,
+    no_bar: bool,
+    compact: bool,
+    // The games always displayed first, without duplicates
+    pins: Vec<u32>,
+    sort: DashboardSort,
+}
+
+// The title shown in the box at the top of the text dashboard.
+const TITLE: &str = "Recently Played Games Dashboard";
+
+// The narrowest progress bar, in cells, that still conveys progress.
+const MIN_BAR_WIDTH: usize = 10;
+
+// The share of the width used by progress bars when `--bar-ratio` is not provided.
+const DEFAULT_BAR_RATIO: f32 = 0.5;
+
+// The number of recently played games shown when neither `--limit` nor `--days` is provided.
+const DEFAULT_LIMIT: usize = 10;
+
+// The number of seconds in a day, used to turn `--days` into a cutoff time.
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+// The width of the widest percentage, "100.0%", so that `--no-bar` percentages line up.
+const PERCENTAGE_WIDTH: usize = 6;
+
+// Parses the `--bar-ratio` value.
+//
+// <inputs-start>
+// - `value`: The raw argument value.
+// <inputs-end>
+//
+// <outputs-start>
+// - `Ok(f32)`: The ratio, greater than 0 and at most 1.
+// - `Err(String)`: A user-facing message if the value is not such a number.
+// <outputs-end>
+fn parse_bar_ratio(value: &str) -> Result<f32, String> {
+    match value.parse::<f32>() {
+        Ok(ratio) if ratio > 0.0 && ratio <= 1.0 => Ok(ratio),
+        _ => Err(format!("'{}' is not a number greater than 0 and at most 1", value)),
+    }
+}
+
+impl DashboardOptions {
+    // Builds the dashboard options from the parsed arguments.
+    //
+    // <inputs-start>
+    // - `matches`: The clap argument matches for the `dashboard` subcommand.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `Ok(DashboardOptions)`: The dashboard settings.
+    // - `Err(String)`: A user-facing message if a setting is invalid.
+    // <outputs-end>
+    fn from_matches(matches: &clap::ArgMatches) -> Result<DashboardOptions, String> {
+        let days = matches.get_one::<u64>("days").copied();
+        let filter = matches
+            .get_one::<String>("filter")
+            .map(|f| plugins::NameFilter::new(f, matches.get_flag("regex")))
+            .transpose()?;
+        let above = matches.get_one::<f32>("above").copied();
+        let below = matches.get_one::<f32>("below").copied();
+        if let (Some(above), Some(below)) = (above, below) {
+            if above > below {
+                return Err(format!("--above {} must not be greater than --below {}", above, below));
+            }
+        }
+
+        let mut pins = matches.get_one::<String>("pin").map(|pin| plugins::parse_ids(pin, "--pin")).transpose()?.unwrap_or_default();
+        let mut seen = HashSet::new();
+        pins.retain(|appid| seen.insert(*appid));
+
+        Ok(DashboardOptions {
+            format: *matches.get_one::<DashboardFormat>("format").unwrap(),
+            bar_style: plugins::bar_style(matches)?,
+            fail_fast: matches.get_flag("fail_fast"),
+            width: matches.get_one::<usize>("width").copied(),
+            box_width: matches.get_one::<usize>("box_width").copied(),
+            bar_ratio: matches.get_one::<f32>("bar_ratio").copied().unwrap_or(DEFAULT_BAR_RATIO),
+            // `--days` alone shows every game played within the window
+            limit: matches.get_one::<usize>("limit").copied().unwrap_or(if days.is_some() { usize::MAX } else { DEFAULT_LIMIT }),
+            days,
+            filter,
+            above,
+            below,
+            tree: matches.get_flag("tree"),
+            no_bar: matches.get_flag("no_bar"),
+            compact: matches.get_flag("compact"),
+            pins,
+            sort: *matches.get_one::<DashboardSort>("sort").unwrap(),
+        })
+    }
+
+    // Tells whether a game passes the `--above` and `--below` completion filters.
+    //
+    // <purpose-start>
+    // `--above` is inclusive and `--below` exclusive, so that `--above 80 --below 100` keeps the
+    // games close to completion but not completed. Games without achievements have no completion
+    // to compare and are left out whenever one of the filters is given.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `game_achievements`: The fetched achievements of the game.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `bool`: Whether the game is displayed.
+    // <outputs-end>
+    fn in_completion_range(&self, game_achievements: &GameAchievements) -> bool {
+        if self.above.is_none() && self.below.is_none() {
+            return true;
+        }
+        if game_achievements.achievements.is_empty() {
+            return false;
+        }
+
+        let percentage = game_achievements.percentage();
+        self.above.is_none_or(|above| percentage >= above) && self.below.is_none_or(|below| percentage < below)
+    }
+
+    // Computes the widths of the title box and of the progress bars.
+    //
+    // <purpose-start>
+    // The box and the bars are sized independently: the box defaults to half of the available
+    // width unless `--box-width` is given, while the bars take the `--bar-ratio` share of it.
+    // Both are clamped so that the title fits and the bars stay readable.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `terminal_width`: The width of the terminal, used unless `--width` is given.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `(usize, usize)`: The box width and the bar width, in cells.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None.
+    // <side-effects-end>
+    fn layout(&self, terminal_width: usize) -> (usize, usize) {
+        let width = self.width.unwrap_or(terminal_width);
+
+        let box_width = self.box_width.unwrap_or(width / 2).max(TITLE.len());
+        let bar_width = ((width as f32 * self.bar_ratio) as usize).max(MIN_BAR_WIDTH);
+
+        (box_width, bar_width)
+    }
+}
+
+// Represents a game in the `--no-bar` output.
+//
+// <purpose-start>
+// The lines are only written once every game is known, so that the names can be padded to the
+// longest one and the percentages line up.
+// <purpose-end>
+struct SummaryRow {
+    name: String,
+    // None for games without achievements
+    progress: Option<ProgressSummary>,
+}
+
+// Accumulates the `--compact` summary of the displayed games.
+#[derive(Default)]
+struct CompactSummary {
+    games: usize,
+    // The games with achievements, the only ones with a completion to average
+    with_achievements: usize,
+    percentage_sum: f32,
+    perfect: usize,
+}
+
+impl CompactSummary {
+    // Counts a displayed game.
+    fn add(&mut self, game_achievements: &GameAchievements) {
+        self.games += 1;
+        if game_achievements.achievements.is_empty() {
+            return;
+        }
+
+        self.with_achievements += 1;
+        self.percentage_sum += game_achievements.percentage();
+        if game_achievements.completed() == game_achievements.total() {
+            self.perfect += 1;
+        }
+    }
+
+    // Formats the summary line, such as `3 games | avg 72% | 1 perfect`.
+    //
+    // <purpose-start>
+    // The average only covers the games with achievements, and reads `n/a` when there are none.
+    // When some games have no achievements, their number is given as well, e.g.
+    // `4 games, 3 with achievements | avg 61% | 1 perfect`, so that the count and the average
+    // do not silently refer to different games.
+    // <purpose-end>
+    fn line(&self) -> String {
+        let mut games = format!("{} {}", self.games, if self.games == 1 { "game" } else { "games" });
+        if self.with_achievements < self.games {
+            games.push_str(&format!(", {} with achievements", self.with_achievements));
+        }
+        let average = if self.with_achievements == 0 {
+            "n/a".to_string()
+        } else {
+            format!("{:.0}%", self.percentage_sum / self.with_achievements as f32)
+        };
+        format!("{} | avg {} | {} perfect", games, average, self.perfect)
+    }
+}
+
+// Represents the achievement progress of a single game in the JSON output.
+#[derive(Serialize, Debug)]
+struct DashboardEntry {
+    appid: u32,
+    name: String,
+    total: usize,
+    completed: usize,
+    #[serde(serialize_with = "ui::serialize_percentage")]
+    percentage: f32,
+}
+
 #[async_trait]
 impl Plugin for DashboardPlugin {
     // Defines the clap command for the `dashboard` plugin.
@@ -47,7 +315,123 @@ impl Plugin for DashboardPlugin {
     // <side-effects-end>
     fn command(&self) -> Command {
         Command::new("dashboard")
-            .about("Displays a dashboard with 10 last played games and their achievement progress")
+            .about("Displays a dashboard with the last played games and their achievement progress")
+            .after_help(concat!(
+                "Examples:\n",
+                "  trogue dashboard --limit 5 --tree\n",
+                "  trogue dashboard --days 7 --format json\n",
+                "  trogue dashboard --filter half-life --bar-ratio 0.75\n",
+                "  trogue dashboard --pin 440,620\n",
+                "  trogue dashboard --sort completion",
+            ))
+            .arg(
+                Arg::new("format")
+                    .long("format")
+                    .value_name("format")
+                    .value_parser(clap::value_parser!(DashboardFormat))
+                    .default_value("text")
+                    .help("The output format (text, json)"),
+            )
+            .args(plugins::bar_style_args())
+            .arg(
+                Arg::new("fail_fast")
+                    .long("fail-fast")
+                    .action(clap::ArgAction::SetTrue)
+                    .help("Stops at the first game whose achievements cannot be fetched, instead of skipping it"),
+            )
+            .arg(
+                Arg::new("width")
+                    .long("width")
+                    .value_name("cells")
+                    .value_parser(clap::value_parser!(usize))
+                    .help("The width to lay the dashboard out for (default: the terminal width)"),
+            )
+            .arg(
+                Arg::new("box_width")
+                    .long("box-width")
+                    .value_name("cells")
+                    .value_parser(clap::value_parser!(usize))
+                    .help("The width of the title box (default: half of the width)"),
+            )
+            .arg(
+                Arg::new("bar_ratio")
+                    .long("bar-ratio")
+                    .value_name("ratio")
+                    .value_parser(parse_bar_ratio)
+                    .help("The share of the width used by progress bars, between 0 and 1 (default: 0.5)"),
+            )
+            .arg(
+                Arg::new("limit")
+                    .short('n')
+                    .long("limit")
+                    .value_name("count")
+                    .value_parser(clap::value_parser!(usize))
+                    .help("The number of recently played games to display (default: 10, or all games within --days)"),
+            )
+            .arg(
+                Arg::new("days")
+                    .long("days")
+                    .value_name("n")
+                    .value_parser(clap::value_parser!(u64).range(1..))
+                    .help("Displays every game played within the last n days instead of the last 10, --limit still caps the number of games"),
+            )
+            .arg(
+                Arg::new("filter")
+                    .short('f')
+                    .long("filter")
+                    .value_name("filter")
+                    .help("Displays only the games whose name contains the filter, before the recently played games are limited"),
+            )
+            .arg(plugins::regex_arg())
+            .arg(
+                Arg::new("above")
+                    .long("above")
+                    .value_name("percent")
+                    .value_parser(ui::parse_threshold)
+                    .help("Displays only the games completed to at least the given percentage, leaving out games without achievements"),
+            )
+            .arg(
+                Arg::new("below")
+                    .long("below")
+                    .value_name("percent")
+                    .value_parser(ui::parse_threshold)
+                    .help("Displays only the games completed to less than the given percentage, leaving out games without achievements"),
+            )
+            .arg(
+                Arg::new("tree")
+                    .long("tree")
+                    .action(clap::ArgAction::SetTrue)
+                    .help("Lists the unlocked (✓) and locked (✗) achievements below each game's progress bar, text format only"),
+            )
+            .arg(
+                Arg::new("no_bar")
+                    .long("no-bar")
+                    .action(clap::ArgAction::SetTrue)
+                    .conflicts_with("tree")
+                    .help("Prints one line per game with its percentage instead of a progress bar, text format only"),
+            )
+            .arg(
+                Arg::new("compact")
+                    .long("compact")
+                    .action(clap::ArgAction::SetTrue)
+                    .conflicts_with_all(["tree", "no_bar"])
+                    .help("Prints a single summary line, such as `3 games | avg 72% | 1 perfect`, for status bars, text format only"),
+            )
+            .arg(
+                Arg::new("pin")
+                    .long("pin")
+                    .alias("include-appids")
+                    .value_name("id,id,...")
+                    .help("Always displays the games with the given comma-separated ids first, however long ago they were played, followed by the recently played games"),
+            )
+            .arg(
+                Arg::new("sort")
+                    .long("sort")
+                    .value_name("key")
+                    .value_parser(clap::value_parser!(DashboardSort))
+                    .default_value("recent")
+                    .help("The order of the displayed games, after --limit picks the most recently played ones (recent, completion, name, playtime). Pinned games stay first"),
+            )
     }
 
     // Executes the `dashboard` plugin's logic.
@@ -60,7 +444,7 @@ impl Plugin for DashboardPlugin {
     // <inputs-start>
     // - `&self`: A reference to the plugin instance.
     // - `app_context`: The shared application context.
-    // - `_matches`: The clap argument matches for the `dashboard` subcommand (unused).
+    // - `matches`: The clap argument matches for the `dashboard` subcommand.
     // - `writer`: A mutable reference to a writer for standard output.
     // - `err_writer`: A mutable reference to a writer for standard error.
     // <inputs-end>
@@ -76,68 +460,365 @@ impl Plugin for DashboardPlugin {
     async fn execute(
         &self,
         app_context: &AppContext,
-        _matches: &clap::ArgMatches,
+        matches: &clap::ArgMatches,
         writer: &mut (dyn Write + Send),
         err_writer: &mut (dyn Write + Send),
     ) {
-        let mut games = Vec::new();
-        match app_context.api.get_games_list().await {
-            Ok(resp) => games = resp,
-            Err(e) => writeln!(err_writer, "Error while trying to get Steam data: {}", e).unwrap(),
+        let mut options = match DashboardOptions::from_matches(matches) {
+            Ok(options) => options,
+            Err(e) => {
+                writeln!(err_writer, "{}", e).unwrap();
+                return;
+            }
+        };
+        options.bar_style.colored = ui::colors_enabled(false, app_context.interactive());
+        let mut reporter = ui::progress_reporter(app_context.quiet());
+        render_dashboard(app_context, &options, writer, err_writer, reporter.as_mut()).await;
+    }
+}
+
+// Fetches the most recently played games.
+//
+// <purpose-start>
+// The dedicated endpoint only knows about the last two weeks, so when it returns nothing, or fails,
+// the whole library is fetched and sorted by last played time instead. With `--days` the whole
+// library is always used, as the dedicated endpoint does not report when each game was played.
+// With `--filter` it is used as well, so that the limit applies to the matching games only.
+// <purpose-end>
+//
+// <inputs-start>
+// - `app_context`: The shared application context.
+// - `options`: The dashboard settings, providing the limit, the `--days` window and the name filter.
+// - `err_writer`: A mutable reference to a writer for standard error.
+// <inputs-end>
+//
+// <outputs-start>
+// - `Vec<Game>`: The most recently played games first, empty if they cannot be fetched.
+// <outputs-end>
+//
+// <side-effects-start>
+// - Makes one or two network requests to the Steam API.
+// - Writes an error to `err_writer` if the games list cannot be fetched.
+// <side-effects-end>
+async fn recent_games(app_context: &AppContext, options: &DashboardOptions, err_writer: &mut (dyn Write + Send)) -> Vec<Game> {
+    let limit = options.limit;
+
+    if options.days.is_none() && options.filter.is_none() {
+        let count = u32::try_from(limit).unwrap_or(u32::MAX);
+        if let Ok(games) = app_context.api.get_recently_played_games(count).await {
+            if !games.is_empty() {
+                return games.into_iter().take(limit).collect();
+            }
+        }
+    }
+
+    let mut games = Vec::new();
+    match app_context.get_games_list_cached().await {
+        Ok(resp) => games = resp,
+        Err(e) => writeln!(err_writer, "Error while trying to get Steam data: {}", ui::scrub_api_key(&e.to_string())).unwrap(),
+    }
+
+    if let Some(days) = options.days {
+        let now = u64::try_from(Utc::now().timestamp()).unwrap_or(0);
+        games = played_within(games, days, now);
+    }
+
+    if let Some(filter) = &options.filter {
+        games.retain(|g| filter.matches(&g.name));
+    }
+
+    // Sort games by last played time (most recent first)
+    games.sort_by_key(|g| std::cmp::Reverse(g.rtime_last_played));
+    games.truncate(limit);
+    games
+}
+
+// Looks up the games pinned with `--pin`.
+//
+// <purpose-start>
+// Pinned games are taken from the owned games list, in the order given, regardless of when they
+// were played and of `--filter`, `--days` and `--limit`. Ids that are not owned are reported and
+// left out.
+// <purpose-end>
+//
+// <inputs-start>
+// - `app_context`: The shared application context.
+// - `pins`: The pinned game ids, without duplicates.
+// - `err_writer`: A mutable reference to a writer for standard error.
+// <inputs-end>
+//
+// <outputs-start>
+// - `Vec<Game>`: The pinned games that are owned, in the order given.
+// <outputs-end>
+//
+// <side-effects-start>
+// - Makes a network request to the Steam API to fetch the games list, unless no game is pinned.
+// - Writes errors to `err_writer`.
+// <side-effects-end>
+async fn pinned_games(app_context: &AppContext, pins: &[u32], err_writer: &mut (dyn Write + Send)) -> Vec<Game> {
+    if pins.is_empty() {
+        return Vec::new();
+    }
+
+    let games = match app_context.get_games_list_cached().await {
+        Ok(games) => games,
+        Err(e) => {
+            writeln!(err_writer, "Error while trying to get Steam data: {}", ui::scrub_api_key(&e.to_string())).unwrap();
+            return Vec::new();
         }
+    };
 
-        // Sort games by last played time (most recent first)
-        games.sort_by(|a, b| b.rtime_last_played.cmp(&a.rtime_last_played));
+    let mut pinned = Vec::new();
+    for appid in pins {
+        match games.iter().find(|g| g.appid == *appid) {
+            Some(game) => pinned.push(game.clone()),
+            None => writeln!(err_writer, "Pinned game not found: {}", appid).unwrap(),
+        }
+    }
+    pinned
+}
+
+// Keeps the games played within the last `days` days.
+//
+// <purpose-start>
+// Games that were never played have a last played time of 0 and are always dropped.
+// <purpose-end>
+//
+// <inputs-start>
+// - `games`: The games to filter.
+// - `days`: The size of the window, in days.
+// - `now`: The current Unix timestamp.
+// <inputs-end>
+//
+// <outputs-start>
+// - `Vec<Game>`: The games last played at or after the start of the window.
+// <outputs-end>
+//
+// <side-effects-start>
+// - None.
+// <side-effects-end>
+fn played_within(games: Vec<Game>, days: u64, now: u64) -> Vec<Game> {
+    let cutoff = now.saturating_sub(days.saturating_mul(SECONDS_PER_DAY));
+    games
+        .into_iter()
+        .filter(|g| g.rtime_last_played != 0 && g.rtime_last_played >= cutoff)
+        .collect()
+}
+
+// Renders the dashboard of recently played games.
+//
+// <purpose-start>
+// Fetching achievements for every recent game can take a noticeable amount of time, so the
+// rendering is separated from `execute` to allow the progress reporter to be injected. Every game
+// is fetched before the first one is rendered, so that `--sort completion` can order them.
+// <purpose-end>
+//
+// <inputs-start>
+// - `app_context`: The shared application context.
+// - `options`: The dashboard settings.
+// - `writer`: A mutable reference to a writer for standard output.
+// - `err_writer`: A mutable reference to a writer for standard error.
+// - `reporter`: The reporter notified before each game's achievements are fetched.
+// <inputs-end>
+//
+// <outputs-start>
+// - None.
+// <outputs-end>
+//
+// <side-effects-start>
+// - Makes multiple network requests to the Steam API to fetch game and achievement data.
+// - Writes the dashboard to `writer` and progress and errors to `err_writer`.
+// <side-effects-end>
+async fn render_dashboard(
+    app_context: &AppContext,
+    options: &DashboardOptions,
+    writer: &mut (dyn Write + Send),
+    err_writer: &mut (dyn Write + Send),
+    reporter: &mut (dyn ui::ProgressReporter + Send),
+) {
+    let mut games = pinned_games(app_context, &options.pins, err_writer).await;
+    let recent = recent_games(app_context, options, err_writer).await;
+    games.extend(recent.into_iter().filter(|g| !options.pins.contains(&g.appid)));
 
-        // Take only the 10 most recently played games
-        let recent_games: Vec<_> = games.iter().take(10).collect();
+    let terminal_width = crossterm::terminal::size().unwrap_or((80, 24)).0 as usize;
+    let (box_width, bar_width) = options.layout(terminal_width);
 
-        // Output title
-        let terminal_width = crossterm::terminal::size().unwrap_or((80, 24)).0 as usize;
-        let box_width = terminal_width / 2;
-        let title = "Recently Played Games Dashboard";
-        let padding = (box_width - title.len()) / 2;
+    // Output title
+    if options.format == DashboardFormat::Text && !options.compact {
+        let padding = (box_width - TITLE.len()) / 2;
 
         writeln!(writer, "{}", "=".repeat(box_width)).unwrap();
-        writeln!(writer, "{}{}{}", " ".repeat(padding), title, " ".repeat(padding)).unwrap();
+        writeln!(writer, "{}{}{}", " ".repeat(padding), TITLE, " ".repeat(padding)).unwrap();
         writeln!(writer, "{}", "=".repeat(box_width)).unwrap();
+    }
+
+    let mut skipped = plugins::SkippedGames::default();
+    let mut displayed = Vec::new();
+    let mut stopped = false;
+    let total_games = games.len();
+    let mut results = pin!(app_context.game_achievements(games.iter().map(|g| g.appid).collect()));
 
-        for game in recent_games {
-            let mut achievements = Vec::new();
-            let mut game_name = String::new();
+    for (index, game) in games.into_iter().enumerate() {
+        let mut game_achievements = GameAchievements::default();
 
-            match app_context.api.get_game_achievements(game.appid).await {
-                Ok((name, achs)) => {
-                    game_name = name;
-                    achievements = achs;
+        reporter.report(index + 1, total_games, err_writer);
+
+        let result = results.next().await.unwrap();
+        reporter.clear(err_writer);
+
+        match result {
+            Ok(resp) => game_achievements = resp,
+            Err(e) => {
+                let error = format!("Error while trying to get achievements for {}: {}", game.name, ui::scrub_api_key(&e.to_string()));
+                skipped.skip(app_context, game.appid, &error, err_writer);
+
+                if options.fail_fast {
+                    writeln!(err_writer, "Stopping at {} due to --fail-fast", game.name).unwrap();
+                    skipped.report(err_writer);
+                    stopped = true;
+                    break;
                 }
-                Err(e) => writeln!(err_writer, "Error while trying to get achievements: {}", e).unwrap(),
             }
+        }
 
-            writeln!(writer, "{}", game_name).unwrap();
+        if !options.in_completion_range(&game_achievements) {
+            continue;
+        }
 
-            if achievements.is_empty() {
-                writeln!(writer, "No achievements found for this game").unwrap();
-                continue;
-            }
+        // Private stats come back without a game name, the games list still has it
+        if game_achievements.game_name.is_empty() {
+            game_achievements.game_name = game.name.clone();
+        }
+
+        displayed.push((game, game_achievements));
+    }
+
+    // Pinned games stay on top in the order given, only the other games are sorted
+    let pinned = displayed.iter().take_while(|(game, _)| options.pins.contains(&game.appid)).count();
+    sort_games(&mut displayed[pinned..], options.sort);
+
+    let mut entries = Vec::new();
+    let mut rows = Vec::new();
+    let mut summary = CompactSummary::default();
+
+    for (game, game_achievements) in displayed {
+        if options.format == DashboardFormat::Json {
+            let summary = game_achievements.summary();
+            entries.push(DashboardEntry {
+                appid: game.appid,
+                name: game.name,
+                total: summary.total,
+                completed: summary.completed,
+                percentage: summary.percentage,
+            });
+            continue;
+        }
+
+        if options.compact {
+            summary.add(&game_achievements);
+            continue;
+        }
+
+        if options.no_bar {
+            let progress = (!game_achievements.achievements.is_empty()).then(|| game_achievements.summary());
+            rows.push(SummaryRow { name: game_achievements.game_name, progress });
+            continue;
+        }
+
+        writeln!(writer, "{}", game_achievements.game_name).unwrap();
 
-            let total = achievements.len();
-            let completed = achievements.iter().filter(|a| a.achieved > 0).count();
-            let percentage = (completed as f32 / total as f32) * 100.0;
+        if game_achievements.achievements.is_empty() {
+            writeln!(writer, "No achievements found for this game").unwrap();
+            continue;
+        }
 
-            let bar_width = terminal_width / 2;
+        let progress = game_achievements.summary();
+        let bar = ui::render_progress_bar(progress.percentage, bar_width, &options.bar_style);
 
-            let filled_chars = ((percentage / 100.0) * bar_width as f32).round() as usize;
-            let empty_chars = bar_width - filled_chars;
+        writeln!(writer, "{} {}", bar, progress).unwrap();
 
-            write!(writer, "[").unwrap();
-            for _ in 0..filled_chars {
-                write!(writer, "█").unwrap();
+        if options.tree {
+            for achievement in &game_achievements.achievements {
+                let mark = if achievement.is_unlocked() { '✓' } else { '✗' };
+                writeln!(writer, "  {} {}", mark, achievement.name).unwrap();
             }
-            for _ in 0..empty_chars {
-                write!(writer, " ").unwrap();
+        }
+    }
+
+    // A stopped dashboard only keeps the games rendered line by line
+    if stopped {
+        write_summary_rows(&rows, writer);
+        return;
+    }
+
+    if options.format == DashboardFormat::Json {
+        writeln!(writer, "{}", serde_json::to_string(&entries).unwrap()).unwrap();
+    }
+
+    if options.format == DashboardFormat::Text && options.compact {
+        writeln!(writer, "{}", summary.line()).unwrap();
+    }
+
+    write_summary_rows(&rows, writer);
+    skipped.report(err_writer);
+}
+
+// Orders the displayed games by the `--sort` key.
+//
+// <purpose-start>
+// The sort is stable, so games with equal keys keep their recency order. Completion and playtime
+// put the highest first, and games without achievements last when sorting by completion.
+// <purpose-end>
+//
+// <inputs-start>
+// - `games`: The games and their fetched achievements, most recently played first.
+// - `sort`: The sort key.
+// <inputs-end>
+//
+// <side-effects-start>
+// - Reorders `games` in place.
+// <side-effects-end>
+fn sort_games(games: &mut [(Game, GameAchievements)], sort: DashboardSort) {
+    match sort {
+        DashboardSort::Recent => {}
+        DashboardSort::Completion => games.sort_by(|(_, a), (_, b)| {
+            let completion = |g: &GameAchievements| (!g.achievements.is_empty()).then(|| g.percentage());
+            completion(b).partial_cmp(&completion(a)).unwrap_or(std::cmp::Ordering::Equal)
+        }),
+        DashboardSort::Name => games.sort_by_key(|(_, g)| g.game_name.to_lowercase()),
+        DashboardSort::Playtime => games.sort_by_key(|(game, _)| std::cmp::Reverse(game.playtime_forever)),
+    }
+}
+
+// Writes the `--no-bar` lines, such as `Game — 66.7% (2/3)`.
+//
+// <purpose-start>
+// The names are padded to the longest one and the percentages right-aligned, so that the
+// percentages form a column.
+// <purpose-end>
+//
+// <inputs-start>
+// - `rows`: The games to write, in display order.
+// - `writer`: A mutable reference to a writer for standard output.
+// <inputs-end>
+//
+// <outputs-start>
+// - None.
+// <outputs-end>
+//
+// <side-effects-start>
+// - Writes one line per game to `writer`.
+// <side-effects-end>
+fn write_summary_rows(rows: &[SummaryRow], writer: &mut (dyn Write + Send)) {
+    let name_width = rows.iter().map(|r| r.name.chars().count()).max().unwrap_or(0);
+
+    for row in rows {
+        match &row.progress {
+            Some(progress) => {
+                let percentage = format!("{:.1}%", progress.percentage);
+                writeln!(writer, "{:<name_width$} — {:>PERCENTAGE_WIDTH$} ({}/{})", row.name, percentage, progress.completed, progress.total).unwrap();
             }
-            writeln!(writer, "] {:.1}% ({}/{})", percentage, completed, total).unwrap();
+            None => writeln!(writer, "{:<name_width$} — no achievements", row.name).unwrap(),
         }
     }
 }
@@ -146,7 +827,8 @@ impl Plugin for DashboardPlugin {
 mod tests {
     use super::*;
     use crate::app::AppContext;
-    use crate::steam_api::{Api, Achievement, Game};
+    use crate::fake_api::FakeApi;
+    use crate::steam_api::{Api, Achievement};
     use clap::ArgMatches;
 
     fn create_mock_game(appid: u32, name: &str, rtime_last_played: u64) -> Game {
@@ -202,7 +884,7 @@ mod tests {
         }
 
         let api = Api::new("test_key".to_string(), "test_id".to_string(), server.url());
-        let app_context = AppContext { api };
+        let app_context = AppContext::with_api(api);
         (app_context, server)
     }
 
@@ -283,6 +965,7 @@ mod tests {
         ];
 
         let (app_context, _server) = setup_test_env(&games_list_body, 200, &achievements_mocks).await;
+        let app_context = app_context.with_verbose(true);
         let matches = get_matches_for_args(&["dashboard"]);
         let mut writer = Vec::new();
         let mut err_writer = Vec::new();
@@ -290,7 +973,8 @@ mod tests {
         DashboardPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
 
         let err_output = String::from_utf8(err_writer).unwrap();
-        assert!(err_output.contains("Error while trying to get achievements"));
+        assert!(err_output.contains("Error while trying to get achievements for Game 1"));
+        assert!(err_output.ends_with("Skipped 1 game due to errors: [1]\n"));
     }
 
     #[tokio::test]
@@ -337,4 +1021,777 @@ mod tests {
         assert!(output.contains("Game 1"));
         assert!(output.contains("No achievements found for this game"));
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_render_with_noop_reporter_matches_execute_output() {
+        let games = vec![create_mock_game(1, "Game 1", 100)];
+        let games_list_body = serde_json::to_string(&serde_json::json!({
+            "response": { "game_count": 1, "games": games }
+        })).unwrap();
+
+        let achievements = vec![create_mock_achievement(1), create_mock_achievement(0)];
+        let achievements_body = serde_json::to_string(&serde_json::json!({
+            "playerstats": { "steamID": "test_id", "gameName": "Game 1", "achievements": achievements, "success": true }
+        })).unwrap();
+
+        let achievements_mocks = vec![
+            MockGameAchievements { appid: 1, body: achievements_body, status: 200 },
+        ];
+
+        let (app_context, _server) = setup_test_env(&games_list_body, 200, &achievements_mocks).await;
+        let matches = get_matches_for_args(&["dashboard"]);
+
+        let mut execute_writer = Vec::new();
+        let mut execute_err_writer = Vec::new();
+        DashboardPlugin.execute(&app_context, &matches, &mut execute_writer, &mut execute_err_writer).await;
+
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+        let options = DashboardOptions::from_matches(&matches).unwrap();
+        render_dashboard(&app_context, &options, &mut writer, &mut err_writer, &mut ui::NoopProgressReporter).await;
+
+        assert_eq!(String::from_utf8(writer).unwrap(), String::from_utf8(execute_writer).unwrap());
+        assert!(err_writer.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_execute_json_format() {
+        let games = vec![
+            create_mock_game(1, "Game 1", 100),
+            create_mock_game(2, "Game 2", 300),
+            create_mock_game(3, "Game 3", 200),
+        ];
+        let games_list_body = serde_json::to_string(&serde_json::json!({
+            "response": { "game_count": 3, "games": games }
+        })).unwrap();
+
+        let achievements1 = vec![create_mock_achievement(1), create_mock_achievement(1)];
+        let achievements_body1 = serde_json::to_string(&serde_json::json!({
+            "playerstats": { "steamID": "test_id", "gameName": "Game 1", "achievements": achievements1, "success": true }
+        })).unwrap();
+
+        let achievements2 = vec![create_mock_achievement(1), create_mock_achievement(0)];
+        let achievements_body2 = serde_json::to_string(&serde_json::json!({
+            "playerstats": { "steamID": "test_id", "gameName": "Game 2", "achievements": achievements2, "success": true }
+        })).unwrap();
+
+        let achievements_body3 = serde_json::to_string(&serde_json::json!({
+            "playerstats": { "steamID": "test_id", "gameName": "Game 3", "achievements": [], "success": true }
+        })).unwrap();
+
+        let achievements_mocks = vec![
+            MockGameAchievements { appid: 1, body: achievements_body1, status: 200 },
+            MockGameAchievements { appid: 2, body: achievements_body2, status: 200 },
+            MockGameAchievements { appid: 3, body: achievements_body3, status: 200 },
+        ];
+
+        let (app_context, _server) = setup_test_env(&games_list_body, 200, &achievements_mocks).await;
+        let matches = get_matches_for_args(&["dashboard", "--format", "json"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        DashboardPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        assert!(!output.contains("Recently Played Games Dashboard"));
+
+        let entries: serde_json::Value = serde_json::from_str(&output).unwrap();
+        let entries = entries.as_array().unwrap();
+        assert_eq!(entries.len(), 3);
+
+        // Ordered by last played, most recent first
+        assert_eq!(entries[0]["appid"], 2);
+        assert_eq!(entries[0]["name"], "Game 2");
+        assert_eq!(entries[0]["total"], 2);
+        assert_eq!(entries[0]["completed"], 1);
+        assert_eq!(entries[0]["percentage"], 50.0);
+
+        assert_eq!(entries[1]["appid"], 3);
+        assert_eq!(entries[1]["total"], 0);
+        assert_eq!(entries[1]["completed"], 0);
+        assert_eq!(entries[1]["percentage"], 0.0);
+
+        assert_eq!(entries[2]["appid"], 1);
+        assert_eq!(entries[2]["completed"], 2);
+        assert_eq!(entries[2]["percentage"], 100.0);
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_custom_bar_glyphs() {
+        let games = vec![create_mock_game(1, "Game 1", 100)];
+        let games_list_body = serde_json::to_string(&serde_json::json!({
+            "response": { "game_count": 1, "games": games }
+        })).unwrap();
+
+        let achievements = vec![create_mock_achievement(1), create_mock_achievement(0)];
+        let achievements_body = serde_json::to_string(&serde_json::json!({
+            "playerstats": { "steamID": "test_id", "gameName": "Game 1", "achievements": achievements, "success": true }
+        })).unwrap();
+
+        let achievements_mocks = vec![
+            MockGameAchievements { appid: 1, body: achievements_body, status: 200 },
+        ];
+
+        let (app_context, _server) = setup_test_env(&games_list_body, 200, &achievements_mocks).await;
+        let matches = get_matches_for_args(&["dashboard", "--bar-fill", "#", "--bar-empty", "-"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        DashboardPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        assert!(output.contains("[#"));
+        assert!(output.contains("-] 50.0% (1/2)"));
+        assert!(!output.contains('█'));
+    }
+
+    #[test]
+    fn test_layout_defaults() {
+        let matches = get_matches_for_args(&["dashboard"]);
+        let options = DashboardOptions::from_matches(&matches).unwrap();
+
+        assert_eq!(options.layout(120), (60, 60));
+    }
+
+    #[test]
+    fn test_layout_box_and_bar_widths_are_independent() {
+        let matches = get_matches_for_args(&["dashboard", "--width", "100", "--box-width", "100"]);
+        let options = DashboardOptions::from_matches(&matches).unwrap();
+        assert_eq!(options.layout(80), (100, 50));
+
+        let matches = get_matches_for_args(&["dashboard", "--width", "100", "--bar-ratio", "0.25"]);
+        let options = DashboardOptions::from_matches(&matches).unwrap();
+        assert_eq!(options.layout(80), (50, 25));
+    }
+
+    #[test]
+    fn test_layout_clamps_to_minimums() {
+        let matches = get_matches_for_args(&["dashboard", "--width", "20", "--box-width", "5"]);
+        let options = DashboardOptions::from_matches(&matches).unwrap();
+
+        assert_eq!(options.layout(80), (TITLE.len(), MIN_BAR_WIDTH));
+    }
+
+    #[test]
+    fn test_invalid_bar_ratio() {
+        let result = DashboardPlugin.command().try_get_matches_from(["dashboard", "--bar-ratio", "1.5"]);
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_widths() {
+        let games = vec![create_mock_game(1, "Game 1", 100)];
+        let games_list_body = serde_json::to_string(&serde_json::json!({
+            "response": { "game_count": 1, "games": games }
+        })).unwrap();
+
+        let achievements = vec![create_mock_achievement(1), create_mock_achievement(0)];
+        let achievements_body = serde_json::to_string(&serde_json::json!({
+            "playerstats": { "steamID": "test_id", "gameName": "Game 1", "achievements": achievements, "success": true }
+        })).unwrap();
+
+        let achievements_mocks = vec![
+            MockGameAchievements { appid: 1, body: achievements_body, status: 200 },
+        ];
+
+        let (app_context, _server) = setup_test_env(&games_list_body, 200, &achievements_mocks).await;
+        let matches = get_matches_for_args(&["dashboard", "--width", "40", "--box-width", "40", "--bar-ratio", "0.5", "--bar-fill", "#", "--bar-empty", "-"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        DashboardPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        assert!(output.starts_with(&format!("{}\n", "=".repeat(40))));
+        assert!(output.contains("[##########----------] 50.0% (1/2)"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_recovers_empty_game_name_from_games_list() {
+        let games = vec![create_mock_game(1, "Name From Library", 100)];
+        let games_list_body = serde_json::to_string(&serde_json::json!({
+            "response": { "game_count": 1, "games": games }
+        })).unwrap();
+
+        let achievements = vec![create_mock_achievement(1)];
+        let achievements_body = serde_json::to_string(&serde_json::json!({
+            "playerstats": { "steamID": "test_id", "gameName": "", "achievements": achievements, "success": true }
+        })).unwrap();
+
+        let achievements_mocks = vec![
+            MockGameAchievements { appid: 1, body: achievements_body, status: 200 },
+        ];
+
+        let (app_context, _server) = setup_test_env(&games_list_body, 200, &achievements_mocks).await;
+        let matches = get_matches_for_args(&["dashboard"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        DashboardPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        assert!(output.contains("Name From Library\n"));
+    }
+
+    fn fail_fast_test_mocks() -> (String, Vec<MockGameAchievements>) {
+        let games = vec![
+            create_mock_game(1, "Game 1", 300),
+            create_mock_game(2, "Game 2", 200),
+            create_mock_game(3, "Game 3", 100),
+        ];
+        let games_list_body = serde_json::to_string(&serde_json::json!({
+            "response": { "game_count": 3, "games": games }
+        })).unwrap();
+
+        let achievements = vec![create_mock_achievement(1), create_mock_achievement(0)];
+        let achievements_body1 = serde_json::to_string(&serde_json::json!({
+            "playerstats": { "steamID": "test_id", "gameName": "Game 1", "achievements": achievements, "success": true }
+        })).unwrap();
+        let achievements_body3 = serde_json::to_string(&serde_json::json!({
+            "playerstats": { "steamID": "test_id", "gameName": "Game 3", "achievements": achievements, "success": true }
+        })).unwrap();
+
+        let achievements_mocks = vec![
+            MockGameAchievements { appid: 1, body: achievements_body1, status: 200 },
+            MockGameAchievements { appid: 2, body: "".to_string(), status: 500 },
+            MockGameAchievements { appid: 3, body: achievements_body3, status: 200 },
+        ];
+
+        (games_list_body, achievements_mocks)
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_fail_fast_stops_at_first_error() {
+        let (games_list_body, achievements_mocks) = fail_fast_test_mocks();
+        let (app_context, _server) = setup_test_env(&games_list_body, 200, &achievements_mocks).await;
+        let app_context = app_context.with_verbose(true);
+        let matches = get_matches_for_args(&["dashboard", "--fail-fast"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        DashboardPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        let err_output = String::from_utf8(err_writer).unwrap();
+        assert!(output.contains("Game 1"));
+        assert!(!output.contains("Game 3"));
+        assert!(err_output.contains("Error while trying to get achievements"));
+        assert!(err_output.contains("Stopping at Game 2 due to --fail-fast"));
+        assert!(err_output.contains("Skipped 1 game due to errors: [2]"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_without_fail_fast_continues_after_error() {
+        let (games_list_body, achievements_mocks) = fail_fast_test_mocks();
+        let (app_context, _server) = setup_test_env(&games_list_body, 200, &achievements_mocks).await;
+        let app_context = app_context.with_verbose(true);
+        let matches = get_matches_for_args(&["dashboard"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        DashboardPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        let err_output = String::from_utf8(err_writer).unwrap();
+        assert!(output.contains("Game 1"));
+        assert!(output.contains("Game 3"));
+        assert!(err_output.contains("Error while trying to get achievements"));
+        assert!(!err_output.contains("--fail-fast"));
+    }
+
+    fn pin_test_api() -> FakeApi {
+        FakeApi::new()
+            .with_played_game(70, "Half-Life", 1_000_000)
+            .with_played_game(440, "Team Fortress 2", 1_700_000_000)
+            .with_recent_game(440, "Team Fortress 2")
+            .with_recent_game(620, "Portal 2")
+            .with_achievements(70, "Half-Life", vec![create_mock_achievement(1)])
+            .with_achievements(440, "Team Fortress 2", vec![create_mock_achievement(0)])
+            .with_achievements(620, "Portal 2", vec![create_mock_achievement(1)])
+    }
+
+    #[tokio::test]
+    async fn test_execute_pinned_games_come_first() {
+        let app_context = AppContext::with_api(pin_test_api());
+        // Half-Life was last played decades ago, Team Fortress 2 is also recent and shown once
+        let matches = get_matches_for_args(&["dashboard", "--no-bar", "--pin", "70,440,70"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        DashboardPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        let names: Vec<&str> = output.lines().skip(3).map(|line| line.split(" — ").next().unwrap().trim_end()).collect();
+        assert_eq!(names, vec!["Half-Life", "Team Fortress 2", "Portal 2"]);
+        assert!(err_writer.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_execute_pinned_game_not_owned() {
+        let app_context = AppContext::with_api(pin_test_api());
+        let matches = get_matches_for_args(&["dashboard", "--no-bar", "--pin", "999"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        DashboardPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        assert!(String::from_utf8(writer).unwrap().contains("Portal 2"));
+        assert_eq!(String::from_utf8(err_writer).unwrap(), "Pinned game not found: 999\n");
+    }
+
+    #[tokio::test]
+    async fn test_execute_invalid_pin() {
+        let app_context = AppContext::with_api(pin_test_api());
+        let matches = get_matches_for_args(&["dashboard", "--pin", "70,hl2"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        DashboardPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        assert!(writer.is_empty());
+        assert_eq!(
+            String::from_utf8(err_writer).unwrap(),
+            "Invalid game id 'hl2' in --pin, expected a comma-separated list of numbers\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_execute_summarizes_skipped_games() {
+        let api = FakeApi::new()
+            .with_recent_game(440, "Team Fortress 2")
+            .with_recent_game(570, "Dota 2")
+            .with_recent_game(620, "Portal 2")
+            .with_achievements(570, "Dota 2", vec![create_mock_achievement(1)]);
+        let app_context = AppContext::with_api(api);
+        let matches = get_matches_for_args(&["dashboard"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        DashboardPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        assert!(String::from_utf8(writer).unwrap().contains("Dota 2"));
+        // Without --verbose, the errors of the games are left out
+        assert_eq!(String::from_utf8(err_writer).unwrap(), "Skipped 2 games due to errors: [440, 620]\n");
+    }
+
+    fn tree_test_api() -> FakeApi {
+        let achievements = vec![
+            Achievement { name: "Won".to_string(), ..create_mock_achievement(1) },
+            Achievement { name: "Lost".to_string(), ..create_mock_achievement(0) },
+        ];
+        FakeApi::new()
+            .with_game(1, "Game 1")
+            .with_game(2, "Game 2")
+            .with_achievements(1, "Game 1", achievements.clone())
+            .with_achievements(2, "Game 2", achievements)
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_tree() {
+        let app_context = AppContext::with_api(tree_test_api());
+        let matches = get_matches_for_args(&["dashboard", "--tree"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        DashboardPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        let game_line = lines.iter().position(|l| *l == "Game 1").unwrap();
+        assert!(lines[game_line + 1].ends_with("50.0% (1/2)"));
+        assert_eq!(lines[game_line + 2], "  ✓ Won");
+        assert_eq!(lines[game_line + 3], "  ✗ Lost");
+        assert_eq!(output.matches("  ✓ Won").count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_tree_and_limit() {
+        let app_context = AppContext::with_api(tree_test_api());
+        let matches = get_matches_for_args(&["dashboard", "--tree", "--limit", "1"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        DashboardPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        assert!(output.contains("Game 1"));
+        assert!(!output.contains("Game 2"));
+        assert_eq!(output.matches("  ✓ Won").count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_execute_without_tree_omits_achievements() {
+        let app_context = AppContext::with_api(tree_test_api());
+        let matches = get_matches_for_args(&["dashboard"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        DashboardPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        assert!(!output.contains("✓"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_no_bar() {
+        let api = FakeApi::new()
+            .with_game(1, "Portal")
+            .with_game(2, "Half-Life 2")
+            .with_game(3, "Empty")
+            .with_achievements(1, "Portal", vec![create_mock_achievement(1), create_mock_achievement(1), create_mock_achievement(0)])
+            .with_achievements(2, "Half-Life 2", vec![create_mock_achievement(1)])
+            .with_achievements(3, "Empty", vec![]);
+        let app_context = AppContext::with_api(api);
+        let matches = get_matches_for_args(&["dashboard", "--no-bar"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        DashboardPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        let lines: Vec<&str> = output.lines().skip(3).collect();
+        assert!(lines.contains(&"Portal      —  66.7% (2/3)"));
+        assert!(lines.contains(&"Half-Life 2 — 100.0% (1/1)"));
+        assert!(lines.contains(&"Empty       — no achievements"));
+        assert!(!lines.iter().any(|l| l.contains('[') || l.contains('█')));
+    }
+
+    #[test]
+    fn test_no_bar_conflicts_with_tree() {
+        let result = DashboardPlugin.command().try_get_matches_from(["dashboard", "--no-bar", "--tree"]);
+        assert!(result.is_err());
+    }
+
+    fn compact_test_api() -> FakeApi {
+        FakeApi::new()
+            .with_game(1, "Portal")
+            .with_game(2, "Half-Life 2")
+            .with_game(3, "Half-Life")
+            .with_game(4, "Empty")
+            .with_achievements(1, "Portal", vec![create_mock_achievement(1), create_mock_achievement(0)])
+            .with_achievements(2, "Half-Life 2", vec![create_mock_achievement(1), create_mock_achievement(1)])
+            .with_achievements(3, "Half-Life", vec![create_mock_achievement(1), create_mock_achievement(0), create_mock_achievement(0)])
+            .with_achievements(4, "Empty", vec![])
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_compact() {
+        let app_context = AppContext::with_api(compact_test_api());
+        let matches = get_matches_for_args(&["dashboard", "--compact"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        DashboardPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        // (50 + 100 + 33.3) / 3, the game without achievements is counted but not averaged
+        assert_eq!(String::from_utf8(writer).unwrap(), "4 games, 3 with achievements | avg 61% | 1 perfect\n");
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_compact_and_filter() {
+        let app_context = AppContext::with_api(compact_test_api());
+        let matches = get_matches_for_args(&["dashboard", "--compact", "--filter", "half-life"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        DashboardPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        assert_eq!(String::from_utf8(writer).unwrap(), "2 games | avg 67% | 1 perfect\n");
+    }
+
+    #[test]
+    fn test_compact_summary_without_achievements() {
+        let mut summary = CompactSummary::default();
+        summary.add(&GameAchievements::default());
+        assert_eq!(summary.line(), "1 game, 0 with achievements | avg n/a | 0 perfect");
+    }
+
+    #[tokio::test]
+    async fn test_execute_uses_recently_played_games() {
+        let mut server = mockito::Server::new_async().await;
+        let recent = vec![create_mock_game(2, "Recent Game", 0)];
+        server.mock("GET", "/IPlayerService/GetRecentlyPlayedGames/v0001/?key=test_key&steamid=test_id&count=10&format=json")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(serde_json::json!({ "response": { "total_count": 1, "games": recent } }).to_string())
+            .create_async().await;
+        let games_list = server.mock("GET", "/IPlayerService/GetOwnedGames/v0001/?key=test_key&steamid=test_id&format=json&include_appinfo=1")
+            .expect(0)
+            .create_async().await;
+        server.mock("GET", "/ISteamUserStats/GetPlayerAchievements/v0001/?appid=2&key=test_key&steamid=test_id&l=en")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(serde_json::json!({
+                "playerstats": { "steamID": "test_id", "gameName": "Recent Game", "achievements": [create_mock_achievement(1)], "success": true }
+            }).to_string())
+            .create_async().await;
+        let api = Api::new("test_key".to_string(), "test_id".to_string(), server.url());
+        let app_context = AppContext::with_api(api);
+        let matches = get_matches_for_args(&["dashboard"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        DashboardPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        assert!(output.contains("Recent Game\n"));
+        assert!(output.contains("100.0% (1/1)"));
+        games_list.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_execute_falls_back_to_games_list_without_recent_games() {
+        let api = FakeApi::new()
+            .with_game(1, "Owned Game")
+            .with_achievements(1, "Owned Game", vec![create_mock_achievement(1)]);
+        let app_context = AppContext::with_api(api);
+        let matches = get_matches_for_args(&["dashboard"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        DashboardPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        assert!(output.contains("Owned Game\n"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_limits_recently_played_games() {
+        let api = FakeApi::new()
+            .with_recent_game(1, "First Game")
+            .with_recent_game(2, "Second Game")
+            .with_achievements(1, "First Game", vec![create_mock_achievement(1)])
+            .with_achievements(2, "Second Game", vec![create_mock_achievement(1)]);
+        let app_context = AppContext::with_api(api);
+        let matches = get_matches_for_args(&["dashboard", "--limit", "1"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        DashboardPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        assert!(output.contains("First Game\n"));
+        assert!(!output.contains("Second Game"));
+    }
+
+    #[test]
+    fn test_played_within() {
+        let now = 100 * SECONDS_PER_DAY;
+        let games = vec![
+            create_mock_game(1, "Yesterday", now - SECONDS_PER_DAY),
+            create_mock_game(2, "Last Month", now - 30 * SECONDS_PER_DAY),
+            create_mock_game(3, "Never Played", 0),
+            create_mock_game(4, "Exactly A Week Ago", now - 7 * SECONDS_PER_DAY),
+        ];
+
+        let names: Vec<String> = played_within(games, 7, now).into_iter().map(|g| g.name).collect();
+        assert_eq!(names, vec!["Yesterday", "Exactly A Week Ago"]);
+    }
+
+    fn create_days_test_api() -> FakeApi {
+        let now = u64::try_from(Utc::now().timestamp()).unwrap();
+        let mut api = FakeApi::new().with_recent_game(9, "Recent Endpoint Game");
+        let games = [
+            (1, "Played Today", now - 3600),
+            (2, "Played Three Days Ago", now - 3 * SECONDS_PER_DAY),
+            (3, "Played Last Month", now - 30 * SECONDS_PER_DAY),
+            (4, "Never Played", 0),
+        ];
+        for (appid, name, rtime_last_played) in games {
+            api = api
+                .with_played_game(appid, name, rtime_last_played)
+                .with_achievements(appid, name, vec![create_mock_achievement(1)]);
+        }
+        api
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_days() {
+        let app_context = AppContext::with_api(create_days_test_api());
+        let matches = get_matches_for_args(&["dashboard", "--days", "7"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        DashboardPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        assert!(output.contains("Played Today\n"));
+        assert!(output.contains("Played Three Days Ago\n"));
+        assert!(!output.contains("Played Last Month"));
+        assert!(!output.contains("Never Played"));
+        assert!(!output.contains("Recent Endpoint Game"));
+        assert!(output.find("Played Today").unwrap() < output.find("Played Three Days Ago").unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_days_and_limit() {
+        let app_context = AppContext::with_api(create_days_test_api());
+        let matches = get_matches_for_args(&["dashboard", "--days", "60", "--limit", "2"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        DashboardPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        assert!(output.contains("Played Today\n"));
+        assert!(output.contains("Played Three Days Ago\n"));
+        assert!(!output.contains("Played Last Month"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_filter() {
+        let app_context = AppContext::with_api(create_days_test_api());
+        let matches = get_matches_for_args(&["dashboard", "--filter", "three DAYS", "--limit", "1"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        DashboardPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        assert!(output.contains("Played Three Days Ago\n"));
+        assert!(!output.contains("Played Today"));
+        assert!(!output.contains("Played Last Month"));
+        assert!(!output.contains("Recent Endpoint Game"));
+    }
+
+    #[test]
+    fn test_invalid_filter_regex() {
+        let result = DashboardOptions::from_matches(&get_matches_for_args(&["dashboard", "--filter", "[", "--regex"]));
+        assert!(result.is_err_and(|e| e.starts_with("Invalid --filter regex")));
+    }
+
+    // Creates a fake with the given recently played games, most recent first, each as
+    // `(appid, name, unlocked, total, playtime)`, with `total` achievements of which the first `unlocked` are unlocked.
+    fn recent_games_api(games: &[(u32, &str, usize, usize, u32)]) -> FakeApi {
+        let mut api = FakeApi::new();
+        for &(appid, name, unlocked, total, playtime) in games {
+            let achievements = (0..total).map(|i| create_mock_achievement(u8::from(i < unlocked))).collect();
+            api = api.with_recent_game_playtime(appid, name, playtime).with_achievements(appid, name, achievements);
+        }
+        api
+    }
+
+    fn create_sort_test_api() -> FakeApi {
+        recent_games_api(&[(1, "portal", 1, 2, 50), (2, "Bioshock", 9, 10, 500), (3, "Celeste", 2, 2, 5), (4, "Aquaria", 0, 0, 100)])
+            .with_game(3, "Celeste")
+    }
+
+    async fn render_sorted_names(args: &[&str]) -> Vec<String> {
+        let app_context = AppContext::with_api(create_sort_test_api());
+        let matches = get_matches_for_args(args);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        DashboardPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        assert!(err_writer.is_empty());
+        let output = String::from_utf8(writer).unwrap();
+        output.lines().skip(3).map(|line| line.split(" — ").next().unwrap().trim_end().to_string()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_execute_sort() {
+        let cases: [(&str, [&str; 4]); 4] = [
+            ("recent", ["portal", "Bioshock", "Celeste", "Aquaria"]),
+            ("completion", ["Celeste", "Bioshock", "portal", "Aquaria"]),
+            ("name", ["Aquaria", "Bioshock", "Celeste", "portal"]),
+            ("playtime", ["Bioshock", "Aquaria", "portal", "Celeste"]),
+        ];
+
+        for (sort, expected) in cases {
+            assert_eq!(render_sorted_names(&["dashboard", "--no-bar", "--sort", sort]).await, expected, "--sort {}", sort);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_execute_sort_defaults_to_recent() {
+        assert_eq!(render_sorted_names(&["dashboard", "--no-bar"]).await, ["portal", "Bioshock", "Celeste", "Aquaria"]);
+    }
+
+    #[tokio::test]
+    async fn test_execute_sort_keeps_pinned_games_first() {
+        let names = render_sorted_names(&["dashboard", "--no-bar", "--sort", "name", "--pin", "3"]).await;
+        assert_eq!(names, ["Celeste", "Aquaria", "Bioshock", "portal"]);
+    }
+
+    #[tokio::test]
+    async fn test_execute_sort_json() {
+        let app_context = AppContext::with_api(create_sort_test_api());
+        let matches = get_matches_for_args(&["dashboard", "--format", "json", "--sort", "completion"]);
+        let mut writer = Vec::new();
+
+        DashboardPlugin.execute(&app_context, &matches, &mut writer, &mut Vec::new()).await;
+
+        let entries: Vec<serde_json::Value> = serde_json::from_slice(&writer).unwrap();
+        let appids: Vec<u64> = entries.iter().map(|e| e["appid"].as_u64().unwrap()).collect();
+        assert_eq!(appids, [3, 2, 1, 4]);
+    }
+
+    #[tokio::test]
+    async fn test_execute_json_rounds_percentage() {
+        let app_context = AppContext::with_api(recent_games_api(&[(1, "Three Fifths", 3, 5, 0), (2, "One Third", 1, 3, 0)]));
+        let matches = get_matches_for_args(&["dashboard", "--format", "json"]);
+        let mut writer = Vec::new();
+
+        DashboardPlugin.execute(&app_context, &matches, &mut writer, &mut Vec::new()).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        assert!(output.contains(r#""percentage":60.0}"#), "{}", output);
+        assert!(output.contains(r#""percentage":33.3}"#), "{}", output);
+    }
+
+    fn create_completion_test_api() -> FakeApi {
+        recent_games_api(&[(1, "Half Done", 1, 2, 0), (2, "Nearly Done", 9, 10, 0), (3, "Completed", 2, 2, 0), (4, "No Achievements", 0, 0, 0)])
+    }
+
+    async fn render_completion_dashboard(args: &[&str]) -> String {
+        let app_context = AppContext::with_api(create_completion_test_api());
+        let matches = get_matches_for_args(args);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        DashboardPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        String::from_utf8(writer).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_completion_range() {
+        let output = render_completion_dashboard(&["dashboard", "--above", "80", "--below", "100"]).await;
+        assert!(output.contains("Nearly Done\n"));
+        assert!(!output.contains("Half Done"));
+        assert!(!output.contains("Completed"));
+        assert!(!output.contains("No Achievements"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_above_only() {
+        let output = render_completion_dashboard(&["dashboard", "--above", "50", "--format", "json"]).await;
+        let entries: Vec<serde_json::Value> = serde_json::from_str(&output).unwrap();
+        let names: Vec<&str> = entries.iter().map(|e| e["name"].as_str().unwrap()).collect();
+        assert_eq!(names, vec!["Half Done", "Nearly Done", "Completed"]);
+    }
+
+    #[tokio::test]
+    async fn test_execute_without_completion_range_keeps_games_without_achievements() {
+        let output = render_completion_dashboard(&["dashboard"]).await;
+        assert!(output.contains("No Achievements\nNo achievements found for this game\n"));
+    }
+
+    #[test]
+    fn test_invalid_completion_range() {
+        let result = DashboardOptions::from_matches(&get_matches_for_args(&["dashboard", "--above", "90", "--below", "80"]));
+        assert!(result.is_err_and(|e| e == "--above 90 must not be greater than --below 80"));
+        assert!(DashboardPlugin.command().try_get_matches_from(["dashboard", "--below", "120"]).is_err());
+    }
+
+    #[test]
+    fn test_days_lifts_default_limit() {
+        let options = DashboardOptions::from_matches(&get_matches_for_args(&["dashboard", "--days", "7"])).unwrap();
+        assert_eq!(options.limit, usize::MAX);
+
+        let options = DashboardOptions::from_matches(&get_matches_for_args(&["dashboard"])).unwrap();
+        assert_eq!(options.limit, DEFAULT_LIMIT);
+        assert_eq!(options.days, None);
+    }
+}
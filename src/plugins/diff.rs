@@ -0,0 +1,319 @@
+//! Plugin for comparing two achievement snapshots.
+//!
+//! <purpose-start>
+//! This plugin provides the `diff` command, which shows the progress made between two snapshots,
+//! e.g. the achievements unlocked since the last export.
+//! <purpose-end>
+//!
+//! <inputs-start>
+//! - `matches`: The command-line arguments parsed by `clap`.
+//! <inputs-end>
+//!
+//! <outputs-start>
+//! - The newly unlocked achievements grouped by game, and the games present in only one snapshot.
+//! <outputs-end>
+//!
+//! <side-effects-start>
+//! - Reads the snapshot files.
+//! <side-effects-end>
+
+use crate::{
+    app::AppContext,
+    plugins::Plugin,
+    snapshot::{self, GameSnapshot},
+    steam_api::Achievement,
+};
+use async_trait::async_trait;
+use clap::{Arg, Command};
+use std::io::Write;
+use std::path::PathBuf;
+
+pub struct DiffPlugin;
+
+// Represents a change of a single game between two snapshots.
+#[derive(Debug, PartialEq)]
+enum GameChange<'a> {
+    // Achievements unlocked in the newer snapshot that were not unlocked in the older one
+    Unlocked(&'a GameSnapshot, Vec<&'a Achievement>),
+    // A game present only in the newer snapshot
+    Added(&'a GameSnapshot),
+    // A game present only in the older snapshot
+    Removed(&'a GameSnapshot),
+}
+
+#[async_trait]
+impl Plugin for DiffPlugin {
+    // Defines the clap command for the `diff` plugin.
+    //
+    // <purpose-start>
+    // This method provides the command-line interface for the `diff` plugin,
+    // which compares two achievement snapshots.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `&self`: A reference to the plugin instance.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `clap::Command`: The clap command definition for the `diff` plugin.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None.
+    // <side-effects-end>
+    fn command(&self) -> Command {
+        Command::new("diff")
+            .about("Displays the achievements unlocked between two JSON snapshots, grouped by game.")
+            .after_help(concat!(
+                "Examples:\n",
+                "  trogue export > before.json\n",
+                "  trogue diff before.json after.json",
+            ))
+            .arg(
+                Arg::new("older")
+                    .value_name("older")
+                    .value_parser(clap::value_parser!(PathBuf))
+                    .required(true)
+                    .help("The older snapshot file"),
+            )
+            .arg(
+                Arg::new("newer")
+                    .value_name("newer")
+                    .value_parser(clap::value_parser!(PathBuf))
+                    .required(true)
+                    .help("The newer snapshot file"),
+            )
+    }
+
+    // Executes the `diff` plugin's logic.
+    //
+    // <purpose-start>
+    // This method is called by the core application when the `diff` command is invoked.
+    // It reads both snapshots and prints the changes between them.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `&self`: A reference to the plugin instance.
+    // - `_app_context`: The shared application context (unused).
+    // - `matches`: The clap argument matches for the `diff` subcommand.
+    // - `writer`: A mutable reference to a writer for standard output.
+    // - `err_writer`: A mutable reference to a writer for standard error.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - None.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - Reads the snapshot files.
+    // - Writes the changes to the provided writer.
+    // <side-effects-end>
+    async fn execute(
+        &self,
+        _app_context: &AppContext,
+        matches: &clap::ArgMatches,
+        writer: &mut (dyn Write + Send),
+        err_writer: &mut (dyn Write + Send),
+    ) {
+        let older_path = matches.get_one::<PathBuf>("older").unwrap();
+        let newer_path = matches.get_one::<PathBuf>("newer").unwrap();
+
+        let (older, newer) = match (snapshot::read_snapshot(older_path), snapshot::read_snapshot(newer_path)) {
+            (Ok(older), Ok(newer)) => (older, newer),
+            (Err(e), _) | (_, Err(e)) => {
+                writeln!(err_writer, "{}", e).unwrap();
+                return;
+            }
+        };
+
+        let changes = diff_snapshots(&older, &newer);
+        if changes.is_empty() {
+            writeln!(writer, "No changes").unwrap();
+            return;
+        }
+
+        for change in changes {
+            match change {
+                GameChange::Unlocked(game, achievements) => {
+                    writeln!(writer, "{}", game.name).unwrap();
+                    for achievement in achievements {
+                        writeln!(writer, " + {}", achievement.name).unwrap();
+                    }
+                }
+                GameChange::Added(game) => writeln!(writer, "New game: {}", game.name).unwrap(),
+                GameChange::Removed(game) => writeln!(writer, "Removed game: {}", game.name).unwrap(),
+            }
+        }
+    }
+}
+
+// Computes the changes between two snapshots.
+//
+// <purpose-start>
+// Games are matched by id and achievements by API name, as display names may be localized
+// differently in each snapshot. Games without new unlocks are left out.
+// <purpose-end>
+//
+// <inputs-start>
+// - `older`: The games of the older snapshot.
+// - `newer`: The games of the newer snapshot.
+// <inputs-end>
+//
+// <outputs-start>
+// - `Vec<GameChange>`: The changes, in the order of the newer snapshot, followed by the removed games.
+// <outputs-end>
+//
+// <side-effects-start>
+// - None.
+// <side-effects-end>
+fn diff_snapshots<'a>(older: &'a [GameSnapshot], newer: &'a [GameSnapshot]) -> Vec<GameChange<'a>> {
+    let mut changes = Vec::new();
+
+    for game in newer {
+        let Some(old_game) = older.iter().find(|g| g.appid == game.appid) else {
+            changes.push(GameChange::Added(game));
+            continue;
+        };
+
+        let unlocked = snapshot::new_unlocks(&old_game.achievements, &game.achievements);
+
+        if !unlocked.is_empty() {
+            changes.push(GameChange::Unlocked(game, unlocked));
+        }
+    }
+
+    for game in older {
+        if !newer.iter().any(|g| g.appid == game.appid) {
+            changes.push(GameChange::Removed(game));
+        }
+    }
+
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::steam_api::Api;
+    use clap::ArgMatches;
+    use std::{env, fs};
+
+    fn create_mock_achievement(apiname: &str, achieved: u8) -> Achievement {
+        Achievement {
+            apiname: apiname.to_string(),
+            achieved,
+            unlocktime: if achieved == 1 { 1672531200 } else { 0 },
+            name: format!("{} name", apiname),
+            description: "".to_string(),
+        }
+    }
+
+    fn create_mock_game(appid: u32, name: &str, achievements: Vec<Achievement>) -> GameSnapshot {
+        GameSnapshot {
+            appid,
+            name: name.to_string(),
+            achievements,
+        }
+    }
+
+    fn get_matches_for_args(args: &[&str]) -> ArgMatches {
+        DiffPlugin.command().get_matches_from(args)
+    }
+
+    #[test]
+    fn test_command() {
+        let plugin = DiffPlugin;
+        let cmd = plugin.command();
+        assert_eq!(cmd.get_name(), "diff");
+        assert!(cmd.get_about().is_some());
+        assert!(cmd.get_arguments().any(|arg| arg.get_id() == "older"));
+        assert!(cmd.get_arguments().any(|arg| arg.get_id() == "newer"));
+    }
+
+    #[test]
+    fn test_diff_new_unlocks() {
+        let older = vec![create_mock_game(1, "Game 1", vec![
+            create_mock_achievement("a1", 1),
+            create_mock_achievement("a2", 0),
+            create_mock_achievement("a3", 0),
+        ])];
+        let newer = vec![create_mock_game(1, "Game 1", vec![
+            create_mock_achievement("a1", 1),
+            create_mock_achievement("a2", 1),
+            create_mock_achievement("a3", 0),
+        ])];
+
+        let changes = diff_snapshots(&older, &newer);
+
+        assert_eq!(changes, vec![GameChange::Unlocked(&newer[0], vec![&newer[0].achievements[1]])]);
+    }
+
+    #[test]
+    fn test_diff_added_and_removed_games() {
+        let older = vec![
+            create_mock_game(1, "Game 1", vec![create_mock_achievement("a1", 1)]),
+            create_mock_game(2, "Game 2", vec![]),
+        ];
+        let newer = vec![
+            create_mock_game(1, "Game 1", vec![create_mock_achievement("a1", 1)]),
+            create_mock_game(3, "Game 3", vec![create_mock_achievement("b1", 1)]),
+        ];
+
+        let changes = diff_snapshots(&older, &newer);
+
+        assert_eq!(changes, vec![GameChange::Added(&newer[1]), GameChange::Removed(&older[1])]);
+    }
+
+    #[test]
+    fn test_diff_identical_snapshots() {
+        let snapshot = vec![create_mock_game(1, "Game 1", vec![
+            create_mock_achievement("a1", 1),
+            create_mock_achievement("a2", 0),
+        ])];
+
+        assert!(diff_snapshots(&snapshot, &snapshot).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_execute_prints_changes() {
+        let dir = env::temp_dir().join(format!("trogue-diff-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let older_path = dir.join("older.json");
+        let newer_path = dir.join("newer.json");
+        let older = vec![
+            create_mock_game(1, "Game 1", vec![create_mock_achievement("a1", 0)]),
+            create_mock_game(2, "Game 2", vec![]),
+        ];
+        let newer = vec![create_mock_game(1, "Game 1", vec![create_mock_achievement("a1", 1)])];
+        fs::write(&older_path, serde_json::to_string(&older).unwrap()).unwrap();
+        fs::write(&newer_path, serde_json::to_string(&newer).unwrap()).unwrap();
+
+        let api = Api::new("test_key".to_string(), "test_id".to_string(), "http://127.0.0.1:1".to_string());
+        let app_context = AppContext::with_api(api);
+        let matches = get_matches_for_args(&["diff", older_path.to_str().unwrap(), newer_path.to_str().unwrap()]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        DiffPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        assert_eq!(output, "Game 1\n + a1 name\nRemoved game: Game 2\n");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_execute_missing_file() {
+        let api = Api::new("test_key".to_string(), "test_id".to_string(), "http://127.0.0.1:1".to_string());
+        let app_context = AppContext::with_api(api);
+        let matches = get_matches_for_args(&["diff", "/nonexistent/older.json", "/nonexistent/newer.json"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        DiffPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let err_output = String::from_utf8(err_writer).unwrap();
+        assert!(err_output.contains("Could not read snapshot /nonexistent/older.json"));
+        assert!(writer.is_empty());
+    }
+}
@@ -0,0 +1,505 @@
+//! Plugin for exporting the achievements of all owned games.
+//!
+//! <purpose-start>
+//! This plugin provides the `export` command, which writes a snapshot of the user's achievements
+//! as JSON, readable by the `diff` command, as NDJSON, or as CSV for spreadsheets. With `--split`, each game
+//! is written to its own JSON file instead.
+//! <purpose-end>
+//!
+//! <inputs-start>
+//! - `app_context`: The shared application context, providing access to the Steam API client.
+//! - `matches`: The command-line arguments parsed by `clap`.
+//! <inputs-end>
+//!
+//! <outputs-start>
+//! - The snapshot printed to the console, or one file per game with `--split`.
+//! <outputs-end>
+//!
+//! <side-effects-start>
+//! - Makes one network request for the list of games and one per game for its achievements.
+//! - Creates the output directory and writes files to it with `--split`.
+//! <side-effects-end>
+
+use crate::{app::AppContext, plugins::{self, Plugin}, snapshot::SnapshotGame, steam_api::Achievement, ui};
+use async_trait::async_trait;
+use clap::{Arg, Command, ValueEnum};
+use futures::StreamExt;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::pin::pin;
+
+pub struct ExportPlugin;
+
+// Represents the supported export formats.
+//
+// <purpose-start>
+// JSON snapshots can be compared with the `diff` command, while CSV can be opened in spreadsheets.
+// NDJSON streams the games as they are fetched, for tools processing large libraries line by line.
+// <purpose-end>
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+enum ExportFormat {
+    // An array of games with their achievements, in the snapshot format
+    Json,
+    // One compact game per line, in the snapshot format, written as soon as it is fetched
+    Ndjson,
+    // One row per achievement, preceded by a header row
+    Csv,
+}
+
+// The header row of the CSV export.
+const CSV_HEADER: &str = "appid,game,apiname,name,description,achieved,unlocktime";
+
+#[async_trait]
+impl Plugin for ExportPlugin {
+    // Defines the clap command for the `export` plugin.
+    //
+    // <purpose-start>
+    // This method provides the command-line interface for the `export` plugin,
+    // which exports the achievements of all owned games.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `&self`: A reference to the plugin instance.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `clap::Command`: The clap command definition for the `export` plugin.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None.
+    // <side-effects-end>
+    fn command(&self) -> Command {
+        Command::new("export")
+            .about("Exports the achievements of all owned games as a JSON snapshot, as NDJSON or as CSV.")
+            .after_help(concat!(
+                "Examples:\n",
+                "  trogue export > snapshot.json\n",
+                "  trogue export --format csv --exclude-descriptions > achievements.csv\n",
+                "  trogue export --format ndjson | jq .name\n",
+                "  trogue export --split --output-dir archive",
+            ))
+            .arg(
+                Arg::new("format")
+                    .long("format")
+                    .value_name("format")
+                    .value_parser(clap::value_parser!(ExportFormat))
+                    .default_value("json")
+                    .help("The output format (json, ndjson, csv)"),
+            )
+            .arg(
+                Arg::new("exclude_descriptions")
+                    .long("exclude-descriptions")
+                    .action(clap::ArgAction::SetTrue)
+                    .help("Leaves out achievement descriptions to reduce the size of the export. JSON omits the field, CSV leaves the column empty"),
+            )
+            .arg(
+                Arg::new("split")
+                    .long("split")
+                    .action(clap::ArgAction::SetTrue)
+                    .requires("output_dir")
+                    .help("Writes each game to its own <appid>.json file in --output-dir instead of printing one snapshot, JSON only"),
+            )
+            .arg(
+                Arg::new("output_dir")
+                    .long("output-dir")
+                    .value_name("dir")
+                    .value_parser(clap::value_parser!(PathBuf))
+                    .requires("split")
+                    .help("The directory --split writes to, created if needed"),
+            )
+    }
+
+    // Executes the `export` plugin's logic.
+    //
+    // <purpose-start>
+    // This method is called by the core application when the `export` command is invoked.
+    // It fetches the achievements of every owned game and writes them in the requested format,
+    // or to one file per game with `--split`. Games whose achievements cannot be fetched are
+    // reported and left out.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `&self`: A reference to the plugin instance.
+    // - `app_context`: The shared application context.
+    // - `matches`: The clap argument matches for the `export` subcommand.
+    // - `writer`: A mutable reference to a writer for standard output.
+    // - `err_writer`: A mutable reference to a writer for standard error.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - None.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - Makes network requests to the Steam API to fetch the games and their achievements.
+    // - Writes the export to the provided writer, or to files in the output directory with `--split`.
+    // - Writes progress, errors and the summary of written files to `err_writer`.
+    // <side-effects-end>
+    async fn execute(
+        &self,
+        app_context: &AppContext,
+        matches: &clap::ArgMatches,
+        writer: &mut (dyn Write + Send),
+        err_writer: &mut (dyn Write + Send),
+    ) {
+        let format = *matches.get_one::<ExportFormat>("format").unwrap();
+        let include_descriptions = !matches.get_flag("exclude_descriptions");
+        let output_dir = matches.get_one::<PathBuf>("output_dir");
+
+        if let Some(dir) = output_dir {
+            if format != ExportFormat::Json {
+                writeln!(err_writer, "--split only supports the json format").unwrap();
+                return;
+            }
+            if let Err(e) = prepare_output_dir(dir) {
+                writeln!(err_writer, "{}", e).unwrap();
+                return;
+            }
+        }
+
+        let games = match app_context.get_games_list_cached().await {
+            Ok(games) => games,
+            Err(e) => {
+                writeln!(err_writer, "Error while trying to get games list: {}", ui::scrub_api_key(&e.to_string())).unwrap();
+                return;
+            }
+        };
+
+        let mut reporter = ui::progress_reporter(app_context.quiet());
+        let mut results = pin!(app_context.game_achievements(games.iter().map(|g| g.appid).collect()));
+        let mut exported: Vec<(u32, String, Vec<Achievement>)> = Vec::new();
+        let mut skipped = plugins::SkippedGames::default();
+
+        for (index, game) in games.iter().enumerate() {
+            reporter.report(index + 1, games.len(), err_writer);
+            let result = results.next().await.unwrap();
+            reporter.clear(err_writer);
+
+            match result {
+                // Streaming output: each line is flushed as soon as it is written
+                Ok(game_achievements) if format == ExportFormat::Ndjson => {
+                    let snapshot = SnapshotGame::new(game.appid, &game.name, &game_achievements.achievements, include_descriptions);
+                    writeln!(writer, "{}", serde_json::to_string(&snapshot).unwrap()).unwrap();
+                    writer.flush().unwrap();
+                }
+                Ok(game_achievements) => exported.push((game.appid, game.name.clone(), game_achievements.achievements)),
+                Err(e) => {
+                    let error = format!("Error while trying to get achievements for {}: {}", game.name, ui::scrub_api_key(&e.to_string()));
+                    skipped.skip(app_context, game.appid, &error, err_writer);
+                }
+            }
+        }
+        skipped.report(err_writer);
+
+        if let Some(dir) = output_dir {
+            let mut written = 0;
+            for (appid, name, achievements) in &exported {
+                match write_game_file(dir, &SnapshotGame::new(*appid, name, achievements, include_descriptions)) {
+                    Ok(()) => written += 1,
+                    Err(e) => writeln!(err_writer, "{}", e).unwrap(),
+                }
+            }
+
+            plugins::notice(app_context, err_writer, &format!("Wrote {} of {} games to {}", written, exported.len(), dir.display()));
+            return;
+        }
+
+        match format {
+            ExportFormat::Json => {
+                let snapshot: Vec<SnapshotGame> = exported
+                    .iter()
+                    .map(|(appid, name, achievements)| SnapshotGame::new(*appid, name, achievements, include_descriptions))
+                    .collect();
+                writeln!(writer, "{}", serde_json::to_string_pretty(&snapshot).unwrap()).unwrap();
+            }
+            // Already written while fetching
+            ExportFormat::Ndjson => {}
+            ExportFormat::Csv => {
+                writeln!(writer, "{}", CSV_HEADER).unwrap();
+                for (appid, name, achievements) in &exported {
+                    for achievement in achievements {
+                        let description = if include_descriptions { achievement.description.as_str() } else { "" };
+                        writeln!(
+                            writer,
+                            "{},{},{},{},{},{},{}",
+                            appid,
+                            ui::csv_field(name),
+                            ui::csv_field(&achievement.apiname),
+                            ui::csv_field(&achievement.name),
+                            ui::csv_field(description),
+                            achievement.achieved,
+                            achievement.unlocktime
+                        )
+                        .unwrap();
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Makes sure that `--split` can write to the output directory.
+//
+// <purpose-start>
+// The directory is created, with its parents, before any achievements are fetched, so that an
+// unusable path is reported right away instead of after a long export.
+// <purpose-end>
+//
+// <inputs-start>
+// - `dir`: The output directory.
+// <inputs-end>
+//
+// <outputs-start>
+// - `Ok(())` if the directory exists.
+// - `Err(String)`: A user-facing message if the path is a file or the directory cannot be created.
+// <outputs-end>
+//
+// <side-effects-start>
+// - **Creates directories**: Creates the output directory if it does not exist.
+// <side-effects-end>
+fn prepare_output_dir(dir: &Path) -> Result<(), String> {
+    if dir.exists() && !dir.is_dir() {
+        return Err(format!("Cannot export to {}: it exists and is not a directory", dir.display()));
+    }
+
+    fs::create_dir_all(dir).map_err(|e| format!("Could not create {}: {}", dir.display(), e))
+}
+
+// Writes a game to `<appid>.json` in the output directory.
+//
+// <inputs-start>
+// - `dir`: The output directory.
+// - `game`: The game to write.
+// <inputs-end>
+//
+// <outputs-start>
+// - `Ok(())` if the file was written.
+// - `Err(String)`: A user-facing message if it could not be.
+// <outputs-end>
+//
+// <side-effects-start>
+// - **Writes a file**: Creates or replaces the file of the game.
+// <side-effects-end>
+fn write_game_file(dir: &Path, game: &SnapshotGame) -> Result<(), String> {
+    let path = dir.join(format!("{}.json", game.appid));
+    let contents = format!("{}\n", serde_json::to_string_pretty(game).unwrap());
+
+    fs::write(&path, contents).map_err(|e| format!("Could not write {}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_api::FakeApi;
+    use crate::snapshot::GameSnapshot;
+    use clap::ArgMatches;
+
+    fn create_mock_achievement(apiname: &str, name: &str, description: &str, achieved: u8) -> Achievement {
+        Achievement {
+            apiname: apiname.to_string(),
+            name: name.to_string(),
+            description: description.to_string(),
+            achieved,
+            unlocktime: if achieved > 0 { 1672531200 } else { 0 },
+        }
+    }
+
+    fn create_test_api() -> FakeApi {
+        FakeApi::new()
+            .with_game(1, "Game, The First")
+            .with_game(2, "No Stats Game")
+            .with_achievements(
+                1,
+                "Game, The First",
+                vec![
+                    create_mock_achievement("a1", "First", "Finish \"the\" tutorial", 1),
+                    create_mock_achievement("a2", "Second", "Win", 0),
+                ],
+            )
+    }
+
+    fn get_matches_for_args(args: &[&str]) -> ArgMatches {
+        ExportPlugin.command().get_matches_from(args)
+    }
+
+    #[test]
+    fn test_command() {
+        let plugin = ExportPlugin;
+        let cmd = plugin.command();
+        assert_eq!(cmd.get_name(), "export");
+        assert!(cmd.get_about().is_some());
+        assert!(cmd.get_arguments().any(|arg| arg.get_id() == "format"));
+        assert!(cmd.get_arguments().any(|arg| arg.get_id() == "exclude_descriptions"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_json() {
+        let app_context = AppContext::with_api(create_test_api()).with_verbose(true);
+        let matches = get_matches_for_args(&["export"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ExportPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        let snapshot: Vec<GameSnapshot> = serde_json::from_str(&output).unwrap();
+        assert_eq!(snapshot.len(), 1);
+        assert_eq!(snapshot[0].name, "Game, The First");
+        assert_eq!(snapshot[0].achievements[0].description, "Finish \"the\" tutorial");
+        assert!(String::from_utf8(err_writer).unwrap().contains("Error while trying to get achievements for No Stats Game"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_summarizes_skipped_games() {
+        let api = create_test_api().with_game(3, "Another No Stats Game");
+        let app_context = AppContext::with_api(api);
+        let matches = get_matches_for_args(&["export"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ExportPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let snapshot: Vec<GameSnapshot> = serde_json::from_str(&String::from_utf8(writer).unwrap()).unwrap();
+        assert_eq!(snapshot.len(), 1);
+        assert_eq!(String::from_utf8(err_writer).unwrap(), "Skipped 2 games due to errors: [2, 3]\n");
+    }
+
+    #[tokio::test]
+    async fn test_execute_json_exclude_descriptions() {
+        let app_context = AppContext::with_api(create_test_api());
+        let matches = get_matches_for_args(&["export", "--exclude-descriptions"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ExportPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        assert!(!output.contains("description"));
+        let snapshot: Vec<GameSnapshot> = serde_json::from_str(&output).unwrap();
+        assert_eq!(snapshot[0].achievements[0].description, "");
+    }
+
+    #[tokio::test]
+    async fn test_execute_ndjson() {
+        let api = create_test_api()
+            .with_game(3, "Game Three")
+            .with_achievements(3, "Game Three", vec![create_mock_achievement("b1", "Only", "Play", 1)]);
+        let app_context = AppContext::with_api(api);
+        let matches = get_matches_for_args(&["export", "--format", "ndjson"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ExportPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        let games: Vec<GameSnapshot> = output.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(output.lines().count(), 2);
+        assert_eq!(games[0].name, "Game, The First");
+        assert_eq!(games[0].achievements.len(), 2);
+        assert_eq!(games[1].name, "Game Three");
+    }
+
+    #[tokio::test]
+    async fn test_execute_csv() {
+        let app_context = AppContext::with_api(create_test_api());
+        let matches = get_matches_for_args(&["export", "--format", "csv"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ExportPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        assert_eq!(
+            output,
+            "appid,game,apiname,name,description,achieved,unlocktime\n\
+             1,\"Game, The First\",a1,First,\"Finish \"\"the\"\" tutorial\",1,1672531200\n\
+             1,\"Game, The First\",a2,Second,Win,0,0\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_execute_csv_exclude_descriptions() {
+        let app_context = AppContext::with_api(create_test_api());
+        let matches = get_matches_for_args(&["export", "--format", "csv", "--exclude-descriptions"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ExportPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        assert!(output.contains("1,\"Game, The First\",a1,First,,1,1672531200\n"));
+        assert!(!output.contains("tutorial"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_games_list_error() {
+        let app_context = AppContext::with_api(FakeApi::failing());
+        let matches = get_matches_for_args(&["export"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ExportPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        assert!(writer.is_empty());
+        assert!(String::from_utf8(err_writer).unwrap().starts_with("Error while trying to get games list"));
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("trogue-export-test-{}-{}", std::process::id(), name))
+    }
+
+    #[tokio::test]
+    async fn test_execute_split() {
+        let dir = temp_dir("split").join("nested");
+        let api = create_test_api()
+            .with_game(3, "Third Game")
+            .with_achievements(3, "Third Game", vec![create_mock_achievement("b1", "Only", "Play", 0)]);
+        let app_context = AppContext::with_api(api);
+        let matches = get_matches_for_args(&["export", "--split", "--output-dir", dir.to_str().unwrap()]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ExportPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        assert!(writer.is_empty());
+        let err_output = String::from_utf8(err_writer).unwrap();
+        assert!(err_output.contains(&format!("Wrote 2 of 2 games to {}", dir.display())));
+
+        let first: GameSnapshot = serde_json::from_str(&fs::read_to_string(dir.join("1.json")).unwrap()).unwrap();
+        assert_eq!(first.name, "Game, The First");
+        assert_eq!(first.achievements.len(), 2);
+        assert_eq!(first.achievements[0].description, "Finish \"the\" tutorial");
+
+        let third: GameSnapshot = serde_json::from_str(&fs::read_to_string(dir.join("3.json")).unwrap()).unwrap();
+        assert_eq!(third.appid, 3);
+        assert_eq!(third.achievements[0].apiname, "b1");
+        assert!(!dir.join("2.json").exists());
+
+        fs::remove_dir_all(temp_dir("split")).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_execute_split_into_file() {
+        let path = temp_dir("file");
+        fs::write(&path, "").unwrap();
+        let app_context = AppContext::with_api(create_test_api());
+        let matches = get_matches_for_args(&["export", "--split", "--output-dir", path.to_str().unwrap()]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ExportPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let err_output = String::from_utf8(err_writer).unwrap();
+        assert_eq!(err_output, format!("Cannot export to {}: it exists and is not a directory\n", path.display()));
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_split_requires_output_dir() {
+        assert!(ExportPlugin.command().try_get_matches_from(["export", "--split"]).is_err());
+        assert!(ExportPlugin.command().try_get_matches_from(["export", "--output-dir", "out"]).is_err());
+    }
+}
@@ -0,0 +1,218 @@
+//! Plugin for reporting the build and configuration of trogue.
+//!
+//! <purpose-start>
+//! This plugin provides the `info` command, which prints what is needed to triage a user report:
+//! the version, where requests are sent to, where the credentials come from and which Steam
+//! responses are cached. No request is made and no secret is printed.
+//! <purpose-end>
+//!
+//! <inputs-start>
+//! - `app_context`: The shared application context, providing access to the Steam API client.
+//! - `matches`: The command-line arguments parsed by `clap` (unused in this plugin).
+//! <inputs-end>
+//!
+//! <outputs-start>
+//! - One line per piece of information printed to the console.
+//! <outputs-end>
+//!
+//! <side-effects-start>
+//! - Reads environment variables and the `.env` file.
+//! <side-effects-end>
+
+use crate::{app::AppContext, cfg::Cfg, constants, plugins::Plugin, state::{VanityIdStore, VANITY_ID_TTL_SECS}};
+use async_trait::async_trait;
+use clap::Command;
+use std::io::Write;
+use std::path::Path;
+
+pub struct InfoPlugin;
+
+// The configuration variables whose source is reported.
+const CREDENTIAL_VARS: [&str; 2] = ["TROGUE_STEAM_API_KEY", "TROGUE_STEAM_ID"];
+
+#[async_trait]
+impl Plugin for InfoPlugin {
+    // Defines the clap command for the `info` plugin.
+    //
+    // <purpose-start>
+    // This method provides the command-line interface for the `info` plugin,
+    // which reports the build and configuration of trogue.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `&self`: A reference to the plugin instance.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `clap::Command`: The clap command definition for the `info` plugin.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None.
+    // <side-effects-end>
+    fn command(&self) -> Command {
+        Command::new("info")
+            .about("Displays the version of trogue and where its configuration comes from, to include in bug reports")
+            .after_help(concat!(
+                "Examples:\n",
+                "  trogue info\n",
+                "  trogue --offline info",
+            ))
+    }
+
+    // Executes the `info` plugin's logic.
+    //
+    // <purpose-start>
+    // This method is called by the core application when the `info` command is invoked.
+    // Environment variables take precedence over the `.env` file, as when the configuration is loaded.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `&self`: A reference to the plugin instance.
+    // - `app_context`: The shared application context.
+    // - `_matches`: The clap argument matches for the `info` subcommand (unused).
+    // - `writer`: A mutable reference to a writer for standard output.
+    // - `_err_writer`: A mutable reference to a writer for standard error (unused).
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - None.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - Reads the credential environment variables and the `.env` file in the current directory.
+    // - Writes the information to the provided writer.
+    // <side-effects-end>
+    async fn execute(
+        &self,
+        app_context: &AppContext,
+        _matches: &clap::ArgMatches,
+        writer: &mut (dyn Write + Send),
+        _err_writer: &mut (dyn Write + Send),
+    ) {
+        writeln!(writer, "{}", version_line()).unwrap();
+
+        match app_context.api.base_url() {
+            Some(base_url) => writeln!(writer, "API base URL: {}", base_url).unwrap(),
+            None => writeln!(writer, "API base URL: none, offline sample data").unwrap(),
+        }
+
+        let dotenv = Cfg::read_dotenv(Path::new(constants::DOTENV_PATH));
+        for key in CREDENTIAL_VARS {
+            let source = config_source(Cfg::read_env(key).is_ok(), dotenv.contains_key(key));
+            writeln!(writer, "{}: {}", key, source).unwrap();
+        }
+
+        let vanity_ids = VanityIdStore::default_path();
+        writeln!(writer, "{}", caching_line(vanity_ids.as_deref())).unwrap();
+    }
+}
+
+// Describes what is reused between runs instead of being requested from Steam again.
+//
+// <inputs-start>
+// - `vanity_ids`: The resolved vanity names file, `None` if no state directory is available.
+// <inputs-end>
+//
+// <outputs-start>
+// - `String`: The caching line, naming the file and how long its entries are reused.
+// <outputs-end>
+fn caching_line(vanity_ids: Option<&Path>) -> String {
+    match vanity_ids {
+        Some(path) => format!(
+            "Caching: vanity names are resolved to a SteamID64 once and reused for {} days from {} (--refresh-id resolves them again), other data is requested fresh on every run",
+            VANITY_ID_TTL_SECS / (24 * 60 * 60),
+            path.display()
+        ),
+        None => "Caching: disabled, no state directory, every run requests fresh data from Steam".to_string(),
+    }
+}
+
+// Returns the name and version of the running build.
+fn version_line() -> String {
+    format!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"))
+}
+
+// Describes where a configuration variable is read from.
+//
+// <inputs-start>
+// - `in_env`: Whether the variable is set in the environment.
+// - `in_dotenv`: Whether the variable is set in the `.env` file.
+// <inputs-end>
+//
+// <outputs-start>
+// - `&str`: The source that wins, as environment variables take precedence over the `.env` file.
+// <outputs-end>
+fn config_source(in_env: bool, in_dotenv: bool) -> &'static str {
+    match (in_env, in_dotenv) {
+        (true, _) => "environment",
+        (false, true) => ".env file",
+        (false, false) => "not set",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_api::FakeApi;
+    use crate::steam_api::Api;
+
+    #[test]
+    fn test_command() {
+        let cmd = InfoPlugin.command();
+        assert_eq!(cmd.get_name(), "info");
+        assert!(cmd.get_about().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_execute() {
+        let api = Api::new("test_key".to_string(), "test_id".to_string(), "http://localhost:1234".to_string());
+        let app_context = AppContext::with_api(api);
+        let matches = InfoPlugin.command().get_matches_from(["info"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        InfoPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines[0], format!("trogue {}", env!("CARGO_PKG_VERSION")));
+        assert_eq!(lines[1], "API base URL: http://localhost:1234");
+        assert!(lines[2].starts_with("TROGUE_STEAM_API_KEY: "));
+        assert!(lines[3].starts_with("TROGUE_STEAM_ID: "));
+        assert!(lines[4].starts_with("Caching: "));
+        assert!(lines[4].contains("--refresh-id") || lines[4].contains("no state directory"));
+        assert!(!output.contains("test_key"));
+        assert!(err_writer.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_execute_offline() {
+        let app_context = AppContext::with_api(FakeApi::new());
+        let matches = InfoPlugin.command().get_matches_from(["info"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        InfoPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        assert!(output.contains("API base URL: none, offline sample data\n"));
+    }
+
+    #[test]
+    fn test_caching_line() {
+        assert_eq!(
+            caching_line(Some(Path::new("/state/trogue/vanity_ids.json"))),
+            "Caching: vanity names are resolved to a SteamID64 once and reused for 30 days from /state/trogue/vanity_ids.json (--refresh-id resolves them again), other data is requested fresh on every run"
+        );
+        assert!(caching_line(None).starts_with("Caching: disabled"));
+    }
+
+    #[test]
+    fn test_config_source() {
+        assert_eq!(config_source(true, true), "environment");
+        assert_eq!(config_source(true, false), "environment");
+        assert_eq!(config_source(false, true), ".env file");
+        assert_eq!(config_source(false, false), "not set");
+    }
+}
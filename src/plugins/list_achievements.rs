@@ -2,7 +2,8 @@
 //!
 //! <purpose-start>
 //! This plugin provides the `achievements` command, which allows users to list the achievements for a given game.
-//! It supports filtering by achieved status and can include global achievement percentages.
+//! It supports filtering by name, achieved status and unlock date, and can include global achievement percentages
+//! and the descriptions of hidden achievements, and find the longest streak of unlocks in a session.
 //! <purpose-end>
 //!
 //! <inputs-start>
@@ -16,15 +17,96 @@
 //!
 //! <side-effects-start>
 //! - Makes network requests to the Steam API to fetch achievement data.
+//! - Records the unlocks of the game, see `state::UnlockHistoryStore`.
 //! <side-effects-end>
 
-use crate::{app::AppContext, plugins::Plugin, ui};
+use crate::{app::AppContext, cfg::Cfg, plugins::{self, Plugin}, snapshot, steam_api::Achievement, ui};
 use async_trait::async_trait;
-use clap::{Arg, Command};
+use chrono::{DateTime, NaiveDate, Utc};
+use clap::{Arg, Command, ValueEnum};
+use std::collections::{HashMap, HashSet};
 use std::io::Write;
 
 pub struct ListAchievementsPlugin;
 
+// The number of achievements recommended by `--recommend` without `--recommend-count`.
+const DEFAULT_RECOMMEND_COUNT: usize = 5;
+
+// The most minutes between two unlocks of the same `--streaks` streak without `--streak-gap`.
+const DEFAULT_STREAK_GAP_MINUTES: u64 = 30;
+
+// Prefixes the descriptions revealed by `--hidden`, which Steam hides until the achievement is unlocked.
+const HIDDEN_MARKER: &str = "[hidden] ";
+
+// Represents the direction of the `--sort-date` option.
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+enum SortDirection {
+    // Oldest unlocks first
+    Asc,
+    // Newest unlocks first
+    Desc,
+}
+
+// Represents the values of the `--group-by` option.
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+enum GroupBy {
+    // Sections by global unlock percentage, from common to ultra rare
+    Rarity,
+}
+
+// Represents the supported achievement list output formats.
+//
+// <purpose-start>
+// The text format is meant for humans, while tab-separated values suit scripts and YAML reports. The automatic
+// format picks between them depending on whether the output goes to a terminal.
+// <purpose-end>
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+enum AchievementsFormat {
+    // Text in a terminal, tab-separated values otherwise
+    Auto,
+    // One achievement per line, colored in a terminal
+    Text,
+    // The api name, name, unlock status (Y/N) and unlock time separated by tabs, followed by the
+    // global percentage with --global and the description with --verbose or --hidden
+    Tsv,
+    // A YAML sequence of the achievements, with the fields of the Steam response
+    Yaml,
+    // Comma-separated values with a header row, the columns chosen with --columns
+    Csv,
+}
+
+// Represents the columns of the CSV format, selected with `--columns`.
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+enum CsvColumn {
+    Apiname,
+    Name,
+    Description,
+    // 1 if unlocked, 0 otherwise
+    Achieved,
+    // The Unix time of the unlock, 0 if locked
+    Unlocktime,
+    // The global unlock percentage, fetched only when the column is requested
+    Global,
+}
+
+// The CSV columns written when `--columns` is not provided.
+const DEFAULT_CSV_COLUMNS: [CsvColumn; 5] =
+    [CsvColumn::Apiname, CsvColumn::Name, CsvColumn::Description, CsvColumn::Achieved, CsvColumn::Unlocktime];
+
+impl CsvColumn {
+    // Returns the name of the column in the header row, as given to `--columns`.
+    fn header(self) -> &'static str {
+        match self {
+            CsvColumn::Apiname => "apiname",
+            CsvColumn::Name => "name",
+            CsvColumn::Description => "description",
+            CsvColumn::Achieved => "achieved",
+            CsvColumn::Unlocktime => "unlocktime",
+            CsvColumn::Global => "global",
+        }
+    }
+}
+
 #[async_trait]
 impl Plugin for ListAchievementsPlugin {
     // Defines the clap command for the `achievements` plugin.
@@ -48,13 +130,24 @@ impl Plugin for ListAchievementsPlugin {
     fn command(&self) -> Command {
         Command::new("achievements")
             .about("Displays achievements for a specific game. Game ID or part of game title should be provided as an argument")
+            .after_help(concat!(
+                "Examples:\n",
+                "  trogue achievements 440 --global --remaining\n",
+                "  trogue achievements \"fallout 4\" --since 2024-01-01 --sort-date desc\n",
+                "  trogue achievements 440 --global-only --format tsv\n",
+                "  trogue achievements 440 --format csv --columns name,achieved,global --no-headers\n",
+                "  trogue achievements 440 --remaining --hidden\n",
+                "  trogue achievements 440 --recommend --recommend-count 10\n",
+                "  trogue achievements 440 --streaks --streak-gap 15",
+            ))
             .arg(
                 Arg::new("game")
                     .value_name("game")
                     .action(clap::ArgAction::Set)
-                    .required(true)
-                    .help("The ID of the game or part of game title to list achievements for"),
+                    .required(false)
+                    .help("The ID of the game or part of game title to list achievements for. Defaults to the last used game"),
             )
+            .arg(plugins::game_name_arg())
             .arg(
                 Arg::new("global")
                     .short('g')
@@ -62,6 +155,13 @@ impl Plugin for ListAchievementsPlugin {
                     .action(clap::ArgAction::SetTrue)
                     .help("Adds global achievement percentages for the output of game achievements."),
             )
+            .arg(
+                Arg::new("global_only")
+                    .long("global-only")
+                    .action(clap::ArgAction::SetTrue)
+                    .conflicts_with_all(["remaining", "filter", "since", "until", "sort_date", "summary", "verbose", "changes", "hidden", "recommend", "streaks"])
+                    .help("Displays only the global unlock percentage of each achievement, rarest first, without your own progress. The game id does not need to be in your library."),
+            )
             .arg(
                 Arg::new("remaining")
                     .short('r')
@@ -69,6 +169,150 @@ impl Plugin for ListAchievementsPlugin {
                     .action(clap::ArgAction::SetTrue)
                     .help("Displays only remaining locked achievements."),
             )
+            .arg(
+                Arg::new("filter")
+                    .short('f')
+                    .long("filter")
+                    .value_name("filter")
+                    .help("Displays only achievements whose name contains the given text, ignoring case."),
+            )
+            .arg(plugins::regex_arg())
+            .arg(
+                Arg::new("since")
+                    .long("since")
+                    .value_name("YYYY-MM-DD")
+                    .help("Displays only achievements unlocked on or after the given date (UTC)."),
+            )
+            .arg(
+                Arg::new("until")
+                    .long("until")
+                    .value_name("YYYY-MM-DD")
+                    .help("Displays only achievements unlocked on or before the given date (UTC)."),
+            )
+            .arg(
+                Arg::new("sort_date")
+                    .long("sort-date")
+                    .value_name("direction")
+                    .value_parser(clap::value_parser!(SortDirection))
+                    .help("Sorts achievements by unlock date (asc, desc). Locked achievements are always listed last."),
+            )
+            .arg(
+                Arg::new("group_by")
+                    .long("group-by")
+                    .value_name("key")
+                    .value_parser(clap::value_parser!(GroupBy))
+                    .requires("global")
+                    .help("Groups achievements under headers (rarity: Common, Uncommon, Rare, Ultra Rare). Requires --global. Piped output adds the group as the last column instead."),
+            )
+            .arg(
+                Arg::new("head")
+                    .long("head")
+                    .value_name("n")
+                    .value_parser(clap::value_parser!(usize))
+                    .conflicts_with("tail")
+                    .help("Displays only the first n achievements, after filtering and sorting."),
+            )
+            .arg(
+                Arg::new("tail")
+                    .long("tail")
+                    .value_name("n")
+                    .value_parser(clap::value_parser!(usize))
+                    .help("Displays only the last n achievements, after filtering and sorting."),
+            )
+            .arg(
+                Arg::new("date_format")
+                    .long("date-format")
+                    .value_name("format")
+                    .help("The strftime format of unlock times (overrides TROGUE_DATE_FORMAT, default: %Y-%m-%d %H:%M:%S)."),
+            )
+            .arg(
+                Arg::new("local_time")
+                    .long("local-time")
+                    .action(clap::ArgAction::SetTrue)
+                    .help("Displays unlock times in the local timezone instead of UTC."),
+            )
+            .arg(
+                Arg::new("summary")
+                    .long("summary")
+                    .action(clap::ArgAction::SetTrue)
+                    .help("Prints the number of unlocked achievements after the list, counting all achievements regardless of filters."),
+            )
+            .arg(
+                Arg::new("verbose")
+                    .short('v')
+                    .long("verbose")
+                    .action(clap::ArgAction::SetTrue)
+                    .help("Displays the description of each achievement on an indented line below it."),
+            )
+            .arg(
+                Arg::new("hidden")
+                    .long("hidden")
+                    .action(clap::ArgAction::SetTrue)
+                    .help("Like --verbose, and reveals the descriptions of locked hidden achievements from the game schema, marked [hidden]."),
+            )
+            .arg(
+                Arg::new("format")
+                    .long("format")
+                    .value_name("format")
+                    .value_parser(clap::value_parser!(AchievementsFormat))
+                    .default_value("auto")
+                    .help("The output format (auto, text, tsv, yaml, csv). auto is text in a terminal and tsv otherwise. yaml and csv leave out --global and --group-by. tsv, yaml and csv send the --summary and --streaks lines to stderr."),
+            )
+            .arg(
+                Arg::new("columns")
+                    .long("columns")
+                    .value_name("columns")
+                    .value_parser(clap::value_parser!(CsvColumn))
+                    .value_delimiter(',')
+                    .help("The comma-separated columns of the csv format, in order (apiname, name, description, achieved, unlocktime, global). Default: apiname,name,description,achieved,unlocktime."),
+            )
+            .arg(
+                Arg::new("no_headers")
+                    .long("no-headers")
+                    .action(clap::ArgAction::SetTrue)
+                    .help("Leaves out the header row of the csv format."),
+            )
+            .arg(
+                Arg::new("no_color")
+                    .long("no-color")
+                    .action(clap::ArgAction::SetTrue)
+                    .help("Disables colored output. Colors are also disabled when NO_COLOR is set or the output is not a terminal."),
+            )
+            .arg(
+                Arg::new("recommend")
+                    .long("recommend")
+                    .action(clap::ArgAction::SetTrue)
+                    .conflicts_with_all(["sort_date", "head", "tail", "changes"])
+                    .help("Recommends the locked achievements most players unlocked, the easiest first, with their global percentages (implies --global and --remaining)."),
+            )
+            .arg(
+                Arg::new("recommend_count")
+                    .long("recommend-count")
+                    .value_name("n")
+                    .value_parser(clap::value_parser!(usize))
+                    .requires("recommend")
+                    .help("The number of achievements recommended by --recommend (default: 5)."),
+            )
+            .arg(
+                Arg::new("streaks")
+                    .long("streaks")
+                    .action(clap::ArgAction::SetTrue)
+                    .help("Prints the longest streak of unlocks after the list, each within --streak-gap minutes of the previous one, counting all achievements regardless of filters."),
+            )
+            .arg(
+                Arg::new("streak_gap")
+                    .long("streak-gap")
+                    .value_name("minutes")
+                    .value_parser(clap::value_parser!(u64).range(1..))
+                    .requires("streaks")
+                    .help("The most minutes between two unlocks of the same streak (default: 30)."),
+            )
+            .arg(plugins::changes_arg())
+            .arg(plugins::raw_arg().conflicts_with_all([
+                "global", "global_only", "remaining", "filter", "since", "until", "sort_date", "group_by", "head", "tail",
+                "date_format", "local_time", "summary", "verbose", "hidden", "format", "no_color", "changes", "recommend", "columns",
+                "no_headers", "streaks",
+            ]))
     }
 
     // Executes the `achievements` plugin's logic.
@@ -101,61 +345,113 @@ impl Plugin for ListAchievementsPlugin {
         writer: &mut (dyn Write + Send),
         err_writer: &mut (dyn Write + Send),
     ) {
-        let game_arg = matches.get_one::<String>("game").unwrap();
-        let add_global = matches.get_flag("global");
-        let remaining = matches.get_flag("remaining");
+        let game_arg = match plugins::game_arg_or_last(app_context, matches, "game", err_writer).await {
+            Some(game) => game,
+            None => return,
+        };
+        let recommend = matches.get_flag("recommend");
+        let columns: Vec<CsvColumn> = match matches.get_many::<CsvColumn>("columns") {
+            Some(columns) => columns.copied().collect(),
+            None => DEFAULT_CSV_COLUMNS.to_vec(),
+        };
+        let format = match *matches.get_one::<AchievementsFormat>("format").unwrap() {
+            AchievementsFormat::Auto if app_context.interactive() => AchievementsFormat::Text,
+            AchievementsFormat::Auto => AchievementsFormat::Tsv,
+            format => format,
+        };
+        let add_global = matches.get_flag("global")
+            || recommend
+            || (format == AchievementsFormat::Csv && columns.contains(&CsvColumn::Global));
+        let remaining = matches.get_flag("remaining") || recommend;
+        let sort_date = matches.get_one::<SortDirection>("sort_date").copied();
+        let summary = matches.get_flag("summary");
+        let streak_gap = matches
+            .get_flag("streaks")
+            .then(|| matches.get_one::<u64>("streak_gap").copied().unwrap_or(DEFAULT_STREAK_GAP_MINUTES));
+        let hidden = matches.get_flag("hidden");
+        let verbose = matches.get_flag("verbose") || hidden;
+        let global_only = matches.get_flag("global_only");
+        let group_by = matches.get_one::<GroupBy>("group_by").copied();
+        let raw = matches.get_flag("raw");
+        let changes = matches.get_flag("changes");
+        // Recommendations are the first achievements once sorted by global percentage
+        let head = if recommend {
+            Some(matches.get_one::<usize>("recommend_count").copied().unwrap_or(DEFAULT_RECOMMEND_COUNT))
+        } else {
+            matches.get_one::<usize>("head").copied()
+        };
+        let tail = matches.get_one::<usize>("tail").copied();
+        let name_filter = match matches
+            .get_one::<String>("filter")
+            .map(|f| plugins::NameFilter::new(f, matches.get_flag("regex")))
+            .transpose()
+        {
+            Ok(name_filter) => name_filter,
+            Err(e) => {
+                writeln!(err_writer, "{}", e).unwrap();
+                return;
+            }
+        };
+        let date_style = date_style(app_context, matches, err_writer);
+        let palette = ui::Palette {
+            enabled: ui::colors_enabled(matches.get_flag("no_color"), app_context.interactive()),
+        };
 
-        let games = match app_context.api.get_games_list().await {
-            Ok(g) => g,
+        let since = match parse_date_arg(matches, "since") {
+            Ok(date) => date.map(start_of_day),
+            Err(e) => {
+                writeln!(err_writer, "{}", e).unwrap();
+                return;
+            }
+        };
+        let until = match parse_date_arg(matches, "until") {
+            Ok(date) => date.and_then(|d| d.succ_opt()).map(start_of_day),
             Err(e) => {
-                writeln!(err_writer, "Error while trying to get games list: {}", e).unwrap();
+                writeln!(err_writer, "{}", e).unwrap();
                 return;
             }
         };
 
-        let mut resolved_game_id = None;
+        let game_id = match game_arg.parse::<u32>() {
+            // Rarity and raw responses can be fetched for games outside the library, so ids are used as given
+            Ok(game_id) if global_only || raw => game_id,
+            _ => match resolve_game(app_context, &game_arg, writer, err_writer).await {
+                Some(game_id) => game_id,
+                None => return,
+            },
+        };
 
-        // 1. Try numeric match
-        if let Ok(game_id) = game_arg.parse::<u32>() {
-            if games.iter().any(|g| g.appid == game_id) {
-                resolved_game_id = Some(game_id);
-            }
+        if raw {
+            plugins::write_raw(app_context.api.get_game_achievements_raw(game_id).await, writer, err_writer);
+            return;
         }
 
-        // 2. Try substring match if not resolved
-        if resolved_game_id.is_none() {
-            let matches: Vec<_> = games
-                .iter()
-                .filter(|g| g.name.to_lowercase().contains(&game_arg.to_lowercase()))
-                .collect();
-
-            match matches.len() {
-                0 => {
-                    writeln!(err_writer, "Game not found: {}", game_arg).unwrap();
-                    return;
-                }
-                1 => {
-                    resolved_game_id = Some(matches[0].appid);
-                }
-                _ => {
-                    writeln!(writer, "Multiple games match '{}':", game_arg).unwrap();
-                    for m in matches {
-                        writeln!(writer, " - {}", m.name).unwrap();
-                    }
-                    return;
-                }
-            }
+        if global_only {
+            list_global_only(app_context, game_id, format, &palette, writer, err_writer).await;
+            return;
         }
 
-        let game_id = resolved_game_id.unwrap();
-        let mut achievements = Vec::new();
+        let mut fetched = None;
 
         match app_context.api.get_game_achievements(game_id).await {
-            Ok((_, achs)) => achievements = achs,
-            Err(e) => writeln!(err_writer, "Error while trying to get achievements: {}", e).unwrap(),
+            Ok(game_achievements) => {
+                plugins::remember_game(app_context, game_id, err_writer);
+                fetched = Some(game_achievements);
+            }
+            Err(e) => writeln!(err_writer, "Error while trying to get achievements: {}", ui::scrub_api_key(&e.to_string())).unwrap(),
         }
 
-        let mut global_achievement_map = std::collections::HashMap::new();
+        let mut achievements = fetched.as_ref().map(|g| g.achievements.clone()).unwrap_or_default();
+        let revealed = match &fetched {
+            Some(_) if hidden => reveal_hidden(app_context, game_id, &mut achievements, err_writer).await,
+            _ => HashSet::new(),
+        };
+        let new_unlocks = match &fetched {
+            Some(game_achievements) if changes => Some(new_unlocks(app_context, game_id, &game_achievements.achievements, err_writer)),
+            _ => None,
+        };
+
+        let mut global_achievement_map = HashMap::new();
         if add_global {
             match app_context.api.get_global_achievements(game_id).await {
                 Ok(resp) => {
@@ -164,43 +460,766 @@ impl Plugin for ListAchievementsPlugin {
                             .insert(global_achievement.name.clone(), global_achievement.percent);
                     }
                 }
-                Err(e) => writeln!(err_writer, "Error while trying to get global achievements: {}", e).unwrap(),
+                Err(e) => writeln!(err_writer, "Error while trying to get global achievements: {}", ui::scrub_api_key(&e.to_string())).unwrap(),
+            }
+        }
+
+        // The summary covers the whole game, computed before filters drop achievements
+        let total = achievements.len();
+        let completed = achievements.iter().filter(|a| a.is_unlocked()).count();
+        let streak_line = streak_gap.map(|gap| streak_line(longest_streak(&achievements, gap), &date_style));
+
+        if let Some(direction) = sort_date {
+            sort_by_unlock_date(&mut achievements, direction);
+        }
+        if recommend {
+            sort_by_global_percent(&mut achievements, &global_achievement_map);
+        }
+
+        achievements.retain(|achievement| {
+            !(remaining && achievement.is_unlocked())
+                && unlocked_within(achievement, since, until)
+                && name_filter.as_ref().is_none_or(|f| f.matches(&achievement.name))
+                && new_unlocks.as_ref().is_none_or(|apinames| apinames.contains(&achievement.apiname))
+        });
+
+        let matching = achievements.len();
+        let achievements = truncate(achievements, head, tail);
+        let shown = achievements.len();
+
+        // Stable output for scripts, regardless of the format options
+        if app_context.porcelain() {
+            for achievement in &achievements {
+                let fields = [
+                    achievement.apiname.clone(),
+                    u8::from(achievement.is_unlocked()).to_string(),
+                    achievement.unlocktime.to_string(),
+                ];
+                writeln!(writer, "{}", ui::porcelain_record("achievement", &fields)).unwrap();
+            }
+            if let Some(game_achievements) = fetched {
+                plugins::record_unlocks(app_context, game_id, &game_achievements.game_name, &game_achievements.achievements, err_writer);
+            }
+            return;
+        }
+
+        if format == AchievementsFormat::Yaml || format == AchievementsFormat::Csv {
+            if format == AchievementsFormat::Yaml {
+                write!(writer, "{}", serde_yaml::to_string(&achievements).unwrap()).unwrap();
+            } else {
+                let no_headers = matches.get_flag("no_headers");
+                write_csv(&achievements, &columns, no_headers, &global_achievement_map, &revealed, writer);
+            }
+            // Keeps the output a single document, the requested lines are still printed with --quiet
+            if shown < matching {
+                plugins::notice(app_context, err_writer, &format!("... (showing {} of {})", shown, matching));
+            }
+            if summary {
+                let percentage = ui::completion_percentage(completed, total);
+                writeln!(err_writer, "Unlocked {}/{} ({:.1}%)", completed, total, percentage).unwrap();
+            }
+            if let Some(line) = &streak_line {
+                writeln!(err_writer, "{}", line).unwrap();
+            }
+            if let Some(game_achievements) = fetched {
+                plugins::record_unlocks(app_context, game_id, &game_achievements.game_name, &game_achievements.achievements, err_writer);
+            }
+            return;
+        }
+
+        let groups = match group_by {
+            Some(GroupBy::Rarity) => group_by_rarity(achievements, &global_achievement_map),
+            None => vec![(None, achievements)],
+        };
+        let indent = if group_by.is_some() { "  " } else { "" };
+
+        for (index, (rarity, achievements)) in groups.into_iter().enumerate() {
+            if let Some(rarity) = rarity.filter(|_| format == AchievementsFormat::Text) {
+                if index > 0 {
+                    writeln!(writer).unwrap();
+                }
+                writeln!(writer, "{}", rarity.label()).unwrap();
+            }
+
+            for achievement in achievements {
+                let displayable_achievement = ui::DisplayableAchievement {
+                    achievement,
+                    date_style: date_style.clone(),
+                };
+
+                if format == AchievementsFormat::Tsv {
+                    let mut fields = vec![
+                        ui::tsv_field(&displayable_achievement.achievement.apiname),
+                        ui::tsv_field(&displayable_achievement.achievement.name),
+                        displayable_achievement.format("s"),
+                        if displayable_achievement.achievement.is_unlocked() { displayable_achievement.format("t") } else { String::new() },
+                    ];
+                    if add_global {
+                        let global_percent = global_achievement_map.get(&displayable_achievement.achievement.apiname).unwrap_or(&0.0);
+                        fields.push(global_percent.to_string());
+                    }
+                    if verbose {
+                        fields.push(ui::tsv_field(&description(&displayable_achievement.achievement, &revealed)));
+                    }
+                    if let Some(rarity) = rarity {
+                        fields.push(rarity.label().to_string());
+                    }
+
+                    writeln!(writer, "{}", fields.join("\t")).unwrap();
+                    continue;
+                }
+
+                let mut title: String;
+                if displayable_achievement.achievement.is_unlocked() {
+                    title = palette.unlocked(&displayable_achievement.format("n - s (t)"));
+                } else {
+                    title = palette.locked(&displayable_achievement.format("n"));
+                }
+
+                if add_global {
+                    let global_percent = global_achievement_map
+                        .get(&displayable_achievement.achievement.apiname)
+                        .unwrap_or(&0.0);
+
+                    title.push(' ');
+                    title.push_str(&palette.percentage(&format!("{}%", global_percent)));
+                }
+
+                writeln!(writer, "{}{}", indent, title).unwrap();
+
+                let description = description(&displayable_achievement.achievement, &revealed);
+                if verbose && !description.is_empty() {
+                    writeln!(writer, "{}    {}", indent, description).unwrap();
+                }
             }
         }
 
-        for achievement in achievements {
-            if remaining && achievement.achieved > 0 {
-                continue;
+        if shown < matching {
+            let footer = format!("... (showing {} of {})", shown, matching);
+            // Keeps piped output to achievement rows
+            if format == AchievementsFormat::Tsv {
+                plugins::notice(app_context, err_writer, &footer);
+            } else {
+                writeln!(writer, "{}", footer).unwrap();
             }
+        }
 
-            let displayable_achievement = ui::DisplayableAchievement { achievement };
+        if summary {
+            let percentage = ui::completion_percentage(completed, total);
+            let line = format!("Unlocked {}/{} ({:.1}%)", completed, total, percentage);
+            // Requested output, kept on stderr so that piped output stays achievement rows, even with --quiet
+            if format == AchievementsFormat::Tsv {
+                writeln!(err_writer, "{}", line).unwrap();
+            } else {
+                writeln!(writer, "{}", line).unwrap();
+            }
+        }
 
-            let mut title: String;
-            if displayable_achievement.achievement.achieved > 0 {
-                title = displayable_achievement.format("n - s (t)");
+        if let Some(line) = streak_line {
+            if format == AchievementsFormat::Tsv {
+                writeln!(err_writer, "{}", line).unwrap();
             } else {
-                title = displayable_achievement.format("n");
+                writeln!(writer, "{}", line).unwrap();
             }
+        }
+
+        if let Some(game_achievements) = fetched {
+            plugins::record_unlocks(app_context, game_id, &game_achievements.game_name, &game_achievements.achievements, err_writer);
+        }
+    }
+}
+
+// Represents a streak of unlocks found by `--streaks`.
+#[derive(Debug, PartialEq)]
+struct Streak {
+    count: usize,
+    // The first and last unlocks of the streak
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+}
+
+// Finds the longest streak of unlocks, such as a session in which several achievements fell.
+//
+// <purpose-start>
+// The unlocks are sorted by time and split wherever two consecutive ones are more than
+// `gap_minutes` apart. A single unlock is a streak of one. Among streaks of the same length the
+// earliest wins. Achievements without an unlock time are left out.
+// <purpose-end>
+//
+// <inputs-start>
+// - `achievements`: The achievements of the game, in any order.
+// - `gap_minutes`: The most minutes between two unlocks of the same streak.
+// <inputs-end>
+//
+// <outputs-start>
+// - `Option<Streak>`: The longest streak, none if no achievement has an unlock time.
+// <outputs-end>
+fn longest_streak(achievements: &[Achievement], gap_minutes: u64) -> Option<Streak> {
+    let mut unlocks: Vec<DateTime<Utc>> = achievements.iter().filter_map(Achievement::unlock_datetime).collect();
+    unlocks.sort();
+    let gap = chrono::Duration::minutes(i64::try_from(gap_minutes).unwrap_or(i64::MAX));
+
+    let mut longest: Option<Streak> = None;
+    let mut start = 0;
+    for index in 0..unlocks.len() {
+        if index > 0 && unlocks[index] - unlocks[index - 1] > gap {
+            start = index;
+        }
+        let count = index - start + 1;
+        if longest.as_ref().is_none_or(|streak| count > streak.count) {
+            longest = Some(Streak { count, start: unlocks[start], end: unlocks[index] });
+        }
+    }
+    longest
+}
+
+// Formats the `--streaks` line, such as `Longest streak: 3 unlocks in 42 minutes, from ... to ...`.
+fn streak_line(streak: Option<Streak>, date_style: &ui::DateStyle) -> String {
+    match streak {
+        Some(streak) => format!(
+            "Longest streak: {} unlock{} in {} minutes, from {} to {}",
+            streak.count,
+            if streak.count == 1 { "" } else { "s" },
+            (streak.end - streak.start).num_minutes(),
+            date_style.format(streak.start),
+            date_style.format(streak.end)
+        ),
+        None => "Longest streak: none, no achievement has an unlock time".to_string(),
+    }
+}
+
+// Writes the achievements as comma-separated values.
+//
+// <purpose-start>
+// Backs the csv format. Only the requested columns are written, in the requested order, so that
+// the output can be fed to spreadsheets and scripts expecting a given layout.
+// <purpose-end>
+//
+// <inputs-start>
+// - `achievements`: The achievements to write, already filtered and sorted.
+// - `columns`: The columns to write, in order.
+// - `no_headers`: Whether to leave out the header row.
+// - `global_achievement_map`: The global unlock percentages by api name, used by the global column.
+// - `revealed`: The api names of the hidden achievements whose description was revealed.
+// - `writer`: A mutable reference to a writer for standard output.
+// <inputs-end>
+//
+// <outputs-start>
+// - None.
+// <outputs-end>
+//
+// <side-effects-start>
+// - Writes the header row, unless `no_headers` is set, and one row per achievement to `writer`.
+// <side-effects-end>
+fn write_csv(
+    achievements: &[Achievement],
+    columns: &[CsvColumn],
+    no_headers: bool,
+    global_achievement_map: &HashMap<String, f32>,
+    revealed: &HashSet<String>,
+    writer: &mut (dyn Write + Send),
+) {
+    if !no_headers {
+        let header: Vec<&str> = columns.iter().map(|c| c.header()).collect();
+        writeln!(writer, "{}", header.join(",")).unwrap();
+    }
+
+    for achievement in achievements {
+        let fields: Vec<String> = columns
+            .iter()
+            .map(|column| match column {
+                CsvColumn::Apiname => ui::csv_field(&achievement.apiname),
+                CsvColumn::Name => ui::csv_field(&achievement.name),
+                CsvColumn::Description => ui::csv_field(&description(achievement, revealed)),
+                CsvColumn::Achieved => achievement.achieved.to_string(),
+                CsvColumn::Unlocktime => achievement.unlocktime.to_string(),
+                CsvColumn::Global => global_achievement_map.get(&achievement.apiname).unwrap_or(&0.0).to_string(),
+            })
+            .collect();
+        writeln!(writer, "{}", fields.join(",")).unwrap();
+    }
+}
+
+// Returns the api names of the achievements unlocked since the previous run.
+//
+// <purpose-start>
+// Backs the `--changes` flag. The first run for a game has nothing to compare against, so no
+// achievement is new and a notice says so.
+// <purpose-end>
+//
+// <inputs-start>
+// - `app_context`: The shared application context, providing the unlock history.
+// - `game_id`: The ID of the game.
+// - `achievements`: The achievements just fetched.
+// - `err_writer`: A mutable reference to a writer for standard error.
+// <inputs-end>
+//
+// <outputs-start>
+// - `HashSet<String>`: The api names of the newly unlocked achievements.
+// <outputs-end>
+//
+// <side-effects-start>
+// - Reads the file of the game in the unlock history.
+// - Writes a notice to `err_writer` if the game was never recorded, unless `--quiet` was given.
+// <side-effects-end>
+fn new_unlocks(
+    app_context: &AppContext,
+    game_id: u32,
+    achievements: &[Achievement],
+    err_writer: &mut (dyn Write + Send),
+) -> HashSet<String> {
+    let Some(previous) = app_context.previous_unlocks(game_id) else {
+        plugins::notice(app_context, err_writer, "No earlier record of this game, changes are listed from the next run");
+        return HashSet::new();
+    };
+
+    snapshot::new_unlocks(&previous.achievements, achievements)
+        .into_iter()
+        .map(|a| a.apiname.clone())
+        .collect()
+}
+
+// Fills in the descriptions Steam hides until an achievement is unlocked.
+//
+// <purpose-start>
+// Backs the `--hidden` flag. The player achievements leave the description of locked hidden
+// achievements empty, the schema of the game has it. Unlocked achievements keep their description.
+// Failing to get the schema is reported and the list is displayed as is.
+// <purpose-end>
+//
+// <inputs-start>
+// - `app_context`: The shared application context.
+// - `game_id`: The ID of the game.
+// - `achievements`: The achievements just fetched, updated in place.
+// - `err_writer`: A mutable reference to a writer for standard error.
+// <inputs-end>
+//
+// <outputs-start>
+// - `HashSet<String>`: The api names of the achievements whose description was revealed.
+// <outputs-end>
+//
+// <side-effects-start>
+// - Makes a network request to the Steam API to fetch the schema of the game.
+// - Writes errors to `err_writer`.
+// <side-effects-end>
+async fn reveal_hidden(
+    app_context: &AppContext,
+    game_id: u32,
+    achievements: &mut [Achievement],
+    err_writer: &mut (dyn Write + Send),
+) -> HashSet<String> {
+    let schema = match app_context.api.get_game_schema(game_id).await {
+        Ok(schema) => schema,
+        Err(e) => {
+            writeln!(err_writer, "Error while trying to get achievement schema: {}", ui::scrub_api_key(&e.to_string())).unwrap();
+            return HashSet::new();
+        }
+    };
+
+    let descriptions: HashMap<&str, &str> = schema
+        .iter()
+        .filter(|a| !a.description.is_empty())
+        .map(|a| (a.name.as_str(), a.description.as_str()))
+        .collect();
+
+    let mut revealed = HashSet::new();
+    for achievement in achievements.iter_mut().filter(|a| !a.is_unlocked() && a.description.is_empty()) {
+        if let Some(description) = descriptions.get(achievement.apiname.as_str()) {
+            achievement.description = description.to_string();
+            revealed.insert(achievement.apiname.clone());
+        }
+    }
+
+    revealed
+}
 
-            if add_global {
-                let global_percent = global_achievement_map
-                    .get(&displayable_achievement.achievement.apiname)
-                    .unwrap_or(&0.0);
+// Returns the description of an achievement as displayed, marked if `--hidden` revealed it.
+fn description(achievement: &Achievement, revealed: &HashSet<String>) -> String {
+    if revealed.contains(&achievement.apiname) {
+        format!("{}{}", HIDDEN_MARKER, achievement.description)
+    } else {
+        achievement.description.clone()
+    }
+}
+
+// Splits achievements into rarity tiers.
+//
+// <purpose-start>
+// Backs `--group-by rarity`. Achievements missing from the global statistics count as 0%, as
+// they are displayed. The order of the achievements is kept within each tier.
+// <purpose-end>
+//
+// <inputs-start>
+// - `achievements`: The filtered and sorted achievements.
+// - `global_percentages`: The global unlock percentages, by achievement api name.
+// <inputs-end>
+//
+// <outputs-start>
+// - The non-empty tiers with their achievements, from the most common to the rarest.
+// <outputs-end>
+fn group_by_rarity(
+    achievements: Vec<Achievement>,
+    global_percentages: &HashMap<String, f32>,
+) -> Vec<(Option<ui::Rarity>, Vec<Achievement>)> {
+    let mut groups: Vec<(Option<ui::Rarity>, Vec<Achievement>)> =
+        ui::Rarity::ALL.iter().map(|rarity| (Some(*rarity), Vec::new())).collect();
+
+    for achievement in achievements {
+        let percent = global_percentages.get(&achievement.apiname).copied().unwrap_or(0.0);
+        let rarity = ui::Rarity::from_percent(percent);
+        let tier = ui::Rarity::ALL.iter().position(|r| *r == rarity).unwrap();
+        groups[tier].1.push(achievement);
+    }
+
+    groups.retain(|(_, achievements)| !achievements.is_empty());
+    groups
+}
+
+// Keeps the first or the last achievements of a list.
+//
+// <inputs-start>
+// - `achievements`: The filtered and sorted achievements.
+// - `head`: The number of achievements to keep from the start, if any.
+// - `tail`: The number of achievements to keep from the end, if any. Ignored when `head` is given.
+// <inputs-end>
+//
+// <outputs-start>
+// - `Vec<Achievement>`: The kept achievements, in their original order.
+// <outputs-end>
+fn truncate(mut achievements: Vec<Achievement>, head: Option<usize>, tail: Option<usize>) -> Vec<Achievement> {
+    if let Some(head) = head {
+        achievements.truncate(head);
+    } else if let Some(tail) = tail {
+        achievements.drain(..achievements.len().saturating_sub(tail));
+    }
+    achievements
+}
+
+// Resolves the game argument to a game id from the user's library.
+//
+// <purpose-start>
+// A numeric argument matching an owned game is used as is, anything else is matched against the
+// game names, ignoring case. Ambiguous names list the matching games instead.
+// <purpose-end>
+//
+// <inputs-start>
+// - `app_context`: The shared application context.
+// - `game_arg`: The game id or part of the game name.
+// - `writer`: A mutable reference to a writer for standard output.
+// - `err_writer`: A mutable reference to a writer for standard error.
+// <inputs-end>
+//
+// <outputs-start>
+// - `Option<u32>`: The id of the game, `None` if it could not be resolved, which has been reported.
+// <outputs-end>
+//
+// <side-effects-start>
+// - Makes a network request to the Steam API to fetch the games list.
+// - Writes the matching games to `writer` when the name is ambiguous, and errors to `err_writer`.
+// <side-effects-end>
+async fn resolve_game(
+    app_context: &AppContext,
+    game_arg: &str,
+    writer: &mut (dyn Write + Send),
+    err_writer: &mut (dyn Write + Send),
+) -> Option<u32> {
+    let games = match app_context.get_games_list_cached().await {
+        Ok(g) => g,
+        Err(e) => {
+            writeln!(err_writer, "Error while trying to get games list: {}", ui::scrub_api_key(&e.to_string())).unwrap();
+            return None;
+        }
+    };
+
+    let mut resolved_game_id = None;
 
-                title.push_str(&format!(" {}%", global_percent));
+    // 1. Try numeric match
+    if let Ok(game_id) = game_arg.parse::<u32>() {
+        if games.iter().any(|g| g.appid == game_id) {
+            resolved_game_id = Some(game_id);
+        }
+    }
+
+    // 2. Try substring match if not resolved
+    if resolved_game_id.is_none() {
+        let matches: Vec<_> = games
+            .iter()
+            .filter(|g| g.name.to_lowercase().contains(&game_arg.to_lowercase()))
+            .collect();
+
+        match matches.len() {
+            0 => {
+                writeln!(err_writer, "Game not found: {}", game_arg).unwrap();
+                return None;
+            }
+            1 => {
+                resolved_game_id = Some(matches[0].appid);
             }
+            _ => {
+                writeln!(writer, "Multiple games match '{}':", game_arg).unwrap();
+                for m in matches {
+                    writeln!(writer, " - {}", m.name).unwrap();
+                }
+                return None;
+            }
+        }
+    }
+
+    resolved_game_id
+}
+
+// Lists the global unlock percentages of a game's achievements, rarest first.
+//
+// <purpose-start>
+// Used by `--global-only` to browse the rarity of a game's achievements without the player's own
+// progress, so the player achievements are never requested.
+// <purpose-end>
+//
+// <inputs-start>
+// - `app_context`: The shared application context.
+// - `game_id`: The id of the game.
+// - `format`: The resolved output format, text or tab-separated values.
+// - `palette`: The colors of the text format.
+// - `writer`: A mutable reference to a writer for standard output.
+// - `err_writer`: A mutable reference to a writer for standard error.
+// <inputs-end>
+//
+// <side-effects-start>
+// - Makes a network request to the Steam API to fetch the global achievement percentages.
+// - Writes one achievement per line to `writer`, and errors to `err_writer`.
+// <side-effects-end>
+async fn list_global_only(
+    app_context: &AppContext,
+    game_id: u32,
+    format: AchievementsFormat,
+    palette: &ui::Palette,
+    writer: &mut (dyn Write + Send),
+    err_writer: &mut (dyn Write + Send),
+) {
+    let mut global_achievements = match app_context.api.get_global_achievements(game_id).await {
+        Ok(global_achievements) => global_achievements,
+        Err(e) => {
+            writeln!(err_writer, "Error while trying to get global achievements: {}", ui::scrub_api_key(&e.to_string())).unwrap();
+            return;
+        }
+    };
 
-            writeln!(writer, "{}", title).unwrap();
+    global_achievements.sort_by(|a, b| a.percent.total_cmp(&b.percent));
+
+    for global_achievement in global_achievements {
+        if format == AchievementsFormat::Tsv {
+            writeln!(writer, "{}\t{}", ui::tsv_field(&global_achievement.name), global_achievement.percent).unwrap();
+        } else {
+            let percent = palette.percentage(&format!("{}%", global_achievement.percent));
+            writeln!(writer, "{} {}", global_achievement.name, percent).unwrap();
+        }
+    }
+}
+
+// Resolves how unlock times are rendered.
+//
+// <purpose-start>
+// The `--date-format` flag takes precedence over the `TROGUE_DATE_FORMAT` environment variable.
+// An invalid format string is reported as a warning and the default format is used instead,
+// as a cosmetic setting should not prevent listing achievements.
+// <purpose-end>
+//
+// <inputs-start>
+// - `app_context`: The shared application context, telling whether output is quiet.
+// - `matches`: The clap argument matches for the `achievements` subcommand.
+// - `err_writer`: A mutable reference to a writer for standard error.
+// <inputs-end>
+//
+// <outputs-start>
+// - `ui::DateStyle`: The resolved date style.
+// <outputs-end>
+//
+// <side-effects-start>
+// - Reads the `TROGUE_DATE_FORMAT` environment variable.
+// - Writes a warning to the error writer if the format string is invalid, unless `--quiet` was given.
+// <side-effects-end>
+fn date_style(app_context: &AppContext, matches: &clap::ArgMatches, err_writer: &mut (dyn Write + Send)) -> ui::DateStyle {
+    let mut date_style = ui::DateStyle {
+        local_time: matches.get_flag("local_time"),
+        ..ui::DateStyle::default()
+    };
+
+    let format = matches
+        .get_one::<String>("date_format")
+        .cloned()
+        .or_else(|| Cfg::read_env("TROGUE_DATE_FORMAT").ok());
+
+    if let Some(format) = format {
+        match ui::parse_date_format(&format) {
+            Ok(format) => date_style.format = format,
+            Err(e) => plugins::notice(app_context, err_writer, &format!("Warning: {}, using the default date format", e)),
         }
     }
+
+    date_style
+}
+
+// Parses an optional `YYYY-MM-DD` date argument.
+//
+// <purpose-start>
+// Date filters are validated up front so that a typo produces a clear message instead of silently
+// returning an empty list.
+// <purpose-end>
+//
+// <inputs-start>
+// - `matches`: The clap argument matches for the `achievements` subcommand.
+// - `id`: The id of the date argument to parse.
+// <inputs-end>
+//
+// <outputs-start>
+// - `Ok(Some(NaiveDate))`: If the argument was provided and is a valid date.
+// - `Ok(None)`: If the argument was not provided.
+// - `Err(String)`: A user-facing message if the argument is not a valid date.
+// <outputs-end>
+//
+// <side-effects-start>
+// - None.
+// <side-effects-end>
+fn parse_date_arg(matches: &clap::ArgMatches, id: &str) -> Result<Option<NaiveDate>, String> {
+    match matches.get_one::<String>(id) {
+        Some(value) => NaiveDate::parse_from_str(value, "%Y-%m-%d")
+            .map(Some)
+            .map_err(|_| format!("Invalid --{} date: {}. Expected format: YYYY-MM-DD", id, value)),
+        None => Ok(None),
+    }
+}
+
+// Converts a date to the first moment of that day in UTC.
+fn start_of_day(date: NaiveDate) -> DateTime<Utc> {
+    date.and_hms_opt(0, 0, 0).unwrap().and_utc()
+}
+
+// Returns the unlock time used to sort an achievement by date.
+//
+// <purpose-start>
+// Locked achievements have an unlock time of 0, which would place them before every real unlock.
+// They have no date instead, as do unlocked achievements without a recorded unlock time.
+// <purpose-end>
+//
+// <inputs-start>
+// - `achievement`: The achievement to get the sort key of.
+// <inputs-end>
+//
+// <outputs-start>
+// - `Some(u64)`: The unlock time as a Unix timestamp.
+// - `None`: If the achievement has no unlock date.
+// <outputs-end>
+//
+// <side-effects-start>
+// - None.
+// <side-effects-end>
+fn sort_key_unlocktime(achievement: &Achievement) -> Option<u64> {
+    if achievement.is_unlocked() && achievement.unlocktime > 0 {
+        Some(achievement.unlocktime)
+    } else {
+        None
+    }
+}
+
+// Sorts achievements by unlock date in the given direction.
+//
+// <purpose-start>
+// Achievements without a date trail the dated ones in both directions. The sort is stable, so
+// they keep the order of the API response.
+// <purpose-end>
+//
+// <inputs-start>
+// - `achievements`: The achievements to sort in place.
+// - `direction`: Whether the oldest or the newest unlocks come first.
+// <inputs-end>
+//
+// <outputs-start>
+// - None.
+// <outputs-end>
+//
+// <side-effects-start>
+// - None.
+// <side-effects-end>
+fn sort_by_unlock_date(achievements: &mut [Achievement], direction: SortDirection) {
+    achievements.sort_by(|a, b| match (sort_key_unlocktime(a), sort_key_unlocktime(b)) {
+        (Some(a), Some(b)) => match direction {
+            SortDirection::Asc => a.cmp(&b),
+            SortDirection::Desc => b.cmp(&a),
+        },
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => std::cmp::Ordering::Equal,
+    });
 }
 
+// Sorts achievements from the most to the least unlocked by all players.
+//
+// <purpose-start>
+// Backs `--recommend`, listing the easiest achievements first. Achievements missing from the
+// global statistics count as 0%, as they are displayed. The sort is stable.
+// <purpose-end>
+//
+// <inputs-start>
+// - `achievements`: The achievements to sort in place.
+// - `global_percentages`: The global unlock percentages, by achievement api name.
+// <inputs-end>
+//
+// <outputs-start>
+// - None.
+// <outputs-end>
+//
+// <side-effects-start>
+// - None.
+// <side-effects-end>
+fn sort_by_global_percent(achievements: &mut [Achievement], global_percentages: &HashMap<String, f32>) {
+    let percent = |achievement: &Achievement| global_percentages.get(&achievement.apiname).copied().unwrap_or(0.0);
+    achievements.sort_by(|a, b| percent(b).total_cmp(&percent(a)));
+}
+
+// Checks whether an achievement was unlocked within the requested window.
+//
+// <purpose-start>
+// Achievements without an unlock date cannot be placed in any window, so they are excluded as
+// soon as any bound is set. Without bounds, every achievement passes.
+// <purpose-end>
+//
+// <inputs-start>
+// - `achievement`: The achievement to check.
+// - `since`: The inclusive lower bound, if any.
+// - `until`: The exclusive upper bound, if any.
+// <inputs-end>
+//
+// <outputs-start>
+// - `bool`: `true` if the achievement should be displayed.
+// <outputs-end>
+//
+// <side-effects-start>
+// - None.
+// <side-effects-end>
+fn unlocked_within(
+    achievement: &Achievement,
+    since: Option<DateTime<Utc>>,
+    until: Option<DateTime<Utc>>,
+) -> bool {
+    if since.is_none() && until.is_none() {
+        return true;
+    }
+
+    match achievement.unlock_datetime() {
+        Some(unlocked_at) => {
+            since.is_none_or(|s| unlocked_at >= s) && until.is_none_or(|u| unlocked_at < u)
+        }
+        None => false,
+    }
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::app::AppContext;
-    use crate::steam_api::{Api, Achievement, GlobalAchievement, Game};
+    use crate::fake_api::FakeApi;
+    use crate::state::UnlockHistoryStore;
+    use crate::steam_api::{Api, Achievement, GlobalAchievement, Game, SchemaAchievement};
     use clap::ArgMatches;
 
     fn create_mock_game(appid: u32, name: &str) -> Game {
@@ -227,6 +1246,13 @@ mod tests {
         }
     }
 
+    fn create_mock_unlocked_achievement(apiname: &str, name: &str, unlocktime: u64) -> Achievement {
+        Achievement {
+            unlocktime,
+            ..create_mock_achievement(apiname, name, 1)
+        }
+    }
+
     fn create_mock_global_achievement(name: &str, percent: f32) -> GlobalAchievement {
         GlobalAchievement {
             name: name.to_string(),
@@ -260,7 +1286,8 @@ mod tests {
             .create_async().await;
 
         let api = Api::new("test_key".to_string(), "test_id".to_string(), server.url());
-        let app_context = AppContext { api };
+        // As if printing to a terminal, tests of piped output opt out
+        let app_context = AppContext::with_api(api).with_interactive(true);
         (app_context, server)
     }
 
@@ -298,6 +1325,15 @@ mod tests {
         assert!(cmd.get_arguments().any(|arg| arg.get_id() == "remaining"));
     }
 
+    #[test]
+    fn test_help_shows_examples() {
+        let error = ListAchievementsPlugin.command().try_get_matches_from(["achievements", "--help"]).unwrap_err();
+        assert_eq!(error.kind(), clap::error::ErrorKind::DisplayHelp);
+
+        let help = error.to_string();
+        assert!(help.contains("Examples:\n  trogue achievements 440 --global --remaining\n"));
+    }
+
     #[tokio::test]
     async fn test_execute_success() {
         let achievements = vec![
@@ -324,6 +1360,27 @@ mod tests {
         assert!(output.contains("Second Achievement"));
     }
 
+    #[tokio::test]
+    async fn test_execute_shows_apiname_when_name_is_missing() {
+        let mock_body = serde_json::json!({
+            "playerstats": {
+                "steamID": "test_id",
+                "gameName": "Test Game",
+                "achievements": [{ "apiname": "ACH_UNTRANSLATED", "achieved": 1, "unlocktime": 0 }],
+                "success": true
+            }
+        }).to_string();
+        let (app_context, _server) = setup_test_env_game_achievements(&mock_body, 200).await;
+        let matches = get_matches_for_args(&["achievements", "123"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListAchievementsPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        assert!(String::from_utf8(writer).unwrap().contains("ACH_UNTRANSLATED"));
+        assert!(String::from_utf8(err_writer).unwrap().is_empty());
+    }
+
     #[tokio::test]
     async fn test_execute_game_not_found() {
         let games_body = serde_json::to_string(&serde_json::json!({
@@ -437,9 +1494,14 @@ mod tests {
         assert!(output.contains("10.2%"));
     }
 
-    #[tokio::test]
-    async fn test_execute_with_global_stats_api_error() {
-        let game_achievements = vec![create_mock_achievement("ach1", "First Achievement", 1)];
+    async fn setup_test_env_rarity() -> (AppContext, mockito::ServerGuard) {
+        let game_achievements = vec![
+            create_mock_achievement("common", "Common One", 1),
+            create_mock_achievement("rare", "Rare One", 0),
+            create_mock_achievement("ultra", "Ultra One", 0),
+            create_mock_achievement("common2", "Common Two", 0),
+            create_mock_achievement("unknown", "Unknown One", 0),
+        ];
         let game_ach_body = serde_json::to_string(&serde_json::json!({
             "playerstats": {
                 "steamID": "test_id",
@@ -448,29 +1510,105 @@ mod tests {
                 "success": true
             }
         })).unwrap();
+        let global_achievements = vec![
+            create_mock_global_achievement("common", 80.0),
+            create_mock_global_achievement("rare", 5.0),
+            create_mock_global_achievement("ultra", 0.5),
+            create_mock_global_achievement("common2", 50.0),
+        ];
+        let global_ach_body = serde_json::to_string(&serde_json::json!({
+            "achievementpercentages": { "achievements": global_achievements }
+        })).unwrap();
 
-        let (app_context, _server) = setup_test_env_with_global(&game_ach_body, 200, "", 500).await;
-        let matches = get_matches_for_args(&["achievements", "123", "--global"]);
+        setup_test_env_with_global(&game_ach_body, 200, &global_ach_body, 200).await
+    }
+
+    #[tokio::test]
+    async fn test_execute_group_by_rarity() {
+        let (app_context, _server) = setup_test_env_rarity().await;
+        let matches = get_matches_for_args(&["achievements", "123", "--global", "--group-by", "rarity", "--no-color"]);
         let mut writer = Vec::new();
         let mut err_writer = Vec::new();
 
         ListAchievementsPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
 
-        let err_output = String::from_utf8(err_writer).unwrap();
-        assert!(err_output.contains("Error while trying to get global achievements"));
-
         let output = String::from_utf8(writer).unwrap();
-        assert!(output.contains("First Achievement"));
+        let sections: Vec<Vec<&str>> = output.split("\n\n").map(|section| section.lines().collect()).collect();
+        assert_eq!(sections.len(), 3);
+        assert_eq!(sections[0][0], "Common");
+        assert!(sections[0][1].starts_with("  Common One - Y ("));
+        assert_eq!(sections[0][2], "  Common Two 50%");
+        assert_eq!(sections[1], vec!["Rare", "  Rare One 5%"]);
+        assert_eq!(sections[2], vec!["Ultra Rare", "  Ultra One 0.5%", "  Unknown One 0%"]);
+        assert!(!output.contains("Uncommon"));
     }
 
     #[tokio::test]
-    async fn test_execute_substring_success() {
-        let games = vec![create_mock_game(123, "Specific Game Title")];
-        let games_body = serde_json::to_string(&serde_json::json!({
-            "response": { "game_count": 1, "games": games }
-        })).unwrap();
-        let achievements = vec![create_mock_achievement("ach1", "Achievement 1", 1)];
-        let ach_body = serde_json::to_string(&serde_json::json!({
+    async fn test_execute_group_by_rarity_when_piped() {
+        let (app_context, _server) = setup_test_env_rarity().await;
+        let app_context = app_context.with_interactive(false);
+        let matches = get_matches_for_args(&["achievements", "123", "--global", "--group-by", "rarity"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListAchievementsPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        let groups: Vec<(&str, &str)> = output
+            .lines()
+            .map(|line| {
+                let fields: Vec<&str> = line.split('\t').collect();
+                (fields[0], *fields.last().unwrap())
+            })
+            .collect();
+        assert_eq!(
+            groups,
+            vec![("common", "Common"), ("common2", "Common"), ("rare", "Rare"), ("ultra", "Ultra Rare"), ("unknown", "Ultra Rare")]
+        );
+    }
+
+    #[test]
+    fn test_group_by_requires_global() {
+        let result = ListAchievementsPlugin.command().try_get_matches_from(["achievements", "123", "--group-by", "rarity"]);
+        let error = result.unwrap_err();
+        assert_eq!(error.kind(), clap::error::ErrorKind::MissingRequiredArgument);
+        assert!(error.to_string().contains("--global"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_global_stats_api_error() {
+        let game_achievements = vec![create_mock_achievement("ach1", "First Achievement", 1)];
+        let game_ach_body = serde_json::to_string(&serde_json::json!({
+            "playerstats": {
+                "steamID": "test_id",
+                "gameName": "Test Game",
+                "achievements": game_achievements,
+                "success": true
+            }
+        })).unwrap();
+
+        let (app_context, _server) = setup_test_env_with_global(&game_ach_body, 200, "", 500).await;
+        let matches = get_matches_for_args(&["achievements", "123", "--global"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListAchievementsPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let err_output = String::from_utf8(err_writer).unwrap();
+        assert!(err_output.contains("Error while trying to get global achievements"));
+
+        let output = String::from_utf8(writer).unwrap();
+        assert!(output.contains("First Achievement"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_substring_success() {
+        let games = vec![create_mock_game(123, "Specific Game Title")];
+        let games_body = serde_json::to_string(&serde_json::json!({
+            "response": { "game_count": 1, "games": games }
+        })).unwrap();
+        let achievements = vec![create_mock_achievement("ach1", "Achievement 1", 1)];
+        let ach_body = serde_json::to_string(&serde_json::json!({
             "playerstats": {
                 "steamID": "test_id",
                 "gameName": "Specific Game Title",
@@ -541,7 +1679,7 @@ mod tests {
             .create_async().await;
 
         let api = Api::new("test_key".to_string(), "test_id".to_string(), server.url());
-        let app_context = AppContext { api };
+        let app_context = AppContext::with_api(api).with_interactive(true);
 
         let matches = get_matches_for_args(&["achievements", "123"]);
         let mut writer = Vec::new();
@@ -552,4 +1690,858 @@ mod tests {
         let output = String::from_utf8(writer).unwrap();
         assert!(output.contains("Achievement from fallback"));
     }
-}
\ No newline at end of file
+
+    fn dated_achievements_body() -> String {
+        let achievements = vec![
+            create_mock_unlocked_achievement("ach1", "January First", 1672531200), // 2023-01-01 00:00:00
+            create_mock_unlocked_achievement("ach2", "Mid January", 1673740800 + 3600), // 2023-01-15 01:00:00
+            create_mock_unlocked_achievement("ach3", "February First", 1675209600), // 2023-02-01 00:00:00
+            create_mock_achievement("ach4", "Still Locked", 0),
+        ];
+        serde_json::to_string(&serde_json::json!({
+            "playerstats": {
+                "steamID": "test_id",
+                "gameName": "Test Game",
+                "achievements": achievements,
+                "success": true
+            }
+        })).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_since_filter() {
+        let (app_context, _server) = setup_test_env_game_achievements(&dated_achievements_body(), 200).await;
+        let matches = get_matches_for_args(&["achievements", "123", "--since", "2023-01-15"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListAchievementsPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        assert!(!output.contains("January First"));
+        assert!(output.contains("Mid January"));
+        assert!(output.contains("February First"));
+        assert!(!output.contains("Still Locked"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_until_filter() {
+        let (app_context, _server) = setup_test_env_game_achievements(&dated_achievements_body(), 200).await;
+        let matches = get_matches_for_args(&["achievements", "123", "--until", "2023-01-15"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListAchievementsPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        assert!(output.contains("January First"));
+        assert!(output.contains("Mid January"));
+        assert!(!output.contains("February First"));
+        assert!(!output.contains("Still Locked"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_date_range() {
+        let (app_context, _server) = setup_test_env_game_achievements(&dated_achievements_body(), 200).await;
+        let matches = get_matches_for_args(&["achievements", "123", "--since", "2023-01-02", "--until", "2023-01-31"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListAchievementsPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        assert!(!output.contains("January First"));
+        assert!(output.contains("Mid January"));
+        assert!(!output.contains("February First"));
+        assert!(!output.contains("Still Locked"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_invalid_date() {
+        let (app_context, _server) = setup_test_env_game_achievements(&dated_achievements_body(), 200).await;
+        let matches = get_matches_for_args(&["achievements", "123", "--since", "2023-13-45"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListAchievementsPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let err_output = String::from_utf8(err_writer).unwrap();
+        assert!(err_output.contains("Invalid --since date: 2023-13-45"));
+        assert!(writer.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_date_format() {
+        let (app_context, _server) = setup_test_env_game_achievements(&dated_achievements_body(), 200).await;
+        let matches = get_matches_for_args(&["achievements", "123", "--date-format", "%d.%m.%Y"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListAchievementsPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        assert!(output.contains("January First - Y (01.01.2023)"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_invalid_date_format() {
+        let (app_context, _server) = setup_test_env_game_achievements(&dated_achievements_body(), 200).await;
+        let matches = get_matches_for_args(&["achievements", "123", "--date-format", "%Q"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListAchievementsPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        let err_output = String::from_utf8(err_writer).unwrap();
+        assert!(err_output.contains("Warning: '%Q' is not a valid strftime format"));
+        assert!(output.contains("January First - Y (2023-01-01 00:00:00)"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_summary() {
+        let (app_context, _server) = setup_test_env_game_achievements(&dated_achievements_body(), 200).await;
+        let matches = get_matches_for_args(&["achievements", "123", "--remaining", "--summary"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListAchievementsPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        assert!(!output.contains("January First"));
+        assert!(output.contains("Still Locked"));
+        assert!(output.ends_with("Unlocked 3/4 (75.0%)\n"));
+    }
+
+    // 2024-03-01 20:00:00 UTC
+    const STREAK_BASE: u64 = 1_709_323_200;
+
+    fn create_streak_achievements(minutes: &[u64]) -> Vec<Achievement> {
+        minutes
+            .iter()
+            .enumerate()
+            .map(|(index, minute)| create_mock_unlocked_achievement(&format!("a{}", index), "Unlocked", STREAK_BASE + minute * 60))
+            .collect()
+    }
+
+    fn utc(minute: u64) -> DateTime<Utc> {
+        DateTime::from_timestamp((STREAK_BASE + minute * 60) as i64, 0).unwrap()
+    }
+
+    #[test]
+    fn test_longest_streak() {
+        // Out of order, with sessions at 0-20, 200-290 and 1000 minutes
+        let achievements = create_streak_achievements(&[230, 0, 1000, 10, 200, 290, 20, 260]);
+
+        assert_eq!(longest_streak(&achievements, 30), Some(Streak { count: 4, start: utc(200), end: utc(290) }));
+        // A gap of exactly the limit still continues the streak
+        assert_eq!(longest_streak(&achievements, 10), Some(Streak { count: 3, start: utc(0), end: utc(20) }));
+        assert_eq!(longest_streak(&achievements, 1000), Some(Streak { count: 8, start: utc(0), end: utc(1000) }));
+    }
+
+    #[test]
+    fn test_longest_streak_ignores_locked_and_undated_achievements() {
+        let mut achievements = create_streak_achievements(&[0, 5]);
+        achievements.push(create_mock_achievement("locked", "Locked", 0));
+        achievements.push(create_mock_unlocked_achievement("undated", "Undated", 0));
+
+        assert_eq!(longest_streak(&achievements, 30), Some(Streak { count: 2, start: utc(0), end: utc(5) }));
+        assert_eq!(longest_streak(&achievements[2..], 30), None);
+        assert_eq!(longest_streak(&create_streak_achievements(&[60]), 30), Some(Streak { count: 1, start: utc(60), end: utc(60) }));
+    }
+
+    #[test]
+    fn test_streak_line() {
+        let date_style = ui::DateStyle { format: "%H:%M".to_string(), local_time: false };
+
+        assert_eq!(
+            streak_line(Some(Streak { count: 3, start: utc(0), end: utc(42) }), &date_style),
+            "Longest streak: 3 unlocks in 42 minutes, from 20:00 to 20:42"
+        );
+        assert_eq!(
+            streak_line(Some(Streak { count: 1, start: utc(0), end: utc(0) }), &date_style),
+            "Longest streak: 1 unlock in 0 minutes, from 20:00 to 20:00"
+        );
+        assert_eq!(streak_line(None, &date_style), "Longest streak: none, no achievement has an unlock time");
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_streaks() {
+        let api = FakeApi::new()
+            .with_game(123, "Test Game")
+            .with_achievements(123, "Test Game", create_streak_achievements(&[0, 10, 20, 200]));
+        let app_context = AppContext::with_api(api);
+        let matches = get_matches_for_args(&["achievements", "123", "--format", "text", "--streaks", "--streak-gap", "15", "--date-format", "%H:%M"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListAchievementsPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        assert!(output.ends_with("Longest streak: 3 unlocks in 20 minutes, from 20:00 to 20:20\n"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_tsv_stdout_holds_only_rows() {
+        let api = FakeApi::new()
+            .with_game(123, "Test Game")
+            .with_achievements(123, "Test Game", create_streak_achievements(&[0, 10, 20, 200]));
+        let app_context = AppContext::with_api(api);
+        let matches = get_matches_for_args(&["achievements", "123", "--format", "tsv", "--summary", "--streaks", "--head", "3"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListAchievementsPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        let err_output = String::from_utf8(err_writer).unwrap();
+        assert_eq!(output.lines().count(), 3);
+        assert!(output.lines().all(|line| line.split('\t').count() == 4), "{}", output);
+        assert!(err_output.contains("... (showing 3 of 4)"));
+        assert!(err_output.contains("Unlocked 4/4 (100.0%)"));
+        assert!(err_output.contains("Longest streak: 3 unlocks in 20 minutes"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_tsv_quiet_keeps_requested_lines() {
+        let api = FakeApi::new()
+            .with_game(123, "Test Game")
+            .with_achievements(123, "Test Game", create_streak_achievements(&[0, 10, 20, 200]));
+        let app_context = AppContext::with_api(api).with_quiet(true);
+        let matches = get_matches_for_args(&["achievements", "123", "--format", "tsv", "--summary", "--streaks", "--head", "3"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListAchievementsPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let err_output = String::from_utf8(err_writer).unwrap();
+        assert_eq!(String::from_utf8(writer).unwrap().lines().count(), 3);
+        assert!(!err_output.contains("showing"));
+        assert!(err_output.contains("Unlocked 4/4 (100.0%)"));
+        assert!(err_output.contains("Longest streak: 3 unlocks in 20 minutes"));
+    }
+
+    #[test]
+    fn test_streak_gap_requires_streaks() {
+        let result = ListAchievementsPlugin.command().try_get_matches_from(["achievements", "123", "--streak-gap", "10"]);
+        assert_eq!(result.unwrap_err().kind(), clap::error::ErrorKind::MissingRequiredArgument);
+    }
+
+    #[tokio::test]
+    async fn test_execute_colors_follow_interactive_context() {
+        let matches = get_matches_for_args(&["achievements", "123", "--format", "text"]);
+        for interactive in [false, true] {
+            let (app_context, _server) = setup_test_env_game_achievements(&dated_achievements_body(), 200).await;
+            let app_context = app_context.with_interactive(interactive);
+            let mut writer = Vec::new();
+            let mut err_writer = Vec::new();
+
+            ListAchievementsPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+            let colored = interactive && std::env::var("NO_COLOR").map_or(true, |value| value.is_empty());
+            assert_eq!(String::from_utf8(writer).unwrap().contains('\x1b'), colored);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_execute_no_color_output_is_plain() {
+        let (app_context, _server) = setup_test_env_game_achievements(&dated_achievements_body(), 200).await;
+        let matches = get_matches_for_args(&["achievements", "123", "--no-color"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListAchievementsPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        assert!(!output.contains('\x1b'));
+        assert!(output.contains("January First - Y (2023-01-01 00:00:00)\n"));
+        assert!(output.contains("Still Locked\n"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_verbose_shows_descriptions() {
+        let achievements = vec![
+            Achievement {
+                description: "Finish the first level".to_string(),
+                ..create_mock_achievement("ach1", "First Achievement", 0)
+            },
+            Achievement {
+                description: "".to_string(),
+                ..create_mock_achievement("ach2", "Hidden Achievement", 0)
+            },
+            Achievement {
+                description: "Finish the game".to_string(),
+                ..create_mock_achievement("ach3", "Last Achievement", 0)
+            },
+        ];
+        let mock_body = serde_json::to_string(&serde_json::json!({
+            "playerstats": {
+                "steamID": "test_id",
+                "gameName": "Test Game",
+                "achievements": achievements,
+                "success": true
+            }
+        })).unwrap();
+        let (app_context, _server) = setup_test_env_game_achievements(&mock_body, 200).await;
+        let matches = get_matches_for_args(&["achievements", "123", "-v", "--no-color"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListAchievementsPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        assert_eq!(
+            output,
+            "First Achievement\n    Finish the first level\nHidden Achievement\nLast Achievement\n    Finish the game\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_regex_filter() {
+        let (app_context, _server) = setup_test_env_game_achievements(&dated_achievements_body(), 200).await;
+        let matches = get_matches_for_args(&["achievements", "123", "--filter", "first$", "--regex", "--no-color"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListAchievementsPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        assert!(output.contains("January First"));
+        assert!(output.contains("February First"));
+        assert!(!output.contains("Mid January"));
+        assert!(!output.contains("Still Locked"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_invalid_regex_filter() {
+        let (app_context, _server) = setup_test_env_game_achievements(&dated_achievements_body(), 200).await;
+        let matches = get_matches_for_args(&["achievements", "123", "--filter", "(first", "--regex"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListAchievementsPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let err_output = String::from_utf8(err_writer).unwrap();
+        assert!(err_output.starts_with("Invalid --filter regex '(first':"));
+        assert!(writer.is_empty());
+    }
+
+    fn sort_test_achievements() -> Vec<Achievement> {
+        vec![
+            create_mock_achievement("locked1", "Locked One", 0),
+            create_mock_unlocked_achievement("mid", "Mid", 1673740800),
+            create_mock_achievement("locked2", "Locked Two", 0),
+            create_mock_unlocked_achievement("early", "Early", 1672531200),
+            create_mock_unlocked_achievement("late", "Late", 1675209600),
+        ]
+    }
+
+    fn apinames(achievements: &[Achievement]) -> Vec<&str> {
+        achievements.iter().map(|a| a.apiname.as_str()).collect()
+    }
+
+    #[test]
+    fn test_sort_key_unlocktime() {
+        assert_eq!(sort_key_unlocktime(&create_mock_unlocked_achievement("a", "A", 1672531200)), Some(1672531200));
+        assert_eq!(sort_key_unlocktime(&create_mock_achievement("b", "B", 0)), None);
+        assert_eq!(sort_key_unlocktime(&create_mock_achievement("c", "C", 1)), None);
+    }
+
+    #[test]
+    fn test_sort_by_unlock_date_asc_puts_locked_last() {
+        let mut achievements = sort_test_achievements();
+        sort_by_unlock_date(&mut achievements, SortDirection::Asc);
+        assert_eq!(apinames(&achievements), vec!["early", "mid", "late", "locked1", "locked2"]);
+    }
+
+    #[test]
+    fn test_sort_by_unlock_date_desc_puts_locked_last() {
+        let mut achievements = sort_test_achievements();
+        sort_by_unlock_date(&mut achievements, SortDirection::Desc);
+        assert_eq!(apinames(&achievements), vec!["late", "mid", "early", "locked1", "locked2"]);
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_sort_date_desc() {
+        let (app_context, _server) = setup_test_env_game_achievements(&dated_achievements_body(), 200).await;
+        let matches = get_matches_for_args(&["achievements", "123", "--sort-date", "desc", "--no-color"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListAchievementsPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        let names: Vec<&str> = output.lines().map(|l| l.split(" - ").next().unwrap()).collect();
+        assert_eq!(names, vec!["February First", "Mid January", "January First", "Still Locked"]);
+    }
+
+    #[tokio::test]
+    async fn test_execute_auto_format_when_piped() {
+        let (app_context, _server) = setup_test_env_game_achievements(&dated_achievements_body(), 200).await;
+        let app_context = app_context.with_interactive(false);
+        let matches = get_matches_for_args(&["achievements", "123", "--sort-date", "asc"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListAchievementsPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        assert!(!output.contains('\x1b'));
+        let lines: Vec<Vec<&str>> = output.lines().map(|line| line.split('\t').collect()).collect();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0][1..], ["January First", "Y", "2023-01-01 00:00:00"]);
+        assert_eq!(lines[3][1..], ["Still Locked", "N", ""]);
+    }
+
+    #[tokio::test]
+    async fn test_execute_explicit_text_format_when_piped() {
+        let (app_context, _server) = setup_test_env_game_achievements(&dated_achievements_body(), 200).await;
+        let app_context = app_context.with_interactive(false);
+        let matches = get_matches_for_args(&["achievements", "123", "--format", "text"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListAchievementsPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        assert!(output.contains("January First - Y (2023-01-01 00:00:00)\n"));
+    }
+
+    async fn setup_test_env_global_only() -> (AppContext, mockito::ServerGuard, mockito::Mock) {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("GET", "/ISteamUserStats/GetGlobalAchievementPercentagesForApp/v0002/?gameid=999&format=json&l=en")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(serde_json::json!({
+                "achievementpercentages": { "achievements": [
+                    { "name": "COMMON", "percent": 75.5 },
+                    { "name": "RARE", "percent": 1.2 },
+                    { "name": "UNCOMMON", "percent": 20.0 }
+                ] }
+            }).to_string())
+            .create_async().await;
+        let player_achievements = server.mock("GET", mockito::Matcher::Regex("GetPlayerAchievements".to_string()))
+            .expect(0)
+            .create_async().await;
+
+        let api = Api::new("test_key".to_string(), "test_id".to_string(), server.url());
+        (AppContext::with_api(api), server, player_achievements)
+    }
+
+    #[tokio::test]
+    async fn test_execute_global_only() {
+        let (app_context, _server, player_achievements) = setup_test_env_global_only().await;
+        let app_context = app_context.with_interactive(true);
+        let matches = get_matches_for_args(&["achievements", "999", "--global-only", "--no-color"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListAchievementsPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        assert_eq!(output, "RARE 1.2%\nUNCOMMON 20%\nCOMMON 75.5%\n");
+        assert!(err_writer.is_empty());
+        player_achievements.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_execute_global_only_when_piped() {
+        let (app_context, _server, player_achievements) = setup_test_env_global_only().await;
+        let matches = get_matches_for_args(&["achievements", "999", "--global-only"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListAchievementsPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        assert_eq!(output, "RARE\t1.2\nUNCOMMON\t20\nCOMMON\t75.5\n");
+        player_achievements.assert_async().await;
+    }
+
+    #[test]
+    fn test_global_only_conflicts_with_player_filters() {
+        let result = ListAchievementsPlugin.command().try_get_matches_from(["achievements", "999", "--global-only", "--remaining"]);
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_execute_raw() {
+        let mock_body = dated_achievements_body();
+        let (app_context, _server) = setup_test_env_game_achievements(&mock_body, 200).await;
+        let matches = get_matches_for_args(&["achievements", "Test", "--raw"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListAchievementsPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        assert_eq!(writer, mock_body.as_bytes());
+        assert!(err_writer.is_empty());
+    }
+
+    #[test]
+    fn test_raw_conflicts_with_rendering_options() {
+        let result = ListAchievementsPlugin.command().try_get_matches_from(["achievements", "123", "--raw", "--remaining"]);
+        assert_eq!(result.unwrap_err().kind(), clap::error::ErrorKind::ArgumentConflict);
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_head() {
+        let (app_context, _server) = setup_test_env_game_achievements(&dated_achievements_body(), 200).await;
+        let matches = get_matches_for_args(&["achievements", "123", "--no-color", "--sort-date", "desc", "--head", "2"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListAchievementsPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("February First"));
+        assert!(lines[1].starts_with("Mid January"));
+        assert_eq!(lines[2], "... (showing 2 of 4)");
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_tail_after_filtering() {
+        let (app_context, _server) = setup_test_env_game_achievements(&dated_achievements_body(), 200).await;
+        let matches = get_matches_for_args(&["achievements", "123", "--no-color", "--filter", "first", "--tail", "1"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListAchievementsPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        assert_eq!(output, "February First - Y (2023-02-01 00:00:00)\n... (showing 1 of 2)\n");
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_head_larger_than_list_has_no_footer() {
+        let (app_context, _server) = setup_test_env_game_achievements(&dated_achievements_body(), 200).await;
+        let matches = get_matches_for_args(&["achievements", "123", "--no-color", "--head", "10"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListAchievementsPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        assert_eq!(output.lines().count(), 4);
+        assert!(!output.contains("showing"));
+    }
+
+    #[test]
+    fn test_head_conflicts_with_tail() {
+        let error = ListAchievementsPlugin
+            .command()
+            .try_get_matches_from(["achievements", "123", "--head", "1", "--tail", "1"])
+            .unwrap_err();
+        assert_eq!(error.kind(), clap::error::ErrorKind::ArgumentConflict);
+    }
+
+    #[tokio::test]
+    async fn test_execute_without_game_shows_hint() {
+        let app_context = AppContext::with_api(FakeApi::new());
+        let matches = get_matches_for_args(&["achievements"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListAchievementsPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let err_output = String::from_utf8(err_writer).unwrap();
+        assert!(err_output.contains("No game given and no previously used game found"));
+        assert!(err_output.contains("trogue list --filter <name>"));
+        assert!(writer.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_execute_changes_lists_new_unlocks() {
+        let dir = std::env::temp_dir()
+            .join(format!("trogue-achievements-test-{}-changes", std::process::id()))
+            .join("snapshots");
+        let api = FakeApi::new().with_game(123, "Test Game").with_achievements(
+            123,
+            "Test Game",
+            vec![
+                create_mock_achievement("a1", "First", 1),
+                create_mock_achievement("a2", "Second", 1),
+                create_mock_achievement("a3", "Third", 0),
+            ],
+        );
+        let mut app_context = AppContext::with_api(api);
+        app_context.unlock_history = Some(UnlockHistoryStore::new(dir.clone()));
+        let matches = get_matches_for_args(&["achievements", "123", "--changes", "--format", "text", "--no-color"]);
+
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+        ListAchievementsPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+        assert!(writer.is_empty());
+        assert!(String::from_utf8(err_writer).unwrap().contains("No earlier record of this game"));
+
+        UnlockHistoryStore::new(dir.clone())
+            .save(123, "Test Game", &[create_mock_achievement("a1", "First", 1), create_mock_achievement("a2", "Second", 0)])
+            .unwrap();
+        let mut writer = Vec::new();
+        ListAchievementsPlugin.execute(&app_context, &matches, &mut writer, &mut Vec::new()).await;
+        let output = String::from_utf8(writer).unwrap();
+        assert!(output.starts_with("Second - Y"));
+        assert_eq!(output.lines().count(), 1);
+
+        let mut writer = Vec::new();
+        ListAchievementsPlugin.execute(&app_context, &matches, &mut writer, &mut Vec::new()).await;
+        assert!(writer.is_empty());
+
+        std::fs::remove_dir_all(dir.parent().unwrap()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_execute_porcelain() {
+        let api = FakeApi::new().with_game(123, "Test Game").with_achievements(
+            123,
+            "Test Game",
+            vec![
+                create_mock_unlocked_achievement("a1", "First", 1672531200),
+                create_mock_achievement("a2", "Second", 0),
+            ],
+        );
+        let app_context = AppContext::with_api(api).with_porcelain(true);
+        let matches = get_matches_for_args(&["achievements", "123", "--format", "text", "--summary", "--verbose"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListAchievementsPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        assert_eq!(
+            String::from_utf8(writer).unwrap(),
+            "achievement\ta1\t1\t1672531200\nachievement\ta2\t0\t0\n"
+        );
+        assert!(err_writer.is_empty());
+    }
+
+    fn hidden_test_api() -> FakeApi {
+        FakeApi::new()
+            .with_game(123, "Test Game")
+            .with_achievements(
+                123,
+                "Test Game",
+                vec![
+                    Achievement { description: "".to_string(), ..create_mock_achievement("secret", "Secret", 0) },
+                    Achievement { description: "".to_string(), ..create_mock_unlocked_achievement("found", "Found", 1672531200) },
+                    create_mock_achievement("plain", "Plain", 0),
+                ],
+            )
+            .with_schema(
+                123,
+                vec![
+                    SchemaAchievement { name: "secret".to_string(), display_name: "Secret".to_string(), hidden: 1, description: "Find the secret room".to_string() },
+                    SchemaAchievement { name: "found".to_string(), display_name: "Found".to_string(), hidden: 1, description: "Find the other room".to_string() },
+                    SchemaAchievement { name: "plain".to_string(), display_name: "Plain".to_string(), hidden: 0, description: "Test Description".to_string() },
+                ],
+            )
+    }
+
+    #[tokio::test]
+    async fn test_execute_hidden_reveals_locked_descriptions() {
+        let app_context = AppContext::with_api(hidden_test_api());
+        let matches = get_matches_for_args(&["achievements", "123", "--hidden", "--format", "text", "--no-color"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListAchievementsPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        assert_eq!(
+            String::from_utf8(writer).unwrap(),
+            "Secret\n    [hidden] Find the secret room\nFound - Y (2023-01-01 00:00:00)\nPlain\n    Test Description\n"
+        );
+        assert!(err_writer.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_execute_hidden_when_piped() {
+        let app_context = AppContext::with_api(hidden_test_api());
+        let matches = get_matches_for_args(&["achievements", "123", "--hidden", "--remaining", "--format", "tsv"]);
+        let mut writer = Vec::new();
+
+        ListAchievementsPlugin.execute(&app_context, &matches, &mut writer, &mut Vec::new()).await;
+
+        assert_eq!(
+            String::from_utf8(writer).unwrap(),
+            "secret\tSecret\tN\t\t[hidden] Find the secret room\nplain\tPlain\tN\t\tTest Description\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_execute_hidden_schema_error() {
+        let api = FakeApi::new().with_game(123, "Test Game").with_achievements(
+            123,
+            "Test Game",
+            vec![Achievement { description: "".to_string(), ..create_mock_achievement("secret", "Secret", 0) }],
+        );
+        let app_context = AppContext::with_api(api);
+        let matches = get_matches_for_args(&["achievements", "123", "--hidden", "--format", "text", "--no-color"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListAchievementsPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        assert_eq!(String::from_utf8(writer).unwrap(), "Secret\n");
+        assert!(String::from_utf8(err_writer).unwrap().starts_with("Error while trying to get achievement schema"));
+    }
+
+    fn csv_test_api() -> FakeApi {
+        let achievements = vec![
+            create_mock_unlocked_achievement("a1", "First, Blood", 1672531200),
+            create_mock_achievement("a2", "Second", 0),
+        ];
+        FakeApi::new()
+            .with_game(123, "Test Game")
+            .with_achievements(123, "Test Game", achievements)
+            .with_global_achievements(123, vec![create_mock_global_achievement("a1", 12.5), create_mock_global_achievement("a2", 3.0)])
+    }
+
+    #[tokio::test]
+    async fn test_execute_csv_format_default_columns() {
+        let app_context = AppContext::with_api(csv_test_api());
+        let matches = get_matches_for_args(&["achievements", "123", "--format", "csv"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListAchievementsPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines[0], "apiname,name,description,achieved,unlocktime");
+        assert!(lines[1].starts_with("a1,\"First, Blood\","));
+        assert!(lines[1].ends_with(",1,1672531200"));
+        assert_eq!(lines.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_execute_csv_format_custom_columns() {
+        let app_context = AppContext::with_api(csv_test_api());
+        let matches = get_matches_for_args(&["achievements", "123", "--format", "csv", "--columns", "global,apiname,achieved"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListAchievementsPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        assert_eq!(String::from_utf8(writer).unwrap(), "global,apiname,achieved\n12.5,a1,1\n3,a2,0\n");
+    }
+
+    #[tokio::test]
+    async fn test_execute_csv_format_no_headers() {
+        let app_context = AppContext::with_api(csv_test_api());
+        let matches = get_matches_for_args(&["achievements", "123", "--format", "csv", "--columns", "name", "--no-headers"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListAchievementsPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        assert_eq!(String::from_utf8(writer).unwrap(), "\"First, Blood\"\nSecond\n");
+    }
+
+    #[test]
+    fn test_invalid_csv_column() {
+        let error = ListAchievementsPlugin
+            .command()
+            .try_get_matches_from(["achievements", "123", "--format", "csv", "--columns", "name,points"])
+            .unwrap_err();
+        let message = error.to_string();
+        assert!(message.contains("invalid value 'points'"));
+        assert!(message.contains("apiname"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_yaml_format_round_trips() {
+        let achievements = vec![
+            create_mock_unlocked_achievement("a1", "Key: \"Value\"", 1672531200),
+            Achievement { description: "# not a comment\nsecond line".to_string(), ..create_mock_achievement("a2", "- dash", 0) },
+            create_mock_achievement("a3", "null", 0),
+        ];
+        let api = FakeApi::new().with_game(123, "Test Game").with_achievements(123, "Test Game", achievements.clone());
+        let app_context = AppContext::with_api(api);
+        let matches = get_matches_for_args(&["achievements", "123", "--format", "yaml", "--summary"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListAchievementsPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let parsed: Vec<Achievement> = serde_yaml::from_str(&String::from_utf8(writer).unwrap()).unwrap();
+        assert_eq!(parsed, achievements);
+        assert!(String::from_utf8(err_writer).unwrap().contains("Unlocked 1/3"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_csv_quiet_keeps_requested_lines() {
+        let api = FakeApi::new()
+            .with_game(123, "Test Game")
+            .with_achievements(123, "Test Game", create_streak_achievements(&[0, 10, 20, 200]));
+        let app_context = AppContext::with_api(api).with_quiet(true);
+        let matches = get_matches_for_args(&["achievements", "123", "--format", "csv", "--summary", "--streaks", "--head", "3"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListAchievementsPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let err_output = String::from_utf8(err_writer).unwrap();
+        assert!(!err_output.contains("showing"));
+        assert!(err_output.contains("Unlocked 4/4 (100.0%)"));
+        assert!(err_output.contains("Longest streak: 3 unlocks in 20 minutes"));
+    }
+
+    fn recommend_test_api() -> FakeApi {
+        FakeApi::new()
+            .with_game(123, "Test Game")
+            .with_achievements(
+                123,
+                "Test Game",
+                vec![
+                    create_mock_achievement("rare", "Rare", 0),
+                    create_mock_unlocked_achievement("done", "Done", 1672531200),
+                    create_mock_achievement("common", "Common", 0),
+                    create_mock_achievement("unknown", "Unknown", 0),
+                    create_mock_achievement("uncommon", "Uncommon", 0),
+                ],
+            )
+            .with_global_achievements(
+                123,
+                vec![
+                    create_mock_global_achievement("rare", 1.5),
+                    create_mock_global_achievement("done", 95.0),
+                    create_mock_global_achievement("common", 80.0),
+                    create_mock_global_achievement("uncommon", 35.2),
+                ],
+            )
+    }
+
+    #[tokio::test]
+    async fn test_execute_recommend_lists_easiest_locked_first() {
+        let app_context = AppContext::with_api(recommend_test_api());
+        let matches = get_matches_for_args(&["achievements", "123", "--recommend", "--format", "tsv"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListAchievementsPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        assert_eq!(
+            String::from_utf8(writer).unwrap(),
+            "common\tCommon\tN\t\t80\nuncommon\tUncommon\tN\t\t35.2\nrare\tRare\tN\t\t1.5\nunknown\tUnknown\tN\t\t0\n"
+        );
+        assert!(err_writer.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_execute_recommend_count() {
+        let app_context = AppContext::with_api(recommend_test_api());
+        let matches = get_matches_for_args(&["achievements", "123", "--recommend", "--recommend-count", "2", "--format", "text", "--no-color"]);
+        let mut writer = Vec::new();
+
+        ListAchievementsPlugin.execute(&app_context, &matches, &mut writer, &mut Vec::new()).await;
+
+        assert_eq!(String::from_utf8(writer).unwrap(), "Common 80%\nUncommon 35.2%\n... (showing 2 of 4)\n");
+    }
+
+    #[test]
+    fn test_recommend_count_requires_recommend() {
+        assert!(ListAchievementsPlugin.command().try_get_matches_from(["achievements", "123", "--recommend-count", "3"]).is_err());
+        assert!(ListAchievementsPlugin.command().try_get_matches_from(["achievements", "123", "--recommend", "--head", "3"]).is_err());
+    }
+}
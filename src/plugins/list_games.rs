@@ -2,7 +2,7 @@
 //!
 //! <purpose-start>
 //! This plugin provides the `list` command, which allows users to see a list of their games.
-//! It supports filtering by name and custom output formatting.
+//! It supports filtering by name, by a list of game ids or by completion, and custom output formatting.
 //! <purpose-end>
 //!
 //! <inputs-start>
@@ -18,13 +18,67 @@
 //! - Makes a network request to the Steam API to fetch the list of games.
 //! <side-effects-end>
 
-use crate::{app::AppContext, plugins::Plugin, ui};
+use crate::{app::AppContext, plugins::{self, Plugin}, steam_api::{Achievement, Game, GlobalAchievement}, ui};
 use async_trait::async_trait;
-use clap::{Arg, Command};
+use clap::{Arg, Command, ValueEnum};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::io::Write;
+use std::pin::pin;
 
 pub struct ListGamesPlugin;
 
+// The number of games from which filtering by completion warns that it may take a while.
+const LARGE_LIBRARY_GAMES: usize = 100;
+
+// The completion state kept by `--only-perfect` and `--only-incomplete`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Completion {
+    // Every achievement unlocked
+    Perfect,
+    // At least one achievement still locked
+    Incomplete,
+}
+
+// Represents the values of the `--sort` option.
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+enum ListSort {
+    // The average global unlock percentage of the unlocked achievements, rarest first
+    Rarity,
+}
+
+// Represents the supported game list output formats.
+//
+// <purpose-start>
+// The text and table formats are meant for humans, while tab-separated values, newline-delimited JSON,
+// YAML and TOML suit scripts and reports. The automatic format picks between text and tab-separated values depending on
+// whether the output goes to a terminal.
+// <purpose-end>
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+enum ListFormat {
+    // Text in a terminal, tab-separated values otherwise
+    Auto,
+    // Header line followed by one game per line, formatted by --pattern or --delimiter
+    Text,
+    // The game id and name separated by a tab, one game per line, without a header
+    Tsv,
+    // One compact JSON object per game and line, without a header
+    Ndjson,
+    // A YAML sequence of the games, with the fields of ndjson
+    Yaml,
+    // A TOML array of tables under the `games` key, with the fields of ndjson
+    Toml,
+    // The id, name and playtime of each game in aligned columns, below a header
+    Table,
+}
+
+// Wraps the games of the TOML format, as a TOML document cannot be a bare array.
+#[derive(Serialize, Deserialize, Debug)]
+struct TomlGames {
+    games: Vec<Game>,
+}
+
 #[async_trait]
 impl Plugin for ListGamesPlugin {
     // Defines the clap command for the `list` plugin.
@@ -48,6 +102,13 @@ impl Plugin for ListGamesPlugin {
     fn command(&self) -> Command {
         Command::new("list")
             .about("Displays a list of all games on account set in environment variables")
+            .after_help(concat!(
+                "Examples:\n",
+                "  trogue list --filter portal\n",
+                "  trogue list --filter \"^half-life\" --regex --pattern \"{i:>8} n\"\n",
+                "  trogue list --ids 440,570 --format ndjson\n",
+                "  trogue list --only-incomplete --with-progress --filter --pattern \"n: c/a\"",
+            ))
             .arg(
                 Arg::new("filter")
                     .short('f')
@@ -57,6 +118,7 @@ impl Plugin for ListGamesPlugin {
                     .num_args(0..=1)
                     .help("Filters the list of games by name"),
             )
+            .arg(plugins::regex_arg())
             .arg(
                 Arg::new("pattern")
                     .short('p')
@@ -66,11 +128,84 @@ impl Plugin for ListGamesPlugin {
 Possible tokens are:
     n - game name
     i - game id
-E.g.: -p "i: n""#,
+    a - total achievements (requires --with-progress)
+    c - completed achievements (requires --with-progress)
+    l - last played time (UTC), or "never"
+    p - total playtime, see --playtime-format
+A token in braces can be given a width, padding or truncating the value:
+    {n:20} - name in 20 characters, left-aligned
+    {i:>6} - id in 6 characters, right-aligned
+Trailing whitespace is trimmed from each line, e.g. after a padded or empty last token.
+E.g.: -p "i: n" or -p "{i:>8} {n:40} c/a""#,
                     )
                     .requires("filter")
                     .value_name("pattern"),
             )
+            .arg(
+                Arg::new("delimiter")
+                    .short('d')
+                    .long("delimiter")
+                    .value_name("delimiter")
+                    .help("Prints the game id and name separated by the given delimiter, one game per line, without a header. Overrides --pattern"),
+            )
+            .arg(
+                Arg::new("ids")
+                    .long("ids")
+                    .value_name("id,id,...")
+                    .help("Lists only the games with the given comma-separated ids. Combined with --filter, a game must match both"),
+            )
+            .arg(
+                Arg::new("format")
+                    .long("format")
+                    .value_name("format")
+                    .value_parser(clap::value_parser!(ListFormat))
+                    .default_value("auto")
+                    .conflicts_with_all(["pattern", "delimiter", "with_progress"])
+                    .help("The output format (auto, text, tsv, ndjson, yaml, toml, table). auto is text in a terminal and tsv otherwise, or text with --pattern, --delimiter or --with-progress"),
+            )
+            .arg(
+                Arg::new("with_progress")
+                    .long("with-progress")
+                    .action(clap::ArgAction::SetTrue)
+                    .help("Fetches the achievement counts of each listed game for the a and c pattern tokens. Makes one request per game"),
+            )
+            .arg(
+                Arg::new("playtime_format")
+                    .long("playtime-format")
+                    .value_name("style")
+                    .value_parser(clap::value_parser!(ui::PlaytimeStyle))
+                    .default_value("hours")
+                    .help("How the p pattern token and the table format display playtimes (hours: 2h, hm: 2h 33m, decimal: 2.55h)"),
+            )
+            .arg(
+                Arg::new("only_perfect")
+                    .long("only-perfect")
+                    .action(clap::ArgAction::SetTrue)
+                    .conflicts_with("only_incomplete")
+                    .help("Lists only the games with every achievement unlocked. Games without achievements are left out. Makes one request per game"),
+            )
+            .arg(
+                Arg::new("only_incomplete")
+                    .long("only-incomplete")
+                    .action(clap::ArgAction::SetTrue)
+                    .help("Lists only the games with achievements left to unlock. Games without achievements are left out. Makes one request per game"),
+            )
+            .arg(
+                Arg::new("sort")
+                    .long("sort")
+                    .value_name("key")
+                    .value_parser(clap::value_parser!(ListSort))
+                    .help("Sorts the games (rarity: by the average global unlock percentage of your unlocked achievements, rarest first, games without unlocked achievements last). Makes two requests per game"),
+            )
+            .arg(
+                Arg::new("aggregate")
+                    .long("aggregate")
+                    .action(clap::ArgAction::SetTrue)
+                    .help("Prints the number of listed games and their total playtime after the list, e.g. Total: 3 games, 152 hours. Goes to stderr with tsv, ndjson, yaml, toml, table or --delimiter output"),
+            )
+            .arg(plugins::raw_arg().conflicts_with_all([
+                "filter", "pattern", "delimiter", "ids", "format", "with_progress", "only_perfect", "only_incomplete", "sort", "aggregate",
+            ]))
     }
 
     // Executes the `list` plugin's logic.
@@ -103,40 +238,402 @@ E.g.: -p "i: n""#,
         writer: &mut (dyn Write + Send),
         err_writer: &mut (dyn Write + Send),
     ) {
+        if matches.get_flag("raw") {
+            plugins::write_raw(app_context.api.get_games_list_raw().await, writer, err_writer);
+            return;
+        }
+
         let filter = matches.get_one::<String>("filter").cloned();
+        let name_filter = match filter.as_deref().map(|f| plugins::NameFilter::new(f, matches.get_flag("regex"))).transpose() {
+            Ok(name_filter) => name_filter,
+            Err(e) => {
+                writeln!(err_writer, "{}", e).unwrap();
+                return;
+            }
+        };
         let pattern = matches.get_one::<String>("pattern").cloned();
+        let delimiter = matches.get_one::<String>("delimiter").cloned();
+        let with_progress = matches.get_flag("with_progress");
+        let playtime_style = *matches.get_one::<ui::PlaytimeStyle>("playtime_format").unwrap();
+        let completion = if matches.get_flag("only_perfect") {
+            Some(Completion::Perfect)
+        } else if matches.get_flag("only_incomplete") {
+            Some(Completion::Incomplete)
+        } else {
+            None
+        };
+        let format = match *matches.get_one::<ListFormat>("format").unwrap() {
+            // The text-only options are an explicit request for text
+            ListFormat::Auto if pattern.is_some() || delimiter.is_some() || with_progress => ListFormat::Text,
+            ListFormat::Auto if app_context.interactive() => ListFormat::Text,
+            ListFormat::Auto => ListFormat::Tsv,
+            format => format,
+        };
+        let ids = match matches.get_one::<String>("ids").map(|ids| plugins::parse_ids(ids, "--ids")).transpose() {
+            Ok(ids) => ids,
+            Err(e) => {
+                writeln!(err_writer, "{}", e).unwrap();
+                return;
+            }
+        };
 
         let mut games = Vec::new();
-        match app_context.api.get_games_list().await {
+        match app_context.get_games_list_cached().await {
             Ok(resp) => games = resp,
-            Err(e) => writeln!(err_writer, "Error while trying to get Steam data: {}", e).unwrap(),
+            Err(e) => writeln!(err_writer, "Error while trying to get Steam data: {}", ui::scrub_api_key(&e.to_string())).unwrap(),
         }
 
-        match filter {
-            Some(f) => {
-                writeln!(writer, "Displaying games filtered by: {}", f).unwrap();
-                games.retain(|entry| entry.name.to_lowercase().contains(&f.to_lowercase()));
+        let show_header = delimiter.is_none() && format == ListFormat::Text && !app_context.porcelain();
+
+        match (filter, name_filter) {
+            (Some(f), Some(name_filter)) => {
+                if show_header {
+                    writeln!(writer, "Displaying games filtered by: {}", f).unwrap();
+                }
+                games.retain(|entry| name_filter.matches(&entry.name));
+            }
+            _ => {
+                if show_header {
+                    writeln!(writer, "Displaying all games:").unwrap();
+                }
             }
-            None => {
-                writeln!(writer, "Displaying all games:").unwrap();
+        }
+
+        if let Some(ids) = ids {
+            games.retain(|entry| ids.contains(&entry.appid));
+        }
+
+        // The counts fetched to filter by completion are reused by --with-progress
+        let mut known_counts = None;
+        if let Some(completion) = completion {
+            let (retained, counts) = retain_by_completion(app_context, games, completion, err_writer).await;
+            games = retained;
+            known_counts = Some(counts);
+        }
+
+        if matches.get_one::<ListSort>("sort") == Some(&ListSort::Rarity) {
+            let (sorted, counts) = sort_by_rarity(app_context, games, err_writer).await;
+            games = sorted;
+            known_counts.get_or_insert(counts);
+        }
+
+        let aggregate = matches.get_flag("aggregate").then(|| aggregate_line(&games, app_context.group_thousands()));
+
+        // Stable output for scripts, regardless of the format options
+        if app_context.porcelain() {
+            for game in games {
+                writeln!(writer, "{}", ui::porcelain_record("game", &[game.appid.to_string(), game.name])).unwrap();
+            }
+            return;
+        }
+
+        // Streaming output: each line is flushed as soon as it is written
+        if format == ListFormat::Ndjson {
+            for game in games {
+                writeln!(writer, "{}", serde_json::to_string(&game).unwrap()).unwrap();
+                writer.flush().unwrap();
+            }
+            write_aggregate_footer(aggregate.as_deref(), err_writer);
+            return;
+        }
+
+        if format == ListFormat::Yaml {
+            write!(writer, "{}", serde_yaml::to_string(&games).unwrap()).unwrap();
+            write_aggregate_footer(aggregate.as_deref(), err_writer);
+            return;
+        }
+
+        if format == ListFormat::Toml {
+            write!(writer, "{}", toml::to_string(&TomlGames { games }).unwrap()).unwrap();
+            write_aggregate_footer(aggregate.as_deref(), err_writer);
+            return;
+        }
+
+        if format == ListFormat::Table {
+            let mut table = ui::table::Table::new(&["ID", "Name", "Playtime"]).right_align(0).right_align(2);
+            for game in games {
+                let playtime = ui::format_playtime(game.playtime_forever, playtime_style, app_context.group_thousands());
+                table.add_row(vec![game.appid.to_string(), game.name, playtime]);
+            }
+            for line in table.render() {
+                writeln!(writer, "{}", line).unwrap();
+            }
+            write_aggregate_footer(aggregate.as_deref(), err_writer);
+            return;
+        }
+
+        if format == ListFormat::Tsv {
+            for game in games {
+                writeln!(writer, "{}\t{}", game.appid, ui::tsv_field(&game.name)).unwrap();
+            }
+            write_aggregate_footer(aggregate.as_deref(), err_writer);
+            return;
+        }
+
+        // Scriptable output: plain fields joined by the delimiter, no header
+        if let Some(delimiter) = delimiter {
+            for game in games {
+                writeln!(writer, "{}{}{}", game.appid, delimiter, game.name).unwrap();
             }
+            write_aggregate_footer(aggregate.as_deref(), err_writer);
+            return;
         }
 
         let pattern = pattern.unwrap_or("[i] n".to_string());
+        let mut reporter = ui::progress_reporter(app_context.quiet());
+        let total_games = games.len();
+        let appids = if with_progress && known_counts.is_none() { games.iter().map(|g| g.appid).collect() } else { Vec::new() };
+        let mut results = pin!(app_context.game_achievements(appids));
+
+        for (index, game) in games.into_iter().enumerate() {
+            let mut achievements = known_counts.as_mut().and_then(|counts| counts.remove(&game.appid));
+            if with_progress && known_counts.is_none() {
+                reporter.report(index + 1, total_games, err_writer);
+                let result = results.next().await.unwrap();
+                reporter.clear(err_writer);
+
+                match result {
+                    Ok(game_achievements) => {
+                        achievements = Some(ui::AchievementCounts {
+                            total: game_achievements.total(),
+                            completed: game_achievements.completed(),
+                        });
+                    }
+                    Err(e) => writeln!(err_writer, "Error while trying to get achievements for {}: {}", game.name, ui::scrub_api_key(&e.to_string())).unwrap(),
+                }
+            }
 
-        for game in games {
-            let displayable_game = ui::DisplayableGame { game };
+            let displayable_game = ui::DisplayableGame { game, achievements, playtime_style };
             let formatted_game = displayable_game.format(&pattern);
-            writeln!(writer, "{}", formatted_game).unwrap();
+            // A padded or empty last token would leave trailing whitespace, which trips up diff
+            writeln!(writer, "{}", formatted_game.trim_end()).unwrap();
+        }
+
+        if let Some(aggregate) = aggregate {
+            writeln!(writer, "{}", aggregate).unwrap();
+        }
+    }
+}
+
+// Summarizes the listed games for `--aggregate`.
+//
+// <purpose-start>
+// Computed from the games left by the filters, so that any subset of the library can be totaled.
+// Hours are rounded down, as the `hours` playtime style does.
+// <purpose-end>
+//
+// <inputs-start>
+// - `games`: The listed games.
+// - `grouped`: Whether to separate the thousands of the counts, see `ui::format_count`.
+// <inputs-end>
+//
+// <outputs-start>
+// - `String`: The footer line, e.g. `Total: 3 games, 152 hours`.
+// <outputs-end>
+fn aggregate_line(games: &[Game], grouped: bool) -> String {
+    let minutes: u64 = games.iter().map(|g| u64::from(g.playtime_forever)).sum();
+    format!(
+        "Total: {} games, {} hours",
+        ui::format_count(games.len() as u64, grouped),
+        ui::format_count(minutes / 60, grouped)
+    )
+}
+
+// Writes the `--aggregate` footer of scriptable output to standard error, keeping the output to game rows.
+// The footer was requested, so it is written even with `--quiet`.
+fn write_aggregate_footer(aggregate: Option<&str>, err_writer: &mut (dyn Write + Send)) {
+    if let Some(aggregate) = aggregate {
+        writeln!(err_writer, "{}", aggregate).unwrap();
+    }
+}
+
+// Keeps only the games in the requested completion state.
+//
+// <purpose-start>
+// Backs `--only-perfect` and `--only-incomplete`. The achievements of every game are fetched
+// concurrently, bounded by `--concurrency`. Games without achievements match neither state, and
+// games whose achievements cannot be fetched are reported and left out.
+// <purpose-end>
+//
+// <inputs-start>
+// - `app_context`: The shared application context.
+// - `games`: The games to filter.
+// - `completion`: The completion state to keep.
+// - `err_writer`: A mutable reference to a writer for standard error.
+// <inputs-end>
+//
+// <outputs-start>
+// - The retained games, in their original order, and their achievement counts by game id.
+// <outputs-end>
+//
+// <side-effects-start>
+// - Makes one network request to the Steam API per game.
+// - Writes progress, a warning for large libraries and errors to `err_writer`.
+// <side-effects-end>
+async fn retain_by_completion(
+    app_context: &AppContext,
+    games: Vec<Game>,
+    completion: Completion,
+    err_writer: &mut (dyn Write + Send),
+) -> (Vec<Game>, HashMap<u32, ui::AchievementCounts>) {
+    if games.len() > LARGE_LIBRARY_GAMES {
+        plugins::notice(
+            app_context,
+            err_writer,
+            &format!("Fetching the achievements of {} games, this may take a while", games.len()),
+        );
+    }
+
+    let mut reporter = ui::progress_reporter(app_context.quiet());
+    let total_games = games.len();
+    let mut results = pin!(app_context.game_achievements(games.iter().map(|g| g.appid).collect()));
+    let mut retained = Vec::new();
+    let mut counts = HashMap::new();
+
+    for (index, game) in games.into_iter().enumerate() {
+        reporter.report(index + 1, total_games, err_writer);
+        let result = results.next().await.unwrap();
+        reporter.clear(err_writer);
+
+        let game_achievements = match result {
+            Ok(game_achievements) => game_achievements,
+            Err(e) => {
+                writeln!(err_writer, "Error while trying to get achievements for {}: {}", game.name, ui::scrub_api_key(&e.to_string())).unwrap();
+                continue;
+            }
+        };
+
+        let total = game_achievements.total();
+        let completed = game_achievements.completed();
+        let matching = match completion {
+            Completion::Perfect => total > 0 && completed == total,
+            Completion::Incomplete => completed < total,
+        };
+
+        if matching {
+            counts.insert(game.appid, ui::AchievementCounts { total, completed });
+            retained.push(game);
+        }
+    }
+
+    (retained, counts)
+}
+
+// Sorts the games by the rarity of the achievements unlocked in them.
+//
+// <purpose-start>
+// Backs `--sort rarity`. The player and global achievements of every game are fetched
+// concurrently, bounded by `--concurrency`. Games without unlocked achievements, or whose
+// achievements cannot be fetched, have no rarity and keep their order at the end of the list.
+// <purpose-end>
+//
+// <inputs-start>
+// - `app_context`: The shared application context.
+// - `games`: The games to sort.
+// - `err_writer`: A mutable reference to a writer for standard error.
+// <inputs-end>
+//
+// <outputs-start>
+// - The sorted games and their achievement counts by game id, for games whose achievements were fetched.
+// <outputs-end>
+//
+// <side-effects-start>
+// - Makes two network requests to the Steam API per game.
+// - Writes progress, a warning for large libraries and errors to `err_writer`.
+// <side-effects-end>
+async fn sort_by_rarity(
+    app_context: &AppContext,
+    games: Vec<Game>,
+    err_writer: &mut (dyn Write + Send),
+) -> (Vec<Game>, HashMap<u32, ui::AchievementCounts>) {
+    if games.len() > LARGE_LIBRARY_GAMES {
+        plugins::notice(
+            app_context,
+            err_writer,
+            &format!("Fetching the achievements of {} games, this may take a while", games.len()),
+        );
+    }
+
+    let mut reporter = ui::progress_reporter(app_context.quiet());
+    let total_games = games.len();
+    let mut results = pin!(app_context.game_achievements_with_global(games.iter().map(|g| g.appid).collect()));
+    let mut rarities = HashMap::new();
+    let mut counts = HashMap::new();
+
+    for (index, game) in games.iter().enumerate() {
+        reporter.report(index + 1, total_games, err_writer);
+        let (player, global) = results.next().await.unwrap();
+        reporter.clear(err_writer);
+
+        let game_achievements = match player {
+            Ok(game_achievements) => game_achievements,
+            Err(e) => {
+                writeln!(err_writer, "Error while trying to get achievements for {}: {}", game.name, ui::scrub_api_key(&e.to_string())).unwrap();
+                continue;
+            }
+        };
+        counts.insert(game.appid, ui::AchievementCounts { total: game_achievements.total(), completed: game_achievements.completed() });
+
+        match global {
+            Ok(global) => {
+                if let Some(rarity) = average_unlocked_rarity(&game_achievements.achievements, &global) {
+                    rarities.insert(game.appid, rarity);
+                }
+            }
+            Err(e) => writeln!(err_writer, "Error while trying to get global achievements for {}: {}", game.name, ui::scrub_api_key(&e.to_string())).unwrap(),
         }
     }
+
+    (order_by_rarity(games, &rarities), counts)
+}
+
+// Computes the average global unlock percentage of the unlocked achievements of a game.
+//
+// <purpose-start>
+// The lower the average, the rarer the achievements unlocked. Achievements missing from the
+// global percentages count as 0%, as Steam leaves out those nobody unlocked recently.
+// <purpose-end>
+//
+// <inputs-start>
+// - `achievements`: The player's achievements of the game.
+// - `global`: The global unlock percentages of the game.
+// <inputs-end>
+//
+// <outputs-start>
+// - `Some(f32)`: The average percentage.
+// - `None`: If no achievement is unlocked.
+// <outputs-end>
+fn average_unlocked_rarity(achievements: &[Achievement], global: &[GlobalAchievement]) -> Option<f32> {
+    let percentages: HashMap<&str, f32> = global.iter().map(|g| (g.name.as_str(), g.percent)).collect();
+    let unlocked: Vec<f32> = achievements
+        .iter()
+        .filter(|a| a.is_unlocked())
+        .map(|a| percentages.get(a.apiname.as_str()).copied().unwrap_or(0.0))
+        .collect();
+
+    if unlocked.is_empty() {
+        return None;
+    }
+    Some(unlocked.iter().sum::<f32>() / unlocked.len() as f32)
+}
+
+// Orders games by rarity, rarest first, the games without a rarity last in their original order.
+fn order_by_rarity(mut games: Vec<Game>, rarities: &HashMap<u32, f32>) -> Vec<Game> {
+    games.sort_by(|a, b| match (rarities.get(&a.appid), rarities.get(&b.appid)) {
+        (Some(a), Some(b)) => a.total_cmp(b),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => std::cmp::Ordering::Equal,
+    });
+    games
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::app::AppContext;
-    use crate::steam_api::{Api, Game};
+    use crate::fake_api::FakeApi;
+    use crate::steam_api::{Achievement, Api, Game, GlobalAchievement};
     use clap::ArgMatches;
 
     fn create_mock_game(appid: u32, name: &str) -> Game {
@@ -162,7 +659,8 @@ mod tests {
             .create_async().await;
 
         let api = Api::new("test_key".to_string(), "test_id".to_string(), server.url());
-        let app_context = AppContext { api };
+        // As if printing to a terminal, tests of piped output opt out
+        let app_context = AppContext::with_api(api).with_interactive(true);
         (app_context, server)
     }
 
@@ -178,6 +676,31 @@ mod tests {
         assert!(cmd.get_about().is_some());
         assert!(cmd.get_arguments().any(|arg| arg.get_id() == "filter"));
         assert!(cmd.get_arguments().any(|arg| arg.get_id() == "pattern"));
+        assert!(cmd.get_arguments().any(|arg| arg.get_id() == "delimiter"));
+        assert!(cmd.get_arguments().any(|arg| arg.get_id() == "ids"));
+        assert!(cmd.get_arguments().any(|arg| arg.get_id() == "with_progress"));
+        assert!(cmd.get_arguments().any(|arg| arg.get_id() == "format"));
+        assert!(cmd.get_arguments().any(|arg| arg.get_id() == "raw"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_raw() {
+        let mock_body = "{\"response\":{\"game_count\":1,\"games\":[{\"appid\":1,\"name\":\"Game 1\",\"new_field\":true}]}}";
+        let (app_context, _server) = setup_test_env(mock_body, 200).await;
+        let matches = get_matches_for_args(&["list", "--raw"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListGamesPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        assert_eq!(writer, mock_body.as_bytes());
+        assert!(err_writer.is_empty());
+    }
+
+    #[test]
+    fn test_raw_conflicts_with_rendering_options() {
+        let result = ListGamesPlugin.command().try_get_matches_from(["list", "--raw", "--format", "tsv"]);
+        assert_eq!(result.unwrap_err().kind(), clap::error::ErrorKind::ArgumentConflict);
     }
 
     #[tokio::test]
@@ -218,6 +741,41 @@ mod tests {
         assert!(!output.contains("[2] Another Game"));
     }
 
+    #[tokio::test]
+    async fn test_execute_with_regex_filter() {
+        let games = vec![
+            create_mock_game(1, "Awesome Game"),
+            create_mock_game(2, "Another Game"),
+            create_mock_game(3, "Game of Awesome"),
+        ];
+        let mock_body = serde_json::to_string(&serde_json::json!({
+            "response": { "game_count": 3, "games": games }
+        })).unwrap();
+        let (app_context, _server) = setup_test_env(&mock_body, 200).await;
+        let matches = get_matches_for_args(&["list", "--filter", "^a\\w+ game$", "--regex", "--delimiter", ","]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListGamesPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        assert_eq!(output, "1,Awesome Game\n2,Another Game\n");
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_invalid_regex_filter() {
+        let (app_context, _server) = setup_test_env("", 200).await;
+        let matches = get_matches_for_args(&["list", "--filter", "[unclosed", "--regex"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListGamesPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let err_output = String::from_utf8(err_writer).unwrap();
+        assert!(err_output.starts_with("Invalid --filter regex '[unclosed':"));
+        assert!(writer.is_empty());
+    }
+
     #[tokio::test]
     async fn test_execute_success_with_filter_and_pattern() {
         let games = vec![create_mock_game(1, "Awesome Game")];
@@ -236,6 +794,23 @@ mod tests {
         assert!(output.contains("1 - Awesome Game"));
     }
 
+    #[tokio::test]
+    async fn test_execute_with_playtime_format() {
+        let games = vec![Game { playtime_forever: 153, ..create_mock_game(1, "Awesome Game") }];
+        let mock_body = serde_json::to_string(&serde_json::json!({
+            "response": { "game_count": 1, "games": games }
+        })).unwrap();
+        let (app_context, _server) = setup_test_env(&mock_body, 200).await;
+        let matches = get_matches_for_args(&["list", "--filter", "Awesome", "--pattern", "n: p", "--playtime-format", "decimal"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListGamesPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        assert!(output.contains("Awesome Game: 2.55h\n"));
+    }
+
     #[tokio::test]
     async fn test_execute_api_error() {
         let (app_context, _server) = setup_test_env("", 500).await;
@@ -265,4 +840,579 @@ mod tests {
         assert!(output.contains("Displaying all games:"));
         assert!(!output.contains("[")); // No games should be listed
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_execute_with_delimiter() {
+        let games = vec![create_mock_game(1, "Awesome Game"), create_mock_game(2, "Another Game")];
+        let mock_body = serde_json::to_string(&serde_json::json!({
+            "response": { "game_count": 2, "games": games }
+        })).unwrap();
+        let (app_context, _server) = setup_test_env(&mock_body, 200).await;
+        let matches = get_matches_for_args(&["list", "--delimiter", "\t"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListGamesPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        assert_eq!(output, "1\tAwesome Game\n2\tAnother Game\n");
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_delimiter_and_filter() {
+        let games = vec![create_mock_game(1, "Awesome Game"), create_mock_game(2, "Another Game")];
+        let mock_body = serde_json::to_string(&serde_json::json!({
+            "response": { "game_count": 2, "games": games }
+        })).unwrap();
+        let (app_context, _server) = setup_test_env(&mock_body, 200).await;
+        let matches = get_matches_for_args(&["list", "--filter", "Awesome", "--delimiter", ","]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListGamesPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        assert_eq!(output, "1,Awesome Game\n");
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_ids() {
+        let games = vec![
+            create_mock_game(1, "Awesome Game"),
+            create_mock_game(2, "Another Game"),
+            create_mock_game(3, "Awesome Sequel"),
+        ];
+        let mock_body = serde_json::to_string(&serde_json::json!({
+            "response": { "game_count": 3, "games": games }
+        })).unwrap();
+        let (app_context, _server) = setup_test_env(&mock_body, 200).await;
+        let matches = get_matches_for_args(&["list", "--ids", "1,2", "--delimiter", ","]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListGamesPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        assert_eq!(output, "1,Awesome Game\n2,Another Game\n");
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_ids_and_filter() {
+        let games = vec![
+            create_mock_game(1, "Awesome Game"),
+            create_mock_game(2, "Another Game"),
+            create_mock_game(3, "Awesome Sequel"),
+        ];
+        let mock_body = serde_json::to_string(&serde_json::json!({
+            "response": { "game_count": 3, "games": games }
+        })).unwrap();
+        let (app_context, _server) = setup_test_env(&mock_body, 200).await;
+        let matches = get_matches_for_args(&["list", "--ids", "1,2", "--filter", "Awesome", "--delimiter", ","]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListGamesPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        assert_eq!(output, "1,Awesome Game\n");
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_malformed_ids() {
+        let (app_context, _server) = setup_test_env("", 200).await;
+        let matches = get_matches_for_args(&["list", "--ids", "1,two"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListGamesPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(err_writer).unwrap();
+        assert!(output.contains("Invalid game id 'two' in --ids"));
+        assert!(writer.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_progress_tokens() {
+        let games = vec![create_mock_game(123, "Awesome Game")];
+        let mock_body = serde_json::to_string(&serde_json::json!({
+            "response": { "game_count": 1, "games": games }
+        })).unwrap();
+        let (app_context, mut server) = setup_test_env(&mock_body, 200).await;
+        let achievements_body = serde_json::json!({
+            "playerstats": {
+                "steamID": "test_id",
+                "gameName": "Awesome Game",
+                "success": true,
+                "achievements": [
+                    { "apiname": "a1", "name": "A1", "description": "", "achieved": 1, "unlocktime": 1 },
+                    { "apiname": "a2", "name": "A2", "description": "", "achieved": 0, "unlocktime": 0 },
+                    { "apiname": "a3", "name": "A3", "description": "", "achieved": 0, "unlocktime": 0 }
+                ]
+            }
+        }).to_string();
+        server.mock("GET", "/ISteamUserStats/GetPlayerAchievements/v0001/?appid=123&key=test_key&steamid=test_id&l=en")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(achievements_body)
+            .create_async().await;
+        let matches = get_matches_for_args(&["list", "--filter", "Awesome", "--pattern", "i: c/a", "--with-progress"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListGamesPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        assert!(output.contains("123: 1/3"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_progress_tokens_without_flag() {
+        let games = vec![create_mock_game(123, "Awesome Game")];
+        let mock_body = serde_json::to_string(&serde_json::json!({
+            "response": { "game_count": 1, "games": games }
+        })).unwrap();
+        let (app_context, _server) = setup_test_env(&mock_body, 200).await;
+        let matches = get_matches_for_args(&["list", "--filter", "Awesome", "--pattern", "i: c/a"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListGamesPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        assert!(output.contains("123: /"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_pattern_trims_trailing_whitespace() {
+        let games = vec![create_mock_game(123, "Awesome Game")];
+        let mock_body = serde_json::to_string(&serde_json::json!({
+            "response": { "game_count": 1, "games": games }
+        })).unwrap();
+        let (app_context, _server) = setup_test_env(&mock_body, 200).await;
+        // Without --with-progress the c token is empty, and the name is padded
+        let matches = get_matches_for_args(&["list", "--filter", "Awesome", "--pattern", "i {n:20} c"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListGamesPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        assert!(output.lines().any(|line| line == "123 Awesome Game"));
+        assert!(output.lines().all(|line| line == line.trim_end()));
+    }
+
+    #[tokio::test]
+    async fn test_execute_ndjson_format() {
+        let games = vec![
+            create_mock_game(1, "Awesome Game"),
+            create_mock_game(2, "Another Game"),
+            create_mock_game(3, "Awesome Sequel"),
+        ];
+        let mock_body = serde_json::to_string(&serde_json::json!({
+            "response": { "game_count": 3, "games": games }
+        })).unwrap();
+        let (app_context, _server) = setup_test_env(&mock_body, 200).await;
+        let matches = get_matches_for_args(&["list", "--format", "ndjson"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListGamesPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 3);
+        for (line, game) in lines.iter().zip(&games) {
+            let parsed: Game = serde_json::from_str(line).unwrap();
+            assert_eq!(&parsed, game);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_execute_yaml_format_round_trips() {
+        let games = vec![
+            create_mock_game(1, "Portal: Still Alive"),
+            create_mock_game(2, "- #1 \"Quoted\" 'Game'"),
+            create_mock_game(3, "yes"),
+        ];
+        let mock_body = serde_json::to_string(&serde_json::json!({
+            "response": { "game_count": 3, "games": games }
+        })).unwrap();
+        let (app_context, _server) = setup_test_env(&mock_body, 200).await;
+        let matches = get_matches_for_args(&["list", "--format", "yaml", "--aggregate"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListGamesPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let parsed: Vec<Game> = serde_yaml::from_str(&String::from_utf8(writer).unwrap()).unwrap();
+        assert_eq!(parsed, games);
+        assert!(String::from_utf8(err_writer).unwrap().starts_with("Total: 3 games"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_toml_format_round_trips() {
+        let games = vec![
+            create_mock_game(1, "Portal: Still Alive"),
+            create_mock_game(2, "[[games]] \"Quoted\" 'Game'"),
+            create_mock_game(3, "true"),
+        ];
+        let mock_body = serde_json::to_string(&serde_json::json!({
+            "response": { "game_count": 3, "games": games }
+        })).unwrap();
+        let (app_context, _server) = setup_test_env(&mock_body, 200).await;
+        let matches = get_matches_for_args(&["list", "--format", "toml", "--aggregate"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListGamesPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        assert!(output.starts_with("[[games]]\n"));
+        let parsed: TomlGames = toml::from_str(&output).unwrap();
+        assert_eq!(parsed.games, games);
+        assert!(String::from_utf8(err_writer).unwrap().starts_with("Total: 3 games"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_toml_format_without_games() {
+        let mock_body = r#"{"response": {"game_count": 0, "games": []}}"#;
+        let (app_context, _server) = setup_test_env(mock_body, 200).await;
+        let matches = get_matches_for_args(&["list", "--format", "toml"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListGamesPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let parsed: TomlGames = toml::from_str(&String::from_utf8(writer).unwrap()).unwrap();
+        assert!(parsed.games.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_execute_table_format() {
+        let games = vec![
+            Game { playtime_forever: 153, ..create_mock_game(440, "Team Fortress 2") },
+            Game { playtime_forever: 6000, ..create_mock_game(1245620, "エルデンリング") },
+        ];
+        let mock_body = serde_json::to_string(&serde_json::json!({
+            "response": { "game_count": 2, "games": games }
+        })).unwrap();
+        let (app_context, _server) = setup_test_env(&mock_body, 200).await;
+        let matches = get_matches_for_args(&["list", "--format", "table"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListGamesPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        assert_eq!(String::from_utf8(writer).unwrap(), concat!(
+            "     ID  Name             Playtime\n",
+            "-------  ---------------  --------\n",
+            "    440  Team Fortress 2        2h\n",
+            "1245620  エルデンリング       100h\n",
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_execute_auto_format_when_piped() {
+        let games = vec![create_mock_game(1, "Awesome Game"), create_mock_game(2, "Another\tGame")];
+        let mock_body = serde_json::to_string(&serde_json::json!({
+            "response": { "game_count": 2, "games": games }
+        })).unwrap();
+        let (app_context, _server) = setup_test_env(&mock_body, 200).await;
+        let app_context = app_context.with_interactive(false);
+        let matches = get_matches_for_args(&["list"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListGamesPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        assert_eq!(output, "1\tAwesome Game\n2\tAnother Game\n");
+    }
+
+    #[tokio::test]
+    async fn test_execute_auto_format_when_piped_keeps_text_options() {
+        let games = vec![create_mock_game(1, "Awesome Game")];
+        let mock_body = serde_json::to_string(&serde_json::json!({
+            "response": { "game_count": 1, "games": games }
+        })).unwrap();
+        let (app_context, _server) = setup_test_env(&mock_body, 200).await;
+        let app_context = app_context.with_interactive(false);
+        let matches = get_matches_for_args(&["list", "--filter", "Awesome", "--pattern", "i - n"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListGamesPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        assert_eq!(output, "Displaying games filtered by: Awesome\n1 - Awesome Game\n");
+    }
+
+    #[tokio::test]
+    async fn test_execute_explicit_text_format_when_piped() {
+        let games = vec![create_mock_game(1, "Awesome Game")];
+        let mock_body = serde_json::to_string(&serde_json::json!({
+            "response": { "game_count": 1, "games": games }
+        })).unwrap();
+        let (app_context, _server) = setup_test_env(&mock_body, 200).await;
+        let app_context = app_context.with_interactive(false);
+        let matches = get_matches_for_args(&["list", "--format", "text"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListGamesPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        assert_eq!(output, "Displaying all games:\n[1] Awesome Game\n");
+    }
+
+    fn create_mock_achievement(apiname: &str) -> Achievement {
+        Achievement {
+            apiname: apiname.to_string(),
+            name: apiname.to_string(),
+            description: "".to_string(),
+            achieved: 0,
+            unlocktime: 0,
+        }
+    }
+
+    fn create_completion_api() -> FakeApi {
+        let achievement = |apiname: &str, achieved: u8| Achievement {
+            apiname: apiname.to_string(),
+            name: apiname.to_string(),
+            description: "".to_string(),
+            achieved,
+            unlocktime: 0,
+        };
+        FakeApi::new()
+            .with_game(1, "Perfect Game")
+            .with_game(2, "Partial Game")
+            .with_game(3, "Empty Game")
+            .with_achievements(1, "Perfect Game", vec![achievement("a1", 1), achievement("a2", 1)])
+            .with_achievements(2, "Partial Game", vec![achievement("b1", 1), achievement("b2", 0)])
+            .with_achievements(3, "Empty Game", vec![])
+    }
+
+    fn create_rarity_api() -> FakeApi {
+        let global = |name: &str, percent: f32| GlobalAchievement { name: name.to_string(), percent };
+        create_completion_api()
+            .with_game(4, "Locked Game")
+            .with_achievements(4, "Locked Game", vec![Achievement { achieved: 0, ..create_mock_achievement("c1") }])
+            .with_global_achievements(1, vec![global("a1", 80.0), global("a2", 60.0)])
+            .with_global_achievements(2, vec![global("b1", 5.0), global("b2", 1.0)])
+            .with_global_achievements(3, vec![])
+            .with_global_achievements(4, vec![global("c1", 0.5)])
+    }
+
+    #[tokio::test]
+    async fn test_execute_sort_by_rarity() {
+        let app_context = AppContext::with_api(create_rarity_api());
+        let matches = get_matches_for_args(&["list", "--sort", "rarity", "--format", "tsv"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListGamesPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        // Partial Game averages 5%, Perfect Game 70%, the others have nothing unlocked
+        assert_eq!(
+            String::from_utf8(writer).unwrap(),
+            "2\tPartial Game\n1\tPerfect Game\n3\tEmpty Game\n4\tLocked Game\n"
+        );
+        assert!(err_writer.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_execute_sort_by_rarity_with_progress() {
+        let app_context = AppContext::with_api(create_rarity_api());
+        let matches = get_matches_for_args(&["list", "--sort", "rarity", "--with-progress", "--filter", "--pattern", "n: c/a"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListGamesPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        assert!(output.ends_with("\nPartial Game: 1/2\nPerfect Game: 2/2\nEmpty Game: 0/0\nLocked Game: 0/1\n"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_sort_by_rarity_reports_errors_and_sorts_last() {
+        let api = create_rarity_api().with_game(5, "Private Game");
+        let app_context = AppContext::with_api(api);
+        let matches = get_matches_for_args(&["list", "--sort", "rarity", "--format", "tsv"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListGamesPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        assert!(String::from_utf8(writer).unwrap().ends_with("5\tPrivate Game\n"));
+        assert!(String::from_utf8(err_writer).unwrap().contains("Error while trying to get achievements for Private Game"));
+    }
+
+    #[test]
+    fn test_average_unlocked_rarity() {
+        let achievements = vec![
+            Achievement { achieved: 1, ..create_mock_achievement("a1") },
+            Achievement { achieved: 1, ..create_mock_achievement("a2") },
+            Achievement { achieved: 0, ..create_mock_achievement("a3") },
+        ];
+        let global = vec![
+            GlobalAchievement { name: "a1".to_string(), percent: 30.0 },
+            GlobalAchievement { name: "a3".to_string(), percent: 90.0 },
+        ];
+
+        // a2 is missing from the global percentages and counts as 0%
+        assert_eq!(average_unlocked_rarity(&achievements, &global), Some(15.0));
+        assert_eq!(average_unlocked_rarity(&achievements[2..], &global), None);
+        assert_eq!(average_unlocked_rarity(&[], &global), None);
+    }
+
+    #[tokio::test]
+    async fn test_execute_only_perfect() {
+        let app_context = AppContext::with_api(create_completion_api());
+        let matches = get_matches_for_args(&["list", "--only-perfect", "--format", "tsv"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListGamesPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        assert_eq!(String::from_utf8(writer).unwrap(), "1\tPerfect Game\n");
+        assert!(err_writer.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_execute_only_incomplete() {
+        let app_context = AppContext::with_api(create_completion_api());
+        let matches = get_matches_for_args(&["list", "--only-incomplete", "--format", "tsv"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListGamesPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        assert_eq!(String::from_utf8(writer).unwrap(), "2\tPartial Game\n");
+        assert!(err_writer.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_execute_only_incomplete_with_progress() {
+        let app_context = AppContext::with_api(create_completion_api());
+        let matches = get_matches_for_args(&["list", "--only-incomplete", "--with-progress", "--filter", "--pattern", "n: c/a"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListGamesPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        assert!(output.ends_with("\nPartial Game: 1/2\n"));
+        assert!(!output.contains("Perfect Game"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_only_perfect_reports_failing_games() {
+        let api = create_completion_api().with_game(4, "Unknown Game");
+        let app_context = AppContext::with_api(api);
+        let matches = get_matches_for_args(&["list", "--only-perfect", "--format", "tsv"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListGamesPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        assert_eq!(String::from_utf8(writer).unwrap(), "1\tPerfect Game\n");
+        assert!(String::from_utf8(err_writer).unwrap().starts_with("Error while trying to get achievements for Unknown Game"));
+    }
+
+    #[test]
+    fn test_only_perfect_conflicts_with_only_incomplete() {
+        let result = ListGamesPlugin.command().try_get_matches_from(["list", "--only-perfect", "--only-incomplete"]);
+        assert_eq!(result.unwrap_err().kind(), clap::error::ErrorKind::ArgumentConflict);
+    }
+
+    async fn setup_playtime_test_env() -> (AppContext, mockito::ServerGuard) {
+        let games = vec![
+            Game { playtime_forever: 600, ..create_mock_game(22300, "Fallout 3") },
+            Game { playtime_forever: 6150, ..create_mock_game(377160, "Fallout 4") },
+            Game { playtime_forever: 300, ..create_mock_game(400, "Portal") },
+        ];
+        let mock_body = serde_json::json!({ "response": { "game_count": 3, "games": games } }).to_string();
+        setup_test_env(&mock_body, 200).await
+    }
+
+    #[tokio::test]
+    async fn test_execute_aggregate_reflects_filters() {
+        let (app_context, _server) = setup_playtime_test_env().await;
+        let matches = get_matches_for_args(&["list", "--filter", "fallout", "--aggregate"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListGamesPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        assert!(output.ends_with("[377160] Fallout 4\nTotal: 2 games, 112 hours\n"));
+        assert!(!output.contains("Portal"));
+        assert!(err_writer.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_execute_aggregate_when_piped() {
+        let (app_context, _server) = setup_playtime_test_env().await;
+        let matches = get_matches_for_args(&["list", "--ids", "400,22300", "--format", "tsv", "--aggregate"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListGamesPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        assert_eq!(String::from_utf8(writer).unwrap(), "22300\tFallout 3\n400\tPortal\n");
+        assert_eq!(String::from_utf8(err_writer).unwrap(), "Total: 2 games, 15 hours\n");
+    }
+
+    #[tokio::test]
+    async fn test_execute_aggregate_when_quiet() {
+        let (app_context, _server) = setup_playtime_test_env().await;
+        let app_context = app_context.with_quiet(true);
+        let matches = get_matches_for_args(&["list", "--ids", "400,22300", "--format", "tsv", "--aggregate"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListGamesPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        assert_eq!(String::from_utf8(err_writer).unwrap(), "Total: 2 games, 15 hours\n");
+    }
+
+    #[test]
+    fn test_aggregate_line_groups_thousands() {
+        let games = vec![Game { playtime_forever: 7_407_407, ..create_mock_game(440, "Team Fortress 2") }];
+
+        assert_eq!(aggregate_line(&games, true), "Total: 1 games, 123,456 hours");
+        assert_eq!(aggregate_line(&games, false), "Total: 1 games, 123456 hours");
+    }
+
+    #[tokio::test]
+    async fn test_execute_table_groups_playtime_thousands() {
+        let api = || FakeApi::new().with_game_playtime(440, "Team Fortress 2", 90_000);
+        let matches = get_matches_for_args(&["list", "--format", "table"]);
+
+        let mut writer = Vec::new();
+        ListGamesPlugin.execute(&AppContext::with_api(api()), &matches, &mut writer, &mut Vec::new()).await;
+        assert!(String::from_utf8(writer).unwrap().ends_with("Team Fortress 2    1,500h\n"));
+
+        let mut writer = Vec::new();
+        let app_context = AppContext::with_api(api()).with_group_thousands(false);
+        ListGamesPlugin.execute(&app_context, &matches, &mut writer, &mut Vec::new()).await;
+        assert!(String::from_utf8(writer).unwrap().ends_with("Team Fortress 2     1500h\n"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_porcelain() {
+        let api = FakeApi::new().with_game(440, "Team Fortress 2").with_game(620, "Portal 2").with_game(570, "Dota 2");
+        let app_context = AppContext::with_api(api).with_porcelain(true);
+        let matches = get_matches_for_args(&["list", "--filter", "2", "--pattern", "n", "--aggregate"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ListGamesPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        assert_eq!(
+            String::from_utf8(writer).unwrap(),
+            "game\t440\tTeam Fortress 2\ngame\t620\tPortal 2\ngame\t570\tDota 2\n"
+        );
+        assert!(err_writer.is_empty());
+    }
+}
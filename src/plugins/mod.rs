@@ -19,8 +19,10 @@
 //! - None
 //! <side-effects-end>
 
-use crate::app::AppContext;
+use crate::{app::AppContext, cfg::Cfg, steam_api::{Achievement, ApiError, Game}, tui, ui};
 use async_trait::async_trait;
+use clap::Arg;
+use regex::{Regex, RegexBuilder};
 use std::io::Write;
 
 pub mod list_games;
@@ -28,6 +30,15 @@ pub mod dashboard;
 pub mod list_achievements;
 pub mod show_progress;
 pub mod completions;
+pub mod top;
+pub mod verify;
+pub mod open;
+pub mod diff;
+pub mod export;
+pub mod today;
+pub mod complete_games;
+pub mod info;
+pub mod stats;
 
 #[async_trait]
 pub trait Plugin {
@@ -82,6 +93,699 @@ pub trait Plugin {
     );
 }
 
+// Defines the argument selecting a game by its name instead of its id.
+//
+// <purpose-start>
+// Shared by every game-specific command, so that the flag behaves identically everywhere.
+// <purpose-end>
+//
+// <inputs-start>
+// - None.
+// <inputs-end>
+//
+// <outputs-start>
+// - `Arg`: The `--game-name` argument.
+// <outputs-end>
+//
+// <side-effects-start>
+// - None.
+// <side-effects-end>
+pub fn game_name_arg() -> Arg {
+    Arg::new("game_name")
+        .long("game-name")
+        .value_name("name")
+        .help("The name of the game, matched case-insensitively against the owned games. Ignored when a game id is given")
+}
+
+// Finds the id of the owned game matching a name.
+//
+// <purpose-start>
+// A game whose name equals the given one, ignoring case, wins over games merely containing it,
+// so that e.g. "Portal" still selects "Portal" when "Portal 2" is owned as well.
+// <purpose-end>
+//
+// <inputs-start>
+// - `games`: The user's owned games.
+// - `name`: The name to look for.
+// <inputs-end>
+//
+// <outputs-start>
+// - `Ok(u32)`: The id of the only matching game.
+// - `Err(String)`: A user-facing message if no game or several games match.
+// <outputs-end>
+//
+// <side-effects-start>
+// - None.
+// <side-effects-end>
+pub fn find_game_by_name(games: &[Game], name: &str) -> Result<u32, String> {
+    let needle = name.to_lowercase();
+    let exact: Vec<&Game> = games.iter().filter(|g| g.name.to_lowercase() == needle).collect();
+    let candidates = if exact.is_empty() {
+        games.iter().filter(|g| g.name.to_lowercase().contains(&needle)).collect()
+    } else {
+        exact
+    };
+
+    match candidates.as_slice() {
+        [] => Err(format!("No owned game matches '{}'", name)),
+        [game] => Ok(game.appid),
+        _ => {
+            let names: Vec<String> = candidates.iter().map(|g| format!("{} ({})", g.name, g.appid)).collect();
+            Err(format!("Multiple games match '{}': {}", name, names.join(", ")))
+        }
+    }
+}
+
+// Resolves the `--game-name` argument of a game-specific command to a game id.
+//
+// <purpose-start>
+// Fetches the games list and matches the name with `find_game_by_name`.
+// <purpose-end>
+//
+// <inputs-start>
+// - `app_context`: The shared application context, providing the games list.
+// - `name`: The value of the `--game-name` argument.
+// - `err_writer`: A mutable reference to a writer for standard error.
+// <inputs-end>
+//
+// <outputs-start>
+// - `Some(u32)`: The id of the matching game.
+// - `None`: If the games list cannot be fetched, or no game or several games match.
+// <outputs-end>
+//
+// <side-effects-start>
+// - Makes a network request to the Steam API to fetch the games list.
+// - Writes an error message to `err_writer`.
+// <side-effects-end>
+pub async fn resolve_game_name(
+    app_context: &AppContext,
+    name: &str,
+    err_writer: &mut (dyn Write + Send),
+) -> Option<u32> {
+    let games = match app_context.get_games_list_cached().await {
+        Ok(games) => games,
+        Err(e) => {
+            writeln!(err_writer, "Error while trying to get games list: {}", ui::scrub_api_key(&e.to_string())).unwrap();
+            return None;
+        }
+    };
+
+    match find_game_by_name(&games, name) {
+        Ok(appid) => Some(appid),
+        Err(e) => {
+            writeln!(err_writer, "{}", e).unwrap();
+            None
+        }
+    }
+}
+
+// The hint shown when a command is missing the id of a game.
+pub const GAME_ID_HINT: &str = "Hint: run 'trogue list --filter <name>' to find the id of a game, or select it with --game-name <name>.";
+
+// Returns the hint to show after a command-line parsing error, if any.
+//
+// <purpose-start>
+// Clap's usage error for a missing game id does not tell users where to find one. The game id
+// arguments of the commands are all named `game_id`, so this function recognizes them in the error.
+// <purpose-end>
+//
+// <inputs-start>
+// - `error`: The error returned by clap.
+// <inputs-end>
+//
+// <outputs-start>
+// - `Some(&str)`: The hint to print after the error.
+// - `None`: If there is no hint for the error.
+// <outputs-end>
+//
+// <side-effects-start>
+// - None.
+// <side-effects-end>
+pub fn parse_error_hint(error: &clap::Error) -> Option<&'static str> {
+    if error.kind() != clap::error::ErrorKind::MissingRequiredArgument {
+        return None;
+    }
+
+    match error.get(clap::error::ContextKind::InvalidArg) {
+        Some(clap::error::ContextValue::Strings(args)) if args.iter().any(|arg| arg == "<game_id>") => Some(GAME_ID_HINT),
+        _ => None,
+    }
+}
+
+// Writes a non-essential message to standard error.
+//
+// <purpose-start>
+// Notices and warnings that do not prevent a command from producing its output go through this
+// function, so that `--quiet` suppresses them while errors are still reported.
+// <purpose-end>
+//
+// <inputs-start>
+// - `app_context`: The shared application context, telling whether output is quiet.
+// - `err_writer`: A mutable reference to a writer for standard error.
+// - `message`: The message to write.
+// <inputs-end>
+//
+// <outputs-start>
+// - None.
+// <outputs-end>
+//
+// <side-effects-start>
+// - Writes the message to `err_writer`, unless `--quiet` was given.
+// <side-effects-end>
+pub fn notice(app_context: &AppContext, err_writer: &mut (dyn Write + Send), message: &str) {
+    if !app_context.quiet() {
+        writeln!(err_writer, "{}", message).unwrap();
+    }
+}
+
+// Parses a comma-separated list of game ids.
+//
+// <purpose-start>
+// This function converts the value of options such as `list --ids` and `dashboard --pin` into a
+// list of game ids. Whitespace around each id is ignored.
+// <purpose-end>
+//
+// <inputs-start>
+// - `ids`: The comma-separated list of game ids, e.g. "440,570".
+// - `option`: The option the list was given to, named in the error message.
+// <inputs-end>
+//
+// <outputs-start>
+// - `Ok(Vec<u32>)`: The parsed game ids.
+// - `Err(String)`: An error message naming the first token that is not a valid game id.
+// <outputs-end>
+//
+// <side-effects-start>
+// - None.
+// <side-effects-end>
+pub fn parse_ids(ids: &str, option: &str) -> Result<Vec<u32>, String> {
+    ids.split(',')
+        .map(|token| {
+            let token = token.trim();
+            token
+                .parse::<u32>()
+                .map_err(|_| format!("Invalid game id '{}' in {}, expected a comma-separated list of numbers", token, option))
+        })
+        .collect()
+}
+
+// Collects the games skipped by a bulk command because their data could not be fetched.
+//
+// <purpose-start>
+// Commands fetching many games, such as `dashboard` and `export`, keep going when a game fails.
+// The error of each game is only written with `--verbose`, and the skipped games are summarized
+// once at the end, so that failures do not scroll past among the progress lines.
+// <purpose-end>
+#[derive(Default)]
+pub struct SkippedGames {
+    appids: Vec<u32>,
+}
+
+impl SkippedGames {
+    // Records a skipped game, writing its error to `err_writer` with `--verbose`.
+    pub fn skip(&mut self, app_context: &AppContext, appid: u32, error: &str, err_writer: &mut (dyn Write + Send)) {
+        self.appids.push(appid);
+        if app_context.verbose() {
+            writeln!(err_writer, "{}", error).unwrap();
+        }
+    }
+
+    // Writes the summary, such as `Skipped 2 games due to errors: [440, 570]`, if a game was skipped.
+    pub fn report(&self, err_writer: &mut (dyn Write + Send)) {
+        if self.appids.is_empty() {
+            return;
+        }
+
+        let games = if self.appids.len() == 1 { "game" } else { "games" };
+        let appids: Vec<String> = self.appids.iter().map(u32::to_string).collect();
+        writeln!(err_writer, "Skipped {} {} due to errors: [{}]", self.appids.len(), games, appids.join(", ")).unwrap();
+    }
+}
+
+// Returns the game argument of a game-specific command, falling back to the last used game.
+//
+// <purpose-start>
+// Commands like `progress` and `achievements` are often run repeatedly for the same game.
+// An explicit game argument wins over `--game-name`, which is resolved to a game id.
+// When both are omitted, the previously used game id is reused and the user is told so.
+// Without one, an interactive user picks the game from the games list, see `tui::select_game`.
+// <purpose-end>
+//
+// <inputs-start>
+// - `app_context`: The shared application context, providing the last used game id.
+// - `matches`: The clap argument matches for the subcommand, which must define `game_name_arg`.
+// - `id`: The id of the game argument.
+// - `err_writer`: A mutable reference to a writer for standard error.
+// <inputs-end>
+//
+// <outputs-start>
+// - `Some(String)`: The game argument as given, the id of the named game, the last used game id
+//   or the id of the picked game.
+// - `None`: If the named game could not be resolved, or no argument was given, no game id has been
+//   stored and no game was picked.
+// <outputs-end>
+//
+// <side-effects-start>
+// - Reads the last game file.
+// - Makes a network request to the Steam API to resolve `--game-name` or to list the games to pick from.
+// - Takes over the terminal while the user picks a game.
+// - Writes a notice or an error message to `err_writer`.
+// <side-effects-end>
+pub async fn game_arg_or_last(
+    app_context: &AppContext,
+    matches: &clap::ArgMatches,
+    id: &str,
+    err_writer: &mut (dyn Write + Send),
+) -> Option<String> {
+    if let Some(game) = matches.get_one::<String>(id) {
+        return Some(game.clone());
+    }
+
+    if let Some(name) = matches.get_one::<String>("game_name") {
+        return resolve_game_name(app_context, name, err_writer)
+            .await
+            .map(|appid| appid.to_string());
+    }
+
+    match app_context.last_game_id() {
+        Some(game_id) => {
+            notice(app_context, err_writer, &format!("No game given, using last game id: {}", game_id));
+            Some(game_id.to_string())
+        }
+        None if app_context.interactive() => pick_game(app_context, err_writer).await.map(|appid| appid.to_string()),
+        None => {
+            writeln!(err_writer, "No game given and no previously used game found. Please provide a game id.").unwrap();
+            writeln!(err_writer, "{}", GAME_ID_HINT).unwrap();
+            None
+        }
+    }
+}
+
+// Lets the user pick one of the owned games in the terminal.
+//
+// <inputs-start>
+// - `app_context`: The shared application context.
+// - `err_writer`: A mutable reference to a writer for standard error.
+// <inputs-end>
+//
+// <outputs-start>
+// - `Some(u32)`: The id of the picked game.
+// - `None`: If the games list cannot be fetched, the terminal cannot be used or the user cancelled.
+// <outputs-end>
+//
+// <side-effects-start>
+// - Makes a network request to the Steam API to fetch the games list.
+// - Takes over the terminal while the user picks a game.
+// - Writes an error message to `err_writer` if no game was picked.
+// <side-effects-end>
+async fn pick_game(app_context: &AppContext, err_writer: &mut (dyn Write + Send)) -> Option<u32> {
+    let games = match app_context.get_games_list_cached().await {
+        Ok(games) => games,
+        Err(e) => {
+            writeln!(err_writer, "Error while trying to get games list: {}", ui::scrub_api_key(&e.to_string())).unwrap();
+            return None;
+        }
+    };
+
+    // The picker blocks on key presses, which must not stall the runtime
+    match tokio::task::spawn_blocking(move || tui::select_game(games)).await {
+        Ok(Ok(Some(appid))) => Some(appid),
+        Ok(Ok(None)) => {
+            writeln!(err_writer, "No game selected.").unwrap();
+            None
+        }
+        Ok(Err(e)) => {
+            writeln!(err_writer, "Error while trying to pick a game: {}", e).unwrap();
+            None
+        }
+        Err(e) => {
+            writeln!(err_writer, "Error while trying to pick a game: {}", e).unwrap();
+            None
+        }
+    }
+}
+
+// Records the game used by a game-specific command.
+//
+// <purpose-start>
+// Failing to persist the last game must not fail the command itself, so errors are only reported.
+// <purpose-end>
+//
+// <inputs-start>
+// - `app_context`: The shared application context, providing the last game store.
+// - `game_id`: The id of the game to remember.
+// - `err_writer`: A mutable reference to a writer for standard error.
+// <inputs-end>
+//
+// <outputs-start>
+// - None.
+// <outputs-end>
+//
+// <side-effects-start>
+// - Writes the last game file.
+// - Writes a warning to `err_writer` if the game id could not be stored, unless `--quiet` was given.
+// <side-effects-end>
+pub fn remember_game(app_context: &AppContext, game_id: u32, err_writer: &mut (dyn Write + Send)) {
+    if let Err(e) = app_context.remember_game(game_id) {
+        notice(app_context, err_writer, &format!("Warning: could not remember last game: {}", e));
+    }
+}
+
+// Records the unlock state of a game seen by a game-specific command.
+//
+// <purpose-start>
+// Failing to persist the unlocks must not fail the command itself, so errors are only reported.
+// <purpose-end>
+//
+// <inputs-start>
+// - `app_context`: The shared application context, providing the unlock history store.
+// - `game_id`: The id of the game.
+// - `game_name`: The name of the game.
+// - `achievements`: The user's achievements for the game.
+// - `err_writer`: A mutable reference to a writer for standard error.
+// <inputs-end>
+//
+// <outputs-start>
+// - None.
+// <outputs-end>
+//
+// <side-effects-start>
+// - Writes the file of the game in the unlock history.
+// - Writes a warning to `err_writer` if the unlocks could not be stored, unless `--quiet` was given.
+// <side-effects-end>
+pub fn record_unlocks(
+    app_context: &AppContext,
+    game_id: u32,
+    game_name: &str,
+    achievements: &[Achievement],
+    err_writer: &mut (dyn Write + Send),
+) {
+    if let Err(e) = app_context.record_unlocks(game_id, game_name, achievements) {
+        notice(app_context, err_writer, &format!("Warning: could not record unlocked achievements: {}", e));
+    }
+}
+
+// Defines the flag listing the achievements unlocked since the previous run.
+//
+// <purpose-start>
+// Shared by `progress` and `achievements`, which both record the unlocks of the games they fetch.
+// <purpose-end>
+//
+// <inputs-start>
+// - None.
+// <inputs-end>
+//
+// <outputs-start>
+// - `Arg`: The `--changes` argument.
+// <outputs-end>
+//
+// <side-effects-start>
+// - None.
+// <side-effects-end>
+pub fn changes_arg() -> Arg {
+    Arg::new("changes")
+        .long("changes")
+        .action(clap::ArgAction::SetTrue)
+        .help("Lists the achievements unlocked since the game was last displayed by progress or achievements")
+}
+
+// Defines the arguments customizing the progress bar glyphs.
+//
+// <purpose-start>
+// Shared by every command drawing progress bars, so that the flags behave identically everywhere.
+// <purpose-end>
+//
+// <inputs-start>
+// - None.
+// <inputs-end>
+//
+// <outputs-start>
+// - `Vec<Arg>`: The `--bar-fill` and `--bar-empty` arguments.
+// <outputs-end>
+//
+// <side-effects-start>
+// - None.
+// <side-effects-end>
+pub fn bar_style_args() -> Vec<Arg> {
+    vec![
+        Arg::new("bar_fill")
+            .long("bar-fill")
+            .value_name("char")
+            .value_parser(ui::parse_bar_glyph)
+            .help("The character used for the filled part of progress bars (overrides TROGUE_BAR_FILL, default: █)"),
+        Arg::new("bar_empty")
+            .long("bar-empty")
+            .value_name("char")
+            .value_parser(ui::parse_bar_glyph)
+            .help("The character used for the empty part of progress bars (overrides TROGUE_BAR_EMPTY, default: space)"),
+        Arg::new("warn_threshold")
+            .long("warn-threshold")
+            .value_name("percent")
+            .value_parser(ui::parse_threshold)
+            .help("The completion percentage from which progress bars are yellow instead of red (overrides TROGUE_WARN_THRESHOLD, default: 50)"),
+        Arg::new("good_threshold")
+            .long("good-threshold")
+            .value_name("percent")
+            .value_parser(ui::parse_threshold)
+            .help("The completion percentage from which progress bars are green (overrides TROGUE_GOOD_THRESHOLD, default: 100)"),
+    ]
+}
+
+// Resolves the progress bar glyphs and color thresholds from the command line and the environment.
+//
+// <purpose-start>
+// Command-line flags take precedence over the `TROGUE_BAR_FILL`, `TROGUE_BAR_EMPTY`,
+// `TROGUE_WARN_THRESHOLD` and `TROGUE_GOOD_THRESHOLD` environment variables, which in turn take
+// precedence over the defaults. Whether the bars are colored is left to the caller.
+// <purpose-end>
+//
+// <inputs-start>
+// - `matches`: The clap argument matches of a command using `bar_style_args`.
+// <inputs-end>
+//
+// <outputs-start>
+// - `Ok(ui::BarStyle)`: The resolved glyphs and thresholds, uncolored.
+// - `Err(String)`: A user-facing message if an environment variable is invalid or the thresholds are out of order.
+// <outputs-end>
+//
+// <side-effects-start>
+// - Reads the `TROGUE_BAR_FILL`, `TROGUE_BAR_EMPTY`, `TROGUE_WARN_THRESHOLD` and `TROGUE_GOOD_THRESHOLD`
+//   environment variables.
+// <side-effects-end>
+pub fn bar_style(matches: &clap::ArgMatches) -> Result<ui::BarStyle, String> {
+    let default = ui::BarStyle::default();
+
+    let resolve = |id: &str, env_key: &str, default: char| -> Result<char, String> {
+        if let Some(glyph) = matches.get_one::<char>(id) {
+            return Ok(*glyph);
+        }
+
+        match Cfg::read_env(env_key) {
+            Ok(value) => ui::parse_bar_glyph(&value).map_err(|e| format!("Invalid {}: {}", env_key, e)),
+            Err(_) => Ok(default),
+        }
+    };
+
+    let resolve_threshold = |id: &str, env_key: &str, default: f32| -> Result<f32, String> {
+        if let Some(threshold) = matches.get_one::<f32>(id) {
+            return Ok(*threshold);
+        }
+
+        match Cfg::read_env(env_key) {
+            Ok(value) => ui::parse_threshold(&value).map_err(|e| format!("Invalid {}: {}", env_key, e)),
+            Err(_) => Ok(default),
+        }
+    };
+
+    let thresholds = ui::ColorThresholds::new(
+        resolve_threshold("warn_threshold", "TROGUE_WARN_THRESHOLD", default.thresholds.warn)?,
+        resolve_threshold("good_threshold", "TROGUE_GOOD_THRESHOLD", default.thresholds.good)?,
+    )?;
+
+    Ok(ui::BarStyle {
+        fill: resolve("bar_fill", "TROGUE_BAR_FILL", default.fill)?,
+        empty: resolve("bar_empty", "TROGUE_BAR_EMPTY", default.empty)?,
+        thresholds,
+        colored: false,
+    })
+}
+
+// Represents a `--filter` value matched against game or achievement names.
+//
+// <purpose-start>
+// By default the filter is a case-insensitive substring. With `--regex` it is a regular expression,
+// also matched case-insensitively, which may match anywhere in the name unless anchored.
+// <purpose-end>
+pub enum NameFilter {
+    Substring(String),
+    Regex(Regex),
+}
+
+impl NameFilter {
+    // Creates a filter from the value of `--filter`.
+    //
+    // <purpose-start>
+    // This function validates the regular expression up front, so that an invalid pattern is
+    // reported to the user instead of failing later.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `filter`: The filter string.
+    // - `regex`: Whether the filter string is a regular expression.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `Ok(NameFilter)`: The filter.
+    // - `Err(String)`: A user-facing message if the regular expression is invalid.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None.
+    // <side-effects-end>
+    pub fn new(filter: &str, regex: bool) -> Result<NameFilter, String> {
+        if !regex {
+            return Ok(NameFilter::Substring(filter.to_lowercase()));
+        }
+
+        RegexBuilder::new(filter)
+            .case_insensitive(true)
+            .build()
+            .map(NameFilter::Regex)
+            .map_err(|e| format!("Invalid --filter regex '{}': {}", filter, e))
+    }
+
+    // Returns whether a name passes the filter.
+    pub fn matches(&self, name: &str) -> bool {
+        match self {
+            NameFilter::Substring(filter) => name.to_lowercase().contains(filter),
+            NameFilter::Regex(regex) => regex.is_match(name),
+        }
+    }
+}
+
+// Defines the flag switching `--filter` to regular expressions.
+//
+// <purpose-start>
+// Shared by every command with a name filter, so that the flag behaves identically everywhere.
+// <purpose-end>
+//
+// <inputs-start>
+// - None.
+// <inputs-end>
+//
+// <outputs-start>
+// - `Arg`: The `--regex` argument, which requires `--filter`.
+// <outputs-end>
+//
+// <side-effects-start>
+// - None.
+// <side-effects-end>
+pub fn regex_arg() -> Arg {
+    Arg::new("regex")
+        .long("regex")
+        .action(clap::ArgAction::SetTrue)
+        .requires("filter")
+        .help("Interprets --filter as a case-insensitive regular expression instead of a substring")
+}
+
+// Defines the developer flag printing the unmodified Steam response instead of the usual output.
+//
+// <purpose-start>
+// Shared by every command that can dump the response it renders, so that the flag behaves
+// identically everywhere. Each command declares which of its options the flag conflicts with.
+// <purpose-end>
+//
+// <inputs-start>
+// - None.
+// <inputs-end>
+//
+// <outputs-start>
+// - `Arg`: The `--raw` argument.
+// <outputs-end>
+//
+// <side-effects-start>
+// - None.
+// <side-effects-end>
+pub fn raw_arg() -> Arg {
+    Arg::new("raw")
+        .long("raw")
+        .action(clap::ArgAction::SetTrue)
+        .help("Prints the JSON response of the Steam API exactly as received, for debugging")
+}
+
+// Writes a raw Steam response, or the error of fetching it.
+//
+// <inputs-start>
+// - `result`: The response body, or the error of the request.
+// - `writer`: A mutable reference to a writer for standard output.
+// - `err_writer`: A mutable reference to a writer for standard error.
+// <inputs-end>
+//
+// <outputs-start>
+// - `bool`: `true` if the body was written, `false` if an error was reported.
+// <outputs-end>
+//
+// <side-effects-start>
+// - Writes the body byte for byte to `writer`, or the error to `err_writer`.
+// <side-effects-end>
+pub fn write_raw(result: Result<String, ApiError>, writer: &mut (dyn Write + Send), err_writer: &mut (dyn Write + Send)) -> bool {
+    match result {
+        Ok(body) => {
+            writer.write_all(body.as_bytes()).unwrap();
+            true
+        }
+        Err(e) => {
+            writeln!(err_writer, "Error while trying to get the raw Steam response: {}", ui::scrub_api_key(&e.to_string())).unwrap();
+            false
+        }
+    }
+}
+
+// The environment variable naming the command to run when none is given.
+pub const DEFAULT_COMMAND_ENV: &str = "TROGUE_DEFAULT_COMMAND";
+
+// Resolves the command to run when `trogue` is invoked without one.
+//
+// <purpose-start>
+// Users who nearly always run the same command can set `TROGUE_DEFAULT_COMMAND` to it. The name
+// is checked against the registered plugins, hidden ones excluded, so that a typo is reported
+// instead of silently printing the help.
+// <purpose-end>
+//
+// <inputs-start>
+// - `plugins`: The registered plugins.
+// - `default_command`: The value of `TROGUE_DEFAULT_COMMAND`, if set.
+// <inputs-end>
+//
+// <outputs-start>
+// - `Ok(Some(String))`: The name of the command to run.
+// - `Ok(None)`: If no default command is set, or it is blank.
+// - `Err(String)`: A user-facing message if the name does not match any command.
+// <outputs-end>
+//
+// <side-effects-start>
+// - None.
+// <side-effects-end>
+pub fn resolve_default_command(plugins: &[Box<dyn Plugin>], default_command: Option<&str>) -> Result<Option<String>, String> {
+    let name = match default_command.map(str::trim) {
+        Some(name) if !name.is_empty() => name,
+        _ => return Ok(None),
+    };
+
+    let names: Vec<String> = plugins
+        .iter()
+        .map(|plugin| plugin.command())
+        .filter(|command| !command.is_hide_set())
+        .map(|command| command.get_name().to_string())
+        .collect();
+
+    if names.iter().any(|known| known == name) {
+        Ok(Some(name.to_string()))
+    } else {
+        Err(format!("Invalid {}: '{}' is not a command, expected one of: {}", DEFAULT_COMMAND_ENV, name, names.join(", ")))
+    }
+}
+
 pub fn get_plugins() -> Vec<Box<dyn Plugin>> {
     vec![
         Box::new(list_games::ListGamesPlugin),
@@ -89,12 +793,303 @@ pub fn get_plugins() -> Vec<Box<dyn Plugin>> {
         Box::new(list_achievements::ListAchievementsPlugin),
         Box::new(show_progress::ShowProgressPlugin),
         Box::new(completions::CompletionsPlugin),
+        Box::new(top::TopPlugin),
+        Box::new(verify::VerifyPlugin),
+        Box::new(open::OpenPlugin),
+        Box::new(diff::DiffPlugin),
+        Box::new(export::ExportPlugin),
+        Box::new(today::TodayPlugin),
+        Box::new(complete_games::CompleteGamesPlugin),
+        Box::new(info::InfoPlugin),
+        Box::new(stats::StatsPlugin),
     ]
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::fake_api::FakeApi;
+    use crate::mock_api::MockApi;
+
+    fn create_game(appid: u32, name: &str) -> Game {
+        Game {
+            appid,
+            name: name.to_string(),
+            playtime_forever: 0,
+            img_icon_url: "".to_string(),
+            playtime_windows_forever: 0,
+            playtime_mac_forever: 0,
+            playtime_linux_forever: 0,
+            rtime_last_played: 0,
+            playtime_disconnected: 0,
+        }
+    }
+
+    // Tests the matching of `NameFilter`.
+    //
+    // <purpose-start>
+    // Both kinds of filters ignore case, and only the regex filter interprets special characters.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - None
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - None
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None
+    // <side-effects-end>
+    #[test]
+    fn test_name_filter() {
+        let substring = NameFilter::new("portal", false).unwrap();
+        assert!(substring.matches("Portal 2"));
+        assert!(!substring.matches("Half-Life"));
+
+        let regex = NameFilter::new("^portal( \\d)?$", true).unwrap();
+        assert!(regex.matches("Portal"));
+        assert!(regex.matches("Portal 2"));
+        assert!(!regex.matches("Portal Stories: Mel"));
+
+        let literal = NameFilter::new("^portal", false).unwrap();
+        assert!(!literal.matches("Portal"));
+    }
+
+    // Tests that `NameFilter` rejects an invalid regular expression.
+    //
+    // <purpose-start>
+    // This test verifies that an invalid pattern is reported as an error instead of causing a panic.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - None
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - None
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None
+    // <side-effects-end>
+    #[test]
+    fn test_name_filter_invalid_regex() {
+        let err = NameFilter::new("portal(", true).err().unwrap();
+        assert!(err.starts_with("Invalid --filter regex 'portal(':"));
+    }
+
+    // Tests that `find_game_by_name` resolves a unique match.
+    //
+    // <purpose-start>
+    // Matching ignores case and accepts part of a title, and an exact title wins over titles containing it.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - None
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - None
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None
+    // <side-effects-end>
+    #[test]
+    fn test_find_game_by_name_unique_match() {
+        let games = vec![create_game(400, "Portal"), create_game(620, "Portal 2"), create_game(70, "Half-Life")];
+
+        assert_eq!(find_game_by_name(&games, "half"), Ok(70));
+        assert_eq!(find_game_by_name(&games, "PORTAL 2"), Ok(620));
+        assert_eq!(find_game_by_name(&games, "portal"), Ok(400));
+    }
+
+    // Tests that `find_game_by_name` reports a name matching no game.
+    //
+    // <purpose-start>
+    // This test verifies that a missing game is reported with the name as given.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - None
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - None
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None
+    // <side-effects-end>
+    #[test]
+    fn test_find_game_by_name_no_match() {
+        let games = vec![create_game(400, "Portal")];
+
+        assert_eq!(find_game_by_name(&games, "Doom"), Err("No owned game matches 'Doom'".to_string()));
+    }
+
+    // Tests that `find_game_by_name` rejects an ambiguous name.
+    //
+    // <purpose-start>
+    // This test verifies that several matching games are listed with their ids instead of one being picked.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - None
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - None
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None
+    // <side-effects-end>
+    #[test]
+    fn test_find_game_by_name_ambiguous_match() {
+        let games = vec![create_game(400, "Portal"), create_game(620, "Portal 2")];
+
+        assert_eq!(
+            find_game_by_name(&games, "port"),
+            Err("Multiple games match 'port': Portal (400), Portal 2 (620)".to_string())
+        );
+    }
+
+    // Tests how `game_arg_or_last` combines the game id and `--game-name`.
+    //
+    // <purpose-start>
+    // The name is resolved through the games list, and an explicit game id wins over it.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - None
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - None
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None
+    // <side-effects-end>
+    #[tokio::test]
+    async fn test_game_arg_or_last_with_game_name() {
+        let app_context = AppContext::with_api(FakeApi::new().with_game(400, "Portal").with_game(620, "Portal 2"));
+        let command = clap::Command::new("progress")
+            .arg(Arg::new("game_id"))
+            .arg(game_name_arg());
+        let mut err_writer = Vec::new();
+
+        let matches = command.clone().get_matches_from(["progress", "--game-name", "portal 2"]);
+        assert_eq!(game_arg_or_last(&app_context, &matches, "game_id", &mut err_writer).await, Some("620".to_string()));
+
+        let matches = command.clone().get_matches_from(["progress", "123", "--game-name", "portal 2"]);
+        assert_eq!(game_arg_or_last(&app_context, &matches, "game_id", &mut err_writer).await, Some("123".to_string()));
+
+        let matches = command.get_matches_from(["progress", "--game-name", "port"]);
+        assert_eq!(game_arg_or_last(&app_context, &matches, "game_id", &mut err_writer).await, None);
+        assert!(String::from_utf8(err_writer).unwrap().contains("Multiple games match 'port'"));
+    }
+
+    #[tokio::test]
+    async fn test_game_arg_or_last_without_terminal_does_not_prompt() {
+        let app_context = AppContext::with_api(FakeApi::failing());
+        assert!(!app_context.interactive());
+        let command = clap::Command::new("progress")
+            .arg(Arg::new("game_id"))
+            .arg(game_name_arg());
+        let matches = command.get_matches_from(["progress"]);
+        let mut err_writer = Vec::new();
+
+        assert_eq!(game_arg_or_last(&app_context, &matches, "game_id", &mut err_writer).await, None);
+
+        let err_output = String::from_utf8(err_writer).unwrap();
+        assert!(err_output.starts_with("No game given and no previously used game found"));
+        assert!(!err_output.contains("games list"));
+    }
+
+    #[test]
+    fn test_parse_error_hint() {
+        let error = top::TopPlugin.command().try_get_matches_from(["top"]).unwrap_err();
+        assert_eq!(parse_error_hint(&error), Some(GAME_ID_HINT));
+
+        let error = open::OpenPlugin.command().try_get_matches_from(["open"]).unwrap_err();
+        assert_eq!(parse_error_hint(&error), Some(GAME_ID_HINT));
+
+        let error = completions::CompletionsPlugin.command().try_get_matches_from(["completions"]).unwrap_err();
+        assert_eq!(parse_error_hint(&error), None);
+
+        let error = top::TopPlugin.command().try_get_matches_from(["top", "1", "--count", "x"]).unwrap_err();
+        assert_eq!(parse_error_hint(&error), None);
+    }
+
+    #[test]
+    fn test_bar_style_thresholds() {
+        let command = clap::Command::new("progress").args(bar_style_args());
+
+        let matches = command.clone().get_matches_from(["progress", "--warn-threshold", "25", "--good-threshold", "75"]);
+        let style = bar_style(&matches).unwrap();
+        assert_eq!(style.thresholds.color(50.0), ui::BarColor::Yellow);
+        assert_eq!(style.thresholds.color(75.0), ui::BarColor::Green);
+        assert!(!style.colored);
+
+        let matches = command.clone().get_matches_from(["progress", "--warn-threshold", "90", "--good-threshold", "80"]);
+        assert!(bar_style(&matches).is_err());
+
+        assert!(command.try_get_matches_from(["progress", "--warn-threshold", "101"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_ids() {
+        assert_eq!(parse_ids("440", "--ids"), Ok(vec![440]));
+        assert_eq!(parse_ids("440, 570,10", "--ids"), Ok(vec![440, 570, 10]));
+        assert_eq!(
+            parse_ids("440,abc", "--ids"),
+            Err("Invalid game id 'abc' in --ids, expected a comma-separated list of numbers".to_string())
+        );
+        assert!(parse_ids("440,,570", "--pin").is_err());
+    }
+
+    #[test]
+    fn test_skipped_games() {
+        let app_context = AppContext::with_api(FakeApi::new());
+        let mut skipped = SkippedGames::default();
+        let mut err_writer = Vec::new();
+
+        skipped.report(&mut err_writer);
+        assert!(err_writer.is_empty());
+
+        skipped.skip(&app_context, 440, "Error for 440", &mut err_writer);
+        skipped.report(&mut err_writer);
+        assert_eq!(String::from_utf8(err_writer).unwrap(), "Skipped 1 game due to errors: [440]\n");
+
+        let app_context = app_context.with_verbose(true);
+        let mut skipped = SkippedGames::default();
+        let mut err_writer = Vec::new();
+        skipped.skip(&app_context, 440, "Error for 440", &mut err_writer);
+        skipped.skip(&app_context, 570, "Error for 570", &mut err_writer);
+        skipped.report(&mut err_writer);
+        assert_eq!(
+            String::from_utf8(err_writer).unwrap(),
+            "Error for 440\nError for 570\nSkipped 2 games due to errors: [440, 570]\n"
+        );
+    }
+
+    #[test]
+    fn test_plugins_show_examples() {
+        for plugin in get_plugins() {
+            let mut command = plugin.command();
+            if command.is_hide_set() {
+                continue;
+            }
+            let name = command.get_name().to_string();
+            let help = command.render_long_help().to_string();
+            assert!(help.contains("Examples:\n"), "no examples in the help of {}", name);
+            assert!(help.contains(&format!("\n  trogue {}", name)), "no example of {} in its help", name);
+        }
+    }
 
     // Tests the `get_plugins` function.
     //
@@ -114,12 +1109,43 @@ mod tests {
     // <side-effects-start>
     // - None
     // <side-effects-end>
+    #[test]
+    fn test_resolve_default_command() {
+        let plugins = get_plugins();
+
+        assert_eq!(resolve_default_command(&plugins, None), Ok(None));
+        assert_eq!(resolve_default_command(&plugins, Some(" ")), Ok(None));
+        assert_eq!(resolve_default_command(&plugins, Some("dashboard")), Ok(Some("dashboard".to_string())));
+
+        let error = resolve_default_command(&plugins, Some("dashbaord")).unwrap_err();
+        assert!(error.starts_with("Invalid TROGUE_DEFAULT_COMMAND: 'dashbaord' is not a command, expected one of: list, "));
+        assert!(resolve_default_command(&plugins, Some(complete_games::COMMAND_NAME)).is_err());
+    }
+
+    #[test]
+    fn test_bare_invocation_routes_to_default_command() {
+        let plugins = get_plugins();
+        let mut command = clap::Command::new("trogue").arg(Arg::new("offline").long("offline").action(clap::ArgAction::SetTrue));
+        for plugin in &plugins {
+            command = command.subcommand(plugin.command());
+        }
+
+        let mut args = vec!["trogue".to_string(), "--offline".to_string()];
+        if let Some(name) = resolve_default_command(&plugins, Some("list")).unwrap() {
+            args.push(name);
+        }
+        let matches = command.get_matches_from(args);
+
+        assert_eq!(matches.subcommand_name(), Some("list"));
+        assert!(matches.get_flag("offline"));
+    }
+
     #[test]
     fn test_get_plugins() {
         let plugins = get_plugins();
         
         // Expected number of plugins.
-        assert_eq!(plugins.len(), 5);
+        assert_eq!(plugins.len(), 14);
 
         let mut expected_names = vec![
             "list",
@@ -127,6 +1153,15 @@ mod tests {
             "achievements",
             "progress",
             "completions",
+            "top",
+            "verify",
+            "open",
+            "diff",
+            "export",
+            "today",
+            "__complete-games",
+            "info",
+            "stats",
         ];
         expected_names.sort();
 
@@ -135,4 +1170,60 @@ mod tests {
 
         assert_eq!(actual_names, expected_names);
     }
-}
\ No newline at end of file
+
+    // Tests that the display plugins work against the offline sample data.
+    //
+    // <purpose-start>
+    // The offline mode is meant for demos, so every command showing Steam data must produce
+    // output from the bundled sample data without errors.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - None
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - None
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None
+    // <side-effects-end>
+    #[tokio::test]
+    async fn test_display_plugins_run_offline() {
+        // Verbose, so that a game failing to load reports its error instead of being summarized
+        let app_context = AppContext::with_api(MockApi::new()).with_verbose(true);
+        let invocations: Vec<Vec<&str>> = vec![
+            vec!["list"],
+            vec!["dashboard"],
+            vec!["achievements", "100", "--global"],
+            vec!["progress", "200"],
+            vec!["top", "100"],
+            vec!["verify"],
+            vec!["export", "--format", "csv"],
+            vec!["today"],
+            vec!["stats", "--by", "playtime"],
+        ];
+
+        for args in invocations {
+            let plugin = get_plugins()
+                .into_iter()
+                .find(|p| p.command().get_name() == args[0])
+                .unwrap();
+            let matches = plugin.command().get_matches_from(&args);
+            let mut writer = Vec::new();
+            let mut err_writer = Vec::new();
+
+            plugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+            assert!(!writer.is_empty(), "no output from {:?}", args);
+            assert!(
+                !String::from_utf8(err_writer).unwrap().contains("Error"),
+                "error output from {:?}",
+                args
+            );
+        }
+
+        assert_eq!(app_context.exit_code(), 0);
+    }
+}
@@ -0,0 +1,229 @@
+//! Plugin for opening a game's page in the browser.
+//!
+//! <purpose-start>
+//! This plugin provides the `open` command, which opens the Steam store page of a game,
+//! or the user's achievements page for it, in the default browser.
+//! <purpose-end>
+//!
+//! <inputs-start>
+//! - `app_context`: The shared application context, providing the configured Steam ID.
+//! - `matches`: The command-line arguments parsed by `clap`.
+//! <inputs-end>
+//!
+//! <outputs-start>
+//! - The page URL printed to the console, if requested.
+//! <outputs-end>
+//!
+//! <side-effects-start>
+//! - Launches the default browser.
+//! <side-effects-end>
+
+use crate::{app::AppContext, plugins::Plugin};
+use async_trait::async_trait;
+use clap::{Arg, Command};
+use std::io::Write;
+
+pub struct OpenPlugin;
+
+#[async_trait]
+impl Plugin for OpenPlugin {
+    // Defines the clap command for the `open` plugin.
+    //
+    // <purpose-start>
+    // This method provides the command-line interface for the `open` plugin,
+    // which opens the page of a specific game in the browser.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `&self`: A reference to the plugin instance.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `clap::Command`: The clap command definition for the `open` plugin.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None.
+    // <side-effects-end>
+    fn command(&self) -> Command {
+        Command::new("open")
+            .about("Opens the Steam store page of a specific game in the default browser.")
+            .after_help(concat!(
+                "Examples:\n",
+                "  trogue open 440\n",
+                "  trogue open 440 --community --print-url",
+            ))
+            .arg(
+                Arg::new("game_id")
+                    .value_name("game_id")
+                    .action(clap::ArgAction::Set)
+                    .required(true)
+                    .help("The ID of the game to open the page for"),
+            )
+            .arg(
+                Arg::new("community")
+                    .short('c')
+                    .long("community")
+                    .action(clap::ArgAction::SetTrue)
+                    .help("Opens your achievements page for the game in the Steam community instead."),
+            )
+            .arg(
+                Arg::new("print_url")
+                    .long("print-url")
+                    .action(clap::ArgAction::SetTrue)
+                    .help("Prints the URL instead of opening it."),
+            )
+    }
+
+    // Executes the `open` plugin's logic.
+    //
+    // <purpose-start>
+    // This method is called by the core application when the `open` command is invoked.
+    // It builds the page URL and either prints it or opens it in the default browser.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `&self`: A reference to the plugin instance.
+    // - `app_context`: The shared application context.
+    // - `matches`: The clap argument matches for the `open` subcommand.
+    // - `writer`: A mutable reference to a writer for standard output.
+    // - `err_writer`: A mutable reference to a writer for standard error.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - None.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - Launches the default browser, unless `--print-url` is set.
+    // - Writes the URL to the provided writer if `--print-url` is set.
+    // <side-effects-end>
+    async fn execute(
+        &self,
+        app_context: &AppContext,
+        matches: &clap::ArgMatches,
+        writer: &mut (dyn Write + Send),
+        err_writer: &mut (dyn Write + Send),
+    ) {
+        let game_id_str = matches.get_one::<String>("game_id").unwrap();
+
+        let game_id = match game_id_str.parse::<u32>() {
+            Ok(id) => id,
+            Err(_) => {
+                writeln!(err_writer, "Invalid game id: {}", game_id_str).unwrap();
+                return;
+            }
+        };
+
+        let url = if matches.get_flag("community") {
+            community_url(app_context.api.steam_id(), game_id)
+        } else {
+            store_url(game_id)
+        };
+
+        if matches.get_flag("print_url") {
+            writeln!(writer, "{}", url).unwrap();
+            return;
+        }
+
+        if let Err(e) = open::that(&url) {
+            writeln!(err_writer, "Could not open {} in the browser: {}", url, e).unwrap();
+            app_context.set_exit_code(1);
+        }
+    }
+}
+
+// Builds the Steam store page URL of a game.
+//
+// <inputs-start>
+// - `game_id`: The ID of the game.
+// <inputs-end>
+//
+// <outputs-start>
+// - `String`: The store page URL.
+// <outputs-end>
+fn store_url(game_id: u32) -> String {
+    format!("https://store.steampowered.com/app/{}", game_id)
+}
+
+// Builds the Steam community URL of the user's achievements for a game.
+//
+// <inputs-start>
+// - `steam_id`: The Steam ID of the user.
+// - `game_id`: The ID of the game.
+// <inputs-end>
+//
+// <outputs-start>
+// - `String`: The achievements page URL.
+// <outputs-end>
+fn community_url(steam_id: &str, game_id: u32) -> String {
+    format!("https://steamcommunity.com/profiles/{}/stats/{}/achievements", steam_id, game_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::AppContext;
+    use crate::steam_api::Api;
+    use clap::ArgMatches;
+
+    fn create_app_context() -> AppContext {
+        let api = Api::new("test_key".to_string(), "test_id".to_string(), "http://127.0.0.1:1".to_string());
+        AppContext::with_api(api)
+    }
+
+    fn get_matches_for_args(args: &[&str]) -> ArgMatches {
+        OpenPlugin.command().get_matches_from(args)
+    }
+
+    #[test]
+    fn test_command() {
+        let plugin = OpenPlugin;
+        let cmd = plugin.command();
+        assert_eq!(cmd.get_name(), "open");
+        assert!(cmd.get_about().is_some());
+        assert!(cmd.get_arguments().any(|arg| arg.get_id() == "game_id"));
+        assert!(cmd.get_arguments().any(|arg| arg.get_id() == "community"));
+        assert!(cmd.get_arguments().any(|arg| arg.get_id() == "print_url"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_print_store_url() {
+        let app_context = create_app_context();
+        let matches = get_matches_for_args(&["open", "48700", "--print-url"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        OpenPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        assert_eq!(output, "https://store.steampowered.com/app/48700\n");
+    }
+
+    #[tokio::test]
+    async fn test_execute_print_community_url() {
+        let app_context = create_app_context();
+        let matches = get_matches_for_args(&["open", "48700", "--community", "--print-url"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        OpenPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        assert_eq!(output, "https://steamcommunity.com/profiles/test_id/stats/48700/achievements\n");
+    }
+
+    #[tokio::test]
+    async fn test_execute_invalid_game_id() {
+        let app_context = create_app_context();
+        let matches = get_matches_for_args(&["open", "abc", "--print-url"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        OpenPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let err_output = String::from_utf8(err_writer).unwrap();
+        assert!(err_output.contains("Invalid game id: abc"));
+        assert!(writer.is_empty());
+    }
+}
@@ -2,7 +2,7 @@
 //!
 //! <purpose-start>
 //! This plugin provides the `progress` command, which displays a progress bar
-//! representing the achievement completion for a given game.
+//! representing the achievement completion for one or more games, optionally with a rarity-weighted score.
 //! <purpose-end>
 //!
 //! <inputs-start>
@@ -16,12 +16,20 @@
 //!
 //! <side-effects-start>
 //! - Makes a network request to the Steam API to fetch achievement data.
+//! - Records the unlocks of each game, see `state::UnlockHistoryStore`.
+//! - With `--watch-file`, watches a file and displays the progress again whenever it changes.
 //! <side-effects-end>
 
-use crate::{app::AppContext, plugins::Plugin};
+use crate::{app::AppContext, plugins::{self, Plugin}, snapshot, steam_api::{Achievement, ApiError, GameAchievements, GlobalAchievement, ProgressSummary}, ui};
 use async_trait::async_trait;
 use clap::{Arg, Command};
-use std::io::Write;
+use futures::StreamExt;
+use notify::Watcher;
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::pin::pin;
+use tokio::sync::mpsc;
 
 pub struct ShowProgressPlugin;
 
@@ -48,13 +56,58 @@ impl Plugin for ShowProgressPlugin {
     fn command(&self) -> Command {
         Command::new("progress")
             .about("Displays game achievements progress.")
+            .after_help(concat!(
+                "Examples:\n",
+                "  trogue progress 440\n",
+                "  trogue progress 440 570 620\n",
+                "  trogue progress --game-name portal --bar-fill \"#\" --bar-empty \".\"\n",
+                "  trogue progress 440 --minimal --no-newline\n",
+                "  trogue progress 440 --changes\n",
+                "  trogue progress 440 --score\n",
+                "  trogue progress 440 --watch-file /tmp/trogue-trigger\n",
+                "  trogue list --format tsv | cut -f1 | trogue progress -",
+            ))
             .arg(
                 Arg::new("game_id")
                     .value_name("game_id")
                     .action(clap::ArgAction::Set)
-                    .required(true)
-                    .help("The ID of the game to show progress for"),
+                    .num_args(1..)
+                    .required(false)
+                    .help("The IDs of the games to show progress for, or - to read newline-separated ids from stdin. Several games are followed by a combined tally. Defaults to the last used game"),
             )
+            .arg(plugins::game_name_arg())
+            .args(plugins::bar_style_args())
+            .arg(
+                Arg::new("minimal")
+                    .long("minimal")
+                    .action(clap::ArgAction::SetTrue)
+                    .conflicts_with_all(["bar_fill", "bar_empty", "warn_threshold", "good_threshold"])
+                    .help("Prints only the completion percentage, rounded down, e.g. 66%, for embedding in a shell prompt"),
+            )
+            .arg(
+                Arg::new("no_newline")
+                    .long("no-newline")
+                    .action(clap::ArgAction::SetTrue)
+                    .requires("minimal")
+                    .help("Omits the line break after the --minimal percentage"),
+            )
+            .arg(plugins::changes_arg().conflicts_with("minimal"))
+            .arg(
+                Arg::new("score")
+                    .long("score")
+                    .action(clap::ArgAction::SetTrue)
+                    .conflicts_with("minimal")
+                    .help("Adds a rarity score below the bar: each unlocked achievement is worth 100 minus its global unlock percentage"),
+            )
+            .arg(
+                Arg::new("watch_file")
+                    .long("watch-file")
+                    .value_name("path")
+                    .value_parser(clap::value_parser!(PathBuf))
+                    .conflicts_with("no_newline")
+                    .help("Displays the progress of a single game again whenever the given file is modified, e.g. touched by a game launch script, until Ctrl-C"),
+            )
+            .arg(plugins::raw_arg().conflicts_with_all(["bar_fill", "bar_empty", "warn_threshold", "good_threshold", "minimal", "changes", "score", "watch_file"]))
     }
 
     // Executes the `progress` plugin's logic.
@@ -87,52 +140,587 @@ impl Plugin for ShowProgressPlugin {
         writer: &mut (dyn Write + Send),
         err_writer: &mut (dyn Write + Send),
     ) {
-        let game_id_str = matches.get_one::<String>("game_id").unwrap();
+        if matches.get_flag("raw") {
+            show_raw_achievements(app_context, matches, writer, err_writer).await;
+            return;
+        }
 
-        if let Ok(game_id) = game_id_str.parse::<u32>() {
-            match app_context.api.get_game_achievements(game_id).await {
-                Ok((game_name, achievements)) => {
-                    writeln!(writer, "{}", game_name).unwrap();
+        let mut bar_style = match plugins::bar_style(matches) {
+            Ok(style) => style,
+            Err(e) => {
+                writeln!(err_writer, "{}", e).unwrap();
+                return;
+            }
+        };
+        bar_style.colored = ui::colors_enabled(false, app_context.interactive());
+        let output = if app_context.porcelain() {
+            ProgressOutput::Porcelain
+        } else if matches.get_flag("minimal") {
+            ProgressOutput::Minimal { newline: !matches.get_flag("no_newline") }
+        } else {
+            ProgressOutput::Bar { changes: matches.get_flag("changes"), score: matches.get_flag("score") }
+        };
 
-                    if achievements.is_empty() {
-                        writeln!(writer, "No achievements found for this game").unwrap();
-                        return;
-                    }
+        let game_args: Vec<&String> = matches.get_many::<String>("game_id").unwrap_or_default().collect();
+        let watch_file = matches.get_one::<PathBuf>("watch_file");
+        if watch_file.is_some() && (game_args.len() > 1 || game_args.first().is_some_and(|game| *game == "-")) {
+            writeln!(err_writer, "--watch-file takes a single game id").unwrap();
+            return;
+        }
 
-                    let total = achievements.len();
-                    let completed = achievements.iter().filter(|a| a.achieved > 0).count();
-                    let percentage = (completed as f32 / total as f32) * 100.0;
+        if game_args.len() > 1 {
+            if game_args.iter().any(|game| *game == "-") {
+                writeln!(err_writer, "- must be the only game id, to read the ids from stdin").unwrap();
+                return;
+            }
 
-                    let terminal_width = crossterm::terminal::size().unwrap_or((80, 24)).0 as usize;
-                    let bar_width = terminal_width / 2;
+            let mut game_ids = Vec::new();
+            for game in game_args {
+                match game.parse::<u32>() {
+                    Ok(game_id) => game_ids.push(game_id),
+                    Err(_) => writeln!(err_writer, "Invalid game id: {}", game).unwrap(),
+                }
+            }
+            show_multiple_progress(app_context, game_ids, &bar_style, output, writer, err_writer).await;
+            return;
+        }
 
-                    let filled_chars = ((percentage / 100.0) * bar_width as f32).round() as usize;
-                    let empty_chars = bar_width - filled_chars;
+        if game_args.first().is_some_and(|game| *game == "-") {
+            let mut stdin = BufReader::new(std::io::stdin());
+            show_batch_progress(app_context, &mut stdin, &bar_style, output, writer, err_writer).await;
+            return;
+        }
 
-                    write!(writer, "[").unwrap();
-                    for _ in 0..filled_chars {
-                        write!(writer, "█").unwrap();
-                    }
-                    for _ in 0..empty_chars {
-                        write!(writer, " ").unwrap();
+        let game_id_str = match plugins::game_arg_or_last(app_context, matches, "game_id", err_writer).await {
+            Some(game) => game,
+            None => return,
+        };
+
+        let game_id = match game_id_str.parse::<u32>() {
+            Ok(game_id) => game_id,
+            Err(_) => {
+                writeln!(err_writer, "Invalid game id: {}", game_id_str).unwrap();
+                return;
+            }
+        };
+
+        if show_game_progress(app_context, game_id, &bar_style, output, writer, err_writer).await {
+            plugins::remember_game(app_context, game_id, err_writer);
+        }
+
+        if let Some(path) = watch_file {
+            // The watcher stops sending events once dropped, so it is kept until Ctrl-C
+            let (_watcher, events) = match watch_file_changes(path) {
+                Ok(watch) => watch,
+                Err(e) => {
+                    writeln!(err_writer, "Cannot watch {}: {}", path.display(), e).unwrap();
+                    return;
+                }
+            };
+            tokio::select! {
+                _ = watch_progress(app_context, game_id, events, &bar_style, output, writer, err_writer) => {}
+                _ = tokio::signal::ctrl_c() => {}
+            }
+        }
+    }
+}
+
+// Starts watching a file for modifications.
+//
+// <purpose-start>
+// Backs the `--watch-file` option. Creating, writing or touching the file sends an event on the
+// returned channel, other events such as reads are ignored.
+// <purpose-end>
+//
+// <inputs-start>
+// - `path`: The file to watch, which must exist.
+// <inputs-end>
+//
+// <outputs-start>
+// - `Ok((RecommendedWatcher, UnboundedReceiver<()>))`: The watcher, which must be kept alive, and its events.
+// - `Err(notify::Error)`: If the file cannot be watched.
+// <outputs-end>
+//
+// <side-effects-start>
+// - Registers a watch with the operating system, removed when the watcher is dropped.
+// <side-effects-end>
+fn watch_file_changes(path: &Path) -> notify::Result<(notify::RecommendedWatcher, mpsc::UnboundedReceiver<()>)> {
+    let (sender, events) = mpsc::unbounded_channel();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        if event.is_ok_and(|event| event.kind.is_modify() || event.kind.is_create()) {
+            // The receiver is only gone when trogue exits
+            let _ = sender.send(());
+        }
+    })?;
+    watcher.watch(path, notify::RecursiveMode::NonRecursive)?;
+    Ok((watcher, events))
+}
+
+// Displays the progress of a game again on every event, until the events end.
+//
+// <purpose-start>
+// Touching a file usually emits several events at once, so the pending events are drained before
+// rendering, to display the progress once per change. Each rendering fetches the achievements
+// again and is preceded by an empty line, separating it from the previous one.
+// <purpose-end>
+//
+// <inputs-start>
+// - `app_context`: The shared application context.
+// - `game_id`: The ID of the game.
+// - `events`: The modifications of the watched file, see `watch_file_changes`.
+// - `bar_style`: The glyphs of the progress bar.
+// - `output`: Whether to print the bar or only the percentage.
+// - `writer`: A mutable reference to a writer for standard output.
+// - `err_writer`: A mutable reference to a writer for standard error.
+// <inputs-end>
+//
+// <outputs-start>
+// - None.
+// <outputs-end>
+//
+// <side-effects-start>
+// - Makes a network request to the Steam API per rendering.
+// - Writes the progress bar to `writer` and errors to `err_writer`.
+// <side-effects-end>
+async fn watch_progress(
+    app_context: &AppContext,
+    game_id: u32,
+    mut events: mpsc::UnboundedReceiver<()>,
+    bar_style: &ui::BarStyle,
+    output: ProgressOutput,
+    writer: &mut (dyn Write + Send),
+    err_writer: &mut (dyn Write + Send),
+) {
+    while events.recv().await.is_some() {
+        while events.try_recv().is_ok() {}
+        writeln!(writer).unwrap();
+        show_game_progress(app_context, game_id, bar_style, output, writer, err_writer).await;
+        writer.flush().unwrap();
+    }
+}
+
+// Prints the unmodified Steam response the progress of a game is computed from.
+//
+// <purpose-start>
+// Backs the `--raw` flag. Several games and reading game ids from standard input are not
+// supported, as the concatenated responses would not form a single JSON document.
+// <purpose-end>
+//
+// <inputs-start>
+// - `app_context`: The shared application context.
+// - `matches`: The clap argument matches for the `progress` subcommand.
+// - `writer`: A mutable reference to a writer for standard output.
+// - `err_writer`: A mutable reference to a writer for standard error.
+// <inputs-end>
+//
+// <outputs-start>
+// - None.
+// <outputs-end>
+//
+// <side-effects-start>
+// - Makes a network request to the Steam API to fetch the player achievements.
+// - Writes the response body to `writer` and errors to `err_writer`.
+// <side-effects-end>
+async fn show_raw_achievements(
+    app_context: &AppContext,
+    matches: &clap::ArgMatches,
+    writer: &mut (dyn Write + Send),
+    err_writer: &mut (dyn Write + Send),
+) {
+    if matches.get_one::<String>("game_id").is_some_and(|game| game == "-") {
+        writeln!(err_writer, "--raw cannot be used with game ids read from stdin").unwrap();
+        return;
+    }
+    if matches.get_many::<String>("game_id").is_some_and(|games| games.count() > 1) {
+        writeln!(err_writer, "--raw takes a single game id").unwrap();
+        return;
+    }
+
+    let game_id_str = match plugins::game_arg_or_last(app_context, matches, "game_id", err_writer).await {
+        Some(game) => game,
+        None => return,
+    };
+
+    match game_id_str.parse::<u32>() {
+        Ok(game_id) => {
+            plugins::write_raw(app_context.api.get_game_achievements_raw(game_id).await, writer, err_writer);
+        }
+        Err(_) => writeln!(err_writer, "Invalid game id: {}", game_id_str).unwrap(),
+    }
+}
+
+// Describes how the progress of a game is printed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ProgressOutput {
+    // The game name followed by a progress bar and the counts, and optionally the rarity score and
+    // the achievements unlocked since the last run
+    Bar { changes: bool, score: bool },
+    // Only the percentage, rounded down, optionally followed by a line break
+    Minimal { newline: bool },
+    // A stable `progress` record with the game id and the counts, without section headers or tally
+    Porcelain,
+}
+
+// Displays the achievement progress of a single game.
+//
+// <purpose-start>
+// Prints the game name followed by a progress bar, or a notice if the game has no achievements.
+// The minimal output only prints the percentage, the notice then goes to `err_writer`, so that
+// a shell prompt embedding it stays empty.
+// <purpose-end>
+//
+// <inputs-start>
+// - `app_context`: The shared application context.
+// - `game_id`: The ID of the game.
+// - `bar_style`: The glyphs of the progress bar.
+// - `output`: Whether to print the bar or only the percentage.
+// - `writer`: A mutable reference to a writer for standard output.
+// - `err_writer`: A mutable reference to a writer for standard error.
+// <inputs-end>
+//
+// <outputs-start>
+// - `bool`: `true` if the achievements were fetched, `false` if an error was reported.
+// <outputs-end>
+//
+// <side-effects-start>
+// - Makes a network request to the Steam API to fetch achievement data.
+// - Writes the progress bar to `writer` and errors to `err_writer`.
+// <side-effects-end>
+async fn show_game_progress(
+    app_context: &AppContext,
+    game_id: u32,
+    bar_style: &ui::BarStyle,
+    output: ProgressOutput,
+    writer: &mut (dyn Write + Send),
+    err_writer: &mut (dyn Write + Send),
+) -> bool {
+    let result = app_context.api.get_game_achievements(game_id).await;
+    render_game_progress(app_context, game_id, result, bar_style, output, writer, err_writer).await
+}
+
+// Displays the achievement progress of a single game from its fetched achievements.
+//
+// <purpose-start>
+// Kept apart from the request, so that games fetched concurrently are rendered the same way.
+// The unlocks of the game are recorded once reported, so that `--changes` compares against this run.
+// <purpose-end>
+//
+// <inputs-start>
+// - `app_context`: The shared application context.
+// - `game_id`: The ID of the game.
+// - `result`: The game's achievements, or the error of fetching them.
+// - `bar_style`: The glyphs of the progress bar.
+// - `output`: Whether to print the bar or only the percentage.
+// - `writer`: A mutable reference to a writer for standard output.
+// - `err_writer`: A mutable reference to a writer for standard error.
+// <inputs-end>
+//
+// <outputs-start>
+// - `bool`: `true` if the achievements were fetched, `false` if an error was reported.
+// <outputs-end>
+//
+// <side-effects-start>
+// - Makes a network request to the Steam API to recover the name of a game with private stats.
+// - Reads and writes the file of the game in the unlock history.
+// - Writes the progress bar to `writer` and errors to `err_writer`.
+// <side-effects-end>
+async fn render_game_progress(
+    app_context: &AppContext,
+    game_id: u32,
+    result: Result<GameAchievements, ApiError>,
+    bar_style: &ui::BarStyle,
+    output: ProgressOutput,
+    writer: &mut (dyn Write + Send),
+    err_writer: &mut (dyn Write + Send),
+) -> bool {
+    let mut game_achievements = match result {
+        Ok(resp) => resp,
+        Err(e) => {
+            writeln!(err_writer, "Error while trying to get achievements: {}", ui::scrub_api_key(&e.to_string())).unwrap();
+            return false;
+        }
+    };
+
+    match output {
+        ProgressOutput::Minimal { newline } => {
+            if game_achievements.achievements.is_empty() {
+                writeln!(err_writer, "No achievements found for this game").unwrap();
+            } else {
+                write!(writer, "{}%", game_achievements.percentage().floor() as u32).unwrap();
+                if newline {
+                    writeln!(writer).unwrap();
+                }
+            }
+        }
+        ProgressOutput::Porcelain => {
+            let fields = [
+                game_id.to_string(),
+                game_achievements.completed().to_string(),
+                game_achievements.total().to_string(),
+            ];
+            writeln!(writer, "{}", ui::porcelain_record("progress", &fields)).unwrap();
+        }
+        ProgressOutput::Bar { changes, score } => {
+            if game_achievements.game_name.is_empty() {
+                game_achievements.game_name = app_context.game_name(game_id).await.unwrap_or_default();
+            }
+
+            writeln!(writer, "{}", game_achievements.game_name).unwrap();
+            write_progress_bar(&game_achievements, bar_style, writer);
+
+            if score && !game_achievements.achievements.is_empty() {
+                match app_context.api.get_global_achievements(game_id).await {
+                    Ok(global_achievements) => {
+                        let (score, max_score) = rarity_score(&game_achievements.achievements, &global_achievements);
+                        writeln!(writer, "Rarity score: {:.1} of {:.1}", score, max_score).unwrap();
                     }
-                    writeln!(writer, "] {:.1}% ({}/{})", percentage, completed, total).unwrap();
+                    Err(e) => writeln!(err_writer, "Error while trying to get global achievements: {}", ui::scrub_api_key(&e.to_string())).unwrap(),
                 }
-                Err(e) => writeln!(err_writer, "Error while trying to get achievements: {}", e).unwrap(),
             }
-        } else {
-            writeln!(err_writer, "Invalid game id: {}", game_id_str).unwrap();
+
+            if changes && !game_achievements.achievements.is_empty() {
+                let previous = app_context.previous_unlocks(game_id);
+                write_changes(previous.as_ref().map(|p| p.achievements.as_slice()), &game_achievements.achievements, writer);
+            }
+        }
+    }
+
+    plugins::record_unlocks(app_context, game_id, &game_achievements.game_name, &game_achievements.achievements, err_writer);
+    true
+}
+
+// Writes the progress bar of a game, or a notice if the game has no achievements.
+//
+// <inputs-start>
+// - `game_achievements`: The game's achievements.
+// - `bar_style`: The glyphs of the progress bar.
+// - `writer`: A mutable reference to a writer for standard output.
+// <inputs-end>
+//
+// <side-effects-start>
+// - Writes the progress bar and the counts to `writer`.
+// <side-effects-end>
+fn write_progress_bar(game_achievements: &GameAchievements, bar_style: &ui::BarStyle, writer: &mut (dyn Write + Send)) {
+    if game_achievements.achievements.is_empty() {
+        writeln!(writer, "No achievements found for this game").unwrap();
+        return;
+    }
+
+    let summary = game_achievements.summary();
+
+    let terminal_width = crossterm::terminal::size().unwrap_or((80, 24)).0 as usize;
+    let bar_width = terminal_width / 2;
+    let bar = ui::render_progress_bar(summary.percentage, bar_width, bar_style);
+
+    writeln!(writer, "{} {}", bar, summary).unwrap();
+}
+
+// Computes the rarity-weighted score of a game's achievements.
+//
+// <purpose-start>
+// Backs the `--score` flag, telling how hard the unlocks were rather than how many there are.
+// Each achievement is worth 100 minus its global unlock percentage, so rare achievements weigh
+// more. Achievements missing from the global statistics count as 0%, as `achievements` displays them.
+// <purpose-end>
+//
+// <inputs-start>
+// - `achievements`: The game's achievements.
+// - `global_achievements`: The global unlock percentages of the game's achievements.
+// <inputs-end>
+//
+// <outputs-start>
+// - `(f32, f32)`: The score of the unlocked achievements and the score of all achievements.
+// <outputs-end>
+fn rarity_score(achievements: &[Achievement], global_achievements: &[GlobalAchievement]) -> (f32, f32) {
+    let percentages: HashMap<&str, f32> = global_achievements.iter().map(|g| (g.name.as_str(), g.percent)).collect();
+
+    let mut score = 0.0;
+    let mut max_score = 0.0;
+    for achievement in achievements {
+        let points = 100.0 - percentages.get(achievement.apiname.as_str()).copied().unwrap_or(0.0);
+        max_score += points;
+        if achievement.is_unlocked() {
+            score += points;
         }
     }
+
+    (score, max_score)
+}
+
+// Writes the achievements unlocked since the previous run.
+//
+// <purpose-start>
+// Backs the `--changes` flag. The first run for a game has nothing to compare against, which is
+// said instead of listing every unlocked achievement.
+// <purpose-end>
+//
+// <inputs-start>
+// - `previous`: The achievements recorded by the previous run, `None` if the game was never recorded.
+// - `current`: The achievements just fetched.
+// - `writer`: A mutable reference to a writer for standard output.
+// <inputs-end>
+//
+// <side-effects-start>
+// - Writes the newly unlocked achievements to `writer`.
+// <side-effects-end>
+fn write_changes(previous: Option<&[Achievement]>, current: &[Achievement], writer: &mut (dyn Write + Send)) {
+    let Some(previous) = previous else {
+        writeln!(writer, "No earlier record of this game, changes are listed from the next run").unwrap();
+        return;
+    };
+
+    let unlocked = snapshot::new_unlocks(previous, current);
+    if unlocked.is_empty() {
+        writeln!(writer, "No achievements unlocked since the last run").unwrap();
+        return;
+    }
+
+    writeln!(writer, "Unlocked since the last run:").unwrap();
+    for achievement in unlocked {
+        writeln!(writer, " + {}", achievement.name).unwrap();
+    }
 }
 
+// Displays the achievement progress of several games, followed by a combined tally.
+//
+// <purpose-start>
+// Used when `progress` is given more than one game id. The achievements are fetched concurrently,
+// bounded by `--concurrency`, and each game gets its own section labeled with its id, in the
+// given order. Failing games are reported and left out of the tally. The last used game is left unchanged.
+// The porcelain output has one record per game, without sections or tally.
+// <purpose-end>
+//
+// <inputs-start>
+// - `app_context`: The shared application context.
+// - `game_ids`: The ids of the games.
+// - `bar_style`: The glyphs of the progress bars.
+// - `output`: Whether to print the bars or only the percentages.
+// - `writer`: A mutable reference to a writer for standard output.
+// - `err_writer`: A mutable reference to a writer for standard error.
+// <inputs-end>
+//
+// <outputs-start>
+// - None.
+// <outputs-end>
+//
+// <side-effects-start>
+// - Makes one network request to the Steam API per game.
+// - Writes the progress sections and the tally to `writer` and errors to `err_writer`.
+// <side-effects-end>
+async fn show_multiple_progress(
+    app_context: &AppContext,
+    game_ids: Vec<u32>,
+    bar_style: &ui::BarStyle,
+    output: ProgressOutput,
+    writer: &mut (dyn Write + Send),
+    err_writer: &mut (dyn Write + Send),
+) {
+    let mut results = pin!(app_context.game_achievements(game_ids.clone()));
+    let mut games = 0;
+    let mut completed = 0;
+    let mut total = 0;
+
+    for (index, game_id) in game_ids.into_iter().enumerate() {
+        let result = results.next().await.unwrap();
+        if let Ok(game_achievements) = &result {
+            games += 1;
+            completed += game_achievements.completed();
+            total += game_achievements.total();
+        }
+
+        if output != ProgressOutput::Porcelain {
+            if index > 0 {
+                writeln!(writer).unwrap();
+            }
+            writeln!(writer, "[{}]", game_id).unwrap();
+        }
+        render_game_progress(app_context, game_id, result, bar_style, output, writer, err_writer).await;
+    }
+
+    if output == ProgressOutput::Porcelain {
+        return;
+    }
+
+    let summary = ProgressSummary::new(String::new(), completed, total);
+    writeln!(writer).unwrap();
+    writeln!(writer, "Total: {} across {} games", summary, games).unwrap();
+}
+
+// Displays the achievement progress of every game id read from a reader.
+//
+// <purpose-start>
+// Allows `progress -` to be fed newline-separated game ids, e.g. from a pipe. Each game gets its
+// own section labeled with its id. Blank lines are ignored, and invalid lines or failing games are
+// reported without stopping the batch. The last used game is left unchanged.
+// <purpose-end>
+//
+// <inputs-start>
+// - `app_context`: The shared application context.
+// - `reader`: The source of the game ids, standard input outside of tests.
+// - `bar_style`: The glyphs of the progress bars.
+// - `output`: Whether to print the bars or only the percentages.
+// - `writer`: A mutable reference to a writer for standard output.
+// - `err_writer`: A mutable reference to a writer for standard error.
+// <inputs-end>
+//
+// <outputs-start>
+// - None.
+// <outputs-end>
+//
+// <side-effects-start>
+// - Reads all lines from `reader`.
+// - Makes one network request to the Steam API per game.
+// - Writes the progress bars to `writer` and errors to `err_writer`.
+// <side-effects-end>
+async fn show_batch_progress(
+    app_context: &AppContext,
+    reader: &mut (dyn BufRead + Send),
+    bar_style: &ui::BarStyle,
+    output: ProgressOutput,
+    writer: &mut (dyn Write + Send),
+    err_writer: &mut (dyn Write + Send),
+) {
+    let mut game_ids = Vec::new();
+    for line in reader.lines() {
+        let line = match line {
+            Ok(line) => line,
+            Err(e) => {
+                writeln!(err_writer, "Error while reading game ids: {}", e).unwrap();
+                break;
+            }
+        };
+
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        match line.parse::<u32>() {
+            Ok(game_id) => game_ids.push(game_id),
+            Err(_) => writeln!(err_writer, "Invalid game id: {}", line).unwrap(),
+        }
+    }
+
+    for (index, game_id) in game_ids.into_iter().enumerate() {
+        if output != ProgressOutput::Porcelain {
+            if index > 0 {
+                writeln!(writer).unwrap();
+            }
+            writeln!(writer, "[{}]", game_id).unwrap();
+        }
+        show_game_progress(app_context, game_id, bar_style, output, writer, err_writer).await;
+    }
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::app::AppContext;
-    use crate::steam_api::{Api, Achievement};
+    use crate::fake_api::FakeApi;
+    use crate::state::{LastGameStore, UnlockHistoryStore};
+    use crate::steam_api::{Api, Achievement, GlobalAchievement};
     use clap::ArgMatches;
+    use std::path::PathBuf;
 
     fn create_mock_achievement(achieved: u8) -> Achievement {
         Achievement {
@@ -153,10 +741,16 @@ mod tests {
             .create_async().await;
 
         let api = Api::new("test_key".to_string(), "test_id".to_string(), server.url());
-        let app_context = AppContext { api };
+        let app_context = AppContext::with_api(api);
         (app_context, server)
     }
 
+    fn temp_last_game_path(name: &str) -> PathBuf {
+        std::env::temp_dir()
+            .join(format!("trogue-progress-test-{}-{}", std::process::id(), name))
+            .join("last_game")
+    }
+
     fn get_matches_for_args(args: &[&str]) -> ArgMatches {
         ShowProgressPlugin.command().get_matches_from(args)
     }
@@ -172,6 +766,141 @@ mod tests {
 
     #[tokio::test]
     async fn test_execute_success() {
+        let api = FakeApi::new()
+            .with_achievements(123, "Test Game", vec![create_mock_achievement(1), create_mock_achievement(0)]);
+        let app_context = AppContext::with_api(api);
+        let matches = get_matches_for_args(&["progress", "123"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ShowProgressPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        assert!(output.starts_with("Test Game"));
+        assert!(output.contains("50.0% (1/2)"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_no_achievements() {
+        let api = FakeApi::new().with_achievements(123, "Test Game", vec![]);
+        let app_context = AppContext::with_api(api);
+        let matches = get_matches_for_args(&["progress", "123"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ShowProgressPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        assert!(output.starts_with("Test Game"));
+        assert!(output.contains("No achievements found for this game"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_api_error() {
+        let app_context = AppContext::with_api(FakeApi::failing());
+        let matches = get_matches_for_args(&["progress", "123"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ShowProgressPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(err_writer).unwrap();
+        assert!(output.contains("Error while trying to get achievements"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_minimal() {
+        let api = FakeApi::new().with_achievements(
+            123,
+            "Test Game",
+            vec![create_mock_achievement(1), create_mock_achievement(1), create_mock_achievement(0)],
+        );
+        let app_context = AppContext::with_api(api);
+        let matches = get_matches_for_args(&["progress", "123", "--minimal"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ShowProgressPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        assert_eq!(String::from_utf8(writer).unwrap(), "66%\n");
+        assert!(err_writer.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_execute_minimal_no_newline() {
+        let api = FakeApi::new().with_achievements(123, "Test Game", vec![create_mock_achievement(1)]);
+        let app_context = AppContext::with_api(api);
+        let matches = get_matches_for_args(&["progress", "123", "--minimal", "--no-newline"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ShowProgressPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        assert_eq!(String::from_utf8(writer).unwrap(), "100%");
+    }
+
+    #[tokio::test]
+    async fn test_execute_minimal_without_achievements() {
+        let api = FakeApi::new().with_achievements(123, "Test Game", vec![]);
+        let app_context = AppContext::with_api(api);
+        let matches = get_matches_for_args(&["progress", "123", "--minimal"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ShowProgressPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        assert!(writer.is_empty());
+        assert_eq!(String::from_utf8(err_writer).unwrap(), "No achievements found for this game\n");
+    }
+
+    #[test]
+    fn test_no_newline_requires_minimal() {
+        let result = ShowProgressPlugin.command().try_get_matches_from(["progress", "123", "--no-newline"]);
+        assert_eq!(result.unwrap_err().kind(), clap::error::ErrorKind::MissingRequiredArgument);
+    }
+
+    #[tokio::test]
+    async fn test_execute_raw() {
+        let mock_body = "{\n  \"playerstats\": {\"steamID\": \"test_id\", \"gameName\": \"Test Game\", \"achievements\": [], \"success\": true}\n}";
+        let (app_context, _server) = setup_test_env(mock_body, 200).await;
+        let matches = get_matches_for_args(&["progress", "123", "--raw"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ShowProgressPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        assert_eq!(writer, mock_body.as_bytes());
+        assert!(err_writer.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_execute_raw_rejects_stdin() {
+        let app_context = AppContext::with_api(FakeApi::new());
+        let matches = get_matches_for_args(&["progress", "-", "--raw"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ShowProgressPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        assert!(writer.is_empty());
+        assert_eq!(String::from_utf8(err_writer).unwrap(), "--raw cannot be used with game ids read from stdin\n");
+    }
+
+    #[tokio::test]
+    async fn test_execute_invalid_game_id() {
+        let (app_context, _server) = setup_test_env("", 200).await;
+        let matches = get_matches_for_args(&["progress", "invalid"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ShowProgressPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(err_writer).unwrap();
+        assert_eq!(output.trim(), "Invalid game id: invalid");
+    }
+
+    #[tokio::test]
+    async fn test_execute_remembers_and_reuses_last_game() {
         let achievements = vec![create_mock_achievement(1), create_mock_achievement(0)];
         let mock_body = serde_json::to_string(&serde_json::json!({
             "playerstats": {
@@ -181,63 +910,407 @@ mod tests {
                 "success": true
             }
         })).unwrap();
-        let (app_context, _server) = setup_test_env(&mock_body, 200).await;
+        let (mut app_context, _server) = setup_test_env(&mock_body, 200).await;
+        let path = temp_last_game_path("reuse");
+        app_context.last_game = Some(LastGameStore::new(path.clone()));
+
         let matches = get_matches_for_args(&["progress", "123"]);
+        ShowProgressPlugin.execute(&app_context, &matches, &mut Vec::new(), &mut Vec::new()).await;
+        assert_eq!(app_context.last_game_id(), Some(123));
+
+        let matches = get_matches_for_args(&["progress"]);
         let mut writer = Vec::new();
         let mut err_writer = Vec::new();
 
         ShowProgressPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
 
         let output = String::from_utf8(writer).unwrap();
-        assert!(output.starts_with("Test Game"));
+        let err_output = String::from_utf8(err_writer).unwrap();
+        assert!(err_output.contains("using last game id: 123"));
         assert!(output.contains("50.0% (1/2)"));
+
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    fn create_named_achievement(apiname: &str, achieved: u8) -> Achievement {
+        Achievement {
+            apiname: apiname.to_string(),
+            name: format!("Achievement {}", apiname),
+            description: "".to_string(),
+            achieved,
+            unlocktime: 0,
+        }
+    }
+
+    fn temp_history_dir(name: &str) -> PathBuf {
+        std::env::temp_dir()
+            .join(format!("trogue-progress-test-{}-{}", std::process::id(), name))
+            .join("snapshots")
+    }
+
+    async fn execute_changes(history_dir: &std::path::Path) -> String {
+        let api = FakeApi::new().with_achievements(
+            123,
+            "Test Game",
+            vec![create_named_achievement("a1", 1), create_named_achievement("a2", 1), create_named_achievement("a3", 0)],
+        );
+        let mut app_context = AppContext::with_api(api);
+        app_context.unlock_history = Some(UnlockHistoryStore::new(history_dir.to_path_buf()));
+        let matches = get_matches_for_args(&["progress", "123", "--changes"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ShowProgressPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        assert!(err_writer.is_empty());
+        String::from_utf8(writer).unwrap()
     }
 
     #[tokio::test]
-    async fn test_execute_no_achievements() {
+    async fn test_execute_changes_first_run() {
+        let dir = temp_history_dir("changes_first");
+
+        let output = execute_changes(&dir).await;
+
+        assert!(output.contains("66.7% (2/3)"));
+        assert!(output.ends_with("No earlier record of this game, changes are listed from the next run\n"));
+        let recorded = UnlockHistoryStore::new(dir.clone()).load(123).unwrap();
+        assert_eq!(recorded.name, "Test Game");
+        assert_eq!(recorded.achievements.len(), 3);
+
+        std::fs::remove_dir_all(dir.parent().unwrap()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_execute_changes_without_new_unlocks() {
+        let dir = temp_history_dir("changes_none");
+
+        execute_changes(&dir).await;
+        let output = execute_changes(&dir).await;
+
+        assert!(output.ends_with("No achievements unlocked since the last run\n"));
+
+        std::fs::remove_dir_all(dir.parent().unwrap()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_execute_changes_with_new_unlocks() {
+        let dir = temp_history_dir("changes_new");
+        let store = UnlockHistoryStore::new(dir.clone());
+        store
+            .save(123, "Test Game", &[create_named_achievement("a1", 1), create_named_achievement("a2", 0), create_named_achievement("a3", 0)])
+            .unwrap();
+
+        let output = execute_changes(&dir).await;
+
+        assert!(output.ends_with("Unlocked since the last run:\n + Achievement a2\n"));
+        let recorded = store.load(123).unwrap();
+        assert!(recorded.achievements.iter().find(|a| a.apiname == "a2").unwrap().is_unlocked());
+
+        let output = execute_changes(&dir).await;
+        assert!(output.ends_with("No achievements unlocked since the last run\n"));
+
+        std::fs::remove_dir_all(dir.parent().unwrap()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_execute_porcelain() {
+        let api = FakeApi::new()
+            .with_achievements(123, "Test Game", vec![create_mock_achievement(1), create_mock_achievement(0)])
+            .with_achievements(456, "Other Game", vec![]);
+        let app_context = AppContext::with_api(api).with_porcelain(true);
+
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+        let matches = get_matches_for_args(&["progress", "123"]);
+        ShowProgressPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+        assert_eq!(String::from_utf8(writer).unwrap(), "progress\t123\t1\t2\n");
+
+        let mut writer = Vec::new();
+        let matches = get_matches_for_args(&["progress", "123", "456", "--minimal"]);
+        ShowProgressPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+        assert_eq!(String::from_utf8(writer).unwrap(), "progress\t123\t1\t2\nprogress\t456\t0\t0\n");
+        assert!(err_writer.is_empty());
+    }
+
+    fn create_global_achievement(name: &str, percent: f32) -> GlobalAchievement {
+        GlobalAchievement {
+            name: name.to_string(),
+            percent,
+        }
+    }
+
+    #[test]
+    fn test_rarity_score() {
+        let achievements = [create_named_achievement("a1", 1), create_named_achievement("a2", 1), create_named_achievement("a3", 0)];
+        let global_achievements = [create_global_achievement("a1", 90.0), create_global_achievement("a2", 2.5), create_global_achievement("a3", 40.0)];
+
+        assert_eq!(rarity_score(&achievements, &global_achievements), (107.5, 167.5));
+    }
+
+    #[test]
+    fn test_rarity_score_counts_missing_global_data_as_rare() {
+        let achievements = [create_named_achievement("a1", 1)];
+
+        assert_eq!(rarity_score(&achievements, &[]), (100.0, 100.0));
+    }
+
+    #[tokio::test]
+    async fn test_execute_score() {
+        let api = FakeApi::new()
+            .with_achievements(123, "Test Game", vec![create_named_achievement("a1", 1), create_named_achievement("a2", 0)])
+            .with_global_achievements(123, vec![create_global_achievement("a1", 75.0), create_global_achievement("a2", 10.0)]);
+        let app_context = AppContext::with_api(api);
+        let matches = get_matches_for_args(&["progress", "123", "--score"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ShowProgressPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        assert!(output.contains("50.0% (1/2)"));
+        assert!(output.ends_with("Rarity score: 25.0 of 115.0\n"));
+        assert!(err_writer.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_execute_score_global_error() {
+        let api = FakeApi::new().with_achievements(123, "Test Game", vec![create_named_achievement("a1", 1)]);
+        let app_context = AppContext::with_api(api);
+        let matches = get_matches_for_args(&["progress", "123", "--score"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ShowProgressPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        assert!(!String::from_utf8(writer).unwrap().contains("Rarity score"));
+        assert!(String::from_utf8(err_writer).unwrap().starts_with("Error while trying to get global achievements"));
+    }
+
+    #[test]
+    fn test_score_conflicts_with_minimal() {
+        assert!(ShowProgressPlugin.command().try_get_matches_from(["progress", "123", "--score", "--minimal"]).is_err());
+    }
+
+    #[test]
+    fn test_changes_conflicts_with_minimal() {
+        assert!(ShowProgressPlugin.command().try_get_matches_from(["progress", "123", "--changes", "--minimal"]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_execute_without_game_and_no_last_game() {
+        let (mut app_context, _server) = setup_test_env("", 200).await;
+        app_context.last_game = Some(LastGameStore::new(temp_last_game_path("none")));
+        let matches = get_matches_for_args(&["progress"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ShowProgressPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let err_output = String::from_utf8(err_writer).unwrap();
+        assert!(err_output.contains("No game given and no previously used game found"));
+        assert!(writer.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_custom_bar_fill() {
+        let achievements = vec![create_mock_achievement(1), create_mock_achievement(1)];
         let mock_body = serde_json::to_string(&serde_json::json!({
             "playerstats": {
                 "steamID": "test_id",
                 "gameName": "Test Game",
-                "achievements": [],
+                "achievements": achievements,
                 "success": true
             }
         })).unwrap();
         let (app_context, _server) = setup_test_env(&mock_body, 200).await;
-        let matches = get_matches_for_args(&["progress", "123"]);
+        let matches = get_matches_for_args(&["progress", "123", "--bar-fill", "#"]);
         let mut writer = Vec::new();
         let mut err_writer = Vec::new();
 
         ShowProgressPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
 
         let output = String::from_utf8(writer).unwrap();
-        assert!(output.starts_with("Test Game"));
-        assert!(output.contains("No achievements found for this game"));
+        assert!(output.contains("[##"));
+        assert!(output.contains("#] 100.0% (2/2)"));
+        assert!(!output.contains('█'));
+    }
+
+    #[test]
+    fn test_command_rejects_multi_character_bar_fill() {
+        let result = ShowProgressPlugin.command().try_get_matches_from(["progress", "123", "--bar-fill", "##"]);
+        assert!(result.is_err());
     }
 
     #[tokio::test]
-    async fn test_execute_api_error() {
-        let (app_context, _server) = setup_test_env("", 500).await;
+    async fn test_execute_recovers_empty_game_name_from_games_list() {
+        let api = FakeApi::new()
+            .with_game(123, "Name From Library")
+            .with_achievements(123, "", vec![create_mock_achievement(1)]);
+        let app_context = AppContext::with_api(api);
         let matches = get_matches_for_args(&["progress", "123"]);
         let mut writer = Vec::new();
         let mut err_writer = Vec::new();
 
         ShowProgressPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
 
-        let output = String::from_utf8(err_writer).unwrap();
-        assert!(output.contains("Error while trying to get achievements"));
+        let output = String::from_utf8(writer).unwrap();
+        assert!(output.starts_with("Name From Library\n"));
+        assert!(output.contains("100.0% (1/1)"));
     }
 
     #[tokio::test]
-    async fn test_execute_invalid_game_id() {
-        let (app_context, _server) = setup_test_env("", 200).await;
-        let matches = get_matches_for_args(&["progress", "invalid"]);
+    async fn test_execute_multiple_games() {
+        let api = FakeApi::new()
+            .with_achievements(123, "First Game", vec![create_mock_achievement(1)])
+            .with_achievements(456, "Second Game", vec![create_mock_achievement(1), create_mock_achievement(0)]);
+        let app_context = AppContext::with_api(api);
+        let matches = get_matches_for_args(&["progress", "123", "456"]);
         let mut writer = Vec::new();
         let mut err_writer = Vec::new();
 
         ShowProgressPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
 
-        let output = String::from_utf8(err_writer).unwrap();
-        assert_eq!(output.trim(), "Invalid game id: invalid");
+        let output = String::from_utf8(writer).unwrap();
+        let sections: Vec<&str> = output.split("\n\n").collect();
+        assert_eq!(sections.len(), 3);
+        assert!(sections[0].starts_with("[123]\nFirst Game\n"));
+        assert!(sections[0].contains("100.0% (1/1)"));
+        assert!(sections[1].starts_with("[456]\nSecond Game\n"));
+        assert!(sections[1].contains("50.0% (1/2)"));
+        assert_eq!(sections[2], "Total: 66.7% (2/3) across 2 games\n");
+        assert!(err_writer.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_execute_multiple_games_skips_invalid_ids() {
+        let api = FakeApi::new()
+            .with_achievements(123, "First Game", vec![create_mock_achievement(1), create_mock_achievement(0)]);
+        let app_context = AppContext::with_api(api);
+        let matches = get_matches_for_args(&["progress", "123", "abc", "789"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ShowProgressPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        let sections: Vec<&str> = output.split("\n\n").collect();
+        assert_eq!(sections.len(), 3);
+        assert!(sections[0].starts_with("[123]\nFirst Game\n"));
+        assert_eq!(sections[1], "[789]");
+        assert_eq!(sections[2], "Total: 50.0% (1/2) across 1 games\n");
+
+        let err_output = String::from_utf8(err_writer).unwrap();
+        assert!(err_output.contains("Invalid game id: abc\n"));
+        assert!(err_output.contains("Error while trying to get achievements"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_stdin_must_be_the_only_game() {
+        let app_context = AppContext::with_api(FakeApi::new());
+        let matches = get_matches_for_args(&["progress", "123", "-"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ShowProgressPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        assert!(writer.is_empty());
+        assert_eq!(String::from_utf8(err_writer).unwrap(), "- must be the only game id, to read the ids from stdin\n");
+    }
+
+    #[tokio::test]
+    async fn test_show_game_progress() {
+        let api = FakeApi::new()
+            .with_achievements(123, "Test Game", vec![create_mock_achievement(1), create_mock_achievement(0)]);
+        let app_context = AppContext::with_api(api);
+        let bar_style = ui::BarStyle::default();
+        let output = ProgressOutput::Bar { changes: false, score: false };
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        let shown = show_game_progress(&app_context, 123, &bar_style, output, &mut writer, &mut err_writer).await;
+
+        assert!(shown);
+        let output = String::from_utf8(writer).unwrap();
+        assert!(output.starts_with("Test Game\n"));
+        assert!(output.trim_end().ends_with("50.0% (1/2)"));
+        assert!(err_writer.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_watch_progress_renders_once_per_event_burst() {
+        let api = FakeApi::new().with_achievements(123, "Test Game", vec![create_mock_achievement(1)]);
+        let app_context = AppContext::with_api(api);
+        let (sender, events) = mpsc::unbounded_channel();
+        // A touch arrives as several events, which must not render several times
+        sender.send(()).unwrap();
+        sender.send(()).unwrap();
+        drop(sender);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        watch_progress(&app_context, 123, events, &ui::BarStyle::default(), ProgressOutput::Minimal { newline: true }, &mut writer, &mut err_writer).await;
+
+        assert_eq!(String::from_utf8(writer).unwrap(), "\n100%\n");
+        assert!(err_writer.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_watch_file_changes_reports_modifications() {
+        let dir = temp_history_dir("watch");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("trigger");
+        std::fs::write(&path, "").unwrap();
+
+        let (_watcher, mut events) = watch_file_changes(&path).unwrap();
+        std::fs::write(&path, "launched").unwrap();
+
+        let event = tokio::time::timeout(std::time::Duration::from_secs(5), events.recv()).await;
+        assert_eq!(event.unwrap(), Some(()));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_watch_file_changes_missing_file() {
+        assert!(watch_file_changes(Path::new("/nonexistent/trogue-trigger")).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_execute_watch_file_requires_single_game() {
+        let app_context = AppContext::with_api(FakeApi::new());
+        let matches = get_matches_for_args(&["progress", "123", "456", "--watch-file", "/tmp/trigger"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        ShowProgressPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        assert!(writer.is_empty());
+        assert_eq!(String::from_utf8(err_writer).unwrap(), "--watch-file takes a single game id\n");
+    }
+
+    #[tokio::test]
+    async fn test_show_batch_progress() {
+        let api = FakeApi::new()
+            .with_achievements(123, "First Game", vec![create_mock_achievement(1)])
+            .with_achievements(456, "Second Game", vec![create_mock_achievement(1), create_mock_achievement(0)]);
+        let app_context = AppContext::with_api(api);
+        let mut reader = std::io::Cursor::new("123\nnot-a-game\n\n 456 \n789\n");
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        show_batch_progress(&app_context, &mut reader, &ui::BarStyle::default(), ProgressOutput::Bar { changes: false, score: false }, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        let sections: Vec<&str> = output.split("\n\n").collect();
+        assert_eq!(sections.len(), 3);
+        assert!(sections[0].starts_with("[123]\nFirst Game\n"));
+        assert!(sections[0].contains("100.0% (1/1)"));
+        assert!(sections[1].starts_with("[456]\nSecond Game\n"));
+        assert!(sections[1].contains("50.0% (1/2)"));
+        assert_eq!(sections[2], "[789]\n");
+
+        let err_output = String::from_utf8(err_writer).unwrap();
+        assert!(err_output.contains("Invalid game id: not-a-game"));
+        assert!(err_output.contains("Error while trying to get achievements"));
     }
 }
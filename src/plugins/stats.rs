@@ -0,0 +1,560 @@
+//! Plugin for summarizing the achievement progress of the whole library.
+//!
+//! <purpose-start>
+//! This plugin provides the `stats` command, which counts the owned games, the unlocked
+//! achievements and the perfect games. With `--by playtime`, the same figures are broken down by
+//! playtime range, to see whether long-played games are also the most completed ones. With
+//! `--format json`, the headline figures are printed as a JSON object for other tools.
+//! <purpose-end>
+//!
+//! <inputs-start>
+//! - `app_context`: The shared application context, providing access to the Steam API client.
+//! - `matches`: The command-line arguments parsed by `clap`.
+//! <inputs-end>
+//!
+//! <outputs-start>
+//! - The summary printed to the console.
+//! <outputs-end>
+//!
+//! <side-effects-start>
+//! - Makes one network request for the list of games and one per game for its achievements.
+//! <side-effects-end>
+
+use crate::{app::AppContext, plugins::{self, Plugin}, ui::{self, table::Table}};
+use async_trait::async_trait;
+use clap::{Arg, Command, ValueEnum};
+use futures::StreamExt;
+use serde::Serialize;
+use std::io::Write;
+use std::pin::pin;
+
+pub struct StatsPlugin;
+
+// Represents the values of the `--by` option.
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+enum StatsBy {
+    // Playtime ranges, from less than an hour to more than a hundred hours
+    Playtime,
+}
+
+// Represents the output formats of the `stats` command.
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+enum StatsFormat {
+    // One line per figure, followed by the `--by` table
+    Text,
+    // A JSON object with the headline figures
+    Json,
+}
+
+// Represents the `--format json` output.
+//
+// <purpose-start>
+// `overall_percentage` is the share of all achievements that are unlocked, so games with many
+// achievements weigh more, while `average_completion` averages the completion of each game with
+// achievements. Both are 0 without achievements, and rounded to one decimal like the dashboard's.
+// <purpose-end>
+#[derive(Serialize, Debug)]
+struct StatsJson {
+    games_with_achievements: usize,
+    perfect_games: usize,
+    #[serde(serialize_with = "ui::serialize_percentage")]
+    overall_percentage: f32,
+    #[serde(serialize_with = "ui::serialize_percentage")]
+    average_completion: f32,
+}
+
+// Represents the playtime ranges of `--by playtime`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PlaytimeBucket {
+    UnderOneHour,
+    OneToTenHours,
+    TenToHundredHours,
+    OverHundredHours,
+}
+
+impl PlaytimeBucket {
+    // The buckets, in display order.
+    const ALL: [PlaytimeBucket; 4] = [
+        PlaytimeBucket::UnderOneHour,
+        PlaytimeBucket::OneToTenHours,
+        PlaytimeBucket::TenToHundredHours,
+        PlaytimeBucket::OverHundredHours,
+    ];
+
+    // Returns the bucket of a playtime, in minutes as reported by Steam. Lower bounds are inclusive.
+    fn of(playtime_minutes: u32) -> PlaytimeBucket {
+        match playtime_minutes {
+            0..60 => PlaytimeBucket::UnderOneHour,
+            60..600 => PlaytimeBucket::OneToTenHours,
+            600..6000 => PlaytimeBucket::TenToHundredHours,
+            _ => PlaytimeBucket::OverHundredHours,
+        }
+    }
+
+    // Returns the label of the bucket.
+    fn label(self) -> &'static str {
+        match self {
+            PlaytimeBucket::UnderOneHour => "<1h",
+            PlaytimeBucket::OneToTenHours => "1-10h",
+            PlaytimeBucket::TenToHundredHours => "10-100h",
+            PlaytimeBucket::OverHundredHours => "100h+",
+        }
+    }
+}
+
+// The playtime and achievement counts of a game whose achievements were fetched.
+struct GameStats {
+    playtime_minutes: u32,
+    total: usize,
+    completed: usize,
+}
+
+// Accumulates the figures of a group of games.
+#[derive(Debug, Default, PartialEq)]
+struct Totals {
+    games: usize,
+    // The games with achievements, the only ones that can be perfect
+    with_achievements: usize,
+    total: usize,
+    completed: usize,
+    perfect: usize,
+}
+
+impl Totals {
+    // Counts a game.
+    fn add(&mut self, game: &GameStats) {
+        self.games += 1;
+        if game.total == 0 {
+            return;
+        }
+
+        self.with_achievements += 1;
+        self.total += game.total;
+        self.completed += game.completed;
+        if game.completed == game.total {
+            self.perfect += 1;
+        }
+    }
+
+    // Returns the share of the achievements of the group that are unlocked.
+    fn percentage(&self) -> f32 {
+        ui::completion_percentage(self.completed, self.total)
+    }
+
+    // Renders the completion of the group, `n/a` when it has no achievements to complete.
+    fn completion(&self) -> String {
+        if self.total == 0 {
+            return "n/a".to_string();
+        }
+
+        format!("{:.1}%", self.percentage())
+    }
+}
+
+// Returns the mean completion of the games with achievements, 0 if there are none.
+fn average_completion(games: &[GameStats]) -> f32 {
+    let percentages: Vec<f32> = games
+        .iter()
+        .filter(|g| g.total > 0)
+        .map(|g| ui::completion_percentage(g.completed, g.total))
+        .collect();
+    if percentages.is_empty() {
+        return 0.0;
+    }
+    percentages.iter().sum::<f32>() / percentages.len() as f32
+}
+
+// Sums up the games of each playtime bucket.
+//
+// <purpose-start>
+// Every bucket is returned, empty or not, so that the breakdown always has the same rows.
+// <purpose-end>
+//
+// <inputs-start>
+// - `games`: The games whose achievements were fetched.
+// <inputs-end>
+//
+// <outputs-start>
+// - `Vec<(PlaytimeBucket, Totals)>`: The figures of each bucket, shortest playtimes first.
+// <outputs-end>
+fn by_playtime(games: &[GameStats]) -> Vec<(PlaytimeBucket, Totals)> {
+    PlaytimeBucket::ALL
+        .into_iter()
+        .map(|bucket| {
+            let mut totals = Totals::default();
+            for game in games.iter().filter(|g| PlaytimeBucket::of(g.playtime_minutes) == bucket) {
+                totals.add(game);
+            }
+            (bucket, totals)
+        })
+        .collect()
+}
+
+#[async_trait]
+impl Plugin for StatsPlugin {
+    // Defines the clap command for the `stats` plugin.
+    //
+    // <purpose-start>
+    // This method provides the command-line interface for the `stats` plugin,
+    // which summarizes the achievement progress of the whole library.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `&self`: A reference to the plugin instance.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `clap::Command`: The clap command definition for the `stats` plugin.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None.
+    // <side-effects-end>
+    fn command(&self) -> Command {
+        Command::new("stats")
+            .about("Summarizes the achievement progress of all owned games")
+            .after_help(concat!(
+                "Examples:\n",
+                "  trogue stats\n",
+                "  trogue stats --by playtime\n",
+                "  trogue stats --format json",
+            ))
+            .arg(
+                Arg::new("by")
+                    .long("by")
+                    .value_name("key")
+                    .value_parser(clap::value_parser!(StatsBy))
+                    .help("Breaks the figures down (playtime: <1h, 1-10h, 10-100h, 100h+), text format only"),
+            )
+            .arg(
+                Arg::new("format")
+                    .long("format")
+                    .value_name("format")
+                    .value_parser(clap::value_parser!(StatsFormat))
+                    .default_value("text")
+                    .help("The output format (text, json)"),
+            )
+    }
+
+    // Executes the `stats` plugin's logic.
+    //
+    // <purpose-start>
+    // This method is called by the core application when the `stats` command is invoked.
+    // It fetches the achievements of every owned game and prints the totals. Games whose
+    // achievements cannot be fetched are reported and left out, as with `export`.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `&self`: A reference to the plugin instance.
+    // - `app_context`: The shared application context.
+    // - `matches`: The clap argument matches for the `stats` subcommand.
+    // - `writer`: A mutable reference to a writer for standard output.
+    // - `err_writer`: A mutable reference to a writer for standard error.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - None.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - Makes network requests to the Steam API to fetch the games and their achievements.
+    // - Writes the summary to the provided writer, and progress and errors to `err_writer`.
+    // <side-effects-end>
+    async fn execute(
+        &self,
+        app_context: &AppContext,
+        matches: &clap::ArgMatches,
+        writer: &mut (dyn Write + Send),
+        err_writer: &mut (dyn Write + Send),
+    ) {
+        let by = matches.get_one::<StatsBy>("by").copied();
+        let format = *matches.get_one::<StatsFormat>("format").unwrap();
+        if by.is_some() && format != StatsFormat::Text {
+            writeln!(err_writer, "--by only supports the text format").unwrap();
+            return;
+        }
+
+        let games = match app_context.get_games_list_cached().await {
+            Ok(games) => games,
+            Err(e) => {
+                writeln!(err_writer, "Error while trying to get games list: {}", ui::scrub_api_key(&e.to_string())).unwrap();
+                return;
+            }
+        };
+
+        let mut reporter = ui::progress_reporter(app_context.quiet());
+        let mut results = pin!(app_context.game_achievements(games.iter().map(|g| g.appid).collect()));
+        let mut skipped = plugins::SkippedGames::default();
+        let mut stats = Vec::new();
+
+        for (index, game) in games.iter().enumerate() {
+            reporter.report(index + 1, games.len(), err_writer);
+            let result = results.next().await.unwrap();
+            reporter.clear(err_writer);
+
+            match result {
+                Ok(game_achievements) => stats.push(GameStats {
+                    playtime_minutes: game.playtime_forever,
+                    total: game_achievements.total(),
+                    completed: game_achievements.completed(),
+                }),
+                Err(e) => {
+                    let error = format!("Error while trying to get achievements for {}: {}", game.name, ui::scrub_api_key(&e.to_string()));
+                    skipped.skip(app_context, game.appid, &error, err_writer);
+                }
+            }
+        }
+        skipped.report(err_writer);
+
+        let mut totals = Totals::default();
+        for game in &stats {
+            totals.add(game);
+        }
+
+        if format == StatsFormat::Json {
+            let json = StatsJson {
+                games_with_achievements: totals.with_achievements,
+                perfect_games: totals.perfect,
+                overall_percentage: totals.percentage(),
+                average_completion: average_completion(&stats),
+            };
+            writeln!(writer, "{}", serde_json::to_string(&json).unwrap()).unwrap();
+            return;
+        }
+
+        let count = |value: usize| ui::format_count(value as u64, app_context.group_thousands());
+        writeln!(writer, "Games: {} ({} with achievements)", count(totals.games), count(totals.with_achievements)).unwrap();
+        writeln!(writer, "Achievements: {}/{} ({:.1}%)", count(totals.completed), count(totals.total), totals.percentage()).unwrap();
+        writeln!(writer, "Perfect games: {}", count(totals.perfect)).unwrap();
+
+        if by == Some(StatsBy::Playtime) {
+            let mut table = Table::new(&["Playtime", "Games", "Achievements", "Completion", "Perfect"])
+                .right_align(1)
+                .right_align(2)
+                .right_align(3)
+                .right_align(4);
+            for (bucket, totals) in by_playtime(&stats) {
+                table.add_row(vec![
+                    bucket.label().to_string(),
+                    count(totals.games),
+                    format!("{}/{}", count(totals.completed), count(totals.total)),
+                    totals.completion(),
+                    count(totals.perfect),
+                ]);
+            }
+
+            writeln!(writer).unwrap();
+            for line in table.render() {
+                writeln!(writer, "{}", line).unwrap();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_api::FakeApi;
+    use crate::steam_api::Achievement;
+    use clap::ArgMatches;
+
+    fn create_mock_achievements(completed: usize, total: usize) -> Vec<Achievement> {
+        (0..total)
+            .map(|index| Achievement {
+                apiname: format!("a{}", index),
+                name: format!("Achievement {}", index),
+                description: String::new(),
+                achieved: u8::from(index < completed),
+                unlocktime: 0,
+            })
+            .collect()
+    }
+
+    fn create_test_api() -> FakeApi {
+        FakeApi::new()
+            .with_game_playtime(1, "Tried Once", 30)
+            .with_game_playtime(2, "Weekend Game", 300)
+            .with_game_playtime(3, "Long Campaign", 3000)
+            .with_game_playtime(4, "Forever Game", 12000)
+            .with_game_playtime(5, "No Achievements", 120)
+            .with_achievements(1, "Tried Once", create_mock_achievements(1, 10))
+            .with_achievements(2, "Weekend Game", create_mock_achievements(4, 4))
+            .with_achievements(3, "Long Campaign", create_mock_achievements(5, 20))
+            .with_achievements(4, "Forever Game", create_mock_achievements(50, 50))
+            .with_achievements(5, "No Achievements", Vec::new())
+    }
+
+    fn get_matches_for_args(args: &[&str]) -> ArgMatches {
+        StatsPlugin.command().get_matches_from(args)
+    }
+
+    #[test]
+    fn test_command() {
+        let cmd = StatsPlugin.command();
+        assert_eq!(cmd.get_name(), "stats");
+        assert!(cmd.get_about().is_some());
+    }
+
+    #[test]
+    fn test_playtime_buckets() {
+        assert_eq!(PlaytimeBucket::of(0), PlaytimeBucket::UnderOneHour);
+        assert_eq!(PlaytimeBucket::of(59), PlaytimeBucket::UnderOneHour);
+        assert_eq!(PlaytimeBucket::of(60), PlaytimeBucket::OneToTenHours);
+        assert_eq!(PlaytimeBucket::of(599), PlaytimeBucket::OneToTenHours);
+        assert_eq!(PlaytimeBucket::of(600), PlaytimeBucket::TenToHundredHours);
+        assert_eq!(PlaytimeBucket::of(5999), PlaytimeBucket::TenToHundredHours);
+        assert_eq!(PlaytimeBucket::of(6000), PlaytimeBucket::OverHundredHours);
+    }
+
+    #[test]
+    fn test_by_playtime() {
+        let games = [
+            GameStats { playtime_minutes: 10, total: 10, completed: 1 },
+            GameStats { playtime_minutes: 20, total: 4, completed: 4 },
+            GameStats { playtime_minutes: 120, total: 0, completed: 0 },
+        ];
+
+        let buckets = by_playtime(&games);
+
+        assert_eq!(buckets.len(), 4);
+        assert_eq!(buckets[0], (PlaytimeBucket::UnderOneHour, Totals { games: 2, with_achievements: 2, total: 14, completed: 5, perfect: 1 }));
+        assert_eq!(buckets[1], (PlaytimeBucket::OneToTenHours, Totals { games: 1, ..Totals::default() }));
+        assert_eq!(buckets[2].1, Totals::default());
+        assert_eq!(buckets[1].1.percentage(), 0.0);
+        assert_eq!(buckets[1].1.completion(), "n/a");
+        assert_eq!(buckets[0].1.completion(), "35.7%");
+    }
+
+    #[tokio::test]
+    async fn test_execute() {
+        let app_context = AppContext::with_api(create_test_api());
+        let matches = get_matches_for_args(&["stats"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        StatsPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        assert_eq!(
+            String::from_utf8(writer).unwrap(),
+            "Games: 5 (4 with achievements)\nAchievements: 60/84 (71.4%)\nPerfect games: 2\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_execute_by_playtime() {
+        let app_context = AppContext::with_api(create_test_api());
+        let matches = get_matches_for_args(&["stats", "--by", "playtime"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        StatsPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        let lines: Vec<&str> = output.lines().skip(4).collect();
+        assert_eq!(lines, vec![
+            "Playtime  Games  Achievements  Completion  Perfect",
+            "--------  -----  ------------  ----------  -------",
+            "<1h           1          1/10       10.0%        0",
+            "1-10h         2           4/4      100.0%        1",
+            "10-100h       1          5/20       25.0%        0",
+            "100h+         1         50/50      100.0%        1",
+        ]);
+    }
+
+    #[tokio::test]
+    async fn test_execute_by_playtime_without_achievements() {
+        let api = FakeApi::new()
+            .with_game_playtime(1, "Tried Once", 30)
+            .with_game_playtime(2, "No Achievements", 300)
+            .with_achievements(1, "Tried Once", create_mock_achievements(1, 2))
+            .with_achievements(2, "No Achievements", Vec::new());
+        let app_context = AppContext::with_api(api);
+        let matches = get_matches_for_args(&["stats", "--by", "playtime"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        StatsPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        let lines: Vec<&str> = output.lines().skip(6).collect();
+        assert_eq!(lines, vec![
+            "<1h           1           1/2       50.0%        0",
+            "1-10h         1           0/0         n/a        0",
+            "10-100h       0           0/0         n/a        0",
+            "100h+         0           0/0         n/a        0",
+        ]);
+    }
+
+    #[test]
+    fn test_average_completion() {
+        let games = [
+            GameStats { playtime_minutes: 0, total: 4, completed: 1 },
+            GameStats { playtime_minutes: 0, total: 2, completed: 2 },
+            GameStats { playtime_minutes: 0, total: 0, completed: 0 },
+        ];
+
+        assert_eq!(average_completion(&games), 62.5);
+        assert_eq!(average_completion(&games[2..]), 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_execute_json() {
+        let app_context = AppContext::with_api(create_test_api());
+        let matches = get_matches_for_args(&["stats", "--format", "json"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        StatsPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let json: serde_json::Value = serde_json::from_slice(&writer).unwrap();
+        assert_eq!(json["games_with_achievements"], 4);
+        assert_eq!(json["perfect_games"], 2);
+        // 60 of 84 achievements, while the games are 10%, 100%, 25% and 100% complete
+        assert_eq!(json["overall_percentage"], 71.4);
+        assert_eq!(json["average_completion"], 58.8);
+        assert!(err_writer.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_execute_json_rejects_by() {
+        let app_context = AppContext::with_api(create_test_api());
+        let matches = get_matches_for_args(&["stats", "--format", "json", "--by", "playtime"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        StatsPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        assert!(writer.is_empty());
+        assert_eq!(String::from_utf8(err_writer).unwrap(), "--by only supports the text format\n");
+    }
+
+    #[tokio::test]
+    async fn test_execute_skips_games_that_fail() {
+        let api = FakeApi::new()
+            .with_game_playtime(1, "Fetched", 30)
+            .with_game_playtime(2, "Missing", 30)
+            .with_achievements(1, "Fetched", create_mock_achievements(1, 2));
+        let app_context = AppContext::with_api(api).with_verbose(true);
+        let matches = get_matches_for_args(&["stats"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        StatsPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        assert!(String::from_utf8(writer).unwrap().starts_with("Games: 1 (1 with achievements)"));
+        assert!(String::from_utf8(err_writer).unwrap().contains("Error while trying to get achievements for Missing"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_games_list_error() {
+        let app_context = AppContext::with_api(FakeApi::failing());
+        let matches = get_matches_for_args(&["stats"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        StatsPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        assert!(writer.is_empty());
+        assert!(String::from_utf8(err_writer).unwrap().starts_with("Error while trying to get games list"));
+    }
+}
@@ -0,0 +1,461 @@
+//! Plugin for listing the achievements unlocked today.
+//!
+//! <purpose-start>
+//! This plugin provides the `today` command, which lists the achievements unlocked during the
+//! current local day, grouped by game. It can also cover yesterday or the last few days.
+//! <purpose-end>
+//!
+//! <inputs-start>
+//! - `app_context`: The shared application context, providing access to the Steam API client.
+//! - `matches`: The command-line arguments parsed by `clap`.
+//! <inputs-end>
+//!
+//! <outputs-start>
+//! - The achievements unlocked in the period, grouped by game, printed to the console.
+//! <outputs-end>
+//!
+//! <side-effects-start>
+//! - Makes one network request for the list of games and one per recently played game for its achievements.
+//! <side-effects-end>
+
+use crate::{app::AppContext, plugins::Plugin, steam_api::Achievement, ui};
+use async_trait::async_trait;
+use chrono::{DateTime, Days, Local, NaiveDate, TimeZone};
+use clap::{Arg, Command};
+use futures::StreamExt;
+use std::io::Write;
+use std::pin::pin;
+
+pub struct TodayPlugin;
+
+// Describes the period to list the unlocks of.
+//
+// <purpose-start>
+// The bounds are Unix timestamps of local midnights, so that a day means the user's day rather
+// than the UTC one. The label completes the "No achievements unlocked ..." message.
+// <purpose-end>
+#[derive(Debug, PartialEq)]
+struct Period {
+    // The first second of the period.
+    start: u64,
+    // The first second after the period.
+    end: u64,
+    label: String,
+    // The strftime format of unlock times, without the date when the period is a single day.
+    time_format: &'static str,
+}
+
+#[async_trait]
+impl Plugin for TodayPlugin {
+    // Defines the clap command for the `today` plugin.
+    //
+    // <purpose-start>
+    // This method provides the command-line interface for the `today` plugin,
+    // which lists the achievements unlocked today.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `&self`: A reference to the plugin instance.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `clap::Command`: The clap command definition for the `today` plugin.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None.
+    // <side-effects-end>
+    fn command(&self) -> Command {
+        Command::new("today")
+            .about("Displays the achievements unlocked today in recently played games, grouped by game.")
+            .after_help(concat!(
+                "Examples:\n",
+                "  trogue today\n",
+                "  trogue today --yesterday\n",
+                "  trogue today --days 7",
+            ))
+            .arg(
+                Arg::new("yesterday")
+                    .long("yesterday")
+                    .action(clap::ArgAction::SetTrue)
+                    .conflicts_with("days")
+                    .help("Displays the achievements unlocked yesterday instead."),
+            )
+            .arg(
+                Arg::new("days")
+                    .long("days")
+                    .value_name("n")
+                    .value_parser(clap::value_parser!(u32).range(1..))
+                    .help("Displays the achievements unlocked in the last n days, today included."),
+            )
+    }
+
+    // Executes the `today` plugin's logic.
+    //
+    // <purpose-start>
+    // This method is called by the core application when the `today` command is invoked.
+    // It computes the requested period from the current local time and lists its unlocks.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `&self`: A reference to the plugin instance.
+    // - `app_context`: The shared application context.
+    // - `matches`: The clap argument matches for the `today` subcommand.
+    // - `writer`: A mutable reference to a writer for standard output.
+    // - `err_writer`: A mutable reference to a writer for standard error.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - None.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - Makes network requests to the Steam API to fetch the games and their achievements.
+    // - Writes the unlocks to the provided writer, and progress and errors to `err_writer`.
+    // <side-effects-end>
+    async fn execute(
+        &self,
+        app_context: &AppContext,
+        matches: &clap::ArgMatches,
+        writer: &mut (dyn Write + Send),
+        err_writer: &mut (dyn Write + Send),
+    ) {
+        let days = matches.get_one::<u32>("days").copied().unwrap_or(1);
+        let period = period(&Local::now(), days, matches.get_flag("yesterday"));
+        list_unlocks(app_context, &period, writer, err_writer).await;
+    }
+}
+
+// Returns the Unix timestamp of the local midnight starting a day.
+//
+// <purpose-start>
+// Midnight does not exist on some daylight saving time transitions, in which case the UTC
+// midnight is used as an approximation.
+// <purpose-end>
+//
+// <inputs-start>
+// - `timezone`: The timezone the day is in.
+// - `date`: The day.
+// <inputs-end>
+//
+// <outputs-start>
+// - `u64`: The timestamp, 0 for days before 1970.
+// <outputs-end>
+fn start_of_local_day<Tz: TimeZone>(timezone: &Tz, date: NaiveDate) -> u64 {
+    let midnight = date.and_hms_opt(0, 0, 0).unwrap();
+    let timestamp = timezone
+        .from_local_datetime(&midnight)
+        .earliest()
+        .map(|datetime| datetime.timestamp())
+        .unwrap_or_else(|| midnight.and_utc().timestamp());
+    u64::try_from(timestamp).unwrap_or(0)
+}
+
+// Computes the period to list the unlocks of.
+//
+// <inputs-start>
+// - `now`: The current time, in the user's timezone.
+// - `days`: The number of days to cover, today included, at least 1.
+// - `yesterday`: Whether to cover yesterday instead.
+// <inputs-end>
+//
+// <outputs-start>
+// - `Period`: The bounds, label and time format of the period.
+// <outputs-end>
+fn period<Tz: TimeZone>(now: &DateTime<Tz>, days: u32, yesterday: bool) -> Period {
+    let timezone = now.timezone();
+    let today = now.date_naive();
+    let tomorrow = today + Days::new(1);
+
+    if yesterday {
+        let yesterday = today - Days::new(1);
+        return Period {
+            start: start_of_local_day(&timezone, yesterday),
+            end: start_of_local_day(&timezone, today),
+            label: "yesterday".to_string(),
+            time_format: "%H:%M",
+        };
+    }
+
+    let first_day = today - Days::new(u64::from(days.max(1) - 1));
+    Period {
+        start: start_of_local_day(&timezone, first_day),
+        end: start_of_local_day(&timezone, tomorrow),
+        label: if days <= 1 { "today".to_string() } else { format!("in the last {} days", days) },
+        time_format: if days <= 1 { "%H:%M" } else { "%Y-%m-%d %H:%M" },
+    }
+}
+
+// Returns the achievements unlocked within a period, the earliest first.
+//
+// <inputs-start>
+// - `achievements`: The achievements of a game.
+// - `period`: The period the achievements must have been unlocked in.
+// <inputs-end>
+//
+// <outputs-start>
+// - `Vec<Achievement>`: The achievements unlocked in the period.
+// <outputs-end>
+fn unlocked_in(achievements: Vec<Achievement>, period: &Period) -> Vec<Achievement> {
+    let mut unlocked: Vec<Achievement> = achievements
+        .into_iter()
+        .filter(|a| a.unlock_datetime().is_some() && a.unlocktime >= period.start && a.unlocktime < period.end)
+        .collect();
+    unlocked.sort_by_key(|a| a.unlocktime);
+    unlocked
+}
+
+// Lists the achievements unlocked within a period, grouped by game.
+//
+// <purpose-start>
+// Only the games last played since the start of the period can have new unlocks, so the others
+// are not fetched. Games whose achievements cannot be fetched are reported and skipped.
+// <purpose-end>
+//
+// <inputs-start>
+// - `app_context`: The shared application context.
+// - `period`: The period to list the unlocks of.
+// - `writer`: A mutable reference to a writer for standard output.
+// - `err_writer`: A mutable reference to a writer for standard error.
+// <inputs-end>
+//
+// <outputs-start>
+// - None.
+// <outputs-end>
+//
+// <side-effects-start>
+// - Makes network requests to the Steam API to fetch the games and their achievements.
+// - Writes the unlocks to `writer`, and progress and errors to `err_writer`.
+// <side-effects-end>
+async fn list_unlocks(
+    app_context: &AppContext,
+    period: &Period,
+    writer: &mut (dyn Write + Send),
+    err_writer: &mut (dyn Write + Send),
+) {
+    let mut games = match app_context.get_games_list_cached().await {
+        Ok(games) => games,
+        Err(e) => {
+            writeln!(err_writer, "Error while trying to get games list: {}", ui::scrub_api_key(&e.to_string())).unwrap();
+            return;
+        }
+    };
+
+    games.retain(|g| g.rtime_last_played >= period.start);
+    games.sort_by_key(|g| std::cmp::Reverse(g.rtime_last_played));
+
+    let date_style = ui::DateStyle {
+        format: period.time_format.to_string(),
+        local_time: true,
+    };
+    let mut reporter = ui::progress_reporter(app_context.quiet());
+    let mut results = pin!(app_context.game_achievements(games.iter().map(|g| g.appid).collect()));
+    let mut unlocked_count = 0;
+
+    for (index, game) in games.iter().enumerate() {
+        reporter.report(index + 1, games.len(), err_writer);
+        let result = results.next().await.unwrap();
+        reporter.clear(err_writer);
+
+        let achievements = match result {
+            Ok(game_achievements) => unlocked_in(game_achievements.achievements, period),
+            Err(e) => {
+                writeln!(
+                    err_writer,
+                    "Error while trying to get achievements for {}: {}",
+                    game.name,
+                    ui::scrub_api_key(&e.to_string())
+                )
+                .unwrap();
+                continue;
+            }
+        };
+
+        if achievements.is_empty() {
+            continue;
+        }
+
+        if unlocked_count > 0 {
+            writeln!(writer).unwrap();
+        }
+        writeln!(writer, "{}", game.name).unwrap();
+
+        for achievement in achievements {
+            unlocked_count += 1;
+            let displayable_achievement = ui::DisplayableAchievement {
+                achievement,
+                date_style: date_style.clone(),
+            };
+            writeln!(writer, "  {}", displayable_achievement.format("t  n")).unwrap();
+        }
+    }
+
+    if unlocked_count == 0 {
+        writeln!(writer, "No achievements unlocked {}.", period.label).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_api::FakeApi;
+    use chrono::{FixedOffset, Utc};
+    use clap::ArgMatches;
+
+    // 2024-03-10 15:30:00 UTC
+    const NOW: i64 = 1710084600;
+    // 2024-03-10 00:00:00 UTC
+    const TODAY: u64 = 1710028800;
+    const DAY: u64 = 24 * 60 * 60;
+
+    fn create_mock_achievement(apiname: &str, unlocktime: u64) -> Achievement {
+        Achievement {
+            apiname: apiname.to_string(),
+            name: format!("Achievement {}", apiname),
+            description: "".to_string(),
+            achieved: if unlocktime > 0 { 1 } else { 0 },
+            unlocktime,
+        }
+    }
+
+    fn now_utc() -> DateTime<Utc> {
+        Utc.timestamp_opt(NOW, 0).unwrap()
+    }
+
+    fn get_matches_for_args(args: &[&str]) -> ArgMatches {
+        TodayPlugin.command().get_matches_from(args)
+    }
+
+    #[test]
+    fn test_command() {
+        let plugin = TodayPlugin;
+        let cmd = plugin.command();
+        assert_eq!(cmd.get_name(), "today");
+        assert!(cmd.get_about().is_some());
+        assert!(TodayPlugin.command().try_get_matches_from(["today", "--yesterday", "--days", "2"]).is_err());
+    }
+
+    #[test]
+    fn test_period_today() {
+        let period = period(&now_utc(), 1, false);
+        assert_eq!(period.start, TODAY);
+        assert_eq!(period.end, TODAY + DAY);
+        assert_eq!(period.label, "today");
+        assert_eq!(period.time_format, "%H:%M");
+    }
+
+    #[test]
+    fn test_period_yesterday() {
+        let period = period(&now_utc(), 1, true);
+        assert_eq!(period.start, TODAY - DAY);
+        assert_eq!(period.end, TODAY);
+        assert_eq!(period.label, "yesterday");
+    }
+
+    #[test]
+    fn test_period_days() {
+        let period = period(&now_utc(), 3, false);
+        assert_eq!(period.start, TODAY - 2 * DAY);
+        assert_eq!(period.end, TODAY + DAY);
+        assert_eq!(period.label, "in the last 3 days");
+        assert_eq!(period.time_format, "%Y-%m-%d %H:%M");
+    }
+
+    #[test]
+    fn test_period_uses_local_day() {
+        // 2024-03-10 15:30 UTC is already 2024-03-11 01:30 at UTC+10
+        let now = now_utc().with_timezone(&FixedOffset::east_opt(10 * 3600).unwrap());
+        let period = period(&now, 1, false);
+        assert_eq!(period.start, TODAY + DAY - 10 * 3600);
+        assert_eq!(period.end, TODAY + 2 * DAY - 10 * 3600);
+    }
+
+    #[test]
+    fn test_unlocked_in() {
+        let period = period(&now_utc(), 1, false);
+        let achievements = vec![
+            create_mock_achievement("afternoon", TODAY + 15 * 3600),
+            create_mock_achievement("yesterday", TODAY - 60),
+            create_mock_achievement("morning", TODAY + 8 * 3600),
+            create_mock_achievement("midnight", TODAY),
+            create_mock_achievement("tomorrow", TODAY + DAY),
+            create_mock_achievement("locked", 0),
+        ];
+
+        let apinames: Vec<String> = unlocked_in(achievements, &period).into_iter().map(|a| a.apiname).collect();
+        assert_eq!(apinames, vec!["midnight", "morning", "afternoon"]);
+    }
+
+    fn create_test_api() -> FakeApi {
+        FakeApi::new()
+            .with_played_game(1, "First Game", TODAY + 10 * 3600)
+            .with_played_game(2, "Second Game", TODAY + 12 * 3600)
+            .with_played_game(3, "Old Game", TODAY - 30 * DAY)
+            .with_played_game(4, "Quiet Game", TODAY + 9 * 3600)
+            .with_achievements(
+                1,
+                "First Game",
+                vec![create_mock_achievement("f1", TODAY + 9 * 3600), create_mock_achievement("f2", TODAY - 2 * DAY + 3600)],
+            )
+            .with_achievements(2, "Second Game", vec![create_mock_achievement("s1", TODAY + 11 * 3600)])
+            .with_achievements(3, "Old Game", vec![create_mock_achievement("o1", TODAY + 3600)])
+            .with_achievements(4, "Quiet Game", vec![create_mock_achievement("q1", 0)])
+    }
+
+    #[tokio::test]
+    async fn test_list_unlocks_groups_by_game() {
+        let app_context = AppContext::with_api(create_test_api());
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        list_unlocks(&app_context, &period(&now_utc(), 1, false), &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines[0], "Second Game");
+        assert!(lines[1].starts_with("  ") && lines[1].ends_with("  Achievement s1"));
+        assert_eq!(lines[2], "");
+        assert_eq!(lines[3], "First Game");
+        assert!(lines[4].ends_with("  Achievement f1"));
+        assert_eq!(lines.len(), 5);
+        assert!(err_writer.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_list_unlocks_over_several_days() {
+        let app_context = AppContext::with_api(create_test_api());
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        list_unlocks(&app_context, &period(&now_utc(), 3, false), &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        assert!(output.contains("Achievement f2"));
+        assert!(output.contains("2024-03-"));
+        assert!(!output.contains("Achievement o1"));
+    }
+
+    #[tokio::test]
+    async fn test_list_unlocks_without_unlocks() {
+        let app_context = AppContext::with_api(create_test_api());
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        list_unlocks(&app_context, &period(&now_utc(), 1, true), &mut writer, &mut err_writer).await;
+
+        assert_eq!(String::from_utf8(writer).unwrap(), "No achievements unlocked yesterday.\n");
+    }
+
+    #[tokio::test]
+    async fn test_execute_games_list_error() {
+        let app_context = AppContext::with_api(FakeApi::failing());
+        let matches = get_matches_for_args(&["today"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        TodayPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        assert!(writer.is_empty());
+        assert!(String::from_utf8(err_writer).unwrap().starts_with("Error while trying to get games list"));
+    }
+}
@@ -0,0 +1,465 @@
+//! Plugin for listing the rarest achievements unlocked by the user.
+//!
+//! <purpose-start>
+//! This plugin provides the `top` command, which shows the player's rarest unlocked achievements
+//! for a given game, ranked by their global unlock percentage.
+//! <purpose-end>
+//!
+//! <inputs-start>
+//! - `app_context`: The shared application context, providing access to the Steam API client.
+//! - `matches`: The command-line arguments parsed by `clap`.
+//! <inputs-end>
+//!
+//! <outputs-start>
+//! - A list of the rarest unlocked achievements printed to the console.
+//! <outputs-end>
+//!
+//! <side-effects-start>
+//! - Makes network requests to the Steam API to fetch player and global achievement data.
+//! <side-effects-end>
+
+use crate::{app::AppContext, plugins::{self, Plugin}, steam_api::{Achievement, GameAchievements, GlobalAchievement}, ui};
+use async_trait::async_trait;
+use clap::{Arg, Command};
+use std::collections::HashMap;
+use std::io::Write;
+
+pub struct TopPlugin;
+
+// The number of achievements listed when `--count` is not provided.
+const DEFAULT_COUNT: usize = 10;
+
+#[async_trait]
+impl Plugin for TopPlugin {
+    // Defines the clap command for the `top` plugin.
+    //
+    // <purpose-start>
+    // This method provides the command-line interface for the `top` plugin,
+    // which lists the rarest unlocked achievements for a specific game.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `&self`: A reference to the plugin instance.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `clap::Command`: The clap command definition for the `top` plugin.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None.
+    // <side-effects-end>
+    fn command(&self) -> Command {
+        Command::new("top")
+            .about("Displays your rarest unlocked achievements for a specific game.")
+            .after_help(concat!(
+                "Examples:\n",
+                "  trogue top 440\n",
+                "  trogue top --game-name portal --count 3",
+            ))
+            .arg(
+                Arg::new("game_id")
+                    .value_name("game_id")
+                    .action(clap::ArgAction::Set)
+                    .required_unless_present("game_name")
+                    .help("The ID of the game to list the rarest achievements for"),
+            )
+            .arg(plugins::game_name_arg())
+            .arg(
+                Arg::new("count")
+                    .short('n')
+                    .long("count")
+                    .value_name("count")
+                    .value_parser(clap::value_parser!(usize))
+                    .help("The number of achievements to display (default: 10)"),
+            )
+    }
+
+    // Executes the `top` plugin's logic.
+    //
+    // <purpose-start>
+    // This method is called by the core application when the `top` command is invoked.
+    // It joins the player's achievements with the global percentages and prints the rarest unlocked ones.
+    // If the global percentages cannot be fetched, the unlocked achievements are listed without ranking.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `&self`: A reference to the plugin instance.
+    // - `app_context`: The shared application context.
+    // - `matches`: The clap argument matches for the `top` subcommand.
+    // - `writer`: A mutable reference to a writer for standard output.
+    // - `err_writer`: A mutable reference to a writer for standard error.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - None.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - Makes network requests to the Steam API to fetch player and global achievement data.
+    // - Writes the ranked achievements to the provided writer.
+    // <side-effects-end>
+    async fn execute(
+        &self,
+        app_context: &AppContext,
+        matches: &clap::ArgMatches,
+        writer: &mut (dyn Write + Send),
+        err_writer: &mut (dyn Write + Send),
+    ) {
+        let count = matches.get_one::<usize>("count").copied().unwrap_or(DEFAULT_COUNT);
+
+        let game_id = match matches.get_one::<String>("game_id") {
+            Some(game_id_str) => match game_id_str.parse::<u32>() {
+                Ok(id) => id,
+                Err(_) => {
+                    writeln!(err_writer, "Invalid game id: {}", game_id_str).unwrap();
+                    return;
+                }
+            },
+            None => {
+                let name = matches.get_one::<String>("game_name").unwrap();
+                match plugins::resolve_game_name(app_context, name, err_writer).await {
+                    Some(id) => id,
+                    None => return,
+                }
+            }
+        };
+
+        let GameAchievements { game_name, achievements } = match app_context.api.get_game_achievements(game_id).await {
+            Ok(resp) => resp,
+            Err(e) => {
+                writeln!(err_writer, "Error while trying to get achievements: {}", ui::scrub_api_key(&e.to_string())).unwrap();
+                return;
+            }
+        };
+
+        writeln!(writer, "{}", game_name).unwrap();
+
+        match app_context.api.get_global_achievements(game_id).await {
+            Ok(global_achievements) => {
+                for (achievement, percent) in rarest_unlocked(achievements, &global_achievements)
+                    .into_iter()
+                    .take(count)
+                {
+                    match percent {
+                        Some(percent) => writeln!(writer, "{} {}%", achievement.name, percent).unwrap(),
+                        None => writeln!(writer, "{}", achievement.name).unwrap(),
+                    }
+                }
+            }
+            Err(e) => {
+                plugins::notice(
+                    app_context,
+                    err_writer,
+                    &format!(
+                        "Warning: could not get global achievements, listing unlocked achievements without rarity: {}",
+                        ui::scrub_api_key(&e.to_string())
+                    ),
+                );
+
+                for achievement in achievements.into_iter().filter(|a| a.is_unlocked()).take(count) {
+                    writeln!(writer, "{}", achievement.name).unwrap();
+                }
+            }
+        }
+    }
+}
+
+// Ranks the unlocked achievements by their global unlock percentage.
+//
+// <purpose-start>
+// Joins the player's achievements with the global statistics on the achievement API name and
+// orders the unlocked ones from the rarest to the most common. Achievements missing from the
+// global statistics are kept, but placed last, as their rarity is unknown.
+// <purpose-end>
+//
+// <inputs-start>
+// - `achievements`: The player's achievements for a game.
+// - `global_achievements`: The global unlock percentages for the same game.
+// <inputs-end>
+//
+// <outputs-start>
+// - `Vec<(Achievement, Option<f32>)>`: The unlocked achievements paired with their global percentage, rarest first.
+// <outputs-end>
+//
+// <side-effects-start>
+// - None.
+// <side-effects-end>
+fn rarest_unlocked(
+    achievements: Vec<Achievement>,
+    global_achievements: &[GlobalAchievement],
+) -> Vec<(Achievement, Option<f32>)> {
+    let percentages: HashMap<&str, f32> = global_achievements
+        .iter()
+        .map(|g| (g.name.as_str(), g.percent))
+        .collect();
+
+    let mut ranked: Vec<(Achievement, Option<f32>)> = achievements
+        .into_iter()
+        .filter(|a| a.is_unlocked())
+        .map(|a| {
+            let percent = percentages.get(a.apiname.as_str()).copied();
+            (a, percent)
+        })
+        .collect();
+
+    ranked.sort_by(|(_, a), (_, b)| match (a, b) {
+        (Some(a), Some(b)) => a.total_cmp(b),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => std::cmp::Ordering::Equal,
+    });
+
+    ranked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::AppContext;
+    use crate::fake_api::FakeApi;
+    use crate::steam_api::Api;
+    use clap::ArgMatches;
+
+    fn create_mock_achievement(apiname: &str, name: &str, achieved: u8) -> Achievement {
+        Achievement {
+            apiname: apiname.to_string(),
+            name: name.to_string(),
+            description: "Test Description".to_string(),
+            achieved,
+            unlocktime: 0,
+        }
+    }
+
+    fn create_mock_global_achievement(name: &str, percent: f32) -> GlobalAchievement {
+        GlobalAchievement {
+            name: name.to_string(),
+            percent,
+        }
+    }
+
+    async fn setup_test_env(
+        game_ach_body: &str, game_ach_status: u16,
+        global_ach_body: &str, global_ach_status: u16
+    ) -> (AppContext, mockito::ServerGuard) {
+        let mut server = mockito::Server::new_async().await;
+
+        server.mock("GET", "/ISteamUserStats/GetPlayerAchievements/v0001/?appid=123&key=test_key&steamid=test_id&l=en")
+            .with_status(game_ach_status as usize)
+            .with_header("content-type", "application/json")
+            .with_body(game_ach_body)
+            .create_async().await;
+
+        server.mock("GET", "/ISteamUserStats/GetGlobalAchievementPercentagesForApp/v0002/?gameid=123&format=json&l=en")
+            .with_status(global_ach_status as usize)
+            .with_header("content-type", "application/json")
+            .with_body(global_ach_body)
+            .create_async().await;
+
+        let api = Api::new("test_key".to_string(), "test_id".to_string(), server.url());
+        let app_context = AppContext::with_api(api);
+        (app_context, server)
+    }
+
+    fn player_body(achievements: &[Achievement]) -> String {
+        serde_json::to_string(&serde_json::json!({
+            "playerstats": {
+                "steamID": "test_id",
+                "gameName": "Test Game",
+                "achievements": achievements,
+                "success": true
+            }
+        })).unwrap()
+    }
+
+    fn global_body(global_achievements: &[GlobalAchievement]) -> String {
+        serde_json::to_string(&serde_json::json!({
+            "achievementpercentages": { "achievements": global_achievements }
+        })).unwrap()
+    }
+
+    fn get_matches_for_args(args: &[&str]) -> ArgMatches {
+        TopPlugin.command().get_matches_from(args)
+    }
+
+    #[test]
+    fn test_command() {
+        let plugin = TopPlugin;
+        let cmd = plugin.command();
+        assert_eq!(cmd.get_name(), "top");
+        assert!(cmd.get_about().is_some());
+        assert!(cmd.get_arguments().any(|arg| arg.get_id() == "game_id"));
+        assert!(cmd.get_arguments().any(|arg| arg.get_id() == "count"));
+    }
+
+    #[test]
+    fn test_rarest_unlocked_ordering() {
+        let achievements = vec![
+            create_mock_achievement("common", "Common", 1),
+            create_mock_achievement("locked", "Locked", 0),
+            create_mock_achievement("rare", "Rare", 1),
+            create_mock_achievement("unknown", "Unknown", 1),
+            create_mock_achievement("uncommon", "Uncommon", 1),
+        ];
+        let global_achievements = vec![
+            create_mock_global_achievement("common", 80.0),
+            create_mock_global_achievement("locked", 0.5),
+            create_mock_global_achievement("rare", 2.5),
+            create_mock_global_achievement("uncommon", 30.0),
+        ];
+
+        let ranked = rarest_unlocked(achievements, &global_achievements);
+        let names: Vec<&str> = ranked.iter().map(|(a, _)| a.name.as_str()).collect();
+
+        assert_eq!(names, vec!["Rare", "Uncommon", "Common", "Unknown"]);
+        assert_eq!(ranked[0].1, Some(2.5));
+        assert_eq!(ranked[3].1, None);
+    }
+
+    #[tokio::test]
+    async fn test_execute_success() {
+        let achievements = vec![
+            create_mock_achievement("ach1", "Common Achievement", 1),
+            create_mock_achievement("ach2", "Rare Achievement", 1),
+            create_mock_achievement("ach3", "Locked Achievement", 0),
+        ];
+        let global_achievements = vec![
+            create_mock_global_achievement("ach1", 75.5),
+            create_mock_global_achievement("ach2", 1.5),
+            create_mock_global_achievement("ach3", 0.1),
+        ];
+        let (app_context, _server) = setup_test_env(
+            &player_body(&achievements), 200,
+            &global_body(&global_achievements), 200,
+        ).await;
+        let matches = get_matches_for_args(&["top", "123"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        TopPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        assert_eq!(output, "Test Game\nRare Achievement 1.5%\nCommon Achievement 75.5%\n");
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_count() {
+        let achievements = vec![
+            create_mock_achievement("ach1", "Common Achievement", 1),
+            create_mock_achievement("ach2", "Rare Achievement", 1),
+        ];
+        let global_achievements = vec![
+            create_mock_global_achievement("ach1", 75.5),
+            create_mock_global_achievement("ach2", 1.5),
+        ];
+        let (app_context, _server) = setup_test_env(
+            &player_body(&achievements), 200,
+            &global_body(&global_achievements), 200,
+        ).await;
+        let matches = get_matches_for_args(&["top", "123", "--count", "1"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        TopPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        assert!(output.contains("Rare Achievement 1.5%"));
+        assert!(!output.contains("Common Achievement"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_global_api_error_falls_back() {
+        let achievements = vec![
+            create_mock_achievement("ach1", "Unlocked Achievement", 1),
+            create_mock_achievement("ach2", "Locked Achievement", 0),
+        ];
+        let (app_context, _server) = setup_test_env(&player_body(&achievements), 200, "", 500).await;
+        let matches = get_matches_for_args(&["top", "123"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        TopPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let err_output = String::from_utf8(err_writer).unwrap();
+        assert!(err_output.contains("Warning: could not get global achievements"));
+
+        let output = String::from_utf8(writer).unwrap();
+        assert!(output.contains("Unlocked Achievement"));
+        assert!(!output.lines().any(|l| l == "Locked Achievement"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_invalid_game_id() {
+        let (app_context, _server) = setup_test_env("", 200, "", 200).await;
+        let matches = get_matches_for_args(&["top", "invalid"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        TopPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(err_writer).unwrap();
+        assert_eq!(output.trim(), "Invalid game id: invalid");
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_game_name() {
+        let api = FakeApi::new()
+            .with_game(123, "Test Game")
+            .with_game(456, "Other Game")
+            .with_achievements(123, "Test Game", vec![create_mock_achievement("ach1", "Rare Achievement", 1)])
+            .with_global_achievements(123, vec![create_mock_global_achievement("ach1", 1.5)]);
+        let app_context = AppContext::with_api(api);
+        let matches = get_matches_for_args(&["top", "--game-name", "test game"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        TopPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(writer).unwrap();
+        assert_eq!(output, "Test Game\nRare Achievement 1.5%\n");
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_ambiguous_game_name() {
+        let app_context = AppContext::with_api(FakeApi::new().with_game(123, "Test Game").with_game(456, "Other Game"));
+        let matches = get_matches_for_args(&["top", "--game-name", "game"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        TopPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        assert!(writer.is_empty());
+        let output = String::from_utf8(err_writer).unwrap();
+        assert_eq!(output.trim(), "Multiple games match 'game': Test Game (123), Other Game (456)");
+    }
+
+    #[tokio::test]
+    async fn test_execute_quiet_suppresses_warning() {
+        let achievements = vec![create_mock_achievement("ach1", "Unlocked Achievement", 1)];
+        let (app_context, _server) = setup_test_env(&player_body(&achievements), 200, "", 500).await;
+        let app_context = app_context.with_quiet(true);
+        let matches = get_matches_for_args(&["top", "123"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        TopPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        assert!(err_writer.is_empty());
+        assert!(String::from_utf8(writer).unwrap().contains("Unlocked Achievement"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_quiet_keeps_errors() {
+        let (app_context, _server) = setup_test_env("", 500, "", 500).await;
+        let app_context = app_context.with_quiet(true);
+        let matches = get_matches_for_args(&["top", "123"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        TopPlugin.execute(&app_context, &matches, &mut writer, &mut err_writer).await;
+
+        let output = String::from_utf8(err_writer).unwrap();
+        assert_eq!(output.lines().count(), 1);
+        assert!(output.starts_with("Error while trying to get achievements"));
+    }
+}
@@ -0,0 +1,265 @@
+//! Plugin for checking the configuration and the connection to the Steam API.
+//!
+//! <purpose-start>
+//! This plugin provides the `verify` command, a health check meant to be run before relying on
+//! trogue in scripts. It reports the outcome of each check and exits with a non-zero code if any fails.
+//! <purpose-end>
+//!
+//! <inputs-start>
+//! - `app_context`: The shared application context, providing access to the Steam API client.
+//! - `matches`: The command-line arguments parsed by `clap` (unused in this plugin).
+//! <inputs-end>
+//!
+//! <outputs-start>
+//! - One OK/FAILED line per check printed to the console.
+//! <outputs-end>
+//!
+//! <side-effects-start>
+//! - Makes a network request to the Steam API.
+//! - Sets a non-zero process exit code if a check fails.
+//! <side-effects-end>
+
+use crate::{app::AppContext, plugins::Plugin, steam_api::ApiError, ui};
+use async_trait::async_trait;
+use clap::Command;
+use std::io::Write;
+
+pub struct VerifyPlugin;
+
+#[async_trait]
+impl Plugin for VerifyPlugin {
+    // Defines the clap command for the `verify` plugin.
+    //
+    // <purpose-start>
+    // This method provides the command-line interface for the `verify` plugin,
+    // which checks the configuration and the connection to the Steam API.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `&self`: A reference to the plugin instance.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `clap::Command`: The clap command definition for the `verify` plugin.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None.
+    // <side-effects-end>
+    fn command(&self) -> Command {
+        Command::new("verify")
+            .about("Checks the configuration and the connection to the Steam API. Exits with a non-zero code on failure")
+            .after_help(concat!(
+                "Examples:\n",
+                "  trogue verify\n",
+                "  trogue --steam-id https://steamcommunity.com/id/<name> verify",
+            ))
+    }
+
+    // Executes the `verify` plugin's logic.
+    //
+    // <purpose-start>
+    // This method is called by the core application when the `verify` command is invoked.
+    // It validates the configured credentials and makes a lightweight authenticated request,
+    // translating the failure into an actionable message.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `&self`: A reference to the plugin instance.
+    // - `app_context`: The shared application context.
+    // - `_matches`: The clap argument matches for the `verify` subcommand (unused).
+    // - `writer`: A mutable reference to a writer for standard output.
+    // - `_err_writer`: A mutable reference to a writer for standard error (unused).
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - None.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - Makes a network request to the Steam API.
+    // - Writes the check results to the provided writer.
+    // - Sets the process exit code to 1 if a check fails.
+    // <side-effects-end>
+    async fn execute(
+        &self,
+        app_context: &AppContext,
+        _matches: &clap::ArgMatches,
+        writer: &mut (dyn Write + Send),
+        _err_writer: &mut (dyn Write + Send),
+    ) {
+        if let Err(reason) = check_config(app_context) {
+            writeln!(writer, "Configuration: FAILED - {}", reason).unwrap();
+            writeln!(writer, "Steam API: SKIPPED").unwrap();
+            app_context.set_exit_code(1);
+            return;
+        }
+        writeln!(writer, "Configuration: OK").unwrap();
+
+        match app_context.api.get_games_count().await {
+            Ok(count) => writeln!(writer, "Steam API: OK ({} games)", count).unwrap(),
+            Err(e) => {
+                writeln!(writer, "Steam API: FAILED - {}", describe_api_error(&e)).unwrap();
+                app_context.set_exit_code(1);
+            }
+        }
+    }
+}
+
+// Checks that the configured credentials are plausible.
+//
+// <purpose-start>
+// Catches obviously wrong values, such as a vanity name instead of a numeric Steam ID, before
+// any request is made, as Steam's responses to them are not very helpful.
+// <purpose-end>
+//
+// <inputs-start>
+// - `app_context`: The shared application context.
+// <inputs-end>
+//
+// <outputs-start>
+// - `Ok(())` if the configuration looks valid.
+// - `Err(String)`: The reason the configuration is invalid.
+// <outputs-end>
+//
+// <side-effects-start>
+// - None.
+// <side-effects-end>
+fn check_config(app_context: &AppContext) -> Result<(), String> {
+    if !app_context.api.has_api_key() {
+        return Err("TROGUE_STEAM_API_KEY is empty".to_string());
+    }
+
+    let steam_id = app_context.api.steam_id();
+    if steam_id.len() != 17 || !steam_id.chars().all(|c| c.is_ascii_digit()) {
+        return Err(format!("TROGUE_STEAM_ID '{}' is not a 17-digit SteamID64", steam_id));
+    }
+
+    Ok(())
+}
+
+// Translates an API error into an actionable message.
+//
+// <inputs-start>
+// - `error`: The error returned by the Steam API client.
+// <inputs-end>
+//
+// <outputs-start>
+// - `String`: The message explaining the failure, with the API key redacted.
+// <outputs-end>
+fn describe_api_error(error: &ApiError) -> String {
+    let message = match error {
+        ApiError::Unauthorized(status) => format!(
+            "bad API key (HTTP {}), check TROGUE_STEAM_API_KEY",
+            status.as_u16()
+        ),
+        ApiError::ProfilePrivate => {
+            "private profile, set 'Game details' to public in the Steam privacy settings".to_string()
+        }
+        ApiError::Network(e) => format!("network error, check your connection: {}", e),
+        _ => error.to_string(),
+    };
+
+    ui::scrub_api_key(&message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::AppContext;
+    use crate::steam_api::Api;
+    use clap::ArgMatches;
+
+    const STEAM_ID: &str = "76561197960287930";
+
+    async fn setup_test_env(body: &str, status_code: u16) -> (AppContext, mockito::ServerGuard) {
+        let mut server = mockito::Server::new_async().await;
+        let path = format!("/IPlayerService/GetOwnedGames/v0001/?key=test_key&steamid={}&format=json", STEAM_ID);
+        server.mock("GET", path.as_str())
+            .with_status(status_code as usize)
+            .with_header("content-type", "application/json")
+            .with_body(body)
+            .create_async().await;
+
+        let api = Api::new("test_key".to_string(), STEAM_ID.to_string(), server.url());
+        let app_context = AppContext::with_api(api);
+        (app_context, server)
+    }
+
+    fn get_matches_for_args(args: &[&str]) -> ArgMatches {
+        VerifyPlugin.command().get_matches_from(args)
+    }
+
+    async fn run(app_context: &AppContext) -> String {
+        let matches = get_matches_for_args(&["verify"]);
+        let mut writer = Vec::new();
+        let mut err_writer = Vec::new();
+
+        VerifyPlugin.execute(app_context, &matches, &mut writer, &mut err_writer).await;
+
+        String::from_utf8(writer).unwrap()
+    }
+
+    #[test]
+    fn test_command() {
+        let plugin = VerifyPlugin;
+        let cmd = plugin.command();
+        assert_eq!(cmd.get_name(), "verify");
+        assert!(cmd.get_about().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_execute_success() {
+        let (app_context, _server) = setup_test_env(r#"{"response":{"game_count":42}}"#, 200).await;
+
+        let output = run(&app_context).await;
+
+        assert_eq!(output, "Configuration: OK\nSteam API: OK (42 games)\n");
+        assert_eq!(app_context.exit_code(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_execute_bad_api_key() {
+        let (app_context, _server) = setup_test_env("", 401).await;
+
+        let output = run(&app_context).await;
+
+        assert!(output.contains("Steam API: FAILED - bad API key (HTTP 401)"));
+        assert_eq!(app_context.exit_code(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_execute_private_profile() {
+        let (app_context, _server) = setup_test_env(r#"{"response":{}}"#, 200).await;
+
+        let output = run(&app_context).await;
+
+        assert!(output.contains("Steam API: FAILED - private profile"));
+        assert_eq!(app_context.exit_code(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_execute_network_error() {
+        // Nothing listens on port 1, so the connection is refused.
+        let api = Api::new("test_key".to_string(), STEAM_ID.to_string(), "http://127.0.0.1:1".to_string());
+        let app_context = AppContext::with_api(api);
+
+        let output = run(&app_context).await;
+
+        assert!(output.contains("Steam API: FAILED - network error"));
+        assert!(!output.contains("test_key"));
+        assert_eq!(app_context.exit_code(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_execute_invalid_steam_id() {
+        let api = Api::new("test_key".to_string(), "my_vanity_name".to_string(), "http://127.0.0.1:1".to_string());
+        let app_context = AppContext::with_api(api);
+
+        let output = run(&app_context).await;
+
+        assert!(output.contains("Configuration: FAILED - TROGUE_STEAM_ID 'my_vanity_name' is not a 17-digit SteamID64"));
+        assert!(output.contains("Steam API: SKIPPED"));
+        assert_eq!(app_context.exit_code(), 1);
+    }
+}
@@ -0,0 +1,209 @@
+use crate::steam_api::Achievement;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+// The achievement state of a single game at the time a snapshot was taken.
+//
+// <purpose-start>
+// Snapshots are JSON arrays of these records. Keeping the format in one place lets commands
+// that write snapshots and commands that compare them agree on the same structure.
+// <purpose-end>
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GameSnapshot {
+    pub appid: u32,
+    pub name: String,
+    pub achievements: Vec<Achievement>,
+}
+
+// The serialized form of a game in a written snapshot.
+//
+// <purpose-start>
+// Borrows the fetched data instead of copying it, and allows achievement descriptions to be left
+// out. Serializes to the same structure as `GameSnapshot`, so that written snapshots can be read back.
+// <purpose-end>
+#[derive(Serialize, Debug)]
+pub struct SnapshotGame<'a> {
+    pub appid: u32,
+    pub name: &'a str,
+    pub achievements: Vec<SnapshotAchievement<'a>>,
+}
+
+// The serialized form of an achievement in a written snapshot.
+#[derive(Serialize, Debug)]
+pub struct SnapshotAchievement<'a> {
+    pub apiname: &'a str,
+    pub achieved: u8,
+    pub unlocktime: u64,
+    pub name: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<&'a str>,
+}
+
+impl<'a> SnapshotGame<'a> {
+    // Creates the serialized form of a game.
+    //
+    // <inputs-start>
+    // - `appid`: The ID of the game.
+    // - `name`: The name of the game.
+    // - `achievements`: The user's achievements for the game.
+    // - `include_descriptions`: Whether achievement descriptions are written.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `SnapshotGame`: The game, borrowing the given data.
+    // <outputs-end>
+    pub fn new(appid: u32, name: &'a str, achievements: &'a [Achievement], include_descriptions: bool) -> SnapshotGame<'a> {
+        SnapshotGame {
+            appid,
+            name,
+            achievements: achievements
+                .iter()
+                .map(|a| SnapshotAchievement {
+                    apiname: &a.apiname,
+                    achieved: a.achieved,
+                    unlocktime: a.unlocktime,
+                    name: &a.name,
+                    description: include_descriptions.then_some(a.description.as_str()),
+                })
+                .collect(),
+        }
+    }
+}
+
+// Returns the achievements unlocked since an earlier state of the same game.
+//
+// <purpose-start>
+// Achievements are matched by API name, as display names may be localized differently in each state.
+// <purpose-end>
+//
+// <inputs-start>
+// - `previous`: The achievements of the game in the earlier state.
+// - `current`: The achievements of the game in the later state.
+// <inputs-end>
+//
+// <outputs-start>
+// - `Vec<&Achievement>`: The achievements unlocked in `current` but not in `previous`, in the order of `current`.
+// <outputs-end>
+pub fn new_unlocks<'a>(previous: &[Achievement], current: &'a [Achievement]) -> Vec<&'a Achievement> {
+    let previously_unlocked: HashSet<&str> = previous
+        .iter()
+        .filter(|a| a.is_unlocked())
+        .map(|a| a.apiname.as_str())
+        .collect();
+
+    current
+        .iter()
+        .filter(|a| a.is_unlocked() && !previously_unlocked.contains(a.apiname.as_str()))
+        .collect()
+}
+
+// Reads a snapshot from a JSON file.
+//
+// <purpose-start>
+// This function loads the games and achievements stored in a snapshot file.
+// <purpose-end>
+//
+// <inputs-start>
+// - `path`: The path of the snapshot file.
+// <inputs-end>
+//
+// <outputs-start>
+// - `Ok(Vec<GameSnapshot>)`: The games stored in the snapshot.
+// - `Err(String)`: A user-facing message if the file cannot be read or is not a valid snapshot.
+// <outputs-end>
+//
+// <side-effects-start>
+// - **Reads a file**: Reads the snapshot file.
+// <side-effects-end>
+pub fn read_snapshot(path: &Path) -> Result<Vec<GameSnapshot>, String> {
+    let contents = fs::read_to_string(path)
+        .map_err(|e| format!("Could not read snapshot {}: {}", path.display(), e))?;
+
+    serde_json::from_str(&contents)
+        .map_err(|e| format!("Invalid snapshot {}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        env::temp_dir().join(format!("trogue-snapshot-test-{}-{}.json", std::process::id(), name))
+    }
+
+    #[test]
+    fn test_read_snapshot() {
+        let path = temp_path("valid");
+        fs::write(
+            &path,
+            r#"[{"appid":123,"name":"Test Game","achievements":[
+                {"apiname":"a1","achieved":1,"unlocktime":1672531200,"name":"First","description":""}
+            ]}]"#,
+        )
+        .unwrap();
+
+        let snapshot = read_snapshot(&path).unwrap();
+
+        assert_eq!(snapshot.len(), 1);
+        assert_eq!(snapshot[0].appid, 123);
+        assert_eq!(snapshot[0].achievements[0].name, "First");
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_snapshot_game_round_trip() {
+        let achievements = vec![Achievement {
+            apiname: "a1".to_string(),
+            achieved: 1,
+            unlocktime: 1672531200,
+            name: "First".to_string(),
+            description: "Do something".to_string(),
+        }];
+
+        for include_descriptions in [true, false] {
+            let json = serde_json::to_string(&[SnapshotGame::new(123, "Test Game", &achievements, include_descriptions)]).unwrap();
+            let snapshot: Vec<GameSnapshot> = serde_json::from_str(&json).unwrap();
+
+            assert_eq!(json.contains("description"), include_descriptions);
+            assert_eq!(snapshot[0].appid, 123);
+            assert_eq!(snapshot[0].achievements[0].name, "First");
+            assert_eq!(snapshot[0].achievements[0].description, if include_descriptions { "Do something" } else { "" });
+        }
+    }
+
+    #[test]
+    fn test_new_unlocks() {
+        let achievement = |apiname: &str, achieved: u8| Achievement {
+            apiname: apiname.to_string(),
+            achieved,
+            unlocktime: 0,
+            name: apiname.to_string(),
+            description: "".to_string(),
+        };
+        let previous = vec![achievement("a1", 1), achievement("a2", 0), achievement("a3", 0)];
+        let current = vec![achievement("a1", 1), achievement("a2", 1), achievement("a3", 0), achievement("a4", 1)];
+
+        let apinames: Vec<&str> = new_unlocks(&previous, &current).iter().map(|a| a.apiname.as_str()).collect();
+
+        assert_eq!(apinames, vec!["a2", "a4"]);
+        assert!(new_unlocks(&current, &current).is_empty());
+    }
+
+    #[test]
+    fn test_read_snapshot_errors() {
+        let missing = read_snapshot(Path::new("/nonexistent/trogue/snapshot.json"));
+        assert!(missing.unwrap_err().starts_with("Could not read snapshot"));
+
+        let path = temp_path("invalid");
+        fs::write(&path, "not json").unwrap();
+
+        let invalid = read_snapshot(&path);
+        assert!(invalid.unwrap_err().starts_with("Invalid snapshot"));
+
+        fs::remove_file(&path).unwrap();
+    }
+}
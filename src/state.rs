@@ -0,0 +1,461 @@
+use crate::snapshot::{GameSnapshot, SnapshotGame};
+use crate::steam_api::Achievement;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+// How long a resolved vanity name is reused before it is resolved again, 30 days.
+pub const VANITY_ID_TTL_SECS: i64 = 30 * 24 * 60 * 60;
+
+// Returns the state directory of trogue, `$XDG_STATE_HOME/trogue` or `~/.local/state/trogue`.
+fn state_dir() -> Option<PathBuf> {
+    let state_dir = match env::var("XDG_STATE_HOME") {
+        Ok(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(env::var("HOME").ok()?).join(".local").join("state"),
+    };
+
+    Some(state_dir.join("trogue"))
+}
+
+// Persists the id of the last game the user worked with.
+//
+// <purpose-start>
+// Users tend to run `progress` and `achievements` repeatedly for the game they are currently
+// playing. Remembering the last game id lets them omit it on subsequent runs.
+// The file location is injectable so that tests never touch the user's real state directory.
+// <purpose-end>
+pub struct LastGameStore {
+    path: PathBuf,
+}
+
+impl LastGameStore {
+    // Creates a store backed by the given file.
+    //
+    // <purpose-start>
+    // This function initializes a `LastGameStore` for an explicit file path.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `path`: The file in which the last game id is stored.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `LastGameStore`: A new store instance.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None.
+    // <side-effects-end>
+    pub fn new(path: PathBuf) -> LastGameStore {
+        LastGameStore { path }
+    }
+
+    // Returns the default location of the last game file.
+    //
+    // <purpose-start>
+    // Follows the XDG base directory convention, using `$XDG_STATE_HOME/trogue/last_game`
+    // and falling back to `~/.local/state/trogue/last_game`.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - None.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `Some(PathBuf)`: The default file path.
+    // - `None`: If neither `XDG_STATE_HOME` nor `HOME` is set.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - **Reads environment variables**: Reads `XDG_STATE_HOME` and `HOME`.
+    // <side-effects-end>
+    pub fn default_path() -> Option<PathBuf> {
+        Some(state_dir()?.join("last_game"))
+    }
+
+    // Reads the stored game id.
+    //
+    // <purpose-start>
+    // This function returns the previously saved game id, if any.
+    // A missing or malformed file is treated as "no stored id".
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - None.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `Some(u32)`: The stored game id.
+    // - `None`: If no valid game id has been stored.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - **Reads a file**: Reads the last game file.
+    // <side-effects-end>
+    pub fn load(&self) -> Option<u32> {
+        fs::read_to_string(&self.path).ok()?.trim().parse().ok()
+    }
+
+    // Stores the given game id.
+    //
+    // <purpose-start>
+    // This function records the game id so it can be reused by later invocations.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `appid`: The game id to store.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `Ok(())` if the id was stored.
+    // - `Err(io::Error)` if the file or its parent directory could not be written.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - **Writes a file**: Creates the parent directory if needed and overwrites the last game file.
+    // <side-effects-end>
+    pub fn save(&self, appid: u32) -> io::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        fs::write(&self.path, appid.to_string())
+    }
+}
+
+// Persists the unlock state of each game seen by `progress` and `achievements`.
+//
+// <purpose-start>
+// Each game is stored in its own `<appid>.json` file, in the format of a game of a snapshot, so
+// that `--changes` can list the achievements unlocked since the previous run without the user
+// exporting snapshots. The directory is injectable so that tests never touch the user's real data directory.
+// <purpose-end>
+pub struct UnlockHistoryStore {
+    dir: PathBuf,
+}
+
+impl UnlockHistoryStore {
+    // Creates a store backed by the given directory.
+    //
+    // <purpose-start>
+    // This function initializes an `UnlockHistoryStore` for an explicit directory.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `dir`: The directory in which the game files are stored.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `UnlockHistoryStore`: A new store instance.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None.
+    // <side-effects-end>
+    pub fn new(dir: PathBuf) -> UnlockHistoryStore {
+        UnlockHistoryStore { dir }
+    }
+
+    // Returns the default location of the game files.
+    //
+    // <purpose-start>
+    // Follows the XDG base directory convention, using `$XDG_DATA_HOME/trogue/snapshots`
+    // and falling back to `~/.local/share/trogue/snapshots`.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - None.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `Some(PathBuf)`: The default directory.
+    // - `None`: If neither `XDG_DATA_HOME` nor `HOME` is set.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - **Reads environment variables**: Reads `XDG_DATA_HOME` and `HOME`.
+    // <side-effects-end>
+    pub fn default_path() -> Option<PathBuf> {
+        let data_dir = match env::var("XDG_DATA_HOME") {
+            Ok(dir) if !dir.is_empty() => PathBuf::from(dir),
+            _ => PathBuf::from(env::var("HOME").ok()?).join(".local").join("share"),
+        };
+
+        Some(data_dir.join("trogue").join("snapshots"))
+    }
+
+    // Returns the file storing the unlock state of a game.
+    fn path(&self, appid: u32) -> PathBuf {
+        self.dir.join(format!("{}.json", appid))
+    }
+
+    // Reads the stored unlock state of a game.
+    //
+    // <purpose-start>
+    // This function returns the state recorded by the previous run, if any.
+    // A missing or malformed file is treated as "no stored state".
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `appid`: The ID of the game.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `Some(GameSnapshot)`: The stored state of the game.
+    // - `None`: If no valid state has been stored for the game.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - **Reads a file**: Reads the file of the game.
+    // <side-effects-end>
+    pub fn load(&self, appid: u32) -> Option<GameSnapshot> {
+        serde_json::from_str(&fs::read_to_string(self.path(appid)).ok()?).ok()
+    }
+
+    // Stores the unlock state of a game.
+    //
+    // <purpose-start>
+    // This function replaces the state of the game, so that the next run compares against it.
+    // Descriptions are left out, as only unlocks are compared.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `appid`: The ID of the game.
+    // - `name`: The name of the game.
+    // - `achievements`: The user's achievements for the game.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `Ok(())` if the state was stored.
+    // - `Err(io::Error)` if the file or its directory could not be written.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - **Writes a file**: Creates the directory if needed and overwrites the file of the game.
+    // <side-effects-end>
+    pub fn save(&self, appid: u32, name: &str, achievements: &[Achievement]) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let json = serde_json::to_string(&SnapshotGame::new(appid, name, achievements, false))?;
+        fs::write(self.path(appid), json)
+    }
+}
+
+// A vanity name resolved to a SteamID64, as stored by `VanityIdStore`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct ResolvedVanity {
+    steamid: String,
+    // Unix timestamp of the resolution
+    resolved_at: i64,
+}
+
+// Persists the SteamID64 each vanity name was resolved to.
+//
+// <purpose-start>
+// A Steam ID configured as a vanity name is resolved by every command reading player data. Reusing the result
+// saves a request per run and keeps away from the rate limits. Vanity names rarely change hands,
+// so entries are reused for `VANITY_ID_TTL_SECS`. The file location is injectable so that tests
+// never touch the user's real state directory.
+// <purpose-end>
+pub struct VanityIdStore {
+    path: PathBuf,
+}
+
+impl VanityIdStore {
+    // Creates a store backed by the given file.
+    //
+    // <purpose-start>
+    // This function initializes a `VanityIdStore` for an explicit file path.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `path`: The file in which the resolved vanity names are stored.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `VanityIdStore`: A new store instance.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None.
+    // <side-effects-end>
+    pub fn new(path: PathBuf) -> VanityIdStore {
+        VanityIdStore { path }
+    }
+
+    // Returns the default location of the resolved vanity names file.
+    //
+    // <purpose-start>
+    // Follows the XDG base directory convention, using `$XDG_STATE_HOME/trogue/vanity_ids.json`
+    // and falling back to `~/.local/state/trogue/vanity_ids.json`.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - None.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `Some(PathBuf)`: The default file path.
+    // - `None`: If neither `XDG_STATE_HOME` nor `HOME` is set.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - **Reads environment variables**: Reads `XDG_STATE_HOME` and `HOME`.
+    // <side-effects-end>
+    pub fn default_path() -> Option<PathBuf> {
+        Some(state_dir()?.join("vanity_ids.json"))
+    }
+
+    // Reads every stored vanity name, a missing or malformed file holding none.
+    fn read_all(&self) -> HashMap<String, ResolvedVanity> {
+        fs::read_to_string(&self.path)
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    // Reads the SteamID64 a vanity name was resolved to.
+    //
+    // <purpose-start>
+    // This function returns the stored id unless it is older than `VANITY_ID_TTL_SECS`.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `vanity`: The vanity name.
+    // - `now`: The current Unix timestamp.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `Some(String)`: The stored SteamID64.
+    // - `None`: If the vanity name was never resolved or its entry has expired.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - **Reads a file**: Reads the resolved vanity names file.
+    // <side-effects-end>
+    pub fn load(&self, vanity: &str, now: i64) -> Option<String> {
+        self.read_all()
+            .remove(vanity)
+            .filter(|resolved| now - resolved.resolved_at < VANITY_ID_TTL_SECS)
+            .map(|resolved| resolved.steamid)
+    }
+
+    // Stores the SteamID64 a vanity name was resolved to.
+    //
+    // <purpose-start>
+    // This function adds or replaces the entry of the vanity name, keeping the other entries.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `vanity`: The vanity name.
+    // - `steam_id`: The SteamID64 it was resolved to.
+    // - `now`: The current Unix timestamp.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `Ok(())` if the entry was stored.
+    // - `Err(io::Error)` if the file or its parent directory could not be written.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - **Writes a file**: Creates the parent directory if needed and overwrites the resolved vanity names file.
+    // <side-effects-end>
+    pub fn save(&self, vanity: &str, steam_id: &str, now: i64) -> io::Result<()> {
+        let mut entries = self.read_all();
+        entries.insert(vanity.to_string(), ResolvedVanity { steamid: steam_id.to_string(), resolved_at: now });
+
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        fs::write(&self.path, serde_json::to_string(&entries)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        env::temp_dir()
+            .join(format!("trogue-state-test-{}-{}", std::process::id(), name))
+            .join("last_game")
+    }
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let path = temp_path("round_trip");
+        let store = LastGameStore::new(path.clone());
+
+        store.save(440).unwrap();
+        assert_eq!(store.load(), Some(440));
+
+        store.save(570).unwrap();
+        assert_eq!(store.load(), Some(570));
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_load_without_stored_id() {
+        let store = LastGameStore::new(temp_path("missing"));
+        assert_eq!(store.load(), None);
+    }
+
+    #[test]
+    fn test_unlock_history_round_trip() {
+        let dir = temp_path("history").with_file_name("snapshots");
+        let store = UnlockHistoryStore::new(dir.clone());
+        let achievements = vec![Achievement {
+            apiname: "a1".to_string(),
+            achieved: 1,
+            unlocktime: 1672531200,
+            name: "First".to_string(),
+            description: "Do something".to_string(),
+        }];
+
+        assert_eq!(store.load(440), None);
+        store.save(440, "Team Fortress 2", &achievements).unwrap();
+
+        let snapshot = store.load(440).unwrap();
+        assert_eq!(snapshot.appid, 440);
+        assert_eq!(snapshot.name, "Team Fortress 2");
+        assert_eq!(snapshot.achievements[0].apiname, "a1");
+        assert!(snapshot.achievements[0].is_unlocked());
+        assert!(dir.join("440.json").exists());
+        assert_eq!(store.load(570), None);
+
+        fs::remove_dir_all(dir.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_vanity_id_round_trip() {
+        let path = temp_path("vanity").with_file_name("vanity_ids.json");
+        let store = VanityIdStore::new(path.clone());
+
+        assert_eq!(store.load("gabe", 1000), None);
+        store.save("gabe", "76561197960287930", 1000).unwrap();
+        store.save("other", "76561197960287931", 1000).unwrap();
+
+        assert_eq!(store.load("gabe", 1000).as_deref(), Some("76561197960287930"));
+        assert_eq!(store.load("other", 2000).as_deref(), Some("76561197960287931"));
+        assert_eq!(store.load("nobody", 1000), None);
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_vanity_id_expires() {
+        let path = temp_path("vanity_expiry").with_file_name("vanity_ids.json");
+        let store = VanityIdStore::new(path.clone());
+
+        store.save("gabe", "76561197960287930", 1000).unwrap();
+
+        assert!(store.load("gabe", 1000 + VANITY_ID_TTL_SECS - 1).is_some());
+        assert_eq!(store.load("gabe", 1000 + VANITY_ID_TTL_SECS), None);
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
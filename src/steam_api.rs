@@ -1,7 +1,84 @@
-use crate::constants;
-use reqwest;
-use serde::{Deserialize, Serialize};
-use tokio;
+use crate::{constants, state::VanityIdStore, ui};
+use async_trait::async_trait;
+use chrono::{DateTime, TimeZone, Utc};
+use futures::stream::{self, Stream, StreamExt};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::fmt;
+use std::sync::Mutex;
+use tokio::sync::OnceCell;
+use std::time::{Duration, Instant};
+
+// The delay before the first retry of a failed request, growing linearly with each attempt.
+const RETRY_DELAY: Duration = Duration::from_millis(250);
+
+// The number of characters of an unexpected response body quoted in errors.
+const SNIPPET_LENGTH: usize = 120;
+
+// Represents an error returned by the Steam API client.
+//
+// <purpose-start>
+// Callers need to react differently to a rejected API key, a private profile or a network
+// outage, which a plain transport error does not make easy. This enum classifies the failures
+// and renders them with messages that are safe to show to the user: the wrapped transport
+// errors never carry the request URL, as it contains the API key.
+// <purpose-end>
+#[derive(Debug)]
+pub enum ApiError {
+    // The request could not be sent or the response could not be received.
+    Network(reqwest::Error),
+    // Steam rejected the API key.
+    Unauthorized(reqwest::StatusCode),
+    // The profile (or its game details) is private, so the data is not visible.
+    ProfilePrivate,
+    // Steam responded with an unexpected HTTP status.
+    Status(reqwest::StatusCode),
+    // The response body is JSON, but does not match the expected structure.
+    Decode(serde_json::Error),
+    // The response body is not JSON, such as the HTML error pages of an overloaded Steam, with the start of the body.
+    UnexpectedResponse(String),
+    // No Steam profile uses the given vanity name.
+    VanityNotFound(String),
+    // The request was planned but not sent, as `--dry-run` was given.
+    DryRun,
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiError::Network(e) => write!(f, "network error: {}", e),
+            ApiError::Unauthorized(status) => write!(f, "the Steam API key was rejected (HTTP {})", status.as_u16()),
+            ApiError::ProfilePrivate => write!(f, "the Steam profile or its game details are private"),
+            ApiError::Status(status) => write!(f, "unexpected HTTP status {}", status),
+            ApiError::Decode(e) => write!(f, "unexpected response from Steam: {}", e),
+            ApiError::UnexpectedResponse(snippet) => write!(f, "Steam responded with something other than JSON, it may be overloaded: {}", snippet),
+            ApiError::VanityNotFound(name) => write!(f, "no Steam profile found for the vanity name '{}'", name),
+            ApiError::DryRun => write!(f, "the request was not sent, as --dry-run was given"),
+        }
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+impl ApiError {
+    // Tells whether retrying the request may succeed.
+    //
+    // <inputs-start>
+    // - None.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `bool`: `true` for network errors, rate limiting and server-side failures, including error pages served with a success status.
+    // <outputs-end>
+    fn is_transient(&self) -> bool {
+        match self {
+            ApiError::Network(_) | ApiError::UnexpectedResponse(_) => true,
+            ApiError::Status(status) => {
+                status.is_server_error() || *status == reqwest::StatusCode::TOO_MANY_REQUESTS
+            }
+            _ => false,
+        }
+    }
+}
 
 // Represents the response from the GetGamesList API endpoint.
 #[derive(Serialize, Deserialize, Debug)]
@@ -9,14 +86,58 @@ struct GamesListResponse {
     response: GamesList,
 }
 
+// Represents the response from the GetOwnedGames API endpoint when only the count is requested.
+#[derive(Serialize, Deserialize, Debug)]
+struct GamesCountResponse {
+    response: GamesCount,
+}
+
+// Represents the game count in the GamesCountResponse.
+// The count is missing when the profile is private.
+#[derive(Serialize, Deserialize, Debug)]
+struct GamesCount {
+    game_count: Option<u32>,
+}
+
 // Represents the list of games in the GamesListResponse.
+// Private profiles get an empty response, and libraries without games omit the list.
 #[derive(Serialize, Deserialize, Debug)]
 struct GamesList {
-    game_count: u32,
+    game_count: Option<u32>,
+    #[serde(default)]
+    games: Vec<Game>,
+}
+
+// Represents the response from the GetRecentlyPlayedGames API endpoint.
+#[derive(Serialize, Deserialize, Debug)]
+struct RecentlyPlayedResponse {
+    response: RecentlyPlayed,
+}
+
+// Represents the list of games in the RecentlyPlayedResponse.
+// Private profiles and users without recent activity get an empty response.
+#[derive(Serialize, Deserialize, Debug)]
+struct RecentlyPlayed {
+    #[serde(default)]
     games: Vec<Game>,
 }
 
+// Represents the response from the ResolveVanityURL API endpoint.
+#[derive(Serialize, Deserialize, Debug)]
+struct VanityUrlResponse {
+    response: ResolvedVanityUrl,
+}
+
+// Represents the result in the VanityUrlResponse.
+// The Steam ID is missing when no profile matches, signalled by a `success` value other than 1.
+#[derive(Serialize, Deserialize, Debug)]
+struct ResolvedVanityUrl {
+    success: u32,
+    steamid: Option<String>,
+}
+
 // Represents a game owned by the user.
+// The recently played games endpoint omits the last played time and the disconnected playtime.
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Game {
     pub appid: u32,
@@ -27,7 +148,9 @@ pub struct Game {
     pub playtime_windows_forever: u32,
     pub playtime_mac_forever: u32,
     pub playtime_linux_forever: u32,
+    #[serde(default)]
     pub rtime_last_played: u64,
+    #[serde(default)]
     pub playtime_disconnected: u32,
 }
 
@@ -49,15 +172,187 @@ pub struct PlayerStats {
 }
 
 // Represents an achievement for a game.
+// Snapshots exported without descriptions omit the description. Steam omits the name and the
+// description when it has no translation for the requested language.
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Achievement {
     pub apiname: String,
     pub achieved: u8,
     pub unlocktime: u64,
+    #[serde(default)]
     pub name: String,
+    #[serde(default)]
     pub description: String,
 }
 
+// Represents the player's achievements for a single game.
+//
+// <purpose-start>
+// Returned by `ApiClient::get_game_achievements`, so that plugins read named fields instead of
+// destructuring a tuple, and share the progress computation instead of repeating it.
+// <purpose-end>
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GameAchievements {
+    // The name of the game, empty when the player's stats are private.
+    pub game_name: String,
+    pub achievements: Vec<Achievement>,
+}
+
+impl GameAchievements {
+    // Returns the number of achievements of the game.
+    pub fn total(&self) -> usize {
+        self.achievements.len()
+    }
+
+    // Returns the number of achievements unlocked by the player.
+    pub fn completed(&self) -> usize {
+        self.achievements.iter().filter(|a| a.is_unlocked()).count()
+    }
+
+    // Returns the share of unlocked achievements, between 0 and 100.
+    //
+    // <purpose-start>
+    // A game without achievements is at 0 rather than NaN, see `ui::completion_percentage`.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - None.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `f32`: The completion percentage.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None.
+    // <side-effects-end>
+    pub fn percentage(&self) -> f32 {
+        ui::completion_percentage(self.completed(), self.total())
+    }
+
+    // Returns the completion of the game, see `ProgressSummary`.
+    pub fn summary(&self) -> ProgressSummary {
+        ProgressSummary::new(self.game_name.clone(), self.completed(), self.total())
+    }
+}
+
+// Represents the achievement completion of a game, or of several games added up.
+//
+// <purpose-start>
+// Shared by the commands displaying progress, so that the percentage is computed and rendered the
+// same way everywhere. It displays as `66.7% (2/3)` and serializes with its four fields, the
+// percentage rounded to one decimal.
+// <purpose-end>
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct ProgressSummary {
+    pub game_name: String,
+    pub total: usize,
+    pub completed: usize,
+    #[serde(serialize_with = "ui::serialize_percentage")]
+    pub percentage: f32,
+}
+
+impl ProgressSummary {
+    // Creates a summary from the achievement counts, at 0% without achievements.
+    pub fn new(game_name: String, completed: usize, total: usize) -> ProgressSummary {
+        ProgressSummary {
+            game_name,
+            total,
+            completed,
+            percentage: ui::completion_percentage(completed, total),
+        }
+    }
+}
+
+impl fmt::Display for ProgressSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.1}% ({}/{})", self.percentage, self.completed, self.total)
+    }
+}
+
+impl Game {
+    // Returns the moment the game was last played.
+    //
+    // <purpose-start>
+    // Steam reports games that were never played with a last played time of 0. This method makes
+    // that case explicit, so that such games are not displayed as played in 1970.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - None.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `Some(DateTime<Utc>)`: The last played time of a game that was played.
+    // - `None`: If the game was never played, or the last played time is not known or out of range.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None.
+    // <side-effects-end>
+    pub fn last_played_datetime(&self) -> Option<DateTime<Utc>> {
+        if self.rtime_last_played == 0 {
+            return None;
+        }
+
+        let ts = i64::try_from(self.rtime_last_played).ok()?;
+        Utc.timestamp_opt(ts, 0).single()
+    }
+}
+
+impl Achievement {
+    // Checks whether the achievement has been unlocked by the player.
+    //
+    // <purpose-start>
+    // Steam reports the unlock status as a numeric flag. This method gives that flag a single,
+    // named interpretation instead of scattering magic comparisons across the plugins.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - None.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `bool`: `true` if the achievement is unlocked, `false` otherwise.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None.
+    // <side-effects-end>
+    pub fn is_unlocked(&self) -> bool {
+        self.achieved > 0
+    }
+
+    // Returns the moment the achievement was unlocked.
+    //
+    // <purpose-start>
+    // Steam uses a zero timestamp for achievements that were never unlocked, and occasionally for
+    // unlocked achievements whose unlock time is unknown. This method makes the "no date" case
+    // explicit so that callers do not accidentally render the 1970 epoch as a real date.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - None.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `Some(DateTime<Utc>)`: The unlock time for an unlocked achievement with a known timestamp.
+    // - `None`: If the achievement is locked or its unlock time is zero or out of range.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None.
+    // <side-effects-end>
+    pub fn unlock_datetime(&self) -> Option<DateTime<Utc>> {
+        if !self.is_unlocked() || self.unlocktime == 0 {
+            return None;
+        }
+
+        let ts = i64::try_from(self.unlocktime).ok()?;
+        Utc.timestamp_opt(ts, 0).single()
+    }
+}
+
 // Represents the response from the GetGlobalAchievementPercentagesForApp API endpoint.
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct GlobalAchievementsResponse {
@@ -77,11 +372,366 @@ pub struct GlobalAchievement {
     pub percent: f32,
 }
 
+// Represents the response from the GetSchemaForGame API endpoint.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GameSchemaResponse {
+    pub game: GameSchema,
+}
+
+// Represents the game in the GameSchemaResponse.
+// Games without stats have an empty game object.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GameSchema {
+    #[serde(rename = "availableGameStats", default)]
+    pub available_game_stats: AvailableGameStats,
+}
+
+// Represents the stats of a game in the GameSchema.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct AvailableGameStats {
+    #[serde(default)]
+    pub achievements: Vec<SchemaAchievement>,
+}
+
+// Represents the definition of an achievement in the schema of a game.
+// Unlike the player achievements, the schema describes hidden achievements, flagged by `hidden`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SchemaAchievement {
+    pub name: String,
+    #[serde(rename = "displayName", default)]
+    pub display_name: String,
+    #[serde(default)]
+    pub hidden: u8,
+    #[serde(default)]
+    pub description: String,
+}
+
+impl SchemaAchievement {
+    // Checks whether Steam hides the achievement's description until it is unlocked.
+    pub fn is_hidden(&self) -> bool {
+        self.hidden != 0
+    }
+}
+
+// The operations plugins need from a source of Steam data.
+//
+// <purpose-start>
+// Plugins depend on this trait rather than on the HTTP client, so that the application can run
+// against other sources of data, such as the bundled sample data of the offline mode.
+// <purpose-end>
+#[async_trait]
+pub trait ApiClient {
+    // Checks whether an API key has been configured.
+    fn has_api_key(&self) -> bool;
+
+    // Returns the configured Steam ID.
+    fn steam_id(&self) -> &str;
+
+    // Returns the base URL requests are sent to, `None` for sources that do not use the network.
+    fn base_url(&self) -> Option<&str> {
+        None
+    }
+
+    // Returns the URLs of the requests planned under `--dry-run`, with the API key redacted.
+    // Sources that do not use the network never plan any.
+    fn planned_requests(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    // Retrieves the list of games owned by the user.
+    async fn get_games_list(&self) -> Result<Vec<Game>, ApiError>;
+
+    // Retrieves up to `count` games the user played in the last two weeks.
+    async fn get_recently_played_games(&self, count: u32) -> Result<Vec<Game>, ApiError>;
+
+    // Retrieves the number of games owned by the user.
+    async fn get_games_count(&self) -> Result<u32, ApiError>;
+
+    // Retrieves the game name and the user's achievements for a specific game.
+    async fn get_game_achievements(&self, appid: u32) -> Result<GameAchievements, ApiError>;
+
+    // Retrieves the global unlock percentages of the achievements of a specific game.
+    async fn get_global_achievements(&self, appid: u32) -> Result<Vec<GlobalAchievement>, ApiError>;
+
+    // Retrieves the definitions of the achievements of a specific game, including hidden descriptions.
+    async fn get_game_schema(&self, appid: u32) -> Result<Vec<SchemaAchievement>, ApiError>;
+
+    // Retrieves the owned games as the JSON body of the Steam endpoint.
+    // Sources other than the Steam API render their games in the shape of the Steam response.
+    async fn get_games_list_raw(&self) -> Result<String, ApiError> {
+        let games = self.get_games_list().await?;
+        let data = GamesListResponse {
+            response: GamesList {
+                game_count: Some(games.len() as u32),
+                games,
+            },
+        };
+        Ok(serde_json::to_string(&data).expect("games are serializable"))
+    }
+
+    // Retrieves the achievements of a specific game as the JSON body of the Steam endpoint.
+    // Sources other than the Steam API render their achievements in the shape of the Steam response.
+    async fn get_game_achievements_raw(&self, appid: u32) -> Result<String, ApiError> {
+        let game_achievements = self.get_game_achievements(appid).await?;
+        let data = PlayerStatsResponse {
+            playerstats: PlayerStats {
+                steam_id: self.steam_id().to_string(),
+                game_name: game_achievements.game_name,
+                achievements: game_achievements.achievements,
+                success: true,
+            },
+        };
+        Ok(serde_json::to_string(&data).expect("achievements are serializable"))
+    }
+}
+
+// Fetches the achievements of many games with a bounded number of requests in flight.
+//
+// <purpose-start>
+// Commands covering a whole library would otherwise send one request per game at once or wait for
+// each of them in turn. Steam rate-limits bursts of requests, so at most `concurrency` of them are
+// in flight at any time, while the results are still yielded in the order of `appids`.
+// <purpose-end>
+//
+// <inputs-start>
+// - `api`: The source of Steam data.
+// - `appids`: The ids of the games to fetch the achievements of.
+// - `concurrency`: The maximum number of requests in flight, at least 1.
+// <inputs-end>
+//
+// <outputs-start>
+// - A stream of the results of `get_game_achievements`, one per game in the order of `appids`.
+// <outputs-end>
+//
+// <side-effects-start>
+// - **Network requests**: Fetches the achievements of each game once the stream is polled.
+// <side-effects-end>
+pub fn fetch_game_achievements<'a>(
+    api: &'a (dyn ApiClient + Send + Sync),
+    appids: Vec<u32>,
+    concurrency: usize,
+) -> impl Stream<Item = Result<GameAchievements, ApiError>> + Send + 'a {
+    stream::iter(appids)
+        .map(move |appid| api.get_game_achievements(appid))
+        .buffered(concurrency.max(1))
+}
+
+// Fetches the achievements of many games along with their global unlock percentages.
+//
+// <purpose-start>
+// Like `fetch_game_achievements`, for commands ranking games by rarity. The two requests of a game
+// are sent together, and at most `concurrency` games are fetched at any time.
+// <purpose-end>
+//
+// <inputs-start>
+// - `api`: The source of Steam data.
+// - `appids`: The ids of the games to fetch the achievements of.
+// - `concurrency`: The maximum number of games fetched at once, at least 1.
+// <inputs-end>
+//
+// <outputs-start>
+// - A stream of the results of `get_game_achievements` and `get_global_achievements`, one pair per
+//   game in the order of `appids`.
+// <outputs-end>
+//
+// <side-effects-start>
+// - **Network requests**: Fetches the achievements of each game once the stream is polled.
+// <side-effects-end>
+pub fn fetch_game_achievements_with_global<'a>(
+    api: &'a (dyn ApiClient + Send + Sync),
+    appids: Vec<u32>,
+    concurrency: usize,
+) -> impl Stream<Item = (Result<GameAchievements, ApiError>, Result<Vec<GlobalAchievement>, ApiError>)> + Send + 'a {
+    stream::iter(appids)
+        .map(move |appid| futures::future::join(api.get_game_achievements(appid), api.get_global_achievements(appid)))
+        .buffered(concurrency.max(1))
+}
+
+// Resolves the Steam ID given by the user to a SteamID64.
+//
+// <purpose-start>
+// Users often copy their whole profile URL instead of looking up their SteamID64. This function
+// accepts `https://steamcommunity.com/profiles/<id64>` URLs, from which the id is extracted,
+// `https://steamcommunity.com/id/<vanity>` URLs and bare vanity names, which are resolved with the
+// Steam API. Numeric ids are returned unchanged, without a request.
+// <purpose-end>
+//
+// <inputs-start>
+// - `raw`: The Steam ID, profile URL or vanity name, as configured.
+// - `api`: The Steam API client used to resolve vanity names.
+// - `vanity_ids`: The store of previously resolved vanity names, if any.
+// - `refresh`: Whether to resolve vanity names again instead of reusing the stored ids.
+// <inputs-end>
+//
+// <outputs-start>
+// - `Ok(String)`: The SteamID64.
+// - `Err(ApiError::VanityNotFound)`: If no profile uses the vanity name.
+// - `Err(ApiError)`: Any other error if the request fails.
+// <outputs-end>
+//
+// <side-effects-start>
+// - **Network request**: Sends a GET request to the Steam API for vanity names that are not stored.
+// - **Reads and writes a file**: Reads and updates the resolved vanity names file.
+// <side-effects-end>
+pub async fn resolve_steam_identity(
+    raw: &str,
+    api: &Api,
+    vanity_ids: Option<&VanityIdStore>,
+    refresh: bool,
+) -> Result<String, ApiError> {
+    let raw = raw.trim();
+    let path = raw
+        .strip_prefix("https://")
+        .or_else(|| raw.strip_prefix("http://"))
+        .unwrap_or(raw);
+    let path = path.strip_prefix("www.").unwrap_or(path);
+
+    if let Some(profile) = path.strip_prefix("steamcommunity.com/") {
+        let mut segments = profile.split('/');
+        match (segments.next(), segments.next()) {
+            (Some("profiles"), Some(id)) if !id.is_empty() => return Ok(id.to_string()),
+            (Some("id"), Some(vanity)) if !vanity.is_empty() => return resolve_vanity(vanity, api, vanity_ids, refresh).await,
+            _ => {}
+        }
+    }
+
+    if raw.chars().all(|c| c.is_ascii_digit()) {
+        return Ok(raw.to_string());
+    }
+
+    resolve_vanity(raw, api, vanity_ids, refresh).await
+}
+
+// Resolves a vanity name, reusing the SteamID64 stored by an earlier run unless `refresh` is set.
+// Failing to store the result only loses the cache, so it is logged rather than reported.
+// Under `--dry-run` a vanity name that is not stored is returned as is, a placeholder in the planned
+// requests, rather than planning its resolution.
+async fn resolve_vanity(
+    vanity: &str,
+    api: &Api,
+    vanity_ids: Option<&VanityIdStore>,
+    refresh: bool,
+) -> Result<String, ApiError> {
+    let now = Utc::now().timestamp();
+    if let Some(steam_id) = vanity_ids.filter(|_| !refresh).and_then(|store| store.load(vanity, now)) {
+        log::debug!("Reusing the stored Steam ID of the vanity name '{}'", vanity);
+        return Ok(steam_id);
+    }
+
+    if api.dry_run {
+        return Ok(vanity.to_string());
+    }
+
+    let steam_id = api.resolve_vanity_url(vanity).await?;
+    if let Some(Err(e)) = vanity_ids.map(|store| store.save(vanity, &steam_id, now)) {
+        log::warn!("Could not store the Steam ID of the vanity name '{}': {}", vanity, e);
+    }
+
+    Ok(steam_id)
+}
+
+// The paths of the Steam API endpoints used by `Api`, relative to the base URL.
+//
+// <purpose-start>
+// Defaults to the versions in `constants`. Each path can be overridden with a `TROGUE_*_PATH`
+// variable, so that users can move to a newer endpoint version before a release does.
+// <purpose-end>
+#[derive(Debug, Clone, PartialEq)]
+pub struct EndpointPaths {
+    pub owned_games: String,
+    pub recently_played_games: String,
+    pub player_achievements: String,
+    pub global_achievements: String,
+    pub resolve_vanity_url: String,
+    pub game_schema: String,
+}
+
+impl Default for EndpointPaths {
+    fn default() -> Self {
+        EndpointPaths {
+            owned_games: constants::OWNED_GAMES_PATH.to_string(),
+            recently_played_games: constants::RECENTLY_PLAYED_GAMES_PATH.to_string(),
+            player_achievements: constants::PLAYER_ACHIEVEMENTS_PATH.to_string(),
+            global_achievements: constants::GLOBAL_ACHIEVEMENTS_PATH.to_string(),
+            resolve_vanity_url: constants::RESOLVE_VANITY_URL_PATH.to_string(),
+            game_schema: constants::GAME_SCHEMA_PATH.to_string(),
+        }
+    }
+}
+
+impl EndpointPaths {
+    // Creates the endpoint paths, overriding the defaults with configuration variables.
+    //
+    // <purpose-start>
+    // The variables are `TROGUE_OWNED_GAMES_PATH`, `TROGUE_RECENTLY_PLAYED_GAMES_PATH`,
+    // `TROGUE_PLAYER_ACHIEVEMENTS_PATH`, `TROGUE_GLOBAL_ACHIEVEMENTS_PATH`,
+    // `TROGUE_RESOLVE_VANITY_URL_PATH` and `TROGUE_GAME_SCHEMA_PATH`. Surrounding slashes are ignored and empty values keep the default.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `lookup`: Returns the value of a configuration variable, if set.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `EndpointPaths`: The paths to use.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None.
+    // <side-effects-end>
+    pub fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> EndpointPaths {
+        let mut paths = EndpointPaths::default();
+        let overrides = [
+            ("TROGUE_OWNED_GAMES_PATH", &mut paths.owned_games),
+            ("TROGUE_RECENTLY_PLAYED_GAMES_PATH", &mut paths.recently_played_games),
+            ("TROGUE_PLAYER_ACHIEVEMENTS_PATH", &mut paths.player_achievements),
+            ("TROGUE_GLOBAL_ACHIEVEMENTS_PATH", &mut paths.global_achievements),
+            ("TROGUE_RESOLVE_VANITY_URL_PATH", &mut paths.resolve_vanity_url),
+            ("TROGUE_GAME_SCHEMA_PATH", &mut paths.game_schema),
+        ];
+
+        for (key, path) in overrides {
+            if let Some(value) = lookup(key) {
+                let value = value.trim().trim_matches('/');
+                if !value.is_empty() {
+                    *path = value.to_string();
+                }
+            }
+        }
+
+        paths
+    }
+}
+
+// The settings of the resolution of the configured Steam ID, see `Api::with_identity_resolution`.
+struct IdentityResolution {
+    vanity_ids: Option<VanityIdStore>,
+    refresh: bool,
+}
+
 // A client for interacting with the Steam API.
+// A single HTTP client is shared by all requests, so that bulk commands reuse its pooled connections.
 pub struct Api {
     api_key: String,
+    // The Steam ID as configured, see `with_identity_resolution`
     steam_id: String,
+    // How the configured Steam ID is resolved, `None` when it is used as is
+    identity: Option<IdentityResolution>,
+    // The SteamID64 the configured Steam ID resolved to, once a player endpoint needed it
+    resolved_steam_id: OnceCell<String>,
     base_url: String,
+    paths: EndpointPaths,
+    language: String,
+    client: reqwest::Client,
+    // The settings the client was built with, `None` for the defaults of reqwest
+    timeout: Option<Duration>,
+    pool_idle_timeout: Option<Duration>,
+    // An explicit proxy, `None` uses the proxy of the `HTTP_PROXY`/`HTTPS_PROXY` environment variables, if any
+    proxy: Option<String>,
+    retries: u32,
+    // Whether requests are only recorded in `planned`, instead of being sent
+    dry_run: bool,
+    planned: Mutex<Vec<String>>,
 }
 
 impl Api {
@@ -108,148 +758,1237 @@ impl Api {
         Api {
             api_key,
             steam_id,
+            identity: None,
+            resolved_steam_id: OnceCell::new(),
             base_url,
+            paths: EndpointPaths::default(),
+            language: "en".to_string(),
+            client: reqwest::Client::builder().build().expect("failed to initialize the HTTP client"),
+            timeout: None,
+            pool_idle_timeout: None,
+            proxy: None,
+            retries: 0,
+            dry_run: false,
+            planned: Mutex::new(Vec::new()),
         }
     }
 
-    // Retrieves the list of games owned by the user.
+    // Sets the language used for localized achievement data.
     //
     // <purpose-start>
-    // This function sends a request to the Steam API to retrieve the list of games owned by the user.
+    // Steam localizes achievement names and descriptions based on the `l` query parameter.
+    // This allows non-English users to receive achievement data in their own language.
     // <purpose-end>
     //
     // <inputs-start>
-    // - None.
+    // - `language`: The Steam language code (e.g. `en`, `german`, `fr`).
     // <inputs-end>
     //
     // <outputs-start>
-    // - `Ok(Vec<Game>)`: A vector of `Game` structs representing the owned games.
-    // - `Err(reqwest::Error)`: An error if the request fails.
+    // - `Api`: The same `Api` instance configured with the given language.
     // <outputs-end>
     //
     // <side-effects-start>
-    // - **Network request**: Sends a GET request to the Steam API.
+    // - None.
     // <side-effects-end>
-    pub async fn get_games_list(&self) -> Result<Vec<Game>, reqwest::Error> {
-        let api_key = self.api_key.clone();
-        let steam_id = self.steam_id.clone();
-        
-        let url = format!("{}/IPlayerService/GetOwnedGames/v0001/?key={api_key}&steamid={steam_id}&format=json&include_appinfo=1", self.base_url);
-
-        let response = reqwest::get(url).await?;
-
-        if !response.status().is_success() {
-            return Err(response.error_for_status().unwrap_err());
-        }
-
-        let data: GamesListResponse = response.json().await?;
-        Ok(data.response.games)
+    pub fn with_language(mut self, language: String) -> Api {
+        self.language = language;
+        self
     }
 
-    // Retrieves the achievements for a specific game.
+    // Sets the timeout of each request.
     //
     // <purpose-start>
-    // This function sends a request to the Steam API to retrieve the achievements for a specific game.
+    // Without a timeout a request to an unresponsive server hangs forever. Users on slow
+    // networks may need a longer timeout than the default.
     // <purpose-end>
     //
     // <inputs-start>
-    // - `appid`: The ID of the game.
+    // - `timeout`: The maximum duration of a single request, including reading the response.
     // <inputs-end>
     //
     // <outputs-start>
-    // - `Ok((String, Vec<Achievement>))`: A tuple containing the game name and a vector of `Achievement` structs.
-    // - `Err(reqwest::Error)`: An error if the request fails.
+    // - `Api`: The same `Api` instance configured with the given timeout.
     // <outputs-end>
     //
     // <side-effects-start>
-    // - **Network request**: Sends a GET request to the Steam API.
+    // - None.
     // <side-effects-end>
-    pub async fn get_game_achievements(&self, appid: u32) -> Result<(String, Vec<Achievement>), reqwest::Error> {
-        let api_key = self.api_key.clone();
-        let steam_id = self.steam_id.clone();
-
-        let url = format!("{}/ISteamUserStats/GetPlayerAchievements/v0001/?appid={appid}&key={api_key}&steamid={steam_id}&l=en", self.base_url);
-
-        let response = reqwest::get(url).await?;
-
-        if !response.status().is_success() {
-            return Err(response.error_for_status().unwrap_err());
-        }
-
-        let data: PlayerStatsResponse = response.json().await?;
-        Ok((data.playerstats.game_name, data.playerstats.achievements))
+    pub fn with_timeout(mut self, timeout: Duration) -> Api {
+        self.timeout = Some(timeout);
+        self.client = self.build_client();
+        self
     }
 
-    // Retrieves the global achievement percentages for a specific game.
+    // Sets how long an idle connection is kept open for later requests.
     //
     // <purpose-start>
-    // This function sends a request to the Steam API to retrieve the global achievement percentages for a specific game.
+    // Commands fetching the achievements of many games send their requests over the pooled
+    // connections of the shared client, saving a TLS handshake per request. A longer timeout
+    // keeps the connections alive across slow batches, `0` closes them once idle.
     // <purpose-end>
     //
     // <inputs-start>
-    // - `appid`: The ID of the game.
+    // - `pool_idle_timeout`: The time an unused connection stays in the pool.
     // <inputs-end>
     //
     // <outputs-start>
-    // - `Ok(Vec<GlobalAchievement>)`: A vector of `GlobalAchievement` structs.
-    // - `Err(reqwest::Error)`: An error if the request fails.
+    // - `Api`: The same `Api` instance configured with the given idle timeout.
     // <outputs-end>
     //
     // <side-effects-start>
-    // - **Network request**: Sends a GET request to the Steam API.
+    // - None.
     // <side-effects-end>
-    pub async fn get_global_achievements(&self, appid: u32) -> Result<Vec<GlobalAchievement>, reqwest::Error> {
-        let url = format!("{}/ISteamUserStats/GetGlobalAchievementPercentagesForApp/v0002/?gameid={appid}&format=json&l=en", self.base_url);
+    pub fn with_pool_idle_timeout(mut self, pool_idle_timeout: Duration) -> Api {
+        self.pool_idle_timeout = Some(pool_idle_timeout);
+        self.client = self.build_client();
+        self
+    }
 
-        let response = reqwest::get(url).await?;
+    // Sets the proxy all requests are sent through.
+    //
+    // <purpose-start>
+    // The client already uses the proxy of the `HTTP_PROXY`/`HTTPS_PROXY` environment variables. An explicit
+    // proxy takes precedence over them, for networks where the proxy is only needed by trogue.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `proxy`: The http or https URL of the proxy, validated with `cfg::parse_base_url`.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `Api`: The same `Api` instance configured with the given proxy.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None.
+    // <side-effects-end>
+    pub fn with_proxy(mut self, proxy: String) -> Api {
+        self.proxy = Some(proxy);
+        self.client = self.build_client();
+        self
+    }
 
-        if !response.status().is_success() {
-            return Err(response.error_for_status().unwrap_err());
+    // Builds the HTTP client from the configured settings, replacing the client as a whole keeps the other settings.
+    fn build_client(&self) -> reqwest::Client {
+        let mut builder = reqwest::Client::builder();
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
         }
-
-        let data: GlobalAchievementsResponse = response.json().await?;
-        Ok(data.achievementpercentages.achievements)
+        if let Some(pool_idle_timeout) = self.pool_idle_timeout {
+            builder = builder.pool_idle_timeout(pool_idle_timeout);
+        }
+        if let Some(proxy) = &self.proxy {
+            builder = builder.proxy(reqwest::Proxy::all(proxy).expect("invalid proxy URL"));
+        }
+        builder.build().expect("failed to initialize the HTTP client")
+    }
+
+    // Sets the number of times a failed request is retried.
+    //
+    // <purpose-start>
+    // Network errors and server-side failures are often transient, so retrying them saves the
+    // user from rerunning the whole command. Rejected API keys and private profiles are never retried.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `retries`: The number of retries after the first attempt.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `Api`: The same `Api` instance configured with the given number of retries.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None.
+    // <side-effects-end>
+    pub fn with_retries(mut self, retries: u32) -> Api {
+        self.retries = retries;
+        self
+    }
+
+    // Sets the paths of the endpoints.
+    //
+    // <purpose-start>
+    // Allows a deprecated endpoint version to be replaced without a new release, see `EndpointPaths`.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `paths`: The paths of the endpoints, relative to the base URL.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `Api`: The same `Api` instance configured with the given paths.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None.
+    // <side-effects-end>
+    pub fn with_endpoint_paths(mut self, paths: EndpointPaths) -> Api {
+        self.paths = paths;
+        self
+    }
+
+    // Stops requests from being sent.
+    //
+    // <purpose-start>
+    // Backs the `--dry-run` flag, which shows the requests a command would send. Each request is
+    // recorded, see `planned_requests`, and fails with `ApiError::DryRun`, so that requests depending
+    // on its response are not planned.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `dry_run`: Whether to record requests instead of sending them.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `Api`: The same `Api` instance, recording its requests if `dry_run` is set.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None.
+    // <side-effects-end>
+    pub fn with_dry_run(mut self, dry_run: bool) -> Api {
+        self.dry_run = dry_run;
+        self
+    }
+
+    // Resolves the configured Steam ID before the player endpoints use it.
+    //
+    // <purpose-start>
+    // The Steam ID may be configured as a profile URL or a vanity name, see `resolve_steam_identity`.
+    // It is resolved on the first request for player data only, so that commands that never ask
+    // for it work without the network. Without this setting the Steam ID is used as is.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `vanity_ids`: The store of previously resolved vanity names, if any.
+    // - `refresh`: Whether to resolve vanity names again instead of reusing the stored ids.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `Api`: The same `Api` instance, resolving its Steam ID when needed.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None.
+    // <side-effects-end>
+    pub fn with_identity_resolution(mut self, vanity_ids: Option<VanityIdStore>, refresh: bool) -> Api {
+        self.identity = Some(IdentityResolution { vanity_ids, refresh });
+        self
+    }
+
+    // Returns the SteamID64 used by the player endpoints, resolving the configured Steam ID once.
+    //
+    // <purpose-start>
+    // A successful resolution is kept for the lifetime of the client, failures are not, so that a
+    // later request tries again.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - None.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `Ok(&str)`: The SteamID64, or the configured Steam ID if it is used as is.
+    // - `Err(ApiError)`: An error if the Steam ID could not be resolved.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - **Network request**: Resolves a vanity name on the first call, see `resolve_steam_identity`.
+    // - **Reads and writes a file**: Reads and updates the resolved vanity names file.
+    // <side-effects-end>
+    async fn player_steam_id(&self) -> Result<&str, ApiError> {
+        let Some(identity) = &self.identity else {
+            return Ok(&self.steam_id);
+        };
+
+        self.resolved_steam_id
+            .get_or_try_init(|| resolve_steam_identity(&self.steam_id, self, identity.vanity_ids.as_ref(), identity.refresh))
+            .await
+            .map(String::as_str)
+    }
+
+    // Resolves a vanity name to the SteamID64 of its profile.
+    //
+    // <purpose-start>
+    // Vanity names are the custom part of `https://steamcommunity.com/id/<vanity>` profile URLs.
+    // The player endpoints only accept SteamID64s, see `resolve_steam_identity`.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `vanity`: The vanity name to resolve.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `Ok(String)`: The SteamID64 of the profile.
+    // - `Err(ApiError::VanityNotFound)`: If no profile uses the vanity name.
+    // - `Err(ApiError)`: Any other error if the request fails.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - **Network request**: Sends a GET request to the Steam API.
+    // <side-effects-end>
+    pub async fn resolve_vanity_url(&self, vanity: &str) -> Result<String, ApiError> {
+        let data: VanityUrlResponse = self.get_json(self.build_resolve_vanity_url(vanity)).await?;
+        match data.response.steamid {
+            Some(steam_id) if data.response.success == 1 => Ok(steam_id),
+            _ => Err(ApiError::VanityNotFound(vanity.to_string())),
+        }
+    }
+
+    // Returns the URL of the owned games endpoint, including the names of the games.
+    pub fn build_owned_games_url(&self, steam_id: &str) -> String {
+        format!(
+            "{}/{}/?key={}&steamid={}&format=json&include_appinfo=1",
+            self.base_url, self.paths.owned_games, self.api_key, encode_query_value(steam_id)
+        )
+    }
+
+    // Returns the URL of the owned games endpoint, without the games themselves, for their count.
+    pub fn build_games_count_url(&self, steam_id: &str) -> String {
+        format!(
+            "{}/{}/?key={}&steamid={}&format=json",
+            self.base_url, self.paths.owned_games, self.api_key, encode_query_value(steam_id)
+        )
+    }
+
+    // Returns the URL of the recently played games endpoint, for up to `count` games.
+    pub fn build_recently_played_games_url(&self, steam_id: &str, count: u32) -> String {
+        format!(
+            "{}/{}/?key={}&steamid={}&count={count}&format=json",
+            self.base_url, self.paths.recently_played_games, self.api_key, encode_query_value(steam_id)
+        )
+    }
+
+    // Returns the URL of the player achievements endpoint for a specific game.
+    pub fn build_player_achievements_url(&self, steam_id: &str, appid: u32) -> String {
+        format!(
+            "{}/{}/?appid={appid}&key={}&steamid={}&l={}",
+            self.base_url, self.paths.player_achievements, self.api_key, encode_query_value(steam_id), encode_query_value(&self.language)
+        )
+    }
+
+    // Returns the URL of the global achievement percentages endpoint for a specific game.
+    pub fn build_global_achievements_url(&self, appid: u32) -> String {
+        format!(
+            "{}/{}/?gameid={appid}&format=json&l={}",
+            self.base_url, self.paths.global_achievements, encode_query_value(&self.language)
+        )
+    }
+
+    // Returns the URL of the schema endpoint for a specific game.
+    pub fn build_game_schema_url(&self, appid: u32) -> String {
+        format!(
+            "{}/{}/?appid={appid}&key={}&l={}",
+            self.base_url, self.paths.game_schema, self.api_key, encode_query_value(&self.language)
+        )
+    }
+
+    // Returns the URL of the vanity name resolution endpoint.
+    pub fn build_resolve_vanity_url(&self, vanity: &str) -> String {
+        format!(
+            "{}/{}/?key={}&vanityurl={}",
+            self.base_url, self.paths.resolve_vanity_url, self.api_key, encode_query_value(vanity)
+        )
+    }
+
+    // Sends a GET request and deserializes the JSON response.
+    //
+    // <purpose-start>
+    // Centralizes the request handling shared by all endpoints, so that every failure is
+    // classified into the same `ApiError` variants. Transient failures are retried as configured
+    // with `with_retries`.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `url`: The full request URL.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `Ok(T)`: The deserialized response.
+    // - `Err(ApiError)`: An error if the request fails, is rejected or cannot be decoded.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - **Network request**: Sends one or more GET requests to the Steam API.
+    // - **Sleeps**: Waits between retries.
+    // <side-effects-end>
+    async fn get_json<T: DeserializeOwned>(&self, url: String) -> Result<T, ApiError> {
+        self.get_with_retries(url, parse_json).await
+    }
+
+    // Sends a GET request and returns the JSON response body without deserializing it.
+    //
+    // <inputs-start>
+    // - `url`: The full request URL.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `Ok(String)`: The response body, exactly as received.
+    // - `Err(ApiError)`: An error if the request fails, is rejected or the body is not JSON.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - **Network request**: Sends one or more GET requests to the Steam API.
+    // - **Sleeps**: Waits between retries.
+    // <side-effects-end>
+    async fn get_raw(&self, url: String) -> Result<String, ApiError> {
+        self.get_with_retries(url, |body| Ok(body.to_string())).await
+    }
+
+    // Sends a GET request, retrying transient failures as configured with `with_retries`.
+    //
+    // <inputs-start>
+    // - `url`: The full request URL.
+    // - `parse`: Turns the response body into the result, failures of which are retried if transient.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `Ok(T)`: The parsed response.
+    // - `Err(ApiError)`: The error of the last attempt.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - **Network request**: Sends one or more GET requests to the Steam API.
+    // - **Sleeps**: Waits between retries.
+    // - **Records the request**: Under `--dry-run`, records the request instead of sending it.
+    // <side-effects-end>
+    async fn get_with_retries<T>(&self, url: String, parse: fn(&str) -> Result<T, ApiError>) -> Result<T, ApiError> {
+        if self.dry_run {
+            self.planned.lock().unwrap().push(log_url(&url));
+            return Err(ApiError::DryRun);
+        }
+
+        let mut attempt = 0;
+
+        loop {
+            match self.try_get_body(&url).await.and_then(|body| parse(&body)) {
+                Err(e) if attempt < self.retries && e.is_transient() => {
+                    attempt += 1;
+                    log::warn!("Retrying {} ({}/{}) after: {}", log_url(&url), attempt, self.retries, e);
+                    tokio::time::sleep(RETRY_DELAY * attempt).await;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    // Sends a single GET request and returns the JSON response body.
+    //
+    // <inputs-start>
+    // - `url`: The full request URL.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `Ok(String)`: The response body.
+    // - `Err(ApiError)`: An error if the request fails, is rejected or the body is not labelled as JSON.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - **Network request**: Sends a GET request to the Steam API.
+    // - **Logs**: Logs the request, the response status and the elapsed time at debug level, and failures at warn level.
+    // <side-effects-end>
+    async fn try_get_body(&self, url: &str) -> Result<String, ApiError> {
+        log::debug!("GET {}", log_url(url));
+        let started = Instant::now();
+
+        let response = self.client.get(url).send().await.map_err(|e| {
+            let e = e.without_url();
+            log::warn!("GET {} failed after {:?}: {}", log_url(url), started.elapsed(), e);
+            ApiError::Network(e)
+        })?;
+
+        let status = response.status();
+        if status.is_success() {
+            log::debug!("GET {} returned {} in {:?}", log_url(url), status, started.elapsed());
+        } else {
+            log::warn!("GET {} returned {} in {:?}", log_url(url), status, started.elapsed());
+        }
+
+        if status == reqwest::StatusCode::UNAUTHORIZED || status == reqwest::StatusCode::FORBIDDEN {
+            return Err(ApiError::Unauthorized(status));
+        }
+        if !status.is_success() {
+            return Err(ApiError::Status(status));
+        }
+
+        let is_json = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .is_none_or(|value| value.contains("json"));
+        let body = response.text().await.map_err(|e| ApiError::Network(e.without_url()))?;
+
+        if !is_json {
+            return Err(ApiError::UnexpectedResponse(body_snippet(&body)));
+        }
+
+        Ok(body)
+    }
+}
+
+// Deserializes a JSON response body.
+//
+// <inputs-start>
+// - `body`: The response body.
+// <inputs-end>
+//
+// <outputs-start>
+// - `Ok(T)`: The deserialized response.
+// - `Err(ApiError::Decode)`: If the body is JSON with an unexpected structure.
+// - `Err(ApiError::UnexpectedResponse)`: If the body is not JSON at all.
+// <outputs-end>
+fn parse_json<T: DeserializeOwned>(body: &str) -> Result<T, ApiError> {
+    serde_json::from_str(body).map_err(|e| match e.classify() {
+        serde_json::error::Category::Data => ApiError::Decode(e),
+        _ => ApiError::UnexpectedResponse(body_snippet(body)),
+    })
+}
+
+// Shortens a response body for an error message.
+//
+// <purpose-start>
+// Whitespace runs, such as the indentation of HTML pages, are collapsed into single spaces, and
+// the body is cut after `SNIPPET_LENGTH` characters.
+// <purpose-end>
+//
+// <inputs-start>
+// - `body`: The response body.
+// <inputs-end>
+//
+// <outputs-start>
+// - `String`: The start of the body, followed by an ellipsis if it was cut, or `(empty body)`.
+// <outputs-end>
+fn body_snippet(body: &str) -> String {
+    let collapsed = body.split_whitespace().collect::<Vec<_>>().join(" ");
+    if collapsed.is_empty() {
+        return "(empty body)".to_string();
+    }
+
+    match collapsed.char_indices().nth(SNIPPET_LENGTH) {
+        Some((end, _)) => format!("{}…", &collapsed[..end]),
+        None => collapsed,
+    }
+}
+
+// Returns a request URL as written to the log, with the API key redacted.
+fn log_url(url: &str) -> String {
+    ui::scrub_api_key(url)
+}
+
+// Percent-encodes a user-supplied value for the query string of a request URL, so that
+// characters such as `&`, `#` or spaces cannot change the parameters of the request.
+fn encode_query_value(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+#[async_trait]
+impl ApiClient for Api {
+    // Checks whether an API key has been configured.
+    //
+    // <purpose-start>
+    // Allows configuration checks without exposing the secret key itself.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - None.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `bool`: `true` if the API key is not empty.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None.
+    // <side-effects-end>
+    fn has_api_key(&self) -> bool {
+        !self.api_key.trim().is_empty()
+    }
+
+    // Returns the Steam ID the client queries.
+    //
+    // <purpose-start>
+    // This function returns the Steam ID used in player-specific requests.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - None.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `&str`: A reference to the Steam ID.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None.
+    // <side-effects-end>
+    fn steam_id(&self) -> &str {
+        &self.steam_id
+    }
+
+    // Returns the base URL of the Steam API the client sends requests to.
+    //
+    // <purpose-start>
+    // Reported by the `info` command, as tests and proxies may point the client elsewhere.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - None.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `Some(&str)`: The base URL, without a trailing slash.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None.
+    // <side-effects-end>
+    fn base_url(&self) -> Option<&str> {
+        Some(&self.base_url)
+    }
+
+    fn planned_requests(&self) -> Vec<String> {
+        self.planned.lock().unwrap().clone()
+    }
+
+    // Retrieves the list of games owned by the user.
+    //
+    // <purpose-start>
+    // This function sends a request to the Steam API to retrieve the list of games owned by the user.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - None.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `Ok(Vec<Game>)`: A vector of `Game` structs representing the owned games.
+    // - `Err(ApiError::ProfilePrivate)`: If the profile's game details are not public.
+    // - `Err(ApiError)`: An error if the request fails.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - **Network request**: Sends a GET request to the Steam API.
+    // <side-effects-end>
+    async fn get_games_list(&self) -> Result<Vec<Game>, ApiError> {
+        let data: GamesListResponse = self.get_json(self.build_owned_games_url(self.player_steam_id().await?)).await?;
+        if data.response.game_count.is_none() {
+            return Err(ApiError::ProfilePrivate);
+        }
+
+        Ok(data.response.games)
+    }
+
+    // Retrieves the games the user played recently.
+    //
+    // <purpose-start>
+    // This function sends a request to the dedicated Steam API endpoint, which only returns the games
+    // played in the last two weeks instead of the whole library.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `count`: The maximum number of games to return.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `Ok(Vec<Game>)`: The recently played games, empty for private profiles and without recent activity.
+    // - `Err(ApiError)`: An error if the request fails.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - **Network request**: Sends a GET request to the Steam API.
+    // <side-effects-end>
+    async fn get_recently_played_games(&self, count: u32) -> Result<Vec<Game>, ApiError> {
+        let data: RecentlyPlayedResponse = self.get_json(self.build_recently_played_games_url(self.player_steam_id().await?, count)).await?;
+        Ok(data.response.games)
+    }
+
+    // Retrieves the number of games owned by the user.
+    //
+    // <purpose-start>
+    // This is the cheapest authenticated call available. It is used to check that the configured
+    // API key and Steam ID work, without downloading the whole library.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - None.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `Ok(u32)`: The number of owned games.
+    // - `Err(ApiError::ProfilePrivate)`: If Steam does not disclose the games of the profile.
+    // - `Err(ApiError)`: Any other error if the request fails.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - **Network request**: Sends a GET request to the Steam API.
+    // <side-effects-end>
+    async fn get_games_count(&self) -> Result<u32, ApiError> {
+        let data: GamesCountResponse = self.get_json(self.build_games_count_url(self.player_steam_id().await?)).await?;
+        data.response.game_count.ok_or(ApiError::ProfilePrivate)
+    }
+
+    // Retrieves the achievements for a specific game.
+    //
+    // <purpose-start>
+    // This function sends a request to the Steam API to retrieve the achievements for a specific game.
+    // Achievements returned without a name are named after their API name, so that every command
+    // has something to display.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `appid`: The ID of the game.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `Ok(GameAchievements)`: The game name and the player's achievements.
+    // - `Err(ApiError)`: An error if the request fails.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - **Network request**: Sends a GET request to the Steam API.
+    // <side-effects-end>
+    async fn get_game_achievements(&self, appid: u32) -> Result<GameAchievements, ApiError> {
+        let data: PlayerStatsResponse = self.get_json(self.build_player_achievements_url(self.player_steam_id().await?, appid)).await?;
+        let mut achievements = data.playerstats.achievements;
+        for achievement in achievements.iter_mut().filter(|a| a.name.is_empty()) {
+            achievement.name = achievement.apiname.clone();
+        }
+        Ok(GameAchievements {
+            game_name: data.playerstats.game_name,
+            achievements,
+        })
+    }
+
+    // Retrieves the global achievement percentages for a specific game.
+    //
+    // <purpose-start>
+    // This function sends a request to the Steam API to retrieve the global achievement percentages for a specific game.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `appid`: The ID of the game.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `Ok(Vec<GlobalAchievement>)`: A vector of `GlobalAchievement` structs.
+    // - `Err(ApiError)`: An error if the request fails.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - **Network request**: Sends a GET request to the Steam API.
+    // <side-effects-end>
+    async fn get_global_achievements(&self, appid: u32) -> Result<Vec<GlobalAchievement>, ApiError> {
+        let data: GlobalAchievementsResponse = self.get_json(self.build_global_achievements_url(appid)).await?;
+        Ok(data.achievementpercentages.achievements)
+    }
+
+    // Retrieves the schema of the achievements of a specific game.
+    //
+    // <purpose-start>
+    // The player achievements endpoint leaves the descriptions of hidden achievements empty until
+    // they are unlocked. The schema of the game has them, in the configured language.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `appid`: The ID of the game.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `Ok(Vec<SchemaAchievement>)`: The achievement definitions, empty for games without stats.
+    // - `Err(ApiError)`: An error if the request fails.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - **Network request**: Sends a GET request to the Steam API.
+    // <side-effects-end>
+    async fn get_game_schema(&self, appid: u32) -> Result<Vec<SchemaAchievement>, ApiError> {
+        let data: GameSchemaResponse = self.get_json(self.build_game_schema_url(appid)).await?;
+        Ok(data.game.available_game_stats.achievements)
+    }
+
+    // Retrieves the response body of the owned games endpoint, exactly as Steam sent it.
+    //
+    // <purpose-start>
+    // Lets developers inspect changes to the shape of the Steam response, which would otherwise
+    // only surface as decode errors. The body is not checked for a private profile.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - None.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `Ok(String)`: The unmodified response body.
+    // - `Err(ApiError)`: An error if the request fails or the response is not JSON.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - **Network request**: Sends a GET request to the Steam API.
+    // <side-effects-end>
+    async fn get_games_list_raw(&self) -> Result<String, ApiError> {
+        self.get_raw(self.build_owned_games_url(self.player_steam_id().await?)).await
+    }
+
+    // Retrieves the response body of the player achievements endpoint, exactly as Steam sent it.
+    //
+    // <inputs-start>
+    // - `appid`: The ID of the game.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `Ok(String)`: The unmodified response body.
+    // - `Err(ApiError)`: An error if the request fails or the response is not JSON.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - **Network request**: Sends a GET request to the Steam API.
+    // <side-effects-end>
+    async fn get_game_achievements_raw(&self, appid: u32) -> Result<String, ApiError> {
+        self.get_raw(self.build_player_achievements_url(self.player_steam_id().await?, appid)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants;
+    use crate::fake_api::FakeApi;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    #[test]
+    fn test_log_url_redacts_api_key() {
+        let url = format!(
+            "{}/ISteamUserStats/GetPlayerAchievements/v0001/?appid=400&key={}&steamid=76561197960287930&l=en",
+            crate::constants::STEAM_API_BASE_URL, "SECRETKEY123"
+        );
+
+        let logged = log_url(&url);
+        assert!(!logged.contains("SECRETKEY123"));
+        assert!(logged.ends_with("/?appid=400&key=***&steamid=76561197960287930&l=en"));
+        assert_eq!(log_url("http://localhost/?vanityurl=gabe&key=SECRETKEY123"), "http://localhost/?vanityurl=gabe&key=***");
+    }
+
+    #[test]
+    fn test_urls_use_endpoint_constants() {
+        let api = Api::new("test_key".to_string(), "test_id".to_string(), "http://localhost".to_string());
+
+        assert_eq!(
+            api.build_owned_games_url("test_id"),
+            format!("http://localhost/{}/?key=test_key&steamid=test_id&format=json&include_appinfo=1", constants::OWNED_GAMES_PATH)
+        );
+        assert_eq!(
+            api.build_player_achievements_url("test_id", 440),
+            format!("http://localhost/{}/?appid=440&key=test_key&steamid=test_id&l=en", constants::PLAYER_ACHIEVEMENTS_PATH)
+        );
+    }
+
+    // Serves the same JSON body to every request on a local port, counting the accepted connections.
+    async fn serve_counting_connections(body: &'static str) -> (String, Arc<AtomicUsize>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let connections = Arc::new(AtomicUsize::new(0));
+        let accepted = connections.clone();
+
+        tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                accepted.fetch_add(1, Ordering::SeqCst);
+                tokio::spawn(async move {
+                    let mut buffer = [0; 4096];
+                    // The GET requests have no body and fit in a single read
+                    while let Ok(read) = socket.read(&mut buffer).await {
+                        if read == 0 {
+                            return;
+                        }
+                        let response = format!(
+                            "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{}",
+                            body.len(),
+                            body
+                        );
+                        if socket.write_all(response.as_bytes()).await.is_err() {
+                            return;
+                        }
+                    }
+                });
+            }
+        });
+
+        (url, connections)
+    }
+
+    #[tokio::test]
+    async fn test_requests_reuse_pooled_connections() {
+        let (url, connections) = serve_counting_connections(r#"{ "response": { "game_count": 3 } }"#).await;
+        let api = Api::new("test_key".to_string(), "test_id".to_string(), url)
+            .with_timeout(Duration::from_secs(5))
+            .with_pool_idle_timeout(Duration::from_secs(30));
+
+        for _ in 0..3 {
+            assert_eq!(api.get_games_count().await.unwrap(), 3);
+        }
+
+        assert_eq!(connections.load(Ordering::SeqCst), 1);
+        assert_eq!(api.timeout, Some(Duration::from_secs(5)));
+        assert_eq!(api.pool_idle_timeout, Some(Duration::from_secs(30)));
+    }
+
+    #[test]
+    fn test_build_urls() {
+        let api = Api::new("test_key".to_string(), "test_id".to_string(), "http://localhost".to_string())
+            .with_language("german".to_string());
+
+        assert_eq!(api.build_games_count_url("test_id"), "http://localhost/IPlayerService/GetOwnedGames/v0001/?key=test_key&steamid=test_id&format=json");
+        assert_eq!(
+            api.build_recently_played_games_url("test_id", 5),
+            "http://localhost/IPlayerService/GetRecentlyPlayedGames/v0001/?key=test_key&steamid=test_id&count=5&format=json"
+        );
+        assert_eq!(
+            api.build_global_achievements_url(440),
+            "http://localhost/ISteamUserStats/GetGlobalAchievementPercentagesForApp/v0002/?gameid=440&format=json&l=german"
+        );
+        assert_eq!(
+            api.build_game_schema_url(440),
+            "http://localhost/ISteamUserStats/GetSchemaForGame/v2/?appid=440&key=test_key&l=german"
+        );
+        assert_eq!(
+            api.build_resolve_vanity_url("gabe"),
+            "http://localhost/ISteamUser/ResolveVanityURL/v0001/?key=test_key&vanityurl=gabe"
+        );
+        assert_eq!(
+            api.build_resolve_vanity_url("gabe newell&key=x"),
+            "http://localhost/ISteamUser/ResolveVanityURL/v0001/?key=test_key&vanityurl=gabe%20newell%26key%3Dx"
+        );
+    }
+
+    #[test]
+    fn test_build_urls_encode_language() {
+        let api = Api::new("test_key".to_string(), "test_id".to_string(), "http://localhost".to_string())
+            .with_language("en&key=x".to_string());
+
+        assert!(api.build_player_achievements_url("test_id", 440).ends_with("&key=test_key&steamid=test_id&l=en%26key%3Dx"));
+        assert!(api.build_global_achievements_url(440).ends_with("&l=en%26key%3Dx"));
+        assert!(api.build_game_schema_url(440).ends_with("&key=test_key&l=en%26key%3Dx"));
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_records_requests_without_sending_them() {
+        // Nothing listens on this port, so a sent request would fail with a network error
+        let api = Api::new("test_key".to_string(), "test_id".to_string(), "http://127.0.0.1:1".to_string()).with_dry_run(true);
+
+        assert!(matches!(api.get_game_achievements(440).await, Err(ApiError::DryRun)));
+        assert!(matches!(api.get_games_list_raw().await, Err(ApiError::DryRun)));
+
+        assert_eq!(
+            api.planned_requests(),
+            vec![
+                "http://127.0.0.1:1/ISteamUserStats/GetPlayerAchievements/v0001/?appid=440&key=***&steamid=test_id&l=en".to_string(),
+                "http://127.0.0.1:1/IPlayerService/GetOwnedGames/v0001/?key=***&steamid=test_id&format=json&include_appinfo=1".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_endpoint_paths_from_lookup() {
+        let paths = EndpointPaths::from_lookup(|key| match key {
+            "TROGUE_GLOBAL_ACHIEVEMENTS_PATH" => Some("/ISteamUserStats/GetGlobalAchievementPercentagesForApp/v0003/".to_string()),
+            "TROGUE_RESOLVE_VANITY_URL_PATH" => Some("".to_string()),
+            _ => None,
+        });
+
+        assert_eq!(paths.global_achievements, "ISteamUserStats/GetGlobalAchievementPercentagesForApp/v0003");
+        assert_eq!(paths.resolve_vanity_url, constants::RESOLVE_VANITY_URL_PATH);
+        assert_eq!(paths.owned_games, constants::OWNED_GAMES_PATH);
+        assert_eq!(EndpointPaths::from_lookup(|_| None), EndpointPaths::default());
+    }
+
+    #[tokio::test]
+    async fn test_requests_use_overridden_endpoint_paths() {
+        let mut server = mockito::Server::new_async().await;
+        let recent_mock = server.mock("GET", "/IPlayerService/GetRecentlyPlayedGames/v0002/?key=test_key&steamid=test_id&count=5&format=json")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"response": {"total_count": 0, "games": []}}"#)
+            .create_async().await;
+        let paths = EndpointPaths {
+            recently_played_games: "IPlayerService/GetRecentlyPlayedGames/v0002".to_string(),
+            ..EndpointPaths::default()
+        };
+        let api = Api::new("test_key".to_string(), "test_id".to_string(), server.url()).with_endpoint_paths(paths);
+
+        assert!(api.get_recently_played_games(5).await.unwrap().is_empty());
+        recent_mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_requests_are_sent_through_the_proxy() {
+        let mut proxy = mockito::Server::new_async().await;
+        let proxy_mock = proxy.mock("GET", "/IPlayerService/GetOwnedGames/v0001/?key=test_key&steamid=test_id&format=json")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{ "response": { "game_count": 3 } }"#)
+            .create_async().await;
+        let api = Api::new("test_key".to_string(), "test_id".to_string(), "http://steam.invalid".to_string())
+            .with_timeout(Duration::from_secs(5))
+            .with_proxy(proxy.url());
+
+        assert_eq!(api.proxy, Some(proxy.url()));
+        assert_eq!(api.timeout, Some(Duration::from_secs(5)));
+        assert_eq!(api.get_games_count().await.unwrap(), 3);
+        proxy_mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_new_api() {
+        let api = Api::new(
+            "test_key".to_string(),
+            "test_id".to_string(),
+            constants::STEAM_API_BASE_URL.to_string(),
+        );
+        assert_eq!(api.api_key, "test_key");
+        assert_eq!(api.steam_id, "test_id");
+        assert_eq!(api.base_url, "http://api.steampowered.com");
+        assert_eq!(api.language, "en");
+        assert_eq!(api.proxy, None);
+    }
+
+    #[tokio::test]
+    async fn test_requests_use_configured_language() {
+        let mut server = mockito::Server::new_async().await;
+        let url = server.url();
+
+        let player_mock = server.mock("GET", "/ISteamUserStats/GetPlayerAchievements/v0001/?appid=1&key=test_key&steamid=test_id&l=german")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{
+                "playerstats": {
+                    "steamID": "test_id",
+                    "gameName": "Test Game",
+                    "achievements": [],
+                    "success": true
+                }
+            }"#)
+            .create_async().await;
+
+        let global_mock = server.mock("GET", "/ISteamUserStats/GetGlobalAchievementPercentagesForApp/v0002/?gameid=1&format=json&l=german")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{ "achievementpercentages": { "achievements": [] } }"#)
+            .create_async().await;
+
+        let api = Api::new("test_key".to_string(), "test_id".to_string(), url)
+            .with_language("german".to_string());
+
+        assert!(api.get_game_achievements(1).await.is_ok());
+        assert!(api.get_global_achievements(1).await.is_ok());
+
+        player_mock.assert_async().await;
+        global_mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_get_games_list_success() {
+        let mut server = mockito::Server::new_async().await;
+        let url = server.url();
+
+        let _m = server.mock("GET", "/IPlayerService/GetOwnedGames/v0001/?key=test_key&steamid=test_id&format=json&include_appinfo=1")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{
+                "response": {
+                    "game_count": 1,
+                    "games": [
+                        {
+                            "appid": 1,
+                            "name": "Test Game",
+                            "playtime_forever": 100,
+                            "img_icon_url": "",
+                            "playtime_windows_forever": 100,
+                            "playtime_mac_forever": 0,
+                            "playtime_linux_forever": 0,
+                            "rtime_last_played": 0,
+                            "playtime_disconnected": 0
+                        }
+                    ]
+                }
+            }"#)
+            .create_async().await;
+
+        let api = Api::new("test_key".to_string(), "test_id".to_string(), url);
+        let games = api.get_games_list().await.unwrap();
+
+        assert_eq!(games.len(), 1);
+        assert_eq!(games[0].name, "Test Game");
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    async fn mock_vanity_url(server: &mut mockito::Server, vanity: &str, body: &str) -> mockito::Mock {
+        server.mock("GET", format!("/ISteamUser/ResolveVanityURL/v0001/?key=test_key&vanityurl={vanity}").as_str())
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(body)
+            .create_async()
+            .await
+    }
 
     #[tokio::test]
-    async fn test_new_api() {
-        let api = Api::new(
-            "test_key".to_string(),
-            "test_id".to_string(),
-            constants::STEAM_API_BASE_URL.to_string(),
+    async fn test_resolve_steam_identity_profiles_url() {
+        let api = Api::new("test_key".to_string(), "".to_string(), "http://127.0.0.1:1".to_string());
+
+        let steam_id = resolve_steam_identity("https://steamcommunity.com/profiles/76561197960287930/", &api, None, false).await.unwrap();
+        assert_eq!(steam_id, "76561197960287930");
+
+        let steam_id = resolve_steam_identity("76561197960287930", &api, None, false).await.unwrap();
+        assert_eq!(steam_id, "76561197960287930");
+    }
+
+    #[tokio::test]
+    async fn test_resolve_steam_identity_vanity_url() {
+        let mut server = mockito::Server::new_async().await;
+        let _m = mock_vanity_url(&mut server, "gabelogannewell", r#"{ "response": { "steamid": "76561197960287930", "success": 1 } }"#).await;
+        let api = Api::new("test_key".to_string(), "".to_string(), server.url());
+
+        let steam_id = resolve_steam_identity("https://steamcommunity.com/id/gabelogannewell/", &api, None, false).await.unwrap();
+        assert_eq!(steam_id, "76561197960287930");
+    }
+
+    #[tokio::test]
+    async fn test_resolve_steam_identity_bare_vanity_name() {
+        let mut server = mockito::Server::new_async().await;
+        let _m = mock_vanity_url(&mut server, "gabelogannewell", r#"{ "response": { "steamid": "76561197960287930", "success": 1 } }"#).await;
+        let api = Api::new("test_key".to_string(), "".to_string(), server.url());
+
+        let steam_id = resolve_steam_identity("gabelogannewell", &api, None, false).await.unwrap();
+        assert_eq!(steam_id, "76561197960287930");
+    }
+
+    #[tokio::test]
+    async fn test_resolve_steam_identity_unknown_vanity_name() {
+        let mut server = mockito::Server::new_async().await;
+        let _m = mock_vanity_url(&mut server, "nobody", r#"{ "response": { "success": 42, "message": "No match" } }"#).await;
+        let api = Api::new("test_key".to_string(), "".to_string(), server.url());
+
+        let result = resolve_steam_identity("nobody", &api, None, false).await;
+        assert!(matches!(result, Err(ApiError::VanityNotFound(name)) if name == "nobody"));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_steam_identity_reuses_stored_vanity_name() {
+        let mut server = mockito::Server::new_async().await;
+        let m = mock_vanity_url(&mut server, "gabelogannewell", r#"{ "response": { "steamid": "76561197960287930", "success": 1 } }"#)
+            .await
+            .expect(1);
+        let api = Api::new("test_key".to_string(), "".to_string(), server.url());
+        let path = std::env::temp_dir()
+            .join(format!("trogue-steam-api-test-{}-vanity", std::process::id()))
+            .join("vanity_ids.json");
+        let store = VanityIdStore::new(path.clone());
+
+        let first = resolve_steam_identity("gabelogannewell", &api, Some(&store), false).await.unwrap();
+        let second = resolve_steam_identity("https://steamcommunity.com/id/gabelogannewell", &api, Some(&store), false).await.unwrap();
+
+        assert_eq!(first, "76561197960287930");
+        assert_eq!(second, "76561197960287930");
+        m.assert_async().await;
+
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_resolve_steam_identity_refresh_resolves_again() {
+        let mut server = mockito::Server::new_async().await;
+        let m = mock_vanity_url(&mut server, "gabelogannewell", r#"{ "response": { "steamid": "76561197960287930", "success": 1 } }"#)
+            .await
+            .expect(2);
+        let api = Api::new("test_key".to_string(), "".to_string(), server.url());
+        let path = std::env::temp_dir()
+            .join(format!("trogue-steam-api-test-{}-vanity-refresh", std::process::id()))
+            .join("vanity_ids.json");
+        let store = VanityIdStore::new(path.clone());
+        store.save("gabelogannewell", "76561197960287999", Utc::now().timestamp()).unwrap();
+
+        let steam_id = resolve_steam_identity("gabelogannewell", &api, Some(&store), true).await.unwrap();
+        assert_eq!(steam_id, "76561197960287930");
+        assert_eq!(store.load("gabelogannewell", Utc::now().timestamp()).as_deref(), Some("76561197960287930"));
+
+        resolve_steam_identity("gabelogannewell", &api, Some(&store), true).await.unwrap();
+        m.assert_async().await;
+
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_identity_resolution_resolves_once_for_player_endpoints() {
+        let mut server = mockito::Server::new_async().await;
+        let vanity = mock_vanity_url(&mut server, "gabelogannewell", r#"{ "response": { "steamid": "76561197960287930", "success": 1 } }"#)
+            .await
+            .expect(1);
+        let _global = server.mock("GET", "/ISteamUserStats/GetGlobalAchievementPercentagesForApp/v0002/?gameid=440&format=json&l=en")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{ "achievementpercentages": { "achievements": [] } }"#)
+            .create_async()
+            .await;
+        let _count = server.mock("GET", "/IPlayerService/GetOwnedGames/v0001/?key=test_key&steamid=76561197960287930&format=json")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{ "response": { "game_count": 3 } }"#)
+            .expect(2)
+            .create_async()
+            .await;
+        let api = Api::new("test_key".to_string(), "gabelogannewell".to_string(), server.url()).with_identity_resolution(None, false);
+
+        api.get_global_achievements(440).await.unwrap();
+        assert!(!vanity.matched_async().await);
+
+        assert_eq!(api.get_games_count().await.unwrap(), 3);
+        assert_eq!(api.get_games_count().await.unwrap(), 3);
+        vanity.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_identity_resolution_dry_run_keeps_vanity_name() {
+        let api = Api::new("test_key".to_string(), "https://steamcommunity.com/id/gabelogannewell".to_string(), "http://127.0.0.1:1".to_string())
+            .with_identity_resolution(None, false)
+            .with_dry_run(true);
+
+        assert!(matches!(api.get_games_count().await, Err(ApiError::DryRun)));
+        assert_eq!(
+            api.planned_requests(),
+            vec!["http://127.0.0.1:1/IPlayerService/GetOwnedGames/v0001/?key=***&steamid=gabelogannewell&format=json".to_string()]
         );
-        assert_eq!(api.api_key, "test_key");
-        assert_eq!(api.steam_id, "test_id");
-        assert_eq!(api.base_url, "http://api.steampowered.com");
     }
 
     #[tokio::test]
-    async fn test_get_games_list_success() {
+    async fn test_get_recently_played_games_success() {
         let mut server = mockito::Server::new_async().await;
         let url = server.url();
 
-        let _m = server.mock("GET", "/IPlayerService/GetOwnedGames/v0001/?key=test_key&steamid=test_id&format=json&include_appinfo=1")
+        let _m = server.mock("GET", "/IPlayerService/GetRecentlyPlayedGames/v0001/?key=test_key&steamid=test_id&count=5&format=json")
             .with_status(200)
             .with_header("content-type", "application/json")
             .with_body(r#"{
                 "response": {
-                    "game_count": 1,
+                    "total_count": 1,
                     "games": [
                         {
                             "appid": 1,
                             "name": "Test Game",
+                            "playtime_2weeks": 60,
                             "playtime_forever": 100,
                             "img_icon_url": "",
                             "playtime_windows_forever": 100,
                             "playtime_mac_forever": 0,
-                            "playtime_linux_forever": 0,
-                            "rtime_last_played": 0,
-                            "playtime_disconnected": 0
+                            "playtime_linux_forever": 0
                         }
                     ]
                 }
@@ -257,10 +1996,28 @@ mod tests {
             .create_async().await;
 
         let api = Api::new("test_key".to_string(), "test_id".to_string(), url);
-        let games = api.get_games_list().await.unwrap();
+        let games = api.get_recently_played_games(5).await.unwrap();
 
         assert_eq!(games.len(), 1);
         assert_eq!(games[0].name, "Test Game");
+        assert_eq!(games[0].rtime_last_played, 0);
+    }
+
+    #[tokio::test]
+    async fn test_get_recently_played_games_empty() {
+        let mut server = mockito::Server::new_async().await;
+        let url = server.url();
+
+        let _m = server.mock("GET", "/IPlayerService/GetRecentlyPlayedGames/v0001/?key=test_key&steamid=test_id&count=10&format=json")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"response":{}}"#)
+            .create_async().await;
+
+        let api = Api::new("test_key".to_string(), "test_id".to_string(), url);
+        let games = api.get_recently_played_games(10).await.unwrap();
+
+        assert!(games.is_empty());
     }
 
     #[tokio::test]
@@ -278,6 +2035,141 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn test_retries_server_errors() {
+        let mut server = mockito::Server::new_async().await;
+        let url = server.url();
+
+        let mock = server.mock("GET", "/IPlayerService/GetOwnedGames/v0001/?key=test_key&steamid=test_id&format=json&include_appinfo=1")
+            .with_status(503)
+            .expect(3)
+            .create_async().await;
+
+        let api = Api::new("test_key".to_string(), "test_id".to_string(), url).with_retries(2);
+        let result = api.get_games_list().await;
+
+        assert!(matches!(result, Err(ApiError::Status(_))));
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_html_error_page_is_unexpected_response() {
+        let mut server = mockito::Server::new_async().await;
+        let url = server.url();
+
+        let mock = server.mock("GET", "/IPlayerService/GetOwnedGames/v0001/?key=test_key&steamid=test_id&format=json&include_appinfo=1")
+            .with_status(200)
+            .with_header("content-type", "text/html; charset=UTF-8")
+            .with_body("<html>\n  <head><title>Error</title></head>\n  <body>The server is busy</body>\n</html>")
+            .expect(2)
+            .create_async().await;
+
+        let api = Api::new("test_key".to_string(), "test_id".to_string(), url).with_retries(1);
+        let result = api.get_games_list().await;
+
+        match result {
+            Err(ApiError::UnexpectedResponse(snippet)) => {
+                assert_eq!(snippet, "<html> <head><title>Error</title></head> <body>The server is busy</body> </html>")
+            }
+            other => panic!("expected an unexpected response error, got {:?}", other),
+        }
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_unlabelled_non_json_body_is_unexpected_response() {
+        let mut server = mockito::Server::new_async().await;
+        let url = server.url();
+
+        server.mock("GET", "/IPlayerService/GetOwnedGames/v0001/?key=test_key&steamid=test_id&format=json&include_appinfo=1")
+            .with_status(200)
+            .with_body("<html>Service Unavailable</html>")
+            .create_async().await;
+
+        let api = Api::new("test_key".to_string(), "test_id".to_string(), url);
+        let result = api.get_games_list().await;
+
+        assert!(matches!(result, Err(ApiError::UnexpectedResponse(_))));
+    }
+
+    #[tokio::test]
+    async fn test_json_with_unexpected_structure_is_decode_error() {
+        let mut server = mockito::Server::new_async().await;
+        let url = server.url();
+
+        server.mock("GET", "/IPlayerService/GetOwnedGames/v0001/?key=test_key&steamid=test_id&format=json&include_appinfo=1")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{ "unexpected": true }"#)
+            .create_async().await;
+
+        let api = Api::new("test_key".to_string(), "test_id".to_string(), url);
+        let result = api.get_games_list().await;
+
+        assert!(matches!(result, Err(ApiError::Decode(_))));
+    }
+
+    #[test]
+    fn test_body_snippet() {
+        assert_eq!(body_snippet("  \n "), "(empty body)");
+        assert_eq!(body_snippet("short"), "short");
+
+        let snippet = body_snippet(&"é".repeat(SNIPPET_LENGTH + 10));
+        assert_eq!(snippet.chars().count(), SNIPPET_LENGTH + 1);
+        assert!(snippet.ends_with('…'));
+    }
+
+    #[tokio::test]
+    async fn test_does_not_retry_unauthorized() {
+        let mut server = mockito::Server::new_async().await;
+        let url = server.url();
+
+        let mock = server.mock("GET", "/IPlayerService/GetOwnedGames/v0001/?key=test_key&steamid=test_id&format=json&include_appinfo=1")
+            .with_status(401)
+            .expect(1)
+            .create_async().await;
+
+        let api = Api::new("test_key".to_string(), "test_id".to_string(), url).with_retries(2);
+        let result = api.get_games_list().await;
+
+        assert!(matches!(result, Err(ApiError::Unauthorized(_))));
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_get_games_list_private_profile() {
+        let mut server = mockito::Server::new_async().await;
+        let url = server.url();
+
+        let _m = server.mock("GET", "/IPlayerService/GetOwnedGames/v0001/?key=test_key&steamid=test_id&format=json&include_appinfo=1")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"response":{}}"#)
+            .create_async().await;
+
+        let api = Api::new("test_key".to_string(), "test_id".to_string(), url);
+        let result = api.get_games_list().await;
+
+        assert!(matches!(result, Err(ApiError::ProfilePrivate)));
+    }
+
+    #[tokio::test]
+    async fn test_get_games_list_without_games() {
+        let mut server = mockito::Server::new_async().await;
+        let url = server.url();
+
+        let _m = server.mock("GET", "/IPlayerService/GetOwnedGames/v0001/?key=test_key&steamid=test_id&format=json&include_appinfo=1")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"response":{"game_count":0}}"#)
+            .create_async().await;
+
+        let api = Api::new("test_key".to_string(), "test_id".to_string(), url);
+        let games = api.get_games_list().await.unwrap();
+
+        assert!(games.is_empty());
+    }
+
     #[tokio::test]
     async fn test_get_game_achievements_success() {
         let mut server = mockito::Server::new_async().await;
@@ -305,11 +2197,71 @@ mod tests {
             .create_async().await;
 
         let api = Api::new("test_key".to_string(), "test_id".to_string(), url);
-        let (game_name, achievements) = api.get_game_achievements(1).await.unwrap();
+        let game_achievements = api.get_game_achievements(1).await.unwrap();
 
-        assert_eq!(game_name, "Test Game");
-        assert_eq!(achievements.len(), 1);
-        assert_eq!(achievements[0].name, "Test Achievement");
+        assert_eq!(game_achievements.game_name, "Test Game");
+        assert_eq!(game_achievements.achievements.len(), 1);
+        assert_eq!(game_achievements.achievements[0].name, "Test Achievement");
+    }
+
+    #[tokio::test]
+    async fn test_get_raw_returns_body_unchanged() {
+        let mut server = mockito::Server::new_async().await;
+        let body = "{ \"playerstats\": {\"error\": \"Requested app has no stats\", \"success\": false} }\n";
+        let _m = server.mock("GET", "/ISteamUserStats/GetPlayerAchievements/v0001/?appid=1&key=test_key&steamid=test_id&l=en")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(body)
+            .create_async().await;
+
+        let api = Api::new("test_key".to_string(), "test_id".to_string(), server.url());
+
+        assert_eq!(api.get_game_achievements_raw(1).await.unwrap(), body);
+        assert!(matches!(api.get_game_achievements(1).await, Err(ApiError::Decode(_))));
+    }
+
+    #[tokio::test]
+    async fn test_default_raw_renders_steam_response() {
+        let api = FakeApi::new()
+            .with_game(440, "Team Fortress 2")
+            .with_achievements(440, "Team Fortress 2", vec![create_achievement(1, 100)]);
+
+        let games: GamesListResponse = serde_json::from_str(&api.get_games_list_raw().await.unwrap()).unwrap();
+        assert_eq!(games.response.game_count, Some(1));
+        assert_eq!(games.response.games[0].name, "Team Fortress 2");
+
+        let stats: PlayerStatsResponse = serde_json::from_str(&api.get_game_achievements_raw(440).await.unwrap()).unwrap();
+        assert_eq!(stats.playerstats.game_name, "Team Fortress 2");
+        assert_eq!(stats.playerstats.achievements.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_get_game_achievements_without_names() {
+        let mut server = mockito::Server::new_async().await;
+        let url = server.url();
+
+        let _m = server.mock("GET", "/ISteamUserStats/GetPlayerAchievements/v0001/?appid=1&key=test_key&steamid=test_id&l=en")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{
+                "playerstats": {
+                    "steamID": "test_id",
+                    "gameName": "Test Game",
+                    "achievements": [
+                        { "apiname": "ACH_WIN_ONE_GAME", "achieved": 1, "unlocktime": 1700000000 },
+                        { "apiname": "ACH_NAMED", "achieved": 0, "unlocktime": 0, "name": "Named", "description": "" }
+                    ],
+                    "success": true
+                }
+            }"#)
+            .create_async().await;
+
+        let api = Api::new("test_key".to_string(), "test_id".to_string(), url);
+        let game_achievements = api.get_game_achievements(1).await.unwrap();
+
+        assert_eq!(game_achievements.achievements[0].name, "ACH_WIN_ONE_GAME");
+        assert_eq!(game_achievements.achievements[0].description, "");
+        assert_eq!(game_achievements.achievements[1].name, "Named");
     }
 
     #[tokio::test]
@@ -369,4 +2321,147 @@ mod tests {
 
         assert!(result.is_err());
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_get_game_schema_success() {
+        let mut server = mockito::Server::new_async().await;
+        let _m = server.mock("GET", "/ISteamUserStats/GetSchemaForGame/v2/?appid=1&key=test_key&l=en")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"game": {"gameName": "Test Game", "gameVersion": "1", "availableGameStats": {"achievements": [
+                {"name": "a1", "defaultvalue": 0, "displayName": "Secret", "hidden": 1, "description": "Find the secret", "icon": "", "icongray": ""},
+                {"name": "a2", "defaultvalue": 0, "displayName": "Plain", "hidden": 0, "icon": "", "icongray": ""}
+            ]}}}"#)
+            .create_async().await;
+
+        let api = Api::new("test_key".to_string(), "test_id".to_string(), server.url());
+        let schema = api.get_game_schema(1).await.unwrap();
+
+        assert_eq!(schema.len(), 2);
+        assert!(schema[0].is_hidden());
+        assert_eq!(schema[0].description, "Find the secret");
+        assert!(!schema[1].is_hidden());
+        assert_eq!(schema[1].description, "");
+    }
+
+    #[tokio::test]
+    async fn test_get_game_schema_without_stats() {
+        let mut server = mockito::Server::new_async().await;
+        let _m = server.mock("GET", "/ISteamUserStats/GetSchemaForGame/v2/?appid=1&key=test_key&l=en")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"game": {}}"#)
+            .create_async().await;
+
+        let api = Api::new("test_key".to_string(), "test_id".to_string(), server.url());
+
+        assert!(api.get_game_schema(1).await.unwrap().is_empty());
+    }
+
+    fn create_achievement(achieved: u8, unlocktime: u64) -> Achievement {
+        Achievement {
+            apiname: "test_ach".to_string(),
+            achieved,
+            unlocktime,
+            name: "Test Achievement".to_string(),
+            description: "A test achievement".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_achievement_is_unlocked() {
+        assert!(create_achievement(1, 1672531200).is_unlocked());
+        assert!(create_achievement(1, 0).is_unlocked());
+        assert!(!create_achievement(0, 0).is_unlocked());
+    }
+
+    #[test]
+    fn test_game_last_played_datetime() {
+        let mut game = Game {
+            appid: 1,
+            name: "Test Game".to_string(),
+            playtime_forever: 0,
+            img_icon_url: "".to_string(),
+            playtime_windows_forever: 0,
+            playtime_mac_forever: 0,
+            playtime_linux_forever: 0,
+            rtime_last_played: 1672531200,
+            playtime_disconnected: 0,
+        };
+        assert_eq!(game.last_played_datetime(), Some(Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap()));
+
+        // Never played.
+        game.rtime_last_played = 0;
+        assert_eq!(game.last_played_datetime(), None);
+    }
+
+    #[test]
+    fn test_achievement_unlock_datetime() {
+        let unlocked = create_achievement(1, 1672531200);
+        assert_eq!(
+            unlocked.unlock_datetime(),
+            Some(Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap())
+        );
+
+        // Unlocked, but Steam did not record when.
+        assert_eq!(create_achievement(1, 0).unlock_datetime(), None);
+
+        assert_eq!(create_achievement(0, 0).unlock_datetime(), None);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_game_achievements_caps_concurrency() {
+        let mut api = FakeApi::new().with_delay(Duration::from_millis(20));
+        for appid in 1..=10 {
+            api = api.with_achievements(appid, &format!("Game {}", appid), vec![]);
+        }
+
+        let results: Vec<_> = fetch_game_achievements(&api, (1..=10).collect(), 3).collect().await;
+
+        let names: Vec<String> = results.into_iter().map(|r| r.unwrap().game_name).collect();
+        assert_eq!(names, (1..=10).map(|appid| format!("Game {}", appid)).collect::<Vec<_>>());
+        assert_eq!(api.max_in_flight(), 3);
+    }
+
+    fn create_game_achievements(achieved: &[u8]) -> GameAchievements {
+        GameAchievements {
+            game_name: "Test Game".to_string(),
+            achievements: achieved.iter().map(|&achieved| create_achievement(achieved, 0)).collect(),
+        }
+    }
+
+    #[test]
+    fn test_game_achievements_progress() {
+        let game_achievements = create_game_achievements(&[1, 0, 1, 0]);
+        assert_eq!(game_achievements.total(), 4);
+        assert_eq!(game_achievements.completed(), 2);
+        assert_eq!(game_achievements.percentage(), 50.0);
+
+        assert_eq!(create_game_achievements(&[1, 1, 1]).percentage(), 100.0);
+    }
+
+    #[test]
+    fn test_game_achievements_percentage_without_achievements() {
+        let game_achievements = create_game_achievements(&[]);
+        assert_eq!(game_achievements.total(), 0);
+        assert_eq!(game_achievements.completed(), 0);
+        assert_eq!(game_achievements.percentage(), 0.0);
+        assert!(!game_achievements.percentage().is_nan());
+    }
+
+    #[test]
+    fn test_progress_summary_display() {
+        assert_eq!(create_game_achievements(&[1, 1, 0]).summary().to_string(), "66.7% (2/3)");
+        assert_eq!(create_game_achievements(&[1]).summary().to_string(), "100.0% (1/1)");
+        assert_eq!(create_game_achievements(&[]).summary().to_string(), "0.0% (0/0)");
+    }
+
+    #[test]
+    fn test_progress_summary_serialization() {
+        let summary = create_game_achievements(&[1, 0, 0, 0]).summary();
+        assert_eq!(
+            serde_json::to_value(&summary).unwrap(),
+            serde_json::json!({ "game_name": "Test Game", "total": 4, "completed": 1, "percentage": 25.0 })
+        );
+    }
+}
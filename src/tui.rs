@@ -1,125 +1,299 @@
+use crate::steam_api::Game;
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers};
+use crossterm::{cursor, execute, terminal};
+use std::io::{self, stdout, Write};
+
+// The prompt shown on the first line of the game picker, followed by the search query.
+const PROMPT: &str = "Select a game (type to filter, Enter to pick, Esc to cancel): ";
+
+// Represents the outcome of a key press in the game picker.
+#[derive(Debug, PartialEq)]
+pub enum PickerAction {
+    // Keep reading keys.
+    Continue,
+    // The user picked the game with the given id.
+    Select(u32),
+    // The user left the picker without picking a game.
+    Cancel,
+}
+
+// Holds the state of the game picker.
+//
+// <purpose-start>
+// The key handling is kept apart from the terminal, so that the selection logic can be tested
+// without a TTY. The games are sorted by name once, and filtered by the query on every key press.
+// <purpose-end>
+pub struct GamePicker {
+    games: Vec<Game>,
+    query: String,
+    selected: usize,
+}
+
+impl GamePicker {
+    // Creates a picker over the given games.
+    //
+    // <inputs-start>
+    // - `games`: The games to choose from.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `GamePicker`: A picker with an empty query and the first game selected.
+    // <outputs-end>
+    pub fn new(mut games: Vec<Game>) -> GamePicker {
+        games.sort_by_key(|g| g.name.to_lowercase());
+        GamePicker {
+            games,
+            query: String::new(),
+            selected: 0,
+        }
+    }
+
+    // Returns the games whose name contains the query, ignoring case.
+    pub fn filtered(&self) -> Vec<&Game> {
+        let query = self.query.to_lowercase();
+        self.games
+            .iter()
+            .filter(|g| g.name.to_lowercase().contains(&query))
+            .collect()
+    }
+
+    // Returns the position of the selected game among the filtered games.
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    // Applies a key press to the picker.
+    //
+    // <purpose-start>
+    // Typing edits the query and moves the selection back to the first match, the arrow keys move
+    // the selection within the matches. Ctrl+C cancels as well, as raw mode does not turn it into a signal.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - `key`: The key pressed by the user.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `PickerAction`: Whether to keep reading keys, and the picked game if any.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - Updates the query and the selection.
+    // <side-effects-end>
+    pub fn handle_key(&mut self, key: KeyEvent) -> PickerAction {
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return PickerAction::Cancel,
+            KeyCode::Esc => return PickerAction::Cancel,
+            KeyCode::Enter => {
+                if let Some(game) = self.filtered().get(self.selected) {
+                    return PickerAction::Select(game.appid);
+                }
+            }
+            KeyCode::Char(c) => {
+                self.query.push(c);
+                self.selected = 0;
+            }
+            KeyCode::Backspace => {
+                self.query.pop();
+                self.selected = 0;
+            }
+            KeyCode::Up => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down if self.selected + 1 < self.filtered().len() => self.selected += 1,
+            _ => {}
+        }
+
+        PickerAction::Continue
+    }
+}
+
+// Restores the terminal when the picker exits, including on errors.
+struct TerminalGuard;
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = execute!(stdout(), terminal::LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+// Returns the first of the filtered games to display, so that the selected one stays visible.
+//
+// <inputs-start>
+// - `selected`: The position of the selected game.
+// - `rows`: The number of rows available for games, at least 1.
+// <inputs-end>
+//
+// <outputs-start>
+// - `usize`: The position of the first game to display.
+// <outputs-end>
+pub fn scroll_offset(selected: usize, rows: usize) -> usize {
+    selected.saturating_sub(rows.max(1) - 1)
+}
+
+// Draws the picker on the alternate screen.
+//
+// <inputs-start>
+// - `picker`: The picker to draw.
+// <inputs-end>
+//
+// <outputs-start>
+// - `Ok(())` if the picker was drawn, `Err(io::Error)` otherwise.
+// <outputs-end>
+//
+// <side-effects-start>
+// - **Prints to the console**: Redraws the whole screen.
+// <side-effects-end>
+fn draw(picker: &GamePicker) -> io::Result<()> {
+    let mut out = stdout();
+    let rows = terminal::size().map(|(_, height)| height as usize).unwrap_or(24).saturating_sub(1).max(1);
+    let games = picker.filtered();
+    let offset = scroll_offset(picker.selected(), rows);
+
+    execute!(out, terminal::Clear(terminal::ClearType::All), cursor::MoveTo(0, 0))?;
+    write!(out, "{}{}", PROMPT, picker.query)?;
+
+    for (row, (index, game)) in games.iter().enumerate().skip(offset).take(rows).enumerate() {
+        let marker = if index == picker.selected() { '>' } else { ' ' };
+        execute!(out, cursor::MoveTo(0, (row + 1) as u16))?;
+        write!(out, "{} {} ({})", marker, game.name, game.appid)?;
+    }
+
+    let column = u16::try_from(PROMPT.len() + picker.query.chars().count()).unwrap_or(u16::MAX);
+    execute!(out, cursor::MoveTo(column, 0))?;
+    out.flush()
+}
+
 // Allows the user to select a game from a list.
 //
 // <purpose-start>
 // This function provides a text-based user interface for selecting a game from a list.
-// It allows the user to filter the list by typing a search query.
+// It allows the user to filter the list by typing a search query and to move the selection with
+// the arrow keys. It must only be called when the standard output is a terminal.
 // <purpose-end>
 //
 // <inputs-start>
-// - `games`: A vector of `steam_api::Game` structs to select from.
+// - `games`: The games to select from.
 // <inputs-end>
 //
 // <outputs-start>
-// - None.
+// - `Ok(Some(u32))`: The id of the selected game.
+// - `Ok(None)`: If the user cancelled the selection.
+// - `Err(io::Error)`: If the terminal could not be controlled.
 // <outputs-end>
 //
 // <side-effects-start>
-// - **Enters raw mode**: The terminal is put into raw mode to handle key events.
-// - **Clears the screen**: The terminal screen is cleared.
-// - **Prints to the console**: The list of games is printed to the console.
+// - **Enters raw mode**: The terminal is put into raw mode to handle key events, and restored afterwards.
+// - **Uses the alternate screen**: The picker is drawn on the alternate screen, leaving the scrollback intact.
+// - **Blocks**: Waits for key presses.
 // <side-effects-end>
-//
-// # Note
-// This function is currently not used in the application.
-fn select_game(games: &Vec<steam_api::Game>) {
-    // let mut idx = 0;
-    // for game in games {
-    //     idx += 1;
-    //     println!("[{}] {}", idx, game.name);
-    // }
-
-    let mut name_filter = String::new();
-
-    // Initialize term to enter raw mode
-    terminal::enable_raw_mode().expect("Failed to enable terminal raw mode");
-
-    // Clear terminal screen
-    execute!(
-        stdout(),
-        cursor::MoveTo(0, 0),
-        terminal::Clear(terminal::ClearType::All)
-    )
-    .unwrap();
+pub fn select_game(games: Vec<Game>) -> io::Result<Option<u32>> {
+    let mut picker = GamePicker::new(games);
 
-    loop {
-        // io::stdout().flush().map_err(|e| e.to_string())?;
+    terminal::enable_raw_mode()?;
+    let _guard = TerminalGuard;
+    execute!(stdout(), terminal::EnterAlternateScreen)?;
 
-        // io::stdin().read_line(&mut name_filter).map_err(|e| e.to_string())?;
+    loop {
+        draw(&picker)?;
 
-        // io::stdin().read_to_string(name_filter).map_err(|e| e.to_string());
+        if let Event::Key(key) = event::read()? {
+            match picker.handle_key(key) {
+                PickerAction::Continue => {}
+                PickerAction::Select(appid) => return Ok(Some(appid)),
+                PickerAction::Cancel => return Ok(None),
+            }
+        }
+    }
+}
 
-        // name_filter = name_filter.trim().to_string();
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        /*let mut filtered_games = games.iter().filter(|game| {
-            if name_filter.len() == 0 {
-                return true;
-            }
-            return game.name.to_lowercase().contains(&name_filter.to_lowercase());
-        }).collect::<Vec<&steam_api::Game>>();
-
-        if filtered_games.len() == 0 {
-            println!("No games found.");
-            continue;
-        }*/
-
-        // Read the next event from the terminal
-        if let Event::Key(key_event) = crossterm::event::read().expect("Failed to read key event") {
-            match key_event.code {
-                KeyCode::Char(c) => {
-                    // Append the character to the filter
-                    name_filter.push(c);
-                }
-                KeyCode::Backspace => {
-                    // Remove the last character from the filter
-                    name_filter.pop();
-                }
-                KeyCode::Esc | KeyCode::Enter => {
-                    break;
-                }
-                _ => {}
-            }
+    fn create_game(appid: u32, name: &str) -> Game {
+        Game {
+            appid,
+            name: name.to_string(),
+            playtime_forever: 0,
+            img_icon_url: "".to_string(),
+            playtime_windows_forever: 0,
+            playtime_mac_forever: 0,
+            playtime_linux_forever: 0,
+            rtime_last_played: 0,
+            playtime_disconnected: 0,
         }
+    }
+
+    fn create_picker() -> GamePicker {
+        GamePicker::new(vec![
+            create_game(620, "Portal 2"),
+            create_game(400, "Portal"),
+            create_game(70, "Half-Life"),
+        ])
+    }
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn names(picker: &GamePicker) -> Vec<&str> {
+        picker.filtered().iter().map(|g| g.name.as_str()).collect()
+    }
 
-        execute!(
-            stdout(),
-            cursor::MoveTo(0, 0),
-            terminal::Clear(terminal::ClearType::All)
-        )
-        .unwrap();
-        print!("{}\n", name_filter);
-
-        // Filter the games based on the current filter input
-        let mut filtered_games = games.clone();
-        filtered_games.retain(|entry| {
-            entry
-                .name
-                .to_lowercase()
-                .contains(&name_filter.to_lowercase())
-        });
-
-        // Print out the filtered list
-        let mut idx = 0;
-        for game in filtered_games {
-            idx += 1;
-            execute!(stdout(), cursor::MoveTo(0, idx)).unwrap();
-            println!("{}", game.name);
+    #[test]
+    fn test_games_sorted_by_name() {
+        assert_eq!(names(&create_picker()), vec!["Half-Life", "Portal", "Portal 2"]);
+    }
+
+    #[test]
+    fn test_typing_filters_games() {
+        let mut picker = create_picker();
+        for c in "PORT".chars() {
+            assert_eq!(picker.handle_key(key(KeyCode::Char(c))), PickerAction::Continue);
         }
+        assert_eq!(names(&picker), vec!["Portal", "Portal 2"]);
+
+        picker.handle_key(key(KeyCode::Char('x')));
+        assert!(picker.filtered().is_empty());
+        assert_eq!(picker.handle_key(key(KeyCode::Enter)), PickerAction::Continue);
 
-        // Move the cursor to end of first line
-        let name_length: u16 = name_filter
-            .len()
-            .try_into()
-            .expect("Name length too long to fit into u16");
-        execute!(stdout(), cursor::MoveTo(name_length, 0)).unwrap();
+        picker.handle_key(key(KeyCode::Backspace));
+        assert_eq!(names(&picker), vec!["Portal", "Portal 2"]);
     }
 
-    // Reset terminal mode
-    terminal::disable_raw_mode().expect("Failed to disable the raw mode");
-}
+    #[test]
+    fn test_arrow_keys_move_selection_within_matches() {
+        let mut picker = create_picker();
+        picker.handle_key(key(KeyCode::Up));
+        assert_eq!(picker.selected(), 0);
+
+        for _ in 0..5 {
+            picker.handle_key(key(KeyCode::Down));
+        }
+        assert_eq!(picker.selected(), 2);
+        assert_eq!(picker.handle_key(key(KeyCode::Enter)), PickerAction::Select(620));
 
-/*print!("Please select game [1 - {}]: ", filtered_games.len());
-io::stdout().flush().map_err(|e| e.to_string())?;
+        picker.handle_key(key(KeyCode::Char('h')));
+        assert_eq!(picker.selected(), 0);
+        assert_eq!(picker.handle_key(key(KeyCode::Enter)), PickerAction::Select(70));
+    }
 
-let game = games.get(entered_idx as usize - 1).ok_or("Invalid game index.")?;
+    #[test]
+    fn test_cancel() {
+        let mut picker = create_picker();
+        assert_eq!(picker.handle_key(key(KeyCode::Esc)), PickerAction::Cancel);
+        assert_eq!(
+            picker.handle_key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL)),
+            PickerAction::Cancel
+        );
+    }
 
-return Ok(game);*/
+    #[test]
+    fn test_scroll_offset() {
+        assert_eq!(scroll_offset(0, 10), 0);
+        assert_eq!(scroll_offset(9, 10), 0);
+        assert_eq!(scroll_offset(10, 10), 1);
+        assert_eq!(scroll_offset(3, 1), 3);
+        assert_eq!(scroll_offset(3, 0), 3);
+    }
+}
@@ -1,7 +1,14 @@
-use chrono::{TimeZone, Utc};
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, Local, TimeZone, Utc};
+use crossterm::style::Stylize;
+use std::io::{IsTerminal, Write};
+use unicode_width::UnicodeWidthChar;
 
+use crate::cfg::Cfg;
 use crate::steam_api::{Achievement, Game};
 
+pub mod table;
+
 // Prints the application title to the console.
 //
 // <purpose-start>
@@ -87,9 +94,185 @@ pub fn print_game_id(game: &Game) {
     println!("{}", game.appid);
 }
 
+// The alignment of a field with a width in a format pattern.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Align {
+    Left,
+    Right,
+}
+
+// A piece of a format pattern.
+//
+// <purpose-start>
+// Patterns mix literal text with single-letter fields. A field is either a bare letter, e.g. `n`,
+// or a letter in braces with an optional width, e.g. `{n:20}` or `{i:>6}`.
+// <purpose-end>
+#[derive(Debug, Clone, PartialEq)]
+enum FormatToken {
+    Literal(char),
+    Field { name: char, width: Option<usize>, align: Align },
+}
+
+// Parses the braced form of a field, starting after the opening brace.
+//
+// <inputs-start>
+// - `chars`: The pattern characters following the opening brace.
+// - `fields`: The letters of the known fields.
+// <inputs-end>
+//
+// <outputs-start>
+// - `Some((FormatToken, usize))`: The field and the number of characters consumed, including the closing brace.
+// - `None`: If the characters do not form a braced field.
+// <outputs-end>
+fn parse_braced_field(chars: &[char], fields: &str) -> Option<(FormatToken, usize)> {
+    let name = *chars.first().filter(|c| fields.contains(**c))?;
+
+    match chars.get(1)? {
+        '}' => return Some((FormatToken::Field { name, width: None, align: Align::Left }, 2)),
+        ':' => {}
+        _ => return None,
+    }
+
+    let mut index = 2;
+    let align = match chars.get(index)? {
+        '<' => {
+            index += 1;
+            Align::Left
+        }
+        '>' => {
+            index += 1;
+            Align::Right
+        }
+        _ => Align::Left,
+    };
+
+    let digits: String = chars[index..].iter().take_while(|c| c.is_ascii_digit()).collect();
+    let width = digits.parse::<usize>().ok()?;
+    index += digits.len();
+
+    if chars.get(index)? != &'}' {
+        return None;
+    }
+
+    Some((FormatToken::Field { name, width: Some(width), align }, index + 1))
+}
+
+// Splits a format pattern into literal characters and fields.
+//
+// <purpose-start>
+// Braces that do not form a valid field, e.g. `{x}` for an unknown letter, are kept as literal text.
+// <purpose-end>
+//
+// <inputs-start>
+// - `pattern`: The format pattern.
+// - `fields`: The letters of the known fields.
+// <inputs-end>
+//
+// <outputs-start>
+// - `Vec<FormatToken>`: The tokens in pattern order.
+// <outputs-end>
+//
+// <side-effects-start>
+// - None.
+// <side-effects-end>
+fn tokenize_pattern(pattern: &str, fields: &str) -> Vec<FormatToken> {
+    let chars: Vec<char> = pattern.chars().collect();
+    let mut tokens = Vec::new();
+    let mut index = 0;
+
+    while index < chars.len() {
+        let ch = chars[index];
+
+        if ch == '{' {
+            if let Some((token, consumed)) = parse_braced_field(&chars[index + 1..], fields) {
+                tokens.push(token);
+                index += consumed + 1;
+                continue;
+            }
+        }
+
+        if fields.contains(ch) {
+            tokens.push(FormatToken::Field { name: ch, width: None, align: Align::Left });
+        } else {
+            tokens.push(FormatToken::Literal(ch));
+        }
+        index += 1;
+    }
+
+    tokens
+}
+
+// Pads or truncates a field value to its width.
+//
+// <inputs-start>
+// - `value`: The field value.
+// - `width`: The display width in terminal cells, `None` to keep the value as is.
+// - `align`: Where the value goes when it is padded.
+// <inputs-end>
+//
+// <outputs-start>
+// - `String`: The value, exactly `width` cells wide if a width is given. A wide character that
+//   does not fit entirely is left out, and its cell padded, so that CJK and emoji names line up.
+// <outputs-end>
+fn fit_to_width(value: &str, width: Option<usize>, align: Align) -> String {
+    let Some(width) = width else {
+        return value.to_string();
+    };
+
+    let mut truncated = String::new();
+    let mut used = 0;
+    for ch in value.chars() {
+        let ch_width = ch.width().unwrap_or(0);
+        if used + ch_width > width {
+            break;
+        }
+        truncated.push(ch);
+        used += ch_width;
+    }
+
+    let padding = " ".repeat(width - used);
+    match align {
+        Align::Left => format!("{}{}", truncated, padding),
+        Align::Right => format!("{}{}", padding, truncated),
+    }
+}
+
+// Formats a pattern by replacing its fields with values.
+//
+// <inputs-start>
+// - `pattern`: The format pattern.
+// - `fields`: The letters of the known fields.
+// - `value`: Returns the value of a known field.
+// <inputs-end>
+//
+// <outputs-start>
+// - `String`: The formatted string.
+// <outputs-end>
+fn format_pattern(pattern: &str, fields: &str, value: impl Fn(char) -> String) -> String {
+    let mut result = String::new();
+
+    for token in tokenize_pattern(pattern, fields) {
+        match token {
+            FormatToken::Literal(ch) => result.push(ch),
+            FormatToken::Field { name, width, align } => result.push_str(&fit_to_width(&value(name), width, align)),
+        }
+    }
+
+    result
+}
+
+// Achievement counts of a game, used by the `a` and `c` game format tokens.
+pub struct AchievementCounts {
+    pub total: usize,
+    pub completed: usize,
+}
+
 // A wrapper around the `Game` struct to provide display formatting.
+// The achievement counts are only known if they were fetched separately.
 pub struct DisplayableGame {
     pub game: Game,
+    pub achievements: Option<AchievementCounts>,
+    pub playtime_style: PlaytimeStyle,
 }
 
 impl DisplayableGame {
@@ -98,6 +281,11 @@ impl DisplayableGame {
     // <purpose-start>
     // This function formats the game information into a string based on a provided pattern.
     // The pattern can contain tokens that are replaced with game data.
+    // The `a` (total achievements) and `c` (completed achievements) tokens expand to nothing
+    // if the achievement counts are not known. The `l` token expands to the last played time in
+    // UTC, or to `never` for games that were never played, and `p` to the total playtime in the
+    // game's playtime style. Tokens in braces can be given a width, e.g. `{n:20}`
+    // pads or truncates the name to 20 characters and `{i:>6}` right-aligns the id in 6.
     // <purpose-end>
     //
     // <inputs-start>
@@ -112,23 +300,216 @@ impl DisplayableGame {
     // - None.
     // <side-effects-end>
     pub fn format(&self, pattern: &str) -> String {
-        let mut result = String::new();
+        format_pattern(pattern, "nicalp", |field| match field {
+            'n' => self.game.name.clone(),
+            'i' => self.game.appid.to_string(),
+            'a' => self.achievements.as_ref().map(|counts| counts.total.to_string()).unwrap_or_default(),
+            'l' => match self.game.last_played_datetime() {
+                Some(datetime) => format_datetime(datetime, &Utc, DEFAULT_DATE_FORMAT),
+                None => NEVER_PLAYED.to_string(),
+            },
+            // Pattern output is often parsed, so the hours are not grouped
+            'p' => format_playtime(self.game.playtime_forever, self.playtime_style, false),
+            _ => self.achievements.as_ref().map(|counts| counts.completed.to_string()).unwrap_or_default(),
+        })
+    }
+}
 
-        for ch in pattern.chars() {
-            match ch {
-                'n' => result.push_str(&self.game.name),
-                'i' => result.push_str(&self.game.appid.to_string()),
-                _ => result.push(ch),
-            }
+// Describes how playtimes are rendered.
+#[derive(Debug, Clone, Copy, PartialEq, Default, clap::ValueEnum)]
+pub enum PlaytimeStyle {
+    // Whole hours, e.g. `2h`
+    #[default]
+    Hours,
+    // Hours and minutes, e.g. `2h 33m`
+    Hm,
+    // Hours with two decimals, e.g. `2.55h`
+    Decimal,
+}
+
+// Formats a playtime.
+//
+// <purpose-start>
+// Steam reports playtimes in minutes, which are hard to read for long-played games. Whole hours
+// are rounded down, so that a game is not shown as played for longer than it was.
+// <purpose-end>
+//
+// <inputs-start>
+// - `minutes`: The playtime in minutes.
+// - `style`: How to render the playtime.
+// - `grouped`: Whether to separate the thousands of the hours, see `group_thousands`.
+// <inputs-end>
+//
+// <outputs-start>
+// - `String`: The formatted playtime.
+// <outputs-end>
+pub fn format_playtime(minutes: u32, style: PlaytimeStyle, grouped: bool) -> String {
+    let hours = format_count(u64::from(minutes / 60), grouped);
+    match style {
+        PlaytimeStyle::Hours => format!("{}h", hours),
+        PlaytimeStyle::Hm => format!("{}h {}m", hours, minutes % 60),
+        PlaytimeStyle::Decimal => format!("{}.{:02}h", hours, (minutes % 60 * 100 + 30) / 60),
+    }
+}
+
+// Separates the thousands of a number with commas, e.g. `123,456`.
+//
+// <inputs-start>
+// - `value`: The number to format.
+// <inputs-end>
+//
+// <outputs-start>
+// - `String`: The number with a comma every three digits from the right.
+// <outputs-end>
+pub fn group_thousands(value: u64) -> String {
+    let digits = value.to_string();
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+    for (index, digit) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
+
+// Formats a count for human-readable output, grouping its thousands unless `--no-thousands` was given.
+pub fn format_count(value: u64, grouped: bool) -> String {
+    if grouped {
+        group_thousands(value)
+    } else {
+        value.to_string()
+    }
+}
+
+// Describes how rare an achievement is, by the share of players who unlocked it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Rarity {
+    // Unlocked by at least 50% of the players
+    Common,
+    // Unlocked by at least 20% of the players
+    Uncommon,
+    // Unlocked by at least 5% of the players
+    Rare,
+    // Unlocked by less than 5% of the players
+    UltraRare,
+}
+
+impl Rarity {
+    // All tiers, from the most common to the rarest.
+    pub const ALL: [Rarity; 4] = [Rarity::Common, Rarity::Uncommon, Rarity::Rare, Rarity::UltraRare];
+
+    // Returns the tier of a global unlock percentage.
+    pub fn from_percent(percent: f32) -> Rarity {
+        if percent >= 50.0 {
+            Rarity::Common
+        } else if percent >= 20.0 {
+            Rarity::Uncommon
+        } else if percent >= 5.0 {
+            Rarity::Rare
+        } else {
+            Rarity::UltraRare
+        }
+    }
+
+    // Returns the name of the tier, as shown to the user.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Rarity::Common => "Common",
+            Rarity::Uncommon => "Uncommon",
+            Rarity::Rare => "Rare",
+            Rarity::UltraRare => "Ultra Rare",
+        }
+    }
+}
+
+// Displayed instead of the last played time of games that were never played.
+pub const NEVER_PLAYED: &str = "never";
+
+// The strftime format used for unlock times unless configured otherwise.
+pub const DEFAULT_DATE_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+// Describes how unlock times are rendered.
+//
+// <purpose-start>
+// Users may prefer their own date notation, or want to see unlock times in their own
+// timezone rather than in UTC.
+// <purpose-end>
+#[derive(Debug, Clone, PartialEq)]
+pub struct DateStyle {
+    pub format: String,
+    pub local_time: bool,
+}
+
+impl DateStyle {
+    // Formats a date and time in the configured format and timezone.
+    pub fn format(&self, datetime: DateTime<Utc>) -> String {
+        if self.local_time {
+            format_datetime(datetime, &Local, &self.format)
+        } else {
+            format_datetime(datetime, &Utc, &self.format)
+        }
+    }
+}
+
+impl Default for DateStyle {
+    fn default() -> Self {
+        DateStyle {
+            format: DEFAULT_DATE_FORMAT.to_string(),
+            local_time: false,
         }
+    }
+}
 
-        result
+// Validates a strftime format string.
+//
+// <purpose-start>
+// chrono panics while rendering a date with an invalid format string, so user-provided
+// formats are checked before they are used.
+// <purpose-end>
+//
+// <inputs-start>
+// - `format`: The strftime format string.
+// <inputs-end>
+//
+// <outputs-start>
+// - `Ok(String)`: The format string, if it is valid.
+// - `Err(String)`: A user-facing message if the format string contains an invalid specifier.
+// <outputs-end>
+//
+// <side-effects-start>
+// - None.
+// <side-effects-end>
+pub fn parse_date_format(format: &str) -> Result<String, String> {
+    if StrftimeItems::new(format).any(|item| item == Item::Error) {
+        return Err(format!("'{}' is not a valid strftime format", format));
     }
+
+    Ok(format.to_string())
+}
+
+// Formats a UTC date and time in the given timezone.
+//
+// <inputs-start>
+// - `datetime`: The date and time to format.
+// - `timezone`: The timezone the date and time is rendered in.
+// - `format`: A valid strftime format string, see `parse_date_format`.
+// <inputs-end>
+//
+// <outputs-start>
+// - `String`: The formatted date and time.
+// <outputs-end>
+fn format_datetime<Tz: TimeZone>(datetime: DateTime<Utc>, timezone: &Tz, format: &str) -> String
+where
+    Tz::Offset: std::fmt::Display,
+{
+    datetime.with_timezone(timezone).format(format).to_string()
 }
 
 // A wrapper around the `Achievement` struct to provide display formatting.
 pub struct DisplayableAchievement {
     pub achievement: Achievement,
+    pub date_style: DateStyle,
 }
 
 impl DisplayableAchievement {
@@ -136,7 +517,8 @@ impl DisplayableAchievement {
     //
     // <purpose-start>
     // This function formats the achievement information into a string based on a provided pattern.
-    // The pattern can contain tokens that are replaced with achievement data.
+    // The pattern can contain tokens that are replaced with achievement data, with an optional
+    // width in braces as for games, e.g. `{n:30}`.
     // <purpose-end>
     //
     // <inputs-start>
@@ -151,20 +533,13 @@ impl DisplayableAchievement {
     // - None.
     // <side-effects-end>
     pub fn format(&self, pattern: &str) -> String {
-        let mut result = String::new();
-
-        for ch in pattern.chars() {
-            match ch {
-                'i' => result.push_str(&self.achievement.apiname),
-                'n' => result.push_str(&self.achievement.name),
-                'd' => result.push_str(&self.achievement.description),
-                's' => result.push_str(if self.achievement.achieved > 0 { "Y" } else { "N" }),
-                't' => result.push_str(&self.formatted_unlocktime()),
-                _ => result.push(ch),
-            }
-        }
-
-        result
+        format_pattern(pattern, "indst", |field| match field {
+            'i' => self.achievement.apiname.clone(),
+            'n' => self.achievement.name.clone(),
+            'd' => self.achievement.description.clone(),
+            's' => if self.achievement.is_unlocked() { "Y" } else { "N" }.to_string(),
+            _ => self.formatted_unlocktime(),
+        })
     }
 
     // Renders a card-like representation of the achievement.
@@ -186,7 +561,7 @@ impl DisplayableAchievement {
     // <side-effects-end>
     pub fn render_card(&self) -> String {
         let mut card = String::new();
-        let achieved = if self.achievement.achieved == 1 { "Y" } else { "N" };
+        let achieved = if self.achievement.is_unlocked() { "Y" } else { "N" };
         let unlock_date = self.formatted_unlocktime();
 
         let apiname_length = self.achievement.apiname.len();
@@ -199,10 +574,10 @@ impl DisplayableAchievement {
         };
 
         // Generate top ┌──────┐
-        card.push_str("┌");
+        card.push('┌');
         let horizontal_line_width = longest_length + 8;
         for _ in 0..horizontal_line_width {
-            card.push_str("─");
+            card.push('─');
         }
         card.push_str("┐\n");
 
@@ -221,9 +596,9 @@ impl DisplayableAchievement {
         ));
 
         // Lower └─────────┘
-        card.push_str("└");
+        card.push('└');
         for _i in 0..horizontal_line_width {
-            card.push_str("─");
+            card.push('─');
         }
         card.push_str("┘\n");
 
@@ -233,7 +608,8 @@ impl DisplayableAchievement {
     // Formats the unlock time into a human-readable string.
     //
     // <purpose-start>
-    // This function converts the Unix timestamp of the achievement's unlock time into a formatted string.
+    // This function converts the Unix timestamp of the achievement's unlock time into a formatted string,
+    // using the format and timezone of the date style.
     // <purpose-end>
     //
     // <inputs-start>
@@ -248,104 +624,908 @@ impl DisplayableAchievement {
     // - None.
     // <side-effects-end>
     fn formatted_unlocktime(&self) -> String {
-        let ts = self.achievement.unlocktime.try_into().unwrap();
-        let datetime = Utc
-            .timestamp_opt(ts, 0)
-            .single()
-            .expect("Invalid Unix timestamp");
+        // Achievements without an unlock date keep rendering as the Unix epoch.
+        let datetime = self
+            .achievement
+            .unlock_datetime()
+            .unwrap_or(DateTime::UNIX_EPOCH);
 
-        // Format the NaiveDateTime into a human-readable string
-        datetime.format("%Y-%m-%d %H:%M:%S").to_string()
+        self.date_style.format(datetime)
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+// Decides whether the output is colored.
+//
+// <purpose-start>
+// Colors are only useful to a human reading a terminal. They are disabled by `--no-color`,
+// by the `NO_COLOR` convention (https://no-color.org) and when the output is redirected.
+// <purpose-end>
+//
+// <inputs-start>
+// - `no_color_flag`: Whether `--no-color` was passed, for commands offering it.
+// - `is_terminal`: Whether the standard output is a terminal.
+// <inputs-end>
+//
+// <outputs-start>
+// - `bool`: `true` if the output should be colored.
+// <outputs-end>
+//
+// <side-effects-start>
+// - Reads the `NO_COLOR` environment variable.
+// <side-effects-end>
+pub fn colors_enabled(no_color_flag: bool, is_terminal: bool) -> bool {
+    let no_color_env = Cfg::read_env("NO_COLOR").is_ok_and(|value| !value.is_empty());
 
-    fn create_mock_game() -> Game {
-        Game {
-            appid: 123,
-            name: "Test Game".to_string(),
-            playtime_forever: 100,
-            img_icon_url: "icon_url".to_string(),
-            playtime_windows_forever: 100,
-            playtime_mac_forever: 0,
-            playtime_linux_forever: 0,
-            rtime_last_played: 0,
-            playtime_disconnected: 0,
-        }
-    }
+    !no_color_flag && !no_color_env && is_terminal
+}
 
-    fn create_mock_achievement(achieved: u8, unlocktime: u64) -> Achievement {
-        Achievement {
-            apiname: "test_api".to_string(),
-            name: "Test Achievement".to_string(),
-            description: "Test Description".to_string(),
-            achieved,
-            unlocktime,
-        }
+// Applies colors to achievement listings.
+//
+// <purpose-start>
+// Colors make long achievement lists easier to scan in a terminal, but must not leak escape
+// sequences into files, pipes or terminals of users who opted out. A disabled palette returns
+// the text unchanged, so the plain output stays exactly as it was.
+// <purpose-end>
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Palette {
+    pub enabled: bool,
+}
+
+impl Palette {
+    // Styles the text of an unlocked achievement (green).
+    pub fn unlocked(&self, text: &str) -> String {
+        if self.enabled { text.green().to_string() } else { text.to_string() }
     }
 
-    #[test]
-    fn test_displayable_game_format() {
-        let game = create_mock_game();
-        let displayable_game = DisplayableGame { game };
+    // Styles the text of a locked achievement (dim gray).
+    pub fn locked(&self, text: &str) -> String {
+        if self.enabled { text.dark_grey().dim().to_string() } else { text.to_string() }
+    }
 
-        let formatted = displayable_game.format("n (i)");
-        assert_eq!(formatted, "Test Game (123)");
+    // Styles a global unlock percentage (cyan).
+    pub fn percentage(&self, text: &str) -> String {
+        if self.enabled { text.cyan().to_string() } else { text.to_string() }
     }
+}
 
-    #[test]
-    fn test_displayable_achievement_format_achieved() {
-        let achievement = create_mock_achievement(1, 1672531200); // 2023-01-01 00:00:00
-        let displayable_achievement = DisplayableAchievement { achievement };
+// Describes the glyphs used to draw progress bars.
+//
+// <purpose-start>
+// Terminals and fonts render block characters differently, so users can replace the
+// default glyphs with plain ASCII ones.
+// <purpose-end>
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BarStyle {
+    pub fill: char,
+    pub empty: char,
+    pub thresholds: ColorThresholds,
+    // Whether the filled part is colored according to `thresholds`, see `colors_enabled`.
+    pub colored: bool,
+}
 
-        let formatted = displayable_achievement.format("i: n - s, t, d");
-        assert_eq!(
-            formatted,
-            "test_api: Test Achievement - Y, 2023-01-01 00:00:00, Test Description"
-        );
+impl Default for BarStyle {
+    fn default() -> Self {
+        BarStyle {
+            fill: '█',
+            empty: ' ',
+            thresholds: ColorThresholds::default(),
+            colored: false,
+        }
     }
+}
 
-    #[test]
-    fn test_displayable_achievement_format_not_achieved() {
-        let achievement = create_mock_achievement(0, 0);
-        let displayable_achievement = DisplayableAchievement { achievement };
+// Represents the color of a progress bar.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BarColor {
+    // Below the warn threshold
+    Red,
+    // From the warn threshold up to the good threshold
+    Yellow,
+    // From the good threshold
+    Green,
+}
 
-        let formatted = displayable_achievement.format("i: n - s, t, d");
-        assert_eq!(
-            formatted,
-            "test_api: Test Achievement - N, 1970-01-01 00:00:00, Test Description"
-        );
+// Describes the completion percentages at which progress bars change color.
+//
+// <purpose-start>
+// Users chasing full completion may only want a green bar at 100%, while others consider a game
+// done much earlier, so the boundaries are configurable.
+// <purpose-end>
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColorThresholds {
+    pub warn: f32,
+    pub good: f32,
+}
+
+impl Default for ColorThresholds {
+    fn default() -> Self {
+        ColorThresholds { warn: 50.0, good: 100.0 }
     }
+}
 
-    #[test]
-    fn test_formatted_unlocktime() {
-        let achievement = create_mock_achievement(1, 1672531200); // 2023-01-01 00:00:00
-        let displayable_achievement = DisplayableAchievement { achievement };
+impl ColorThresholds {
+    // Creates thresholds, checking that they are in order.
+    //
+    // <inputs-start>
+    // - `warn`: The percentage from which bars are yellow, between 0 and 100.
+    // - `good`: The percentage from which bars are green, between 0 and 100.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `Ok(ColorThresholds)`: The thresholds.
+    // - `Err(String)`: A user-facing message if `warn` is greater than `good`.
+    // <outputs-end>
+    pub fn new(warn: f32, good: f32) -> Result<ColorThresholds, String> {
+        if warn > good {
+            return Err(format!("The warn threshold ({}) must not be greater than the good threshold ({})", warn, good));
+        }
 
-        let formatted_time = displayable_achievement.formatted_unlocktime();
-        assert_eq!(formatted_time, "2023-01-01 00:00:00");
+        Ok(ColorThresholds { warn, good })
     }
 
-    #[test]
-    fn test_render_card_achieved() {
-        let achievement = create_mock_achievement(1, 1672531200); // 2023-01-01 00:00:00
-        let displayable_achievement = DisplayableAchievement { achievement };
-
-        let card = displayable_achievement.render_card();
-        let expected_card = "┌───────────────────────────┐\n│ Name:            test_api │\n│ Achieved:               Y │\n│ Date: 2023-01-01 00:00:00 │\n└───────────────────────────┘\n";
-        assert_eq!(card, expected_card);
+    // Returns the color of a progress bar at the given completion percentage.
+    pub fn color(&self, percentage: f32) -> BarColor {
+        if percentage >= self.good {
+            BarColor::Green
+        } else if percentage >= self.warn {
+            BarColor::Yellow
+        } else {
+            BarColor::Red
+        }
+    }
+}
+
+// Parses a color threshold.
+//
+// <inputs-start>
+// - `value`: The threshold as provided by the user.
+// <inputs-end>
+//
+// <outputs-start>
+// - `Ok(f32)`: The threshold.
+// - `Err(String)`: A user-facing message if the value is not a number between 0 and 100.
+// <outputs-end>
+pub fn parse_threshold(value: &str) -> Result<f32, String> {
+    match value.trim().parse::<f32>() {
+        Ok(threshold) if (0.0..=100.0).contains(&threshold) => Ok(threshold),
+        _ => Err(format!("'{}' is not a number between 0 and 100", value)),
+    }
+}
+
+// Parses a progress bar glyph.
+//
+// <purpose-start>
+// Each glyph occupies exactly one cell of the bar, so anything other than a single character
+// would break the bar width.
+// <purpose-end>
+//
+// <inputs-start>
+// - `value`: The glyph as provided by the user.
+// <inputs-end>
+//
+// <outputs-start>
+// - `Ok(char)`: The glyph.
+// - `Err(String)`: A user-facing message if the value is not exactly one character.
+// <outputs-end>
+//
+// <side-effects-start>
+// - None.
+// <side-effects-end>
+pub fn parse_bar_glyph(value: &str) -> Result<char, String> {
+    let mut chars = value.chars();
+    match (chars.next(), chars.next()) {
+        (Some(ch), None) => Ok(ch),
+        _ => Err(format!("expected a single character, got '{}'", value)),
+    }
+}
+
+// Computes the share of unlocked achievements.
+//
+// <purpose-start>
+// Shared by every command reporting completion, so that they all agree on the same number.
+// The zero total is handled here, so that no caller can end up dividing by zero and printing NaN.
+// <purpose-end>
+//
+// <inputs-start>
+// - `completed`: The number of unlocked achievements.
+// - `total`: The total number of achievements.
+// <inputs-end>
+//
+// <outputs-start>
+// - `f32`: The completion percentage, between 0 and 100. A game without achievements is at 0.
+// <outputs-end>
+//
+// <side-effects-start>
+// - None.
+// <side-effects-end>
+pub fn completion_percentage(completed: usize, total: usize) -> f32 {
+    if total == 0 {
+        return 0.0;
+    }
+
+    (completed as f32 / total as f32) * 100.0
+}
+
+// Serializes a completion percentage with one decimal, the precision of the text output.
+//
+// <purpose-start>
+// Used with `#[serde(serialize_with)]` by the machine-readable outputs. The raw `f32` would
+// serialize 3/5 as `60.000004`, which breaks equality checks in consumers.
+// <purpose-end>
+//
+// <inputs-start>
+// - `percentage`: The percentage to serialize, see `completion_percentage`.
+// - `serializer`: The serde serializer.
+// <inputs-end>
+//
+// <outputs-start>
+// - The result of the serializer, a number such as `60.0` or `33.3`.
+// <outputs-end>
+//
+// <side-effects-start>
+// - None.
+// <side-effects-end>
+pub fn serialize_percentage<S: serde::Serializer>(percentage: &f32, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_f64((f64::from(*percentage) * 10.0).round() / 10.0)
+}
+
+// Renders a bracketed progress bar.
+//
+// <purpose-start>
+// Provides a single rendering of achievement progress, shared by all commands that display bars.
+// When the style is colored, the filled part is red, yellow or green depending on the thresholds.
+// <purpose-end>
+//
+// <inputs-start>
+// - `percentage`: The completion percentage, between 0 and 100.
+// - `width`: The number of cells between the brackets.
+// - `style`: The glyphs used for the filled and empty cells, and the colors.
+// <inputs-end>
+//
+// <outputs-start>
+// - `String`: The bar, e.g. `[█████     ]`.
+// <outputs-end>
+//
+// <side-effects-start>
+// - None.
+// <side-effects-end>
+pub fn render_progress_bar(percentage: f32, width: usize, style: &BarStyle) -> String {
+    let filled_chars = (((percentage / 100.0) * width as f32).round() as usize).min(width);
+    let empty_chars = width - filled_chars;
+
+    let filled: String = std::iter::repeat_n(style.fill, filled_chars).collect();
+
+    let mut bar = String::with_capacity(width + 2);
+    bar.push('[');
+    if style.colored && filled_chars > 0 {
+        let colored = match style.thresholds.color(percentage) {
+            BarColor::Red => filled.red(),
+            BarColor::Yellow => filled.yellow(),
+            BarColor::Green => filled.green(),
+        };
+        bar.push_str(&colored.to_string());
+    } else {
+        bar.push_str(&filled);
+    }
+    bar.extend(std::iter::repeat_n(style.empty, empty_chars));
+    bar.push(']');
+
+    bar
+}
+
+// Reports the progress of long-running bulk fetches.
+//
+// <purpose-start>
+// Commands that fetch data for many games can take a while, and without feedback the
+// application looks frozen. This trait decouples progress reporting from the fetch loops
+// so that the reporting can be disabled for non-interactive output and in tests.
+// <purpose-end>
+pub trait ProgressReporter {
+    // Reports that the `current` item out of `total` is being fetched.
+    //
+    // <inputs-start>
+    // - `current`: The 1-based index of the item being fetched.
+    // - `total`: The total number of items to fetch.
+    // - `err_writer`: The writer for diagnostic output.
+    // <inputs-end>
+    //
+    // <side-effects-start>
+    // - May write a progress line to `err_writer`.
+    // <side-effects-end>
+    fn report(&mut self, current: usize, total: usize, err_writer: &mut (dyn Write + Send));
+
+    // Removes any transient progress output, so regular output can follow on a clean line.
+    //
+    // <inputs-start>
+    // - `err_writer`: The writer for diagnostic output.
+    // <inputs-end>
+    //
+    // <side-effects-start>
+    // - May clear the progress line from `err_writer`.
+    // <side-effects-end>
+    fn clear(&mut self, err_writer: &mut (dyn Write + Send));
+}
+
+// A progress reporter that does nothing.
+//
+// <purpose-start>
+// Used when standard error is not a terminal, so that redirected output is not polluted
+// with progress lines, and in tests that need deterministic output.
+// <purpose-end>
+pub struct NoopProgressReporter;
+
+impl ProgressReporter for NoopProgressReporter {
+    fn report(&mut self, _current: usize, _total: usize, _err_writer: &mut (dyn Write + Send)) {}
+
+    fn clear(&mut self, _err_writer: &mut (dyn Write + Send)) {}
+}
+
+// A progress reporter that keeps a single "Fetching N/M..." line updated in place.
+//
+// <purpose-start>
+// Gives interactive users feedback during bulk fetches without scrolling the terminal.
+// <purpose-end>
+pub struct TerminalProgressReporter;
+
+// Moves the cursor to the start of the line and erases it.
+const CLEAR_LINE: &str = "\r\x1b[2K";
+
+impl ProgressReporter for TerminalProgressReporter {
+    fn report(&mut self, current: usize, total: usize, err_writer: &mut (dyn Write + Send)) {
+        write!(err_writer, "{}", CLEAR_LINE).unwrap();
+        write!(err_writer, "Fetching {}/{}...", current, total).unwrap();
+        err_writer.flush().unwrap();
+    }
+
+    fn clear(&mut self, err_writer: &mut (dyn Write + Send)) {
+        write!(err_writer, "{}", CLEAR_LINE).unwrap();
+        err_writer.flush().unwrap();
+    }
+}
+
+// Redacts the Steam API key from a message.
+//
+// <purpose-start>
+// The API key is sent as the `key` query parameter, so any URL that ends up in an error
+// message would expose it. Every `key=...` value is replaced with `***`, up to the next
+// `&` or whitespace, before the message is shown to the user.
+// <purpose-end>
+//
+// <inputs-start>
+// - `message`: The message that may contain a request URL.
+// <inputs-end>
+//
+// <outputs-start>
+// - `String`: The message with all `key` parameter values redacted.
+// <outputs-end>
+//
+// <side-effects-start>
+// - None.
+// <side-effects-end>
+pub fn scrub_api_key(message: &str) -> String {
+    let mut result = String::with_capacity(message.len());
+    let mut rest = message;
+
+    while let Some(pos) = rest.find("key=") {
+        let (before, after) = rest.split_at(pos + "key=".len());
+        result.push_str(before);
+        result.push_str("***");
+
+        let value_end = after
+            .find(|c: char| c == '&' || c.is_whitespace())
+            .unwrap_or(after.len());
+        rest = &after[value_end..];
+    }
+
+    result.push_str(rest);
+    result
+}
+
+// Escapes a value for a CSV field.
+//
+// <purpose-start>
+// Values containing a delimiter, a quote or a line break are quoted as described in RFC 4180,
+// with quotes doubled. Other values are written as is.
+// <purpose-end>
+//
+// <inputs-start>
+// - `value`: The field value.
+// <inputs-end>
+//
+// <outputs-start>
+// - `String`: The value ready to be joined with commas.
+// <outputs-end>
+//
+// <side-effects-start>
+// - None.
+// <side-effects-end>
+pub fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+// Makes a value safe for a tab-separated field.
+//
+// <purpose-start>
+// Tabs and line breaks would split the value into several fields or rows, so they are replaced
+// with spaces. Unlike CSV, no quoting is involved, which keeps the output easy to `cut`.
+// <purpose-end>
+//
+// <inputs-start>
+// - `value`: The field value.
+// <inputs-end>
+//
+// <outputs-start>
+// - `String`: The value ready to be joined with tabs.
+// <outputs-end>
+pub fn tsv_field(value: &str) -> String {
+    value.replace(['\t', '\n', '\r'], " ")
+}
+
+// Formats a record of the `--porcelain` output.
+//
+// <purpose-start>
+// Every porcelain line is the record type followed by its fields, separated by tabs. Commands
+// go through this function so that all records are escaped the same way.
+// <purpose-end>
+//
+// <inputs-start>
+// - `record_type`: The type of the record, e.g. `game`.
+// - `fields`: The fields of the record, in their documented order.
+// <inputs-end>
+//
+// <outputs-start>
+// - `String`: The line, without its line break.
+// <outputs-end>
+pub fn porcelain_record(record_type: &str, fields: &[String]) -> String {
+    let mut line = record_type.to_string();
+    for field in fields {
+        line.push('\t');
+        line.push_str(&tsv_field(field));
+    }
+    line
+}
+
+// Creates the progress reporter appropriate for the current standard error stream.
+//
+// <purpose-start>
+// Progress lines are only useful to a human watching the terminal, so they are suppressed
+// when standard error is redirected to a file or a pipe, or when the user asked for quiet output.
+// <purpose-end>
+//
+// <inputs-start>
+// - `quiet`: Whether non-essential messages are suppressed.
+// <inputs-end>
+//
+// <outputs-start>
+// - `Box<dyn ProgressReporter + Send>`: A terminal reporter when stderr is a TTY and `quiet` is not set,
+//   a no-op reporter otherwise.
+// <outputs-end>
+//
+// <side-effects-start>
+// - None.
+// <side-effects-end>
+pub fn progress_reporter(quiet: bool) -> Box<dyn ProgressReporter + Send> {
+    if !quiet && std::io::stderr().is_terminal() {
+        Box::new(TerminalProgressReporter)
+    } else {
+        Box::new(NoopProgressReporter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_mock_game() -> Game {
+        Game {
+            appid: 123,
+            name: "Test Game".to_string(),
+            playtime_forever: 100,
+            img_icon_url: "icon_url".to_string(),
+            playtime_windows_forever: 100,
+            playtime_mac_forever: 0,
+            playtime_linux_forever: 0,
+            rtime_last_played: 0,
+            playtime_disconnected: 0,
+        }
+    }
+
+    fn create_mock_achievement(achieved: u8, unlocktime: u64) -> Achievement {
+        Achievement {
+            apiname: "test_api".to_string(),
+            name: "Test Achievement".to_string(),
+            description: "Test Description".to_string(),
+            achieved,
+            unlocktime,
+        }
+    }
+
+    #[test]
+    fn test_scrub_api_key() {
+        assert_eq!(
+            scrub_api_key("error sending request for url (http://x/?key=secret123&steamid=1)"),
+            "error sending request for url (http://x/?key=***&steamid=1)"
+        );
+        assert_eq!(scrub_api_key("bad key=secret123"), "bad key=***");
+        assert_eq!(scrub_api_key("?key=a&key=b c"), "?key=***&key=*** c");
+        assert_eq!(scrub_api_key("no secrets here"), "no secrets here");
+    }
+
+    #[test]
+    fn test_csv_field() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a, b"), "\"a, b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+    }
+
+    #[test]
+    fn test_tsv_field() {
+        assert_eq!(tsv_field("a, \"b\""), "a, \"b\"");
+        assert_eq!(tsv_field("tab\tand\r\nbreak"), "tab and  break");
+    }
+
+    #[test]
+    fn test_displayable_game_format() {
+        let game = create_mock_game();
+        let displayable_game = DisplayableGame { game, achievements: None, playtime_style: PlaytimeStyle::Hours };
+
+        let formatted = displayable_game.format("n (i)");
+        assert_eq!(formatted, "Test Game (123)");
+    }
+
+    #[test]
+    fn test_displayable_game_format_last_played() {
+        let mut game = create_mock_game();
+        game.rtime_last_played = 1672531200;
+        let displayable_game = DisplayableGame { game, achievements: None, playtime_style: PlaytimeStyle::Hours };
+        assert_eq!(displayable_game.format("n: l"), "Test Game: 2023-01-01 00:00:00");
+
+        let mut game = create_mock_game();
+        game.rtime_last_played = 0;
+        let displayable_game = DisplayableGame { game, achievements: None, playtime_style: PlaytimeStyle::Hours };
+        assert_eq!(displayable_game.format("n: {l:>7}"), "Test Game:   never");
+    }
+
+    #[test]
+    fn test_rarity_from_percent() {
+        assert_eq!(Rarity::from_percent(75.0), Rarity::Common);
+        assert_eq!(Rarity::from_percent(50.0), Rarity::Common);
+        assert_eq!(Rarity::from_percent(49.9), Rarity::Uncommon);
+        assert_eq!(Rarity::from_percent(20.0), Rarity::Uncommon);
+        assert_eq!(Rarity::from_percent(5.0), Rarity::Rare);
+        assert_eq!(Rarity::from_percent(4.9), Rarity::UltraRare);
+        assert_eq!(Rarity::from_percent(0.0), Rarity::UltraRare);
+        assert_eq!(Rarity::UltraRare.label(), "Ultra Rare");
+    }
+
+    #[test]
+    fn test_format_playtime() {
+        assert_eq!(format_playtime(153, PlaytimeStyle::Hours, false), "2h");
+        assert_eq!(format_playtime(153, PlaytimeStyle::Hm, false), "2h 33m");
+        assert_eq!(format_playtime(153, PlaytimeStyle::Decimal, false), "2.55h");
+        assert_eq!(format_playtime(0, PlaytimeStyle::Hm, false), "0h 0m");
+        assert_eq!(format_playtime(59, PlaytimeStyle::Decimal, false), "0.98h");
+        assert_eq!(format_playtime(119, PlaytimeStyle::Decimal, false), "1.98h");
+        assert_eq!(format_playtime(740_000, PlaytimeStyle::Hours, true), "12,333h");
+        assert_eq!(format_playtime(740_000, PlaytimeStyle::Hm, true), "12,333h 20m");
+        assert_eq!(format_playtime(740_000, PlaytimeStyle::Decimal, true), "12,333.33h");
+        assert_eq!(format_playtime(740_000, PlaytimeStyle::Hours, false), "12333h");
+    }
+
+    #[test]
+    fn test_group_thousands() {
+        assert_eq!(group_thousands(0), "0");
+        assert_eq!(group_thousands(999), "999");
+        assert_eq!(group_thousands(1000), "1,000");
+        assert_eq!(group_thousands(10_000), "10,000");
+        assert_eq!(group_thousands(123_456), "123,456");
+        assert_eq!(group_thousands(1_234_567), "1,234,567");
+        assert_eq!(group_thousands(u64::MAX), "18,446,744,073,709,551,615");
+    }
+
+    #[test]
+    fn test_format_count() {
+        assert_eq!(format_count(123_456, true), "123,456");
+        assert_eq!(format_count(123_456, false), "123456");
+    }
+
+    #[test]
+    fn test_displayable_game_format_playtime() {
+        let mut game = create_mock_game();
+        game.playtime_forever = 153;
+        let displayable_game = DisplayableGame { game, achievements: None, playtime_style: PlaytimeStyle::Hm };
+
+        assert_eq!(displayable_game.format("n: {p:>7}"), "Test Game:  2h 33m");
+    }
+
+    #[test]
+    fn test_displayable_game_format_achievement_counts() {
+        let game = create_mock_game();
+        let displayable_game = DisplayableGame {
+            game,
+            achievements: Some(AchievementCounts { total: 50, completed: 12 }),
+            playtime_style: PlaytimeStyle::Hours,
+        };
+
+        let formatted = displayable_game.format("i: c/a");
+        assert_eq!(formatted, "123: 12/50");
+    }
+
+    #[test]
+    fn test_displayable_game_format_without_achievement_counts() {
+        let game = create_mock_game();
+        let displayable_game = DisplayableGame { game, achievements: None, playtime_style: PlaytimeStyle::Hours };
+
+        let formatted = displayable_game.format("i: c/a");
+        assert_eq!(formatted, "123: /");
+    }
+
+    #[test]
+    fn test_displayable_game_format_padding() {
+        let game = create_mock_game();
+        let displayable_game = DisplayableGame { game, achievements: None, playtime_style: PlaytimeStyle::Hours };
+
+        assert_eq!(displayable_game.format("{n:12}|"), "Test Game   |");
+        assert_eq!(displayable_game.format("{n:<12}|"), "Test Game   |");
+        assert_eq!(displayable_game.format("{n}|{i}"), "Test Game|123");
+    }
+
+    #[test]
+    fn test_displayable_game_format_right_alignment() {
+        let game = create_mock_game();
+        let displayable_game = DisplayableGame {
+            game,
+            achievements: Some(AchievementCounts { total: 50, completed: 7 }),
+            playtime_style: PlaytimeStyle::Hours,
+        };
+
+        assert_eq!(displayable_game.format("[{i:>6}] {c:>3}/a"), "[   123]   7/50");
+    }
+
+    #[test]
+    fn test_displayable_game_format_truncation() {
+        let game = create_mock_game();
+        let displayable_game = DisplayableGame { game, achievements: None, playtime_style: PlaytimeStyle::Hours };
+
+        assert_eq!(displayable_game.format("{n:4}|{i:>2}"), "Test|12");
+    }
+
+    #[test]
+    fn test_fit_to_width_uses_display_width() {
+        assert_eq!(fit_to_width("原神", Some(6), Align::Left), "原神  ");
+        assert_eq!(fit_to_width("原神", Some(6), Align::Right), "  原神");
+        // The second character would straddle the edge, so its cell is padded instead
+        assert_eq!(fit_to_width("原神インパクト", Some(3), Align::Left), "原 ");
+        assert_eq!(fit_to_width("🎮 Game", Some(4), Align::Left), "🎮 G");
+        assert_eq!(fit_to_width("Portal", None, Align::Left), "Portal");
+    }
+
+    #[test]
+    fn test_tokenize_pattern_keeps_invalid_braces_literal() {
+        let game = create_mock_game();
+        let displayable_game = DisplayableGame { game, achievements: None, playtime_style: PlaytimeStyle::Hours };
+
+        // Unknown letters, missing widths and unclosed braces are not fields
+        assert_eq!(displayable_game.format("{x:5}"), "{x:5}");
+        assert_eq!(displayable_game.format("{n:}"), "{Test Game:}");
+        assert_eq!(displayable_game.format("{i:>4"), "{123:>4");
+    }
+
+    #[test]
+    fn test_displayable_achievement_format_padding() {
+        let achievement = create_mock_achievement(1, 1672531200);
+        let displayable_achievement = DisplayableAchievement { achievement, date_style: DateStyle::default() };
+
+        assert_eq!(displayable_achievement.format("{s:>2} {n:10}|"), " Y Test Achie|");
+    }
+
+    #[test]
+    fn test_displayable_achievement_format_achieved() {
+        let achievement = create_mock_achievement(1, 1672531200); // 2023-01-01 00:00:00
+        let displayable_achievement = DisplayableAchievement { achievement, date_style: DateStyle::default() };
+
+        let formatted = displayable_achievement.format("i: n - s, t, d");
+        assert_eq!(
+            formatted,
+            "test_api: Test Achievement - Y, 2023-01-01 00:00:00, Test Description"
+        );
+    }
+
+    #[test]
+    fn test_displayable_achievement_format_not_achieved() {
+        let achievement = create_mock_achievement(0, 0);
+        let displayable_achievement = DisplayableAchievement { achievement, date_style: DateStyle::default() };
+
+        let formatted = displayable_achievement.format("i: n - s, t, d");
+        assert_eq!(
+            formatted,
+            "test_api: Test Achievement - N, 1970-01-01 00:00:00, Test Description"
+        );
+    }
+
+    #[test]
+    fn test_formatted_unlocktime() {
+        let achievement = create_mock_achievement(1, 1672531200); // 2023-01-01 00:00:00
+        let displayable_achievement = DisplayableAchievement { achievement, date_style: DateStyle::default() };
+
+        let formatted_time = displayable_achievement.formatted_unlocktime();
+        assert_eq!(formatted_time, "2023-01-01 00:00:00");
+    }
+
+    #[test]
+    fn test_formatted_unlocktime_custom_format() {
+        let achievement = create_mock_achievement(1, 1672531200); // 2023-01-01 00:00:00
+        let date_style = DateStyle { format: "%d.%m.%Y".to_string(), local_time: false };
+        let displayable_achievement = DisplayableAchievement { achievement, date_style };
+
+        let formatted_time = displayable_achievement.formatted_unlocktime();
+        assert_eq!(formatted_time, "01.01.2023");
+    }
+
+    #[test]
+    fn test_format_datetime_with_fixed_offset() {
+        let datetime = DateTime::from_timestamp(1672531200, 0).unwrap(); // 2023-01-01 00:00:00
+        let offset = chrono::FixedOffset::east_opt(2 * 3600).unwrap();
+
+        let formatted_time = format_datetime(datetime, &offset, "%Y-%m-%d %H:%M:%S %:z");
+        assert_eq!(formatted_time, "2023-01-01 02:00:00 +02:00");
+    }
+
+    #[test]
+    fn test_parse_date_format() {
+        assert_eq!(parse_date_format("%d/%m/%Y"), Ok("%d/%m/%Y".to_string()));
+        assert!(parse_date_format("%Q").is_err());
+    }
+
+    #[test]
+    fn test_render_card_achieved() {
+        let achievement = create_mock_achievement(1, 1672531200); // 2023-01-01 00:00:00
+        let displayable_achievement = DisplayableAchievement { achievement, date_style: DateStyle::default() };
+
+        let card = displayable_achievement.render_card();
+        let expected_card = "┌───────────────────────────┐\n│ Name:            test_api │\n│ Achieved:               Y │\n│ Date: 2023-01-01 00:00:00 │\n└───────────────────────────┘\n";
+        assert_eq!(card, expected_card);
     }
 
     #[test]
     fn test_render_card_not_achieved() {
         let achievement = create_mock_achievement(0, 0);
-        let displayable_achievement = DisplayableAchievement { achievement };
+        let displayable_achievement = DisplayableAchievement { achievement, date_style: DateStyle::default() };
 
         let card = displayable_achievement.render_card();
         let expected_card = "┌───────────────────────────┐\n│ Name:            test_api │\n│ Achieved:               N │\n│ Date: 1970-01-01 00:00:00 │\n└───────────────────────────┘\n";
         assert_eq!(card, expected_card);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_porcelain_record() {
+        assert_eq!(porcelain_record("game", &["440".to_string(), "Team\tFortress 2".to_string()]), "game\t440\tTeam Fortress 2");
+        assert_eq!(porcelain_record("total", &[]), "total");
+    }
+
+    #[test]
+    fn test_terminal_progress_reporter() {
+        let mut reporter = TerminalProgressReporter;
+        let mut err_writer = Vec::new();
+
+        reporter.report(3, 10, &mut err_writer);
+
+        let output = String::from_utf8(err_writer).unwrap();
+        assert!(output.contains("Fetching 3/10..."));
+    }
+
+    #[test]
+    fn test_noop_progress_reporter() {
+        let mut reporter = NoopProgressReporter;
+        let mut err_writer = Vec::new();
+
+        reporter.report(3, 10, &mut err_writer);
+        reporter.clear(&mut err_writer);
+
+        assert!(err_writer.is_empty());
+    }
+
+    #[test]
+    fn test_disabled_palette_returns_plain_text() {
+        let palette = Palette { enabled: false };
+        assert_eq!(palette.unlocked("Done"), "Done");
+        assert_eq!(palette.locked("Todo"), "Todo");
+        assert_eq!(palette.percentage("12.5%"), "12.5%");
+    }
+
+    #[test]
+    fn test_enabled_palette_adds_escapes() {
+        let palette = Palette { enabled: true };
+        let styled = palette.unlocked("Done");
+        assert!(styled.contains('\x1b'));
+        assert!(styled.contains("Done"));
+        assert_ne!(palette.locked("Todo"), "Todo");
+        assert_ne!(palette.percentage("12.5%"), "12.5%");
+    }
+
+    #[test]
+    fn test_completion_percentage() {
+        assert_eq!(completion_percentage(1, 4), 25.0);
+        assert_eq!(completion_percentage(3, 3), 100.0);
+        assert_eq!(completion_percentage(0, 0), 0.0);
+        assert_eq!(completion_percentage(5, 0), 0.0);
+        assert!((completion_percentage(1, 3) - 33.3).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_render_progress_bar_default_style() {
+        let bar = render_progress_bar(50.0, 10, &BarStyle::default());
+        assert_eq!(bar, "[█████     ]");
+    }
+
+    #[test]
+    fn test_render_progress_bar_custom_style() {
+        let style = BarStyle { fill: '#', empty: '-', ..BarStyle::default() };
+        assert_eq!(render_progress_bar(30.0, 10, &style), "[###-------]");
+        assert_eq!(render_progress_bar(100.0, 4, &style), "[####]");
+        assert_eq!(render_progress_bar(0.0, 4, &style), "[----]");
+    }
+
+    #[test]
+    fn test_colors_enabled() {
+        assert!(!colors_enabled(true, true));
+        assert!(!colors_enabled(false, false));
+        assert_eq!(colors_enabled(false, true), Cfg::read_env("NO_COLOR").map_or(true, |v| v.is_empty()));
+    }
+
+    #[test]
+    fn test_color_thresholds_default() {
+        let thresholds = ColorThresholds::default();
+        assert_eq!(thresholds.color(0.0), BarColor::Red);
+        assert_eq!(thresholds.color(49.9), BarColor::Red);
+        assert_eq!(thresholds.color(50.0), BarColor::Yellow);
+        assert_eq!(thresholds.color(99.9), BarColor::Yellow);
+        assert_eq!(thresholds.color(100.0), BarColor::Green);
+    }
+
+    #[test]
+    fn test_color_thresholds_custom() {
+        let thresholds = ColorThresholds::new(25.0, 80.0).unwrap();
+        assert_eq!(thresholds.color(20.0), BarColor::Red);
+        assert_eq!(thresholds.color(25.0), BarColor::Yellow);
+        assert_eq!(thresholds.color(80.0), BarColor::Green);
+
+        let thresholds = ColorThresholds::new(60.0, 60.0).unwrap();
+        assert_eq!(thresholds.color(59.0), BarColor::Red);
+        assert_eq!(thresholds.color(60.0), BarColor::Green);
+
+        assert!(ColorThresholds::new(80.0, 25.0).is_err());
+    }
+
+    #[test]
+    fn test_parse_threshold() {
+        assert_eq!(parse_threshold("0"), Ok(0.0));
+        assert_eq!(parse_threshold("75.5"), Ok(75.5));
+        assert_eq!(parse_threshold("100"), Ok(100.0));
+        assert!(parse_threshold("101").is_err());
+        assert!(parse_threshold("-1").is_err());
+        assert!(parse_threshold("half").is_err());
+    }
+
+    #[test]
+    fn test_render_progress_bar_colored() {
+        let style = BarStyle { fill: '#', empty: '-', colored: true, ..BarStyle::default() };
+        assert_eq!(render_progress_bar(30.0, 10, &style), format!("[{}-------]", "###".red()));
+        assert_eq!(render_progress_bar(50.0, 4, &style), format!("[{}--]", "##".yellow()));
+        assert_eq!(render_progress_bar(100.0, 4, &style), format!("[{}]", "####".green()));
+        assert_eq!(render_progress_bar(0.0, 4, &style), "[----]");
+    }
+
+    #[test]
+    fn test_parse_bar_glyph() {
+        assert_eq!(parse_bar_glyph("#"), Ok('#'));
+        assert_eq!(parse_bar_glyph("█"), Ok('█'));
+        assert!(parse_bar_glyph("").is_err());
+        assert!(parse_bar_glyph("##").is_err());
+    }
+}
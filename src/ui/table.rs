@@ -0,0 +1,200 @@
+//! Renders rows of values as an aligned table.
+//!
+//! <purpose-start>
+//! Columns are as wide as their widest value, measured in terminal cells rather than characters,
+//! so that names in CJK scripts or with emoji line up with ASCII ones. Values wider than the
+//! column cap are truncated with an ellipsis, which keeps a single long name from pushing the
+//! other columns off screen.
+//! <purpose-end>
+
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+// The widest a column gets when `with_max_column_width` is not called.
+pub const DEFAULT_MAX_COLUMN_WIDTH: usize = 40;
+
+// The spacing between two columns.
+const COLUMN_GAP: &str = "  ";
+
+// The character ending truncated values.
+const ELLIPSIS: char = '…';
+
+// A table of string values with a header row.
+pub struct Table {
+    headers: Vec<String>,
+    right_aligned: Vec<bool>,
+    rows: Vec<Vec<String>>,
+    max_column_width: usize,
+}
+
+impl Table {
+    // Creates an empty table with the given column headers, all left-aligned.
+    pub fn new(headers: &[&str]) -> Table {
+        Table {
+            headers: headers.iter().map(|h| h.to_string()).collect(),
+            right_aligned: vec![false; headers.len()],
+            rows: Vec::new(),
+            max_column_width: DEFAULT_MAX_COLUMN_WIDTH,
+        }
+    }
+
+    // Right-aligns a column, typically one holding numbers.
+    pub fn right_align(mut self, column: usize) -> Table {
+        self.right_aligned[column] = true;
+        self
+    }
+
+    // Caps the width of every column, in terminal cells.
+    pub fn with_max_column_width(mut self, max_column_width: usize) -> Table {
+        self.max_column_width = max_column_width;
+        self
+    }
+
+    // Adds a row. Missing values are left empty, extra values are ignored.
+    pub fn add_row(&mut self, row: Vec<String>) {
+        self.rows.push(row);
+    }
+
+    // Renders the table.
+    //
+    // <purpose-start>
+    // The header is underlined with dashes. Trailing whitespace is trimmed from each line, so
+    // that padding the last column does not trip up `diff`.
+    // <purpose-end>
+    //
+    // <inputs-start>
+    // - None.
+    // <inputs-end>
+    //
+    // <outputs-start>
+    // - `Vec<String>`: The lines of the table, header first.
+    // <outputs-end>
+    //
+    // <side-effects-start>
+    // - None.
+    // <side-effects-end>
+    pub fn render(&self) -> Vec<String> {
+        let cells: Vec<Vec<String>> = std::iter::once(&self.headers)
+            .chain(&self.rows)
+            .map(|row| {
+                (0..self.headers.len())
+                    .map(|column| truncate(row.get(column).map_or("", String::as_str), self.max_column_width))
+                    .collect()
+            })
+            .collect();
+        let widths: Vec<usize> = (0..self.headers.len())
+            .map(|column| cells.iter().map(|row| row[column].width()).max().unwrap_or(0))
+            .collect();
+
+        let mut lines: Vec<String> = cells.iter().map(|row| self.render_row(row, &widths)).collect();
+        let rule: Vec<String> = widths.iter().map(|width| "-".repeat(*width)).collect();
+        lines.insert(1, rule.join(COLUMN_GAP));
+        lines
+    }
+
+    // Pads the values of a row to the column widths and joins them.
+    fn render_row(&self, row: &[String], widths: &[usize]) -> String {
+        let fields: Vec<String> = row
+            .iter()
+            .enumerate()
+            .map(|(column, value)| pad(value, widths[column], self.right_aligned[column]))
+            .collect();
+        fields.join(COLUMN_GAP).trim_end().to_string()
+    }
+}
+
+// Pads a value with spaces to the given display width.
+fn pad(value: &str, width: usize, right_aligned: bool) -> String {
+    let padding = " ".repeat(width.saturating_sub(value.width()));
+    if right_aligned {
+        format!("{}{}", padding, value)
+    } else {
+        format!("{}{}", value, padding)
+    }
+}
+
+// Truncates a value to the given display width, ending it with an ellipsis when it is cut.
+//
+// <inputs-start>
+// - `value`: The value to fit.
+// - `max_width`: The width available, in terminal cells.
+// <inputs-end>
+//
+// <outputs-start>
+// - `String`: The value, at most `max_width` cells wide.
+// <outputs-end>
+fn truncate(value: &str, max_width: usize) -> String {
+    if value.width() <= max_width {
+        return value.to_string();
+    }
+
+    // Keeps room for the ellipsis, which is a single cell wide
+    let mut result = String::new();
+    let mut width = 0;
+    for ch in value.chars() {
+        let ch_width = ch.width().unwrap_or(0);
+        if width + ch_width + 1 > max_width {
+            break;
+        }
+        result.push(ch);
+        width += ch_width;
+    }
+    if max_width > 0 {
+        result.push(ELLIPSIS);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_ascii() {
+        let mut table = Table::new(&["ID", "Name"]).right_align(0);
+        table.add_row(vec!["440".to_string(), "Team Fortress 2".to_string()]);
+        table.add_row(vec!["70".to_string(), "Half-Life".to_string()]);
+
+        assert_eq!(table.render(), vec![
+            " ID  Name",
+            "---  ---------------",
+            "440  Team Fortress 2",
+            " 70  Half-Life",
+        ]);
+    }
+
+    #[test]
+    fn test_render_wide_characters() {
+        let mut table = Table::new(&["Name", "Hours"]).right_align(1);
+        table.add_row(vec!["東方".to_string(), "12".to_string()]);
+        table.add_row(vec!["Portal".to_string(), "3".to_string()]);
+
+        let lines = table.render();
+
+        // 東方 is four cells wide, so it gets two cells of padding where Portal gets none
+        assert_eq!(lines[2], "東方       12");
+        assert_eq!(lines[3], "Portal      3");
+        assert_eq!(lines[2].width(), lines[3].width());
+    }
+
+    #[test]
+    fn test_render_caps_column_width() {
+        let mut table = Table::new(&["Name"]).with_max_column_width(8);
+        table.add_row(vec!["The Elder Scrolls".to_string()]);
+        table.add_row(vec!["ゼルダの伝説".to_string()]);
+
+        let lines = table.render();
+
+        assert_eq!(lines[1], "--------");
+        assert_eq!(lines[2], "The Eld…");
+        // A wide character that does not fit is dropped whole
+        assert_eq!(lines[3], "ゼルダ…");
+    }
+
+    #[test]
+    fn test_render_missing_values() {
+        let mut table = Table::new(&["A", "B"]);
+        table.add_row(vec!["x".to_string()]);
+
+        assert_eq!(table.render(), vec!["A  B", "-  -", "x"]);
+    }
+}